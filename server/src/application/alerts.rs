@@ -0,0 +1,205 @@
+use crate::application::DeliveryQueue;
+use crate::domain::{Alert, AlertCondition, AlertRepository, StockRepository, WebhookTargetPolicy};
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Registers price alerts and notifies their webhooks as fresh live data arrives
+pub struct AlertUseCase {
+    repository: Arc<dyn AlertRepository + Send + Sync>,
+    /// Source of the latest live data alerts are evaluated against
+    stock_repository: Arc<dyn StockRepository + Send + Sync>,
+    deliveries: Arc<DeliveryQueue>,
+    /// Which webhook URLs alerts may deliver to
+    targets: Arc<dyn WebhookTargetPolicy + Send + Sync>,
+    /// Shortest time between two firings of the same alert
+    cooldown: chrono::Duration,
+}
+
+impl AlertUseCase {
+    pub fn new(
+        repository: Arc<dyn AlertRepository + Send + Sync>,
+        stock_repository: Arc<dyn StockRepository + Send + Sync>,
+        deliveries: Arc<DeliveryQueue>,
+        targets: Arc<dyn WebhookTargetPolicy + Send + Sync>,
+        cooldown: chrono::Duration,
+    ) -> Self {
+        Self {
+            repository,
+            stock_repository,
+            deliveries,
+            targets,
+            cooldown,
+        }
+    }
+
+    /// Register an alert, failing with a `RejectedWebhookUrl` if its webhook isn't allowed
+    pub async fn create_alert(
+        &self,
+        symbol: &str,
+        condition: AlertCondition,
+        webhook_url: String,
+    ) -> Result<Alert> {
+        self.targets.check(&webhook_url).await?;
+        let alert = Alert::new(symbol.trim().to_uppercase(), condition, webhook_url);
+        self.repository.store_alert(&alert).await?;
+        Ok(alert)
+    }
+
+    pub async fn get_alerts(&self) -> Result<Vec<Alert>> {
+        self.repository.get_alerts().await
+    }
+
+    /// Delete an alert, returning whether it existed
+    pub async fn delete_alert(&self, id: &str) -> Result<bool> {
+        self.repository.delete_alert(id).await
+    }
+
+    /// Evaluate every alert against its symbol's latest live data, delivering a webhook for each
+    /// one that fires. Returns the number fired.
+    pub async fn check_alerts(&self) -> Result<usize> {
+        let now = Utc::now();
+        let mut fired = 0;
+
+        for mut alert in self.repository.get_alerts().await? {
+            let Some(data) = self
+                .stock_repository
+                .get_latest_live_data(&alert.symbol)
+                .await?
+            else {
+                continue;
+            };
+
+            let was_triggered = alert.triggered;
+            let fires = alert.evaluate(&data, now, self.cooldown);
+            if fires {
+                // The host was checked at creation, but what it resolves to may have changed since
+                match self.targets.check(&alert.webhook_url).await {
+                    Ok(()) => {
+                        let payload = serde_json::json!({
+                            "event": "alert_triggered",
+                            "alert_id": alert.id,
+                            "symbol": alert.symbol,
+                            "condition": alert.condition,
+                            "price": data.price,
+                            "change": data.change,
+                            "fired_at": now,
+                        });
+                        // Deliver in the background so slow webhooks don't hold up the scrape cycle
+                        let deliveries = self.deliveries.clone();
+                        let url = alert.webhook_url.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = deliveries.deliver(&url, payload).await {
+                                warn!("Failed to queue alert webhook to {}: {}", url, e);
+                            }
+                        });
+                        info!(
+                            "Alert {} on {} fired at price {}",
+                            alert.id, alert.symbol, data.price
+                        );
+                        fired += 1;
+                    }
+                    Err(e) => warn!("Not delivering alert {}: {}", alert.id, e),
+                }
+            }
+
+            if fires || alert.triggered != was_triggered {
+                self.repository.store_alert(&alert).await?;
+            }
+        }
+
+        Ok(fired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::DeliveryConfig;
+    use crate::infrastructure::test_support::{live, MockWebhookServer, TempDb};
+    use crate::infrastructure::{
+        RocksDbAlertRepository, RocksDbStockRepository, WebhookClientImpl,
+    };
+
+    /// Lets alerts deliver to the local mock server, which the real policy would refuse
+    struct AllowAllTargets;
+
+    #[async_trait::async_trait]
+    impl WebhookTargetPolicy for AllowAllTargets {
+        async fn check(&self, _url: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn alert_use_case(
+        temp: &TempDb,
+        targets: Arc<dyn WebhookTargetPolicy + Send + Sync>,
+    ) -> AlertUseCase {
+        let stock_repository = Arc::new(RocksDbStockRepository::new(temp.db.clone()));
+        AlertUseCase::new(
+            Arc::new(RocksDbAlertRepository::new(temp.db.clone())),
+            stock_repository.clone(),
+            Arc::new(DeliveryQueue::new(
+                Arc::new(WebhookClientImpl::new().allowing_private_targets()),
+                stock_repository,
+                DeliveryConfig::default(),
+            )),
+            targets,
+            chrono::Duration::hours(1),
+        )
+    }
+
+    #[tokio::test]
+    async fn a_triggered_alert_posts_its_payload_to_the_webhook() {
+        let temp = TempDb::new();
+        let mut server = MockWebhookServer::start().await;
+        let use_case = alert_use_case(&temp, Arc::new(AllowAllTargets));
+        let alert = use_case
+            .create_alert(
+                "mtngh",
+                AlertCondition::Below { price: 2.5 },
+                server.url.clone(),
+            )
+            .await
+            .unwrap();
+        use_case
+            .stock_repository
+            .store_live_data("MTNGH", &live("MTNGH", 2.4, -0.1), Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(use_case.check_alerts().await.unwrap(), 1);
+
+        let payload = server.next_payload().await;
+        assert_eq!(payload["event"], "alert_triggered");
+        assert_eq!(payload["alert_id"], alert.id.as_str());
+        assert_eq!(payload["symbol"], "MTNGH");
+        assert_eq!(payload["price"], 2.4);
+        assert_eq!(payload["condition"]["type"], "below");
+        let stored = use_case.get_alerts().await.unwrap();
+        assert!(stored[0].fired_at.is_some());
+        // Still below the threshold, so the next cycle doesn't fire it again
+        assert_eq!(use_case.check_alerts().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn alerts_on_internal_webhooks_are_refused() {
+        let temp = TempDb::new();
+        let use_case = alert_use_case(&temp, Arc::new(crate::infrastructure::PublicWebhookTargets));
+
+        let error = use_case
+            .create_alert(
+                "MTNGH",
+                AlertCondition::Above { price: 3.0 },
+                "http://169.254.169.254/latest/meta-data/".to_string(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(error
+            .downcast_ref::<crate::domain::RejectedWebhookUrl>()
+            .is_some());
+        assert!(use_case.get_alerts().await.unwrap().is_empty());
+    }
+}
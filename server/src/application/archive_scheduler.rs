@@ -0,0 +1,69 @@
+use crate::domain::DataArchiver;
+use anyhow::Result;
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info};
+
+/// Configuration for compacting old live ticks into monthly archives
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    /// Interval between compaction runs (in seconds)
+    pub interval: u64,
+    /// Months that ended more than this many days ago are archived
+    pub older_than_days: i64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            interval: 86400, // 1 day
+            older_than_days: 365,
+        }
+    }
+}
+
+/// Background task compacting old live ticks into monthly archives on an interval
+pub struct ArchiveScheduler {
+    archiver: Arc<dyn DataArchiver + Send + Sync>,
+    config: ArchiveConfig,
+}
+
+impl ArchiveScheduler {
+    pub fn new(archiver: Arc<dyn DataArchiver + Send + Sync>, config: ArchiveConfig) -> Self {
+        Self { archiver, config }
+    }
+
+    /// Start compacting with the configured interval
+    pub async fn start(&self) -> Result<()> {
+        info!(
+            "Starting archive scheduler with interval: {} seconds, archiving data older than {} days",
+            self.config.interval, self.config.older_than_days
+        );
+
+        let mut interval_timer = interval(Duration::from_secs(self.config.interval));
+
+        loop {
+            interval_timer.tick().await;
+
+            if let Err(e) = self.run_archive().await {
+                error!("Scheduled archive failed: {}", e);
+            }
+        }
+    }
+
+    /// Archive every month that ended before the configured age
+    pub async fn run_archive(&self) -> Result<()> {
+        let cutoff = Utc::now() - chrono::Duration::days(self.config.older_than_days);
+        let summary = self.archiver.archive_before(cutoff).await?;
+
+        if summary.records_archived > 0 {
+            info!(
+                "Archived {} records into {} monthly archives across {} symbols",
+                summary.records_archived, summary.months, summary.symbols
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::domain::{DataSource, EquityLive, StockRepository};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use std::io::Read;
+use tracing::warn;
+
+/// One CSV row, in the same `timestamp,price,volume` layout the history export writes
+#[derive(Debug, Deserialize)]
+struct BackfillRow {
+    /// RFC 3339, or `YYYY-MM-DD` for midnight UTC
+    timestamp: String,
+    price: f64,
+    /// Empty when unknown
+    volume: Option<i64>,
+}
+
+/// Outcome of a backfill import
+#[derive(Debug, Clone, Default)]
+pub struct BackfillSummary {
+    pub imported: usize,
+    /// Rows that failed to parse or carried an invalid timestamp or price
+    pub skipped: usize,
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })
+}
+
+/// Import a symbol's historical points from CSV with a `timestamp,price,volume` header, storing
+/// each as backfilled live data. Malformed rows are skipped and counted rather than failing the
+/// import. Each point's change is taken from the previous point in time order.
+pub async fn import_history_csv<R: Read>(
+    repository: &(dyn StockRepository + Send + Sync),
+    symbol: &str,
+    reader: R,
+) -> Result<BackfillSummary> {
+    let symbol = symbol.trim().to_uppercase();
+    let mut summary = BackfillSummary::default();
+    let mut points: Vec<(DateTime<Utc>, f64, i64)> = Vec::new();
+
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    for (index, row) in csv.deserialize::<BackfillRow>().enumerate() {
+        // Line 1 is the header
+        let line = index + 2;
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                warn!("Skipping line {}: {}", line, e);
+                summary.skipped += 1;
+                continue;
+            }
+        };
+        let Some(timestamp) = parse_timestamp(&row.timestamp) else {
+            warn!(
+                "Skipping line {}: invalid timestamp {:?}",
+                line, row.timestamp
+            );
+            summary.skipped += 1;
+            continue;
+        };
+        if !row.price.is_finite() || row.price <= 0.0 {
+            warn!("Skipping line {}: invalid price {}", line, row.price);
+            summary.skipped += 1;
+            continue;
+        }
+        points.push((timestamp, row.price, row.volume.unwrap_or(0).max(0)));
+    }
+
+    points.sort_by_key(|(timestamp, _, _)| *timestamp);
+    let mut previous_price: Option<f64> = None;
+    for (timestamp, price, volume) in points {
+        let data = EquityLive {
+            change: previous_price.map_or(0.0, |previous| price - previous),
+            name: symbol.clone(),
+            price,
+            volume,
+            source: DataSource::Backfill,
+        };
+        repository
+            .store_live_data(&symbol, &data, timestamp)
+            .await?;
+        previous_price = Some(price);
+        summary.imported += 1;
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::TempDb;
+    use crate::infrastructure::RocksDbStockRepository;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn malformed_rows_are_skipped_and_the_rest_imported_in_time_order() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        let csv = "timestamp,price,volume\n\
+                   2024-03-05T10:00:00Z,1.6,200\n\
+                   2024-03-04,1.5,\n\
+                   not-a-date,1.7,100\n\
+                   2024-03-06T10:00:00Z,-1,100\n\
+                   2024-03-07T10:00:00Z,abc,100\n";
+
+        let summary = import_history_csv(&repository, "mtngh", csv.as_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.skipped, 3);
+        let history = repository
+            .get_historical_data(
+                "MTNGH",
+                Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 3, 31, 0, 0, 0).unwrap(),
+            )
+            .await
+            .unwrap();
+        let points: Vec<(f64, Option<i64>, DataSource)> = history
+            .iter()
+            .map(|point| (point.value, point.volume, point.source))
+            .collect();
+        assert_eq!(
+            points,
+            [
+                (1.5, Some(0), DataSource::Backfill),
+                (1.6, Some(200), DataSource::Backfill),
+            ]
+        );
+        let latest = repository
+            .get_latest_live_data("MTNGH")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!((latest.change - 0.1).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::domain::{DeliveryStatus, StockRepository, WebhookDelivery, WebhookSender};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Configuration for re-attempting webhook deliveries that failed their immediate retries
+#[derive(Debug, Clone)]
+pub struct DeliveryConfig {
+    /// Total delivery attempts (each including the sender's own immediate retries) before giving up
+    pub max_attempts: u32,
+    /// Wait before the first re-attempt (in seconds); doubles after each further failure
+    pub retry_backoff: i64,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            retry_backoff: 60,
+        }
+    }
+}
+
+/// Sends webhooks, persisting failed deliveries so later worker cycles can retry them
+pub struct DeliveryQueue {
+    sender: Arc<dyn WebhookSender + Send + Sync>,
+    repository: Arc<dyn StockRepository + Send + Sync>,
+    config: DeliveryConfig,
+}
+
+impl DeliveryQueue {
+    pub fn new(
+        sender: Arc<dyn WebhookSender + Send + Sync>,
+        repository: Arc<dyn StockRepository + Send + Sync>,
+        config: DeliveryConfig,
+    ) -> Self {
+        Self {
+            sender,
+            repository,
+            config,
+        }
+    }
+
+    /// Deliver a payload now, queueing it for a later retry if that fails
+    pub async fn deliver(&self, url: &str, payload: serde_json::Value) -> Result<()> {
+        let mut delivery = WebhookDelivery {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            payload,
+            attempts: 0,
+            status: DeliveryStatus::Pending,
+            next_retry_at: Utc::now(),
+            last_error: None,
+            created_at: Utc::now(),
+        };
+
+        if self.attempt(&mut delivery).await {
+            return Ok(());
+        }
+        self.repository.store_delivery(&delivery).await
+    }
+
+    /// Re-attempt queued deliveries whose retry time has come
+    pub async fn retry_due(&self) -> Result<()> {
+        let now = Utc::now();
+        let due: Vec<WebhookDelivery> = self
+            .repository
+            .get_deliveries()
+            .await?
+            .into_iter()
+            .filter(|d| d.status == DeliveryStatus::Pending && d.next_retry_at <= now)
+            .collect();
+
+        for mut delivery in due {
+            if self.attempt(&mut delivery).await {
+                info!(
+                    "Delivered queued webhook {} to {} after {} attempts",
+                    delivery.id, delivery.url, delivery.attempts
+                );
+                self.repository.delete_delivery(&delivery.id).await?;
+            } else {
+                self.repository.store_delivery(&delivery).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Try one delivery, updating its bookkeeping on failure; returns whether it was delivered
+    async fn attempt(&self, delivery: &mut WebhookDelivery) -> bool {
+        delivery.attempts += 1;
+
+        match self.sender.send(&delivery.url, &delivery.payload).await {
+            Ok(()) => true,
+            Err(e) => {
+                delivery.last_error = Some(e.to_string());
+                if delivery.attempts >= self.config.max_attempts {
+                    delivery.status = DeliveryStatus::Failed;
+                    warn!(
+                        "Giving up on webhook {} to {} after {} attempts: {}",
+                        delivery.id, delivery.url, delivery.attempts, e
+                    );
+                } else {
+                    delivery.next_retry_at = self.next_retry_at(delivery.attempts);
+                    warn!(
+                        "Webhook {} to {} failed (attempt {}), retrying at {}: {}",
+                        delivery.id, delivery.url, delivery.attempts, delivery.next_retry_at, e
+                    );
+                }
+                false
+            }
+        }
+    }
+
+    fn next_retry_at(&self, attempts: u32) -> DateTime<Utc> {
+        let backoff = self.config.retry_backoff * 2i64.pow(attempts.saturating_sub(1).min(16));
+        Utc::now() + chrono::Duration::seconds(backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::TempDb;
+    use crate::infrastructure::RocksDbStockRepository;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Sender failing its first `failures` sends and succeeding afterwards
+    struct FlakySender {
+        failures: usize,
+        sends: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl WebhookSender for FlakySender {
+        async fn send(&self, _url: &str, _payload: &serde_json::Value) -> Result<()> {
+            if self.sends.fetch_add(1, Ordering::SeqCst) < self.failures {
+                anyhow::bail!("connection refused");
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn a_failed_delivery_is_queued_and_retried_on_the_next_cycle() {
+        let temp = TempDb::new();
+        let repository = Arc::new(RocksDbStockRepository::new(temp.db.clone()));
+        let sender = Arc::new(FlakySender {
+            failures: 1,
+            sends: AtomicUsize::new(0),
+        });
+        let queue = DeliveryQueue::new(
+            sender.clone(),
+            repository.clone(),
+            DeliveryConfig {
+                max_attempts: 5,
+                retry_backoff: 0,
+            },
+        );
+
+        queue
+            .deliver(
+                "http://localhost/hook",
+                serde_json::json!({ "event": "alert" }),
+            )
+            .await
+            .unwrap();
+        let queued = repository.get_deliveries().await.unwrap();
+        queue.retry_due().await.unwrap();
+
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].attempts, 1);
+        assert_eq!(queued[0].status, DeliveryStatus::Pending);
+        assert_eq!(queued[0].last_error.as_deref(), Some("connection refused"));
+        assert_eq!(sender.sends.load(Ordering::SeqCst), 2);
+        assert!(repository.get_deliveries().await.unwrap().is_empty());
+    }
+}
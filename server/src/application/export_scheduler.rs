@@ -0,0 +1,166 @@
+use crate::domain::DataExporter;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, warn};
+
+const EXPORT_FILE_PREFIX: &str = "gse-export-";
+const EXPORT_FILE_SUFFIX: &str = ".jsonl";
+
+/// Configuration for periodic JSONL exports
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    /// Interval between exports (in seconds)
+    pub interval: u64,
+    /// Directory the export files are written to
+    pub directory: PathBuf,
+    /// Number of most recent exports to keep; older ones are deleted
+    pub keep: usize,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            interval: 86400, // 1 day
+            directory: PathBuf::from("./data/exports"),
+            keep: 7,
+        }
+    }
+}
+
+/// Background task writing a full JSONL export on an interval and rotating old ones
+pub struct ExportScheduler {
+    exporter: Arc<dyn DataExporter + Send + Sync>,
+    config: ExportConfig,
+}
+
+impl ExportScheduler {
+    pub fn new(exporter: Arc<dyn DataExporter + Send + Sync>, config: ExportConfig) -> Self {
+        Self { exporter, config }
+    }
+
+    /// Start exporting with the configured interval
+    pub async fn start(&self) -> Result<()> {
+        info!(
+            "Starting export scheduler with interval: {} seconds, writing to {}",
+            self.config.interval,
+            self.config.directory.display()
+        );
+
+        let mut interval_timer = interval(Duration::from_secs(self.config.interval));
+        // The first tick completes immediately; wait a full interval before the first export
+        interval_timer.tick().await;
+
+        loop {
+            interval_timer.tick().await;
+
+            if let Err(e) = self.run_export().await {
+                error!("Scheduled export failed: {}", e);
+            }
+        }
+    }
+
+    /// Write one export, then delete exports beyond the configured count
+    pub async fn run_export(&self) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.config.directory)
+            .context("Failed to create export directory")?;
+
+        let file_name = format!(
+            "{}{}{}",
+            EXPORT_FILE_PREFIX,
+            Utc::now().format("%Y%m%dT%H%M%SZ"),
+            EXPORT_FILE_SUFFIX
+        );
+        let path = self.config.directory.join(file_name);
+        // Write under a temporary name so a half-written file is never mistaken for an export
+        let partial = path.with_extension("jsonl.partial");
+
+        let records = self.exporter.export_jsonl(&partial).await?;
+        std::fs::rename(&partial, &path).context("Failed to finalise export file")?;
+        info!("Exported {} records to {}", records, path.display());
+
+        self.rotate()?;
+        Ok(path)
+    }
+
+    fn rotate(&self) -> Result<()> {
+        let mut exports: Vec<PathBuf> = std::fs::read_dir(&self.config.directory)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(EXPORT_FILE_PREFIX) && name.ends_with(EXPORT_FILE_SUFFIX)
+                    })
+            })
+            .collect();
+        // Timestamped names sort oldest first
+        exports.sort();
+
+        let excess = exports.len().saturating_sub(self.config.keep);
+        for path in exports.into_iter().take(excess) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!("Failed to remove old export {}: {}", path.display(), e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::StockRepository;
+    use crate::infrastructure::test_support::{live, temp_path, TempDb};
+    use crate::infrastructure::RocksDbStockRepository;
+
+    fn exports_in(directory: &std::path::Path) -> Vec<PathBuf> {
+        std::fs::read_dir(directory)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn a_short_interval_produces_an_export_file() {
+        let temp = TempDb::new();
+        let repository = Arc::new(RocksDbStockRepository::new(temp.db.clone()));
+        repository
+            .store_live_data("MTNGH", &live("MTNGH", 1.5, 0.1), Utc::now())
+            .await
+            .unwrap();
+        let directory = temp_path("exports");
+        let scheduler = ExportScheduler::new(
+            repository,
+            ExportConfig {
+                interval: 1,
+                directory: directory.clone(),
+                keep: 2,
+            },
+        );
+
+        let task = tokio::spawn(async move { scheduler.start().await });
+        let mut exports = Vec::new();
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            exports = exports_in(&directory);
+            if !exports.is_empty() {
+                break;
+            }
+        }
+        task.abort();
+
+        assert_eq!(exports.len(), 1);
+        let contents = std::fs::read_to_string(&exports[0]).unwrap();
+        assert!(contents.contains("MTNGH"));
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
@@ -1,6 +1,23 @@
+pub mod alerts;
+pub mod archive_scheduler;
+pub mod backfill;
+pub mod delivery_queue;
+pub mod export_scheduler;
 pub mod portfolio;
+pub mod recently_requested;
+pub mod response_cache;
 pub mod use_cases;
+pub mod watchlist;
 pub mod worker;
+pub mod worker_status;
 
+pub use alerts::*;
+pub use archive_scheduler::*;
+pub use delivery_queue::*;
+pub use export_scheduler::*;
 pub use portfolio::*;
+pub use recently_requested::*;
+pub use response_cache::*;
 pub use use_cases::*;
+pub use watchlist::*;
+pub use worker_status::*;
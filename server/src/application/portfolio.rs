@@ -1,18 +1,249 @@
-use crate::domain::{Portfolio, PortfolioRepository, Transaction};
+use crate::domain::analytics::{
+    self,
+    risk::{beta, dated_log_returns, historical_var, weighted_returns},
+};
+use crate::domain::{
+    CostBasisMethod, DividendIncome, DividendRecord, FxRateProvider, MarketCalendar, Portfolio,
+    PortfolioRepository, PortfolioSnapshot, PortfolioValuePoint, StockRepository, Transaction,
+    TransactionError, TransactionType, PRICE_CURRENCY,
+};
 use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+/// Filter and pagination options for listing a portfolio's transactions
+#[derive(Debug, Clone)]
+pub struct TransactionQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub symbol: Option<String>,
+    /// 1-based page number
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// A dividend to record against a portfolio
+#[derive(Debug, Clone)]
+pub struct NewDividend {
+    pub symbol: String,
+    /// Dividend per share in GHS; defaults to the symbol's latest stored dividend per share
+    pub amount_per_share: Option<f64>,
+    pub ex_date: NaiveDate,
+    pub pay_date: NaiveDate,
+}
+
+/// Every recorded dividend's income to a portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioDividends {
+    pub portfolio_id: String,
+    pub total_income: f64,
+    pub dividends: Vec<DividendIncome>,
+    /// Currency of all amounts, the portfolio's base currency
+    pub currency: String,
+}
+
+/// Corrected fields of an existing transaction
+#[derive(Debug, Clone)]
+pub struct TransactionEdit {
+    pub symbol: String,
+    pub transaction_type: TransactionType,
+    pub quantity: i64,
+    pub price_per_share: f64,
+    pub fee: f64,
+    /// Keeps the original timestamp when `None`
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// A single page of a portfolio's transactions
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionPage {
+    pub transactions: Vec<Transaction>,
+    pub page: usize,
+    pub page_size: usize,
+    pub total: usize,
+    pub total_pages: usize,
+}
+
+/// Which stored price holdings are valued against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    /// Latest live price
+    Live,
+    /// Last price recorded in the most recent completed trading session
+    Close,
+}
+
+/// A single holding valued at the current price, in the portfolio's base currency
+#[derive(Debug, Clone, Serialize)]
+pub struct HoldingValuation {
+    pub symbol: String,
+    pub quantity: i64,
+    pub average_buy_price: f64,
+    /// `None` when no price is stored for the symbol
+    pub price: Option<f64>,
+    pub market_value: Option<f64>,
+    pub cost_basis: f64,
+    pub unrealized_gain: Option<f64>,
+    /// Set when the holding has no price and is valued at cost in the totals
+    pub unpriced: bool,
+}
+
+/// A portfolio valued at the current prices
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioValuation {
+    pub portfolio_id: String,
+    pub price_source: PriceSource,
+    /// Holdings valued at the selected price, or at cost when no price is known
+    pub market_value: f64,
+    pub cost_basis: f64,
+    /// Unrealized gain of the priced holdings
+    pub unrealized_gain: f64,
+    /// Gain realized by past sells against the average cost of earlier buys, before fees
+    pub realized_gain: f64,
+    /// Income from every recorded dividend on the shares held going into its ex-date
+    pub dividend_income: f64,
+    pub holdings: Vec<HoldingValuation>,
+    /// Currency of all amounts, the portfolio's base currency
+    pub currency: String,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// A portfolio rebuilt as it stood at the end of a past day and valued at that day's closes
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioAsOf {
+    pub portfolio_id: String,
+    pub date: NaiveDate,
+    /// Transactions made up to the end of `date`
+    pub transaction_count: usize,
+    /// Holdings valued at their last stored price on or before `date`, or at cost when none
+    pub market_value: f64,
+    pub cost_basis: f64,
+    pub unrealized_gain: f64,
+    /// Gain realized by sells up to `date`, before fees
+    pub realized_gain: f64,
+    pub holdings: Vec<HoldingValuation>,
+    /// Currency of all amounts, the portfolio's base currency converted at today's rate
+    pub currency: String,
+}
+
+/// How far back an as-of valuation looks for a symbol's last price before the as-of date
+const AS_OF_PRICE_LOOKBACK_DAYS: i64 = 30;
+
+/// Which price a valuation uses for each holding
+#[derive(Debug, Clone, Copy)]
+enum Pricing {
+    Current(PriceSource),
+    /// Last stored price at or before the given time
+    AsOf(DateTime<Utc>),
+}
+
+/// A holding's annual dividend income measured against what was paid for it
+#[derive(Debug, Clone, Serialize)]
+pub struct HoldingYieldOnCost {
+    pub symbol: String,
+    pub quantity: i64,
+    pub cost_basis: f64,
+    /// Latest stored dividend per share, `None` when the symbol has no dividend data
+    pub dividend_per_share: Option<f64>,
+    pub annual_income: f64,
+    /// Annual income as a percent of cost basis
+    pub yield_on_cost: f64,
+}
+
+/// Yield on cost of every open holding in a portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioYieldOnCost {
+    pub portfolio_id: String,
+    pub cost_basis: f64,
+    pub annual_income: f64,
+    /// Annual income of all holdings as a percent of their combined cost basis
+    pub yield_on_cost: f64,
+    pub holdings: Vec<HoldingYieldOnCost>,
+    /// Currency of all amounts, the portfolio's base currency
+    pub currency: String,
+}
+
+/// Fewest daily returns shared with the index for a holding's beta to count
+const MIN_RISK_RETURNS: usize = 10;
+
+/// A holding's contribution to portfolio risk
+#[derive(Debug, Clone, Serialize)]
+pub struct HoldingRisk {
+    pub symbol: String,
+    /// Share of the covered holdings' market value
+    pub weight: f64,
+    pub beta: f64,
+}
+
+/// Beta and value at risk of a portfolio over a lookback window
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioRisk {
+    pub portfolio_id: String,
+    pub lookback_days: i64,
+    /// Value-weighted beta of the covered holdings against the composite index
+    pub beta: Option<f64>,
+    pub confidence: f64,
+    /// One-day historical value at risk as a fraction of the covered value
+    pub value_at_risk: Option<f64>,
+    /// One-day value at risk in the portfolio's base currency
+    pub value_at_risk_amount: Option<f64>,
+    pub currency: String,
+    pub holdings: Vec<HoldingRisk>,
+    /// Holdings left out for lack of price history
+    pub excluded_symbols: Vec<String>,
+    /// Share of the portfolio's market value included in the estimates
+    pub coverage: f64,
+}
+
 pub struct PortfolioUseCase {
     repository: Arc<dyn PortfolioRepository + Send + Sync>,
+    /// Source of the latest prices used to value holdings
+    stock_repository: Arc<dyn StockRepository + Send + Sync>,
+    /// Converts GHS valuations into each portfolio's base currency
+    fx_rates: Arc<dyn FxRateProvider + Send + Sync>,
+    /// Decides the default price source and the session closing prices are taken from
+    market_calendar: MarketCalendar,
 }
 
 impl PortfolioUseCase {
-    pub fn new(repository: Arc<dyn PortfolioRepository + Send + Sync>) -> Self {
-        Self { repository }
+    pub fn new(
+        repository: Arc<dyn PortfolioRepository + Send + Sync>,
+        stock_repository: Arc<dyn StockRepository + Send + Sync>,
+        fx_rates: Arc<dyn FxRateProvider + Send + Sync>,
+        market_calendar: MarketCalendar,
+    ) -> Self {
+        Self {
+            repository,
+            stock_repository,
+            fx_rates,
+            market_calendar,
+        }
     }
 
-    pub async fn create_portfolio(&self, name: String) -> Result<Portfolio> {
-        let portfolio = Portfolio::new(name);
+    /// Whether portfolios can be valued in the given currency
+    pub fn supports_currency(&self, currency: &str) -> bool {
+        self.fx_rates.supports(currency)
+    }
+
+    /// Create a portfolio valued in `base_currency`, or in GHS when none is given, that matches
+    /// sells against buys by `cost_basis_method`
+    pub async fn create_portfolio(
+        &self,
+        name: String,
+        base_currency: Option<String>,
+        cost_basis_method: CostBasisMethod,
+    ) -> Result<Portfolio> {
+        let base_currency = base_currency
+            .map(|currency| currency.trim().to_uppercase())
+            .unwrap_or_else(|| PRICE_CURRENCY.to_string());
+        if !self.supports_currency(&base_currency) {
+            anyhow::bail!("Unsupported base currency: {}", base_currency);
+        }
+
+        let portfolio = Portfolio::new(name, base_currency, cost_basis_method);
         self.repository.create_portfolio(&portfolio).await?;
         Ok(portfolio)
     }
@@ -36,13 +267,1078 @@ impl PortfolioUseCase {
             .await?
             .ok_or_else(|| anyhow::anyhow!("Portfolio not found"))?;
 
-        portfolio.add_transaction(transaction);
+        portfolio.add_transaction(transaction)?;
+        self.repository.update_portfolio(&portfolio).await?;
+
+        Ok(portfolio)
+    }
+
+    /// Record a dividend against a portfolio, taking the amount from the symbol's latest stored
+    /// dividend per share when none is given
+    pub async fn record_dividend(
+        &self,
+        portfolio_id: &str,
+        dividend: NewDividend,
+    ) -> Result<Portfolio> {
+        let mut portfolio = self
+            .repository
+            .get_portfolio(portfolio_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Portfolio not found"))?;
+
+        let symbol = dividend.symbol.to_uppercase();
+        let amount_per_share = match dividend.amount_per_share {
+            Some(amount) => amount,
+            None => self
+                .stock_repository
+                .get_latest_equity_data(&symbol)
+                .await?
+                .and_then(|equity| equity.dps)
+                .ok_or_else(|| TransactionError::UnknownDividendAmount {
+                    symbol: symbol.clone(),
+                })?,
+        };
+
+        portfolio.add_dividend(DividendRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            symbol,
+            amount_per_share,
+            ex_date: dividend.ex_date,
+            pay_date: dividend.pay_date,
+        });
+        self.repository.update_portfolio(&portfolio).await?;
+
+        Ok(portfolio)
+    }
+
+    /// Get the income from each of a portfolio's recorded dividends, or `None` if the portfolio
+    /// doesn't exist
+    pub async fn get_dividends(&self, id: &str) -> Result<Option<PortfolioDividends>> {
+        let portfolio = match self.repository.get_portfolio(id).await? {
+            Some(portfolio) => portfolio,
+            None => return Ok(None),
+        };
+
+        let rate = self
+            .fx_rates
+            .rate(PRICE_CURRENCY, &portfolio.base_currency)
+            .await?;
+        let dividends: Vec<DividendIncome> = portfolio
+            .dividend_income()
+            .into_iter()
+            .map(|d| DividendIncome {
+                amount_per_share: d.amount_per_share * rate,
+                income: d.income * rate,
+                ..d
+            })
+            .collect();
+
+        Ok(Some(PortfolioDividends {
+            portfolio_id: portfolio.id,
+            total_income: dividends.iter().map(|d| d.income).sum(),
+            dividends,
+            currency: portfolio.base_currency,
+        }))
+    }
+
+    /// Correct a transaction, recomputing the portfolio's holdings from its full log
+    pub async fn update_transaction(
+        &self,
+        portfolio_id: &str,
+        transaction_id: &str,
+        edit: TransactionEdit,
+    ) -> Result<Portfolio> {
+        let mut portfolio = self
+            .repository
+            .get_portfolio(portfolio_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Portfolio not found"))?;
+
+        let original = portfolio
+            .transactions
+            .iter()
+            .find(|t| t.id == transaction_id)
+            .ok_or_else(|| TransactionError::UnknownTransaction {
+                id: transaction_id.to_string(),
+            })?;
+        let transaction = Transaction {
+            id: original.id.clone(),
+            symbol: edit.symbol,
+            transaction_type: edit.transaction_type,
+            quantity: edit.quantity,
+            price_per_share: edit.price_per_share,
+            fee: edit.fee,
+            timestamp: edit.timestamp.unwrap_or(original.timestamp),
+        };
+        portfolio.replace_transaction(transaction_id, transaction)?;
+        self.repository.update_portfolio(&portfolio).await?;
+
+        Ok(portfolio)
+    }
+
+    /// Delete a transaction, recomputing the portfolio's holdings from what remains
+    pub async fn delete_transaction(
+        &self,
+        portfolio_id: &str,
+        transaction_id: &str,
+    ) -> Result<Portfolio> {
+        let mut portfolio = self
+            .repository
+            .get_portfolio(portfolio_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Portfolio not found"))?;
+
+        portfolio.remove_transaction(transaction_id)?;
         self.repository.update_portfolio(&portfolio).await?;
 
         Ok(portfolio)
     }
 
+    /// List a portfolio's transactions oldest first, filtered and paginated
+    pub async fn list_transactions(
+        &self,
+        portfolio_id: &str,
+        query: &TransactionQuery,
+    ) -> Result<Option<TransactionPage>> {
+        let portfolio = match self.repository.get_portfolio(portfolio_id).await? {
+            Some(portfolio) => portfolio,
+            None => return Ok(None),
+        };
+
+        let mut transactions: Vec<Transaction> = portfolio
+            .transactions
+            .into_iter()
+            .filter(|t| query.from.is_none_or(|from| t.timestamp >= from))
+            .filter(|t| query.to.is_none_or(|to| t.timestamp <= to))
+            .filter(|t| {
+                query
+                    .symbol
+                    .as_ref()
+                    .is_none_or(|symbol| t.symbol.eq_ignore_ascii_case(symbol))
+            })
+            .collect();
+        transactions.sort_by_key(|t| t.timestamp);
+
+        let total = transactions.len();
+        let total_pages = total.div_ceil(query.page_size);
+        let transactions = transactions
+            .into_iter()
+            .skip(query.page.saturating_sub(1).saturating_mul(query.page_size))
+            .take(query.page_size)
+            .collect();
+
+        Ok(Some(TransactionPage {
+            transactions,
+            page: query.page,
+            page_size: query.page_size,
+            total,
+            total_pages,
+        }))
+    }
+
+    /// Value each portfolio at the latest stored prices and store it as the snapshot for the
+    /// most recent session to have closed by `now`, skipping portfolios that already have one.
+    /// Returns how many snapshots were stored.
+    pub async fn snapshot_missing_portfolios(&self, now: DateTime<Utc>) -> Result<usize> {
+        let Some(date) = self.market_calendar.last_completed_trading_day(now) else {
+            return Ok(0);
+        };
+        let mut stored = 0;
+
+        for portfolio in self.repository.get_all_portfolios().await? {
+            if self
+                .repository
+                .get_last_snapshot_date(&portfolio.id)
+                .await?
+                >= Some(date)
+            {
+                continue;
+            }
+            let snapshot = self.valuation_snapshot(&portfolio, date).await?;
+            self.repository.store_snapshot(&snapshot).await?;
+            stored += 1;
+        }
+
+        Ok(stored)
+    }
+
+    async fn valuation_snapshot(
+        &self,
+        portfolio: &Portfolio,
+        date: NaiveDate,
+    ) -> Result<PortfolioSnapshot> {
+        let holdings = self
+            .value_each_holding(portfolio, Pricing::Current(PriceSource::Live))
+            .await?;
+        let (market_value, cost_basis) = totals(&holdings);
+
+        Ok(PortfolioSnapshot {
+            portfolio_id: portfolio.id.clone(),
+            date,
+            market_value,
+            cost_basis,
+            currency: portfolio.base_currency.clone(),
+            computed_at: Utc::now(),
+        })
+    }
+
+    /// Value every portfolio at the latest stored prices and append it to its value time series
+    pub async fn record_all_values(&self, timestamp: DateTime<Utc>) -> Result<usize> {
+        let portfolios = self.repository.get_all_portfolios().await?;
+
+        for portfolio in &portfolios {
+            let holdings = self
+                .value_each_holding(portfolio, Pricing::Current(PriceSource::Live))
+                .await?;
+            let (market_value, cost_basis) = totals(&holdings);
+            let point = PortfolioValuePoint {
+                timestamp,
+                market_value,
+                cost_basis,
+                holdings: holdings.len(),
+                currency: portfolio.base_currency.clone(),
+            };
+            self.repository
+                .store_value_point(&portfolio.id, &point)
+                .await?;
+        }
+
+        Ok(portfolios.len())
+    }
+
+    /// Value a portfolio now, or `None` if it doesn't exist.
+    ///
+    /// Without an explicit source, live prices are used while the market is trading and the
+    /// last session's closing prices otherwise.
+    pub async fn get_valuation(
+        &self,
+        id: &str,
+        price_source: Option<PriceSource>,
+    ) -> Result<Option<PortfolioValuation>> {
+        let portfolio = match self.repository.get_portfolio(id).await? {
+            Some(portfolio) => portfolio,
+            None => return Ok(None),
+        };
+
+        let now = Utc::now();
+        let price_source = price_source.unwrap_or(if self.market_calendar.is_trading_time(now) {
+            PriceSource::Live
+        } else {
+            PriceSource::Close
+        });
+        let holdings = self
+            .value_each_holding(&portfolio, Pricing::Current(price_source))
+            .await?;
+        let (market_value, cost_basis) = totals(&holdings);
+        let rate = self
+            .fx_rates
+            .rate(PRICE_CURRENCY, &portfolio.base_currency)
+            .await?;
+
+        Ok(Some(PortfolioValuation {
+            portfolio_id: portfolio.id.clone(),
+            price_source,
+            market_value,
+            cost_basis,
+            unrealized_gain: holdings.iter().filter_map(|h| h.unrealized_gain).sum(),
+            realized_gain: portfolio.realized_gains().values().sum::<f64>() * rate,
+            dividend_income: portfolio
+                .dividend_income()
+                .iter()
+                .map(|d| d.income)
+                .sum::<f64>()
+                * rate,
+            holdings,
+            currency: portfolio.base_currency,
+            computed_at: now,
+        }))
+    }
+
+    /// Rebuild a portfolio as it stood at the end of `date` and value it at that day's closes,
+    /// or `None` if the portfolio doesn't exist
+    pub async fn get_as_of(&self, id: &str, date: NaiveDate) -> Result<Option<PortfolioAsOf>> {
+        let portfolio = match self.repository.get_portfolio(id).await? {
+            Some(portfolio) => portfolio,
+            None => return Ok(None),
+        };
+
+        let cutoff = date.and_hms_opt(23, 59, 59).unwrap().and_utc();
+        let portfolio = portfolio.as_of(cutoff);
+        let holdings = self
+            .value_each_holding(&portfolio, Pricing::AsOf(cutoff))
+            .await?;
+        let (market_value, cost_basis) = totals(&holdings);
+        let rate = self
+            .fx_rates
+            .rate(PRICE_CURRENCY, &portfolio.base_currency)
+            .await?;
+
+        Ok(Some(PortfolioAsOf {
+            portfolio_id: portfolio.id.clone(),
+            date,
+            transaction_count: portfolio.transactions.len(),
+            market_value,
+            cost_basis,
+            unrealized_gain: holdings.iter().filter_map(|h| h.unrealized_gain).sum(),
+            realized_gain: portfolio.realized_gains().values().sum::<f64>() * rate,
+            holdings,
+            currency: portfolio.base_currency,
+        }))
+    }
+
+    /// Value every holding at the price chosen by `pricing`, in the portfolio's base currency
+    async fn value_each_holding(
+        &self,
+        portfolio: &Portfolio,
+        pricing: Pricing,
+    ) -> Result<Vec<HoldingValuation>> {
+        let rate = self
+            .fx_rates
+            .rate(PRICE_CURRENCY, &portfolio.base_currency)
+            .await?;
+        let mut holdings = Vec::with_capacity(portfolio.items.len());
+
+        for item in &portfolio.items {
+            let symbol = item.symbol.to_uppercase();
+            let price = match pricing {
+                Pricing::Current(price_source) => self.price(&symbol, price_source).await?,
+                Pricing::AsOf(cutoff) => self.price_as_of(&symbol, cutoff).await?,
+            }
+            .map(|price| price * rate);
+            let quantity = item.quantity as f64;
+            let cost_basis = quantity * item.average_buy_price * rate;
+            let market_value = price.map(|price| quantity * price);
+            holdings.push(HoldingValuation {
+                symbol: item.symbol.clone(),
+                quantity: item.quantity,
+                average_buy_price: item.average_buy_price * rate,
+                price,
+                market_value,
+                cost_basis,
+                unrealized_gain: market_value.map(|value| value - cost_basis),
+                unpriced: price.is_none(),
+            });
+        }
+
+        Ok(holdings)
+    }
+
+    /// Stored GHS price of a symbol from the given source.
+    ///
+    /// A symbol that didn't trade during the last session falls back to its latest live price.
+    async fn price(&self, symbol: &str, price_source: PriceSource) -> Result<Option<f64>> {
+        if price_source == PriceSource::Close {
+            if let Some((open, close)) = self.market_calendar.last_completed_session(Utc::now()) {
+                let session = self
+                    .stock_repository
+                    .get_intraday_data(symbol, open, close)
+                    .await?;
+                if let Some(last) = session.last() {
+                    return Ok(Some(last.value));
+                }
+            }
+        }
+
+        Ok(self
+            .stock_repository
+            .get_latest_live_data(symbol)
+            .await?
+            .map(|live| live.price))
+    }
+
+    /// Last stored GHS price of a symbol at or before `cutoff`, looking back a limited window
+    async fn price_as_of(&self, symbol: &str, cutoff: DateTime<Utc>) -> Result<Option<f64>> {
+        let from = cutoff - chrono::Duration::days(AS_OF_PRICE_LOOKBACK_DAYS);
+        let history = self
+            .stock_repository
+            .get_historical_data(symbol, from, cutoff)
+            .await?;
+        Ok(history
+            .iter()
+            .max_by_key(|point| point.timestamp)
+            .map(|point| point.value))
+    }
+
+    /// Estimate a portfolio's beta and one-day value at risk from the last `days` of daily
+    /// closes, or `None` if the portfolio doesn't exist.
+    ///
+    /// Holdings with too little history overlapping the composite index are excluded and
+    /// reported, and the estimates cover only the remaining value.
+    pub async fn get_risk(
+        &self,
+        id: &str,
+        days: i64,
+        confidence: f64,
+    ) -> Result<Option<PortfolioRisk>> {
+        let portfolio = match self.repository.get_portfolio(id).await? {
+            Some(portfolio) => portfolio,
+            None => return Ok(None),
+        };
+
+        let to = Utc::now();
+        let from = to - chrono::Duration::days(days);
+        let index_returns = dated_log_returns(&analytics::daily_closes(
+            &self.stock_repository.get_index_history(from, to).await?,
+        ));
+
+        let mut covered = Vec::new();
+        let mut excluded_symbols = Vec::new();
+        let mut total_value = 0.0;
+        for item in portfolio.items.iter().filter(|item| item.quantity > 0) {
+            let symbol = item.symbol.to_uppercase();
+            let price = self
+                .price(&symbol, PriceSource::Live)
+                .await?
+                .unwrap_or(item.average_buy_price);
+            let value = item.quantity as f64 * price;
+            total_value += value;
+
+            let history = self
+                .stock_repository
+                .get_historical_data(&symbol, from, to)
+                .await?;
+            let returns = dated_log_returns(&analytics::daily_closes(&history));
+            match beta(&returns, &index_returns, MIN_RISK_RETURNS) {
+                Some(beta) => covered.push((symbol, value, beta, returns)),
+                None => excluded_symbols.push(symbol),
+            }
+        }
+
+        let covered_value: f64 = covered.iter().map(|(_, value, _, _)| value).sum();
+        let holdings: Vec<HoldingRisk> = covered
+            .iter()
+            .filter(|_| covered_value > 0.0)
+            .map(|(symbol, value, beta, _)| HoldingRisk {
+                symbol: symbol.clone(),
+                weight: value / covered_value,
+                beta: *beta,
+            })
+            .collect();
+
+        let portfolio_beta =
+            (!holdings.is_empty()).then(|| holdings.iter().map(|h| h.weight * h.beta).sum());
+        let weighted: Vec<(f64, &BTreeMap<NaiveDate, f64>)> = holdings
+            .iter()
+            .zip(&covered)
+            .map(|(holding, (_, _, _, returns))| (holding.weight, returns))
+            .collect();
+        let value_at_risk = historical_var(&weighted_returns(&weighted), confidence);
+
+        let rate = self
+            .fx_rates
+            .rate(PRICE_CURRENCY, &portfolio.base_currency)
+            .await?;
+
+        Ok(Some(PortfolioRisk {
+            portfolio_id: portfolio.id,
+            lookback_days: days,
+            beta: portfolio_beta,
+            confidence,
+            value_at_risk,
+            value_at_risk_amount: value_at_risk.map(|var| var * covered_value * rate),
+            currency: portfolio.base_currency,
+            holdings,
+            excluded_symbols,
+            coverage: if total_value > 0.0 {
+                covered_value / total_value
+            } else {
+                0.0
+            },
+        }))
+    }
+
+    /// Get each open holding's annual dividend income as a percent of its cost basis, or `None`
+    /// if the portfolio doesn't exist.
+    ///
+    /// Income is the latest stored dividend per share times the quantity held. Holdings without
+    /// dividend data report zero income.
+    pub async fn get_yield_on_cost(&self, id: &str) -> Result<Option<PortfolioYieldOnCost>> {
+        let portfolio = match self.repository.get_portfolio(id).await? {
+            Some(portfolio) => portfolio,
+            None => return Ok(None),
+        };
+
+        let rate = self
+            .fx_rates
+            .rate(PRICE_CURRENCY, &portfolio.base_currency)
+            .await?;
+        let mut holdings = Vec::new();
+        for item in portfolio.items.iter().filter(|item| item.quantity > 0) {
+            let dividend_per_share = self
+                .stock_repository
+                .get_latest_equity_data(&item.symbol.to_uppercase())
+                .await?
+                .and_then(|equity| equity.dps)
+                .map(|dps| dps * rate);
+            let cost_basis = item.quantity as f64 * item.average_buy_price * rate;
+            let annual_income = item.quantity as f64 * dividend_per_share.unwrap_or(0.0);
+            holdings.push(HoldingYieldOnCost {
+                symbol: item.symbol.clone(),
+                quantity: item.quantity,
+                cost_basis,
+                dividend_per_share,
+                annual_income,
+                yield_on_cost: percent_of(annual_income, cost_basis),
+            });
+        }
+
+        let cost_basis: f64 = holdings.iter().map(|h| h.cost_basis).sum();
+        let annual_income: f64 = holdings.iter().map(|h| h.annual_income).sum();
+        Ok(Some(PortfolioYieldOnCost {
+            portfolio_id: portfolio.id,
+            cost_basis,
+            annual_income,
+            yield_on_cost: percent_of(annual_income, cost_basis),
+            holdings,
+            currency: portfolio.base_currency,
+        }))
+    }
+
+    /// Get a portfolio's daily valuation history, or `None` if the portfolio doesn't exist
+    pub async fn get_valuation_history(&self, id: &str) -> Result<Option<Vec<PortfolioSnapshot>>> {
+        if self.repository.get_portfolio(id).await?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.repository.get_snapshots(id).await?))
+    }
+
+    /// Get a portfolio's value points within `from..=to`, or `None` if the portfolio doesn't exist.
+    ///
+    /// Points taken while the portfolio held nothing have a zero value rather than being left out,
+    /// so gaps in the series only mean no value was recorded.
+    pub async fn get_value_history(
+        &self,
+        id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<Vec<PortfolioValuePoint>>> {
+        if self.repository.get_portfolio(id).await?.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(self.repository.get_value_points(id, from, to).await?))
+    }
+
     pub async fn delete_portfolio(&self, id: &str) -> Result<()> {
         self.repository.delete_portfolio(id).await
     }
 }
+
+/// Total market value and cost basis of valued holdings, counting unpriced ones at cost
+fn totals(holdings: &[HoldingValuation]) -> (f64, f64) {
+    (
+        holdings
+            .iter()
+            .map(|h| h.market_value.unwrap_or(h.cost_basis))
+            .sum(),
+        holdings.iter().map(|h| h.cost_basis).sum(),
+    )
+}
+
+/// `amount` as a percent of `base`, or 0 when `base` is not positive
+fn percent_of(amount: f64, base: f64) -> f64 {
+    if base > 0.0 {
+        amount / base * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MarketSummary;
+    use crate::infrastructure::test_support::{equity, live, TempDb};
+    use crate::infrastructure::{
+        RocksDbPortfolioRepository, RocksDbStockRepository, StaticFxRateProvider,
+    };
+    use chrono::TimeZone;
+
+    fn use_case(temp: &TempDb) -> PortfolioUseCase {
+        use_case_with_rates(temp, &[])
+    }
+
+    /// Use case converting GHS at the given units of each currency per GHS
+    fn use_case_with_rates(temp: &TempDb, rates: &[(&str, f64)]) -> PortfolioUseCase {
+        PortfolioUseCase::new(
+            Arc::new(RocksDbPortfolioRepository::new(temp.db.clone())),
+            Arc::new(RocksDbStockRepository::new(temp.db.clone())),
+            Arc::new(StaticFxRateProvider::new(
+                rates
+                    .iter()
+                    .map(|(currency, rate)| (currency.to_string(), *rate))
+                    .collect(),
+            )),
+            MarketCalendar::default(),
+        )
+    }
+
+    fn trade(
+        symbol: &str,
+        transaction_type: TransactionType,
+        quantity: i64,
+        price: f64,
+        day: u32,
+    ) -> Transaction {
+        Transaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            symbol: symbol.to_string(),
+            transaction_type,
+            quantity,
+            price_per_share: price,
+            fee: 0.0,
+            timestamp: Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap(),
+        }
+    }
+
+    async fn portfolio_with(use_case: &PortfolioUseCase, transactions: Vec<Transaction>) -> String {
+        let portfolio = use_case
+            .create_portfolio("Test".to_string(), None, CostBasisMethod::Average)
+            .await
+            .unwrap();
+        for transaction in transactions {
+            use_case
+                .add_transaction(&portfolio.id, transaction)
+                .await
+                .unwrap();
+        }
+        portfolio.id
+    }
+
+    fn query(
+        symbol: Option<&str>,
+        from: u32,
+        to: u32,
+        page: usize,
+        page_size: usize,
+    ) -> TransactionQuery {
+        TransactionQuery {
+            from: Some(Utc.with_ymd_and_hms(2024, 3, from, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2024, 3, to, 23, 59, 59).unwrap()),
+            symbol: symbol.map(str::to_string),
+            page,
+            page_size,
+        }
+    }
+
+    #[tokio::test]
+    async fn transactions_are_filtered_by_symbol_and_date_then_paginated() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let id = portfolio_with(
+            &use_case,
+            (1..=6)
+                .map(|day| trade("MTNGH", TransactionType::Buy, 10, 1.0, day))
+                .chain([trade("GCB", TransactionType::Buy, 5, 4.0, 3)])
+                .collect(),
+        )
+        .await;
+
+        let first = use_case
+            .list_transactions(&id, &query(Some("mtngh"), 2, 5, 1, 3))
+            .await
+            .unwrap()
+            .unwrap();
+        let second = use_case
+            .list_transactions(&id, &query(Some("mtngh"), 2, 5, 2, 3))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(first.total, 4);
+        assert_eq!(first.total_pages, 2);
+        let days = |page: &TransactionPage| -> Vec<u32> {
+            page.transactions
+                .iter()
+                .map(|t| chrono::Datelike::day(&t.timestamp))
+                .collect()
+        };
+        assert_eq!(days(&first), vec![2, 3, 4]);
+        assert_eq!(days(&second), vec![5]);
+        assert!(first.transactions.iter().all(|t| t.symbol == "MTNGH"));
+    }
+
+    #[tokio::test]
+    async fn a_page_far_past_the_end_is_empty() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let id = portfolio_with(
+            &use_case,
+            vec![trade("MTNGH", TransactionType::Buy, 10, 1.0, 1)],
+        )
+        .await;
+
+        let page = use_case
+            .list_transactions(&id, &query(None, 1, 31, usize::MAX, 100))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(page.total, 1);
+        assert!(page.transactions.is_empty());
+    }
+
+    async fn priced_portfolio(temp: &TempDb, use_case: &PortfolioUseCase) -> String {
+        RocksDbStockRepository::new(temp.db.clone())
+            .store_live_data(
+                "MTNGH",
+                &live("MTNGH", 1.5, 0.0),
+                Utc.with_ymd_and_hms(2024, 3, 4, 14, 0, 0).unwrap(),
+            )
+            .await
+            .unwrap();
+        portfolio_with(
+            use_case,
+            vec![trade("MTNGH", TransactionType::Buy, 10, 1.0, 1)],
+        )
+        .await
+    }
+
+    async fn snapshot_dates(use_case: &PortfolioUseCase, id: &str) -> Vec<NaiveDate> {
+        use_case
+            .repository
+            .get_snapshots(id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|snapshot| snapshot.date)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn a_close_missed_before_a_restart_is_snapshotted_once() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let id = priced_portfolio(&temp, &use_case).await;
+        // Monday evening, after the session the worker never saw close
+        let evening = Utc.with_ymd_and_hms(2024, 3, 4, 18, 0, 0).unwrap();
+
+        let first = use_case.snapshot_missing_portfolios(evening).await.unwrap();
+        let second = use_case.snapshot_missing_portfolios(evening).await.unwrap();
+
+        assert_eq!(first, 1);
+        assert_eq!(second, 0);
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(snapshot_dates(&use_case, &id).await, vec![monday]);
+        let snapshots = use_case.repository.get_snapshots(&id).await.unwrap();
+        assert_eq!(snapshots[0].market_value, 15.0);
+    }
+
+    #[tokio::test]
+    async fn snapshots_before_the_close_are_for_the_previous_session() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let id = priced_portfolio(&temp, &use_case).await;
+        // Friday's close was missed; the worker comes back on Saturday
+        let saturday = Utc.with_ymd_and_hms(2024, 3, 9, 9, 0, 0).unwrap();
+        // Monday mid-session, e.g. during a trading pause, Friday's snapshot already exists
+        let monday = Utc.with_ymd_and_hms(2024, 3, 11, 12, 0, 0).unwrap();
+
+        let on_saturday = use_case
+            .snapshot_missing_portfolios(saturday)
+            .await
+            .unwrap();
+        let on_monday = use_case.snapshot_missing_portfolios(monday).await.unwrap();
+
+        assert_eq!(on_saturday, 1);
+        assert_eq!(on_monday, 0);
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 8).unwrap();
+        assert_eq!(snapshot_dates(&use_case, &id).await, vec![friday]);
+    }
+
+    #[tokio::test]
+    async fn a_ghs_holding_is_valued_in_the_portfolio_base_currency() {
+        let temp = TempDb::new();
+        let use_case = use_case_with_rates(&temp, &[("USD", 0.25)]);
+        RocksDbStockRepository::new(temp.db.clone())
+            .store_live_data("MTNGH", &live("MTNGH", 2.0, 0.0), Utc::now())
+            .await
+            .unwrap();
+        let portfolio = use_case
+            .create_portfolio(
+                "Diaspora".to_string(),
+                Some("usd".to_string()),
+                CostBasisMethod::Average,
+            )
+            .await
+            .unwrap();
+        use_case
+            .add_transaction(
+                &portfolio.id,
+                trade("MTNGH", TransactionType::Buy, 10, 1.0, 1),
+            )
+            .await
+            .unwrap();
+
+        let valuation = use_case
+            .get_valuation(&portfolio.id, Some(PriceSource::Live))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(portfolio.base_currency, "USD");
+        assert_eq!(valuation.currency, "USD");
+        assert_eq!(valuation.market_value, 5.0);
+        assert_eq!(valuation.cost_basis, 2.5);
+        assert_eq!(valuation.holdings[0].price, Some(0.5));
+    }
+
+    #[tokio::test]
+    async fn live_and_close_price_sources_value_at_different_prices() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let stocks = RocksDbStockRepository::new(temp.db.clone());
+        let (open, _) = use_case
+            .market_calendar
+            .last_completed_session(Utc::now())
+            .unwrap();
+        stocks
+            .store_live_data(
+                "MTNGH",
+                &live("MTNGH", 1.2, 0.0),
+                open + chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        stocks
+            .store_live_data("MTNGH", &live("MTNGH", 2.0, 0.8), Utc::now())
+            .await
+            .unwrap();
+        let id = portfolio_with(
+            &use_case,
+            vec![trade("MTNGH", TransactionType::Buy, 10, 1.0, 1)],
+        )
+        .await;
+
+        let live_valuation = use_case
+            .get_valuation(&id, Some(PriceSource::Live))
+            .await
+            .unwrap()
+            .unwrap();
+        let close_valuation = use_case
+            .get_valuation(&id, Some(PriceSource::Close))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(live_valuation.market_value, 20.0);
+        assert_eq!(close_valuation.market_value, 12.0);
+        assert_eq!(close_valuation.price_source, PriceSource::Close);
+    }
+
+    #[tokio::test]
+    async fn portfolio_beta_weights_each_holding_beta_by_value() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let stocks = RocksDbStockRepository::new(temp.db.clone());
+        let mut index = 100.0;
+        let mut last_prices = (0.0, 0.0);
+        for days_ago in (1..=20).rev() {
+            let timestamp = Utc::now() - chrono::Duration::days(days_ago);
+            index *= 1.0 + ((days_ago * 7) % 5 - 2) as f64 * 0.01;
+            // GCB moves with the index and MTNGH twice as much in log terms, so their betas
+            // are 1 and 2
+            last_prices = (index / 50.0, (index / 100.0).powi(2) * 4.0);
+            stocks
+                .store_live_data("GCB", &live("GCB", last_prices.0, 0.0), timestamp)
+                .await
+                .unwrap();
+            stocks
+                .store_live_data("MTNGH", &live("MTNGH", last_prices.1, 0.0), timestamp)
+                .await
+                .unwrap();
+            let summary = MarketSummary {
+                total_market_cap: 0.0,
+                total_volume: 1000,
+                total_stocks: 2,
+                top_gainers: Vec::new(),
+                top_losers: Vec::new(),
+                index_level: index,
+                prices: Default::default(),
+                data_completeness: None,
+                sectors: Vec::new(),
+                last_updated: timestamp,
+            };
+            stocks
+                .store_market_summary(&summary, timestamp)
+                .await
+                .unwrap();
+        }
+        let id = portfolio_with(
+            &use_case,
+            vec![
+                trade("GCB", TransactionType::Buy, 100, 1.0, 1),
+                trade("MTNGH", TransactionType::Buy, 300, 1.0, 1),
+                trade("CAL", TransactionType::Buy, 50, 1.0, 1),
+            ],
+        )
+        .await;
+
+        let risk = use_case.get_risk(&id, 30, 0.95).await.unwrap().unwrap();
+
+        let (gcb_value, mtngh_value) = (100.0 * last_prices.0, 300.0 * last_prices.1);
+        let gcb_weight = gcb_value / (gcb_value + mtngh_value);
+        let expected_beta = gcb_weight + 2.0 * (1.0 - gcb_weight);
+        assert!((risk.beta.unwrap() - expected_beta).abs() < 1e-9);
+        for (holding, beta) in risk.holdings.iter().zip([1.0, 2.0]) {
+            assert!((holding.beta - beta).abs() < 1e-9, "{:?}", holding);
+        }
+        assert_eq!(risk.excluded_symbols, ["CAL"]);
+        let coverage = (gcb_value + mtngh_value) / (gcb_value + mtngh_value + 50.0);
+        assert!((risk.coverage - coverage).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn valuation_reports_gains_and_flags_unpriced_holdings() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        RocksDbStockRepository::new(temp.db.clone())
+            .store_live_data("MTNGH", &live("MTNGH", 2.0, 0.0), Utc::now())
+            .await
+            .unwrap();
+        let id = portfolio_with(
+            &use_case,
+            vec![
+                trade("MTNGH", TransactionType::Buy, 100, 1.0, 1),
+                trade("MTNGH", TransactionType::Sell, 40, 1.5, 2),
+                trade("CAL", TransactionType::Buy, 10, 0.8, 3),
+            ],
+        )
+        .await;
+
+        let valuation = use_case
+            .get_valuation(&id, Some(PriceSource::Live))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let mtngh = &valuation.holdings[0];
+        assert_eq!(mtngh.symbol, "MTNGH");
+        assert_eq!(mtngh.market_value, Some(120.0));
+        assert_eq!(mtngh.cost_basis, 60.0);
+        assert_eq!(mtngh.unrealized_gain, Some(60.0));
+        let cal = &valuation.holdings[1];
+        assert!(cal.unpriced);
+        assert_eq!(cal.market_value, None);
+        assert_eq!(valuation.realized_gain, 20.0);
+        assert_eq!(valuation.unrealized_gain, 60.0);
+    }
+
+    #[tokio::test]
+    async fn an_as_of_valuation_reflects_only_earlier_transactions() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let stocks = RocksDbStockRepository::new(temp.db.clone());
+        for (symbol, price, day) in [("MTNGH", 1.5, 4), ("MTNGH", 1.8, 11), ("GCB", 4.0, 11)] {
+            stocks
+                .store_live_data(
+                    symbol,
+                    &live(symbol, price, 0.0),
+                    Utc.with_ymd_and_hms(2024, 3, day, 14, 0, 0).unwrap(),
+                )
+                .await
+                .unwrap();
+        }
+        let id = portfolio_with(
+            &use_case,
+            vec![
+                trade("MTNGH", TransactionType::Buy, 10, 1.0, 1),
+                trade("GCB", TransactionType::Buy, 5, 3.0, 10),
+            ],
+        )
+        .await;
+
+        let as_of = use_case
+            .get_as_of(&id, NaiveDate::from_ymd_opt(2024, 3, 5).unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(as_of.transaction_count, 1);
+        let symbols: Vec<&str> = as_of.holdings.iter().map(|h| h.symbol.as_str()).collect();
+        assert_eq!(symbols, ["MTNGH"]);
+        assert_eq!(as_of.market_value, 15.0);
+        assert_eq!(as_of.cost_basis, 10.0);
+    }
+
+    #[tokio::test]
+    async fn yield_on_cost_divides_dividend_income_by_cost_basis() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let mut mtngh = equity("MTNGH", 3.0);
+        mtngh.dps = Some(0.3);
+        RocksDbStockRepository::new(temp.db.clone())
+            .store_equity_data("MTNGH", &mtngh, Utc::now())
+            .await
+            .unwrap();
+        let id = portfolio_with(
+            &use_case,
+            vec![
+                trade("MTNGH", TransactionType::Buy, 100, 2.0, 1),
+                trade("CAL", TransactionType::Buy, 10, 1.0, 2),
+            ],
+        )
+        .await;
+
+        let yields = use_case.get_yield_on_cost(&id).await.unwrap().unwrap();
+
+        let mtngh = &yields.holdings[0];
+        assert_eq!(mtngh.symbol, "MTNGH");
+        assert_eq!(mtngh.cost_basis, 200.0);
+        assert!((mtngh.annual_income - 30.0).abs() < 1e-9);
+        assert!((mtngh.yield_on_cost - 15.0).abs() < 1e-9);
+        let cal = &yields.holdings[1];
+        assert_eq!(cal.dividend_per_share, None);
+        assert_eq!(cal.yield_on_cost, 0.0);
+        assert!((yields.yield_on_cost - 30.0 / 210.0 * 100.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn recorded_values_are_served_by_date_range_including_empty_portfolios() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let stocks = RocksDbStockRepository::new(temp.db.clone());
+        let held = portfolio_with(
+            &use_case,
+            vec![trade("MTNGH", TransactionType::Buy, 100, 1.0, 1)],
+        )
+        .await;
+        let empty = portfolio_with(&use_case, Vec::new()).await;
+        let at = |hour: u32| Utc.with_ymd_and_hms(2024, 3, 7, hour, 0, 0).unwrap();
+        for (hour, price) in [(10, 2.0), (11, 2.5), (12, 3.0)] {
+            stocks
+                .store_live_data("MTNGH", &live("MTNGH", price, 0.0), at(hour))
+                .await
+                .unwrap();
+            use_case.record_all_values(at(hour)).await.unwrap();
+        }
+
+        let history = use_case
+            .get_value_history(&held, at(11), at(12))
+            .await
+            .unwrap()
+            .unwrap();
+        let empty_history = use_case
+            .get_value_history(&empty, at(10), at(12))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let values: Vec<(DateTime<Utc>, f64)> = history
+            .iter()
+            .map(|point| (point.timestamp, point.market_value))
+            .collect();
+        assert_eq!(values, [(at(11), 250.0), (at(12), 300.0)]);
+        assert_eq!(history[0].cost_basis, 100.0);
+        assert_eq!(empty_history.len(), 3);
+        assert!(empty_history
+            .iter()
+            .all(|point| point.market_value == 0.0 && point.holdings == 0));
+        assert!(use_case
+            .get_value_history("missing", at(10), at(12))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
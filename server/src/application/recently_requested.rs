@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Most distinct symbols whose request counts are kept; the least requested is evicted beyond it
+const MAX_COUNTED_SYMBOLS: usize = 500;
+
+/// How often a symbol was fetched on demand since startup
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolPopularity {
+    pub symbol: String,
+    pub requests: u64,
+}
+
+/// Bounded set of symbols recently fetched on demand, evicting the least recently requested,
+/// along with how often each was requested
+pub struct RecentlyRequested {
+    capacity: usize,
+    symbols: Mutex<VecDeque<String>>,
+    counts: Mutex<HashMap<String, u64>>,
+}
+
+impl RecentlyRequested {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            symbols: Mutex::new(VecDeque::with_capacity(capacity)),
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Mark a symbol as requested, moving it to the front of the set and counting the request
+    pub fn touch(&self, symbol: &str) {
+        self.count(symbol);
+        self.mark_recent(symbol);
+    }
+
+    fn count(&self, symbol: &str) {
+        let mut counts = self.counts.lock().unwrap();
+        if !counts.contains_key(symbol) && counts.len() >= MAX_COUNTED_SYMBOLS {
+            let least = counts
+                .iter()
+                .min_by_key(|(_, requests)| **requests)
+                .map(|(symbol, _)| symbol.clone());
+            if let Some(least) = least {
+                counts.remove(&least);
+            }
+        }
+        *counts.entry(symbol.to_string()).or_insert(0) += 1;
+    }
+
+    fn mark_recent(&self, symbol: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut symbols = self.symbols.lock().unwrap();
+        if let Some(position) = symbols.iter().position(|s| s == symbol) {
+            symbols.remove(position);
+        }
+        symbols.push_front(symbol.to_string());
+        symbols.truncate(self.capacity);
+    }
+
+    /// Symbols ordered from most to least recently requested
+    pub fn symbols(&self) -> Vec<String> {
+        self.symbols.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// The `limit` most requested symbols, most requested first
+    pub fn popular(&self, limit: usize) -> Vec<SymbolPopularity> {
+        let mut popular: Vec<SymbolPopularity> = self
+            .counts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(symbol, requests)| SymbolPopularity {
+                symbol: symbol.clone(),
+                requests: *requests,
+            })
+            .collect();
+        popular.sort_by(|a, b| {
+            b.requests
+                .cmp(&a.requests)
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+        popular.truncate(limit);
+        popular
+    }
+
+    /// Reload symbols saved from `symbols`, keeping their order; later touches still take precedence.
+    /// Restored symbols are not counted as requests.
+    pub fn restore(&self, symbols: Vec<String>) {
+        for symbol in symbols.iter().rev() {
+            self.mark_recent(symbol);
+        }
+    }
+
+    /// Request count for every counted symbol
+    pub fn counts(&self) -> HashMap<String, u64> {
+        self.counts.lock().unwrap().clone()
+    }
+
+    /// Reload counts saved from `counts`, adding them to any requests made since startup
+    pub fn restore_counts(&self, saved: HashMap<String, u64>) {
+        let mut counts = self.counts.lock().unwrap();
+        for (symbol, requests) in saved {
+            *counts.entry(symbol).or_insert(0) += requests;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_least_recently_requested_symbol_is_evicted_beyond_capacity() {
+        let recently_requested = RecentlyRequested::new(2);
+
+        recently_requested.touch("MTNGH");
+        recently_requested.touch("GCB");
+        recently_requested.touch("MTNGH");
+        recently_requested.touch("SCB");
+
+        assert_eq!(recently_requested.symbols(), ["SCB", "MTNGH"]);
+        assert_eq!(recently_requested.popular(1)[0].symbol, "MTNGH");
+    }
+}
@@ -0,0 +1,185 @@
+use chrono::{DateTime, Utc};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// Most distinct route and parameter combinations cached at once; responses beyond it are
+/// computed on every request until the next invalidation
+const MAX_CACHED_RESPONSES: usize = 256;
+
+/// A serialized response payload together with the ETag identifying it
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub etag: String,
+    pub body: serde_json::Value,
+}
+
+impl CachedResponse {
+    fn new(body: serde_json::Value, last_updated: Option<DateTime<Utc>>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        body.to_string().hash(&mut hasher);
+        last_updated.map(|t| t.timestamp_micros()).hash(&mut hasher);
+        Self {
+            etag: format!("W/\"{:016x}\"", hasher.finish()),
+            body,
+        }
+    }
+
+    /// Whether an `If-None-Match` header value names this response
+    pub fn matches(&self, if_none_match: &str) -> bool {
+        let etag = self.etag.trim_start_matches("W/");
+        if_none_match
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+    }
+}
+
+#[derive(Default)]
+struct Entries {
+    last_updated: Option<DateTime<Utc>>,
+    cells: HashMap<String, Arc<OnceCell<Arc<CachedResponse>>>>,
+}
+
+/// Responses of read endpoints keyed by route and parameters, valid until the next live data
+/// write. Concurrent requests for the same key wait for a single computation.
+#[derive(Default)]
+pub struct ResponseCache {
+    entries: Mutex<Entries>,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached response for `key`, computing it with `compute` if there is none. Errors are
+    /// returned as-is and never cached.
+    pub async fn get_or_try_insert<E, F, Fut>(
+        &self,
+        key: &str,
+        compute: F,
+    ) -> Result<Arc<CachedResponse>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<serde_json::Value, E>>,
+    {
+        let (cell, last_updated) = {
+            let mut entries = self.entries.lock().unwrap();
+            let last_updated = entries.last_updated;
+            let cell = match entries.cells.get(key) {
+                Some(cell) => Some(cell.clone()),
+                None if entries.cells.len() < MAX_CACHED_RESPONSES => {
+                    Some(entries.cells.entry(key.to_string()).or_default().clone())
+                }
+                None => None,
+            };
+            (cell, last_updated)
+        };
+
+        let Some(cell) = cell else {
+            return Ok(Arc::new(CachedResponse::new(
+                compute().await?,
+                last_updated,
+            )));
+        };
+
+        // A cell dropped by an invalidation while being filled is still filled for the requests
+        // already waiting on it, but never served to later ones
+        let result = cell
+            .get_or_try_init(|| async move {
+                Ok(Arc::new(CachedResponse::new(
+                    compute().await?,
+                    last_updated,
+                )))
+            })
+            .await
+            .cloned();
+        if result.is_err() {
+            self.discard_unfilled(key, &cell);
+        }
+        result
+    }
+
+    /// Drop the cell for `key` if it is still `cell` and a failed computation left it empty, so
+    /// failing keys don't use up the cache's capacity
+    fn discard_unfilled(&self, key: &str, cell: &Arc<OnceCell<Arc<CachedResponse>>>) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries
+            .cells
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, cell) && !current.initialized())
+        {
+            entries.cells.remove(key);
+        }
+    }
+
+    /// Drop every cached response after stored data changed at `timestamp`
+    pub fn invalidate(&self, timestamp: DateTime<Utc>) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.last_updated = Some(timestamp);
+        entries.cells.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn failed_computations_are_not_kept() {
+        let cache = ResponseCache::new();
+
+        for i in 0..MAX_CACHED_RESPONSES + 10 {
+            let result = cache
+                .get_or_try_insert(&format!("/missing/{}", i), || async { Err("not found") })
+                .await;
+            assert!(result.is_err());
+        }
+
+        assert!(cache.entries.lock().unwrap().cells.is_empty());
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        for _ in 0..2 {
+            cache
+                .get_or_try_insert::<(), _, _>("/found", || async {
+                    calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    Ok(serde_json::json!({ "ok": true }))
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn a_failed_key_is_computed_again() {
+        let cache = ResponseCache::new();
+        let _ = cache
+            .get_or_try_insert("/flaky", || async { Err::<serde_json::Value, _>("down") })
+            .await;
+
+        let response = cache
+            .get_or_try_insert::<&str, _, _>("/flaky", || async { Ok(serde_json::json!(1)) })
+            .await
+            .unwrap();
+
+        assert_eq!(response.body, serde_json::json!(1));
+    }
+
+    #[tokio::test]
+    async fn invalidation_changes_the_etag() {
+        let cache = ResponseCache::new();
+        let compute = || async { Ok::<_, ()>(serde_json::json!({ "price": 1.0 })) };
+        let before = cache.get_or_try_insert("/stocks", compute).await.unwrap();
+
+        cache.invalidate(Utc::now());
+        let after = cache.get_or_try_insert("/stocks", compute).await.unwrap();
+
+        assert_ne!(before.etag, after.etag);
+        assert!(after.matches(&after.etag));
+        assert!(!after.matches(&before.etag));
+    }
+}
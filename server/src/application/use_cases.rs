@@ -1,54 +1,333 @@
+use crate::application::{
+    CachedResponse, JobState, RecentlyRequested, ResponseCache, SymbolPopularity, WorkerStatus,
+};
+use crate::domain::analytics::{
+    self,
+    adjustment::{adjusted_history, AdjustedHistory},
+    breadth::{market_breadth, BreadthInput, MarketBreadth},
+    candles::{candles, Candle, CandleInterval},
+    comparison::{rebased_comparison, ComparisonPoint},
+    correlation::{correlation_matrix, CorrelationMatrix},
+    history::{history_stats, HistoryStat},
+    ladder::{synthetic_ladder, LadderConfig, PriceLadder},
+    metrics::{traded_volume, turnover_ratio, volume_alert, Turnover, VolumeAlert},
+    relative_strength::{rank_by_total_return, RelativeStrengthEntry},
+    risk::dated_log_returns,
+    sectors::{sector_summaries, SectorMember, SectorSummary},
+    timeline::{summary_timeline, SummaryTimelinePoint},
+    volatility::{volatility_cone, VolatilityConeWindow},
+};
 use crate::domain::{
-    Equity, EquityLive, GseApiClient, MarketSummary, StockRepository, TimeSeriesPoint,
+    data_completeness, matches_classification, Announcement, AnnouncementCategory, Bond,
+    CalendarDay, CircuitOpen, CircuitState, DataSource, Equity, EquityLive, FreshnessSla,
+    GseApiClient, LiveUpdate, MarketCalendar, MarketEvent, MarketSummary, ScrapeCycle, SearchEntry,
+    SearchResult, SearchType, SectorBreakdown, SnapshotDiff, SnapshotPriceChange, StaleSymbol,
+    StockRepository, StockSplit, StoredRecord, SymbolMetrics, TimeSeriesPoint, WebhookDelivery,
+    YieldCurvePoint,
 };
 use anyhow::Result;
-use chrono::{DateTime, Utc};
-use std::sync::Arc;
+use chrono::{DateTime, Days, FixedOffset, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use tokio::sync::{broadcast, mpsc, OwnedMutexGuard};
+
+/// Points read from storage per page when streaming history
+const HISTORY_STREAM_PAGE_SIZE: usize = 500;
+
+/// Longest time cached symbol metrics are used in place of computing them from history
+const METRICS_CACHE_MAX_AGE_HOURS: i64 = 24;
+
+/// Batches of live data buffered per streaming client before the oldest are dropped
+const LIVE_UPDATE_CAPACITY: usize = 16;
+
+/// Market summaries buffered per streaming client before the oldest are dropped
+const SUMMARY_UPDATE_CAPACITY: usize = 4;
+
+/// Calendar days of history the average daily volume behind volume alerts covers
+const VOLUME_ALERT_ADV_DAYS: i64 = 30;
+
+/// Fewest daily returns two symbols must share for their correlation to be reported
+const MIN_CORRELATION_RETURNS: usize = 10;
+
+/// What to do with live records priced at or below the minimum valid price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidPriceMode {
+    /// Never store such records
+    SkipStore,
+    /// Store them, but leave them out of listings and summaries
+    #[default]
+    FilterOnRead,
+}
+
+impl std::str::FromStr for InvalidPriceMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "skip_store" => Ok(Self::SkipStore),
+            "filter_on_read" => Ok(Self::FilterOnRead),
+            other => Err(format!("unknown invalid price mode: {}", other)),
+        }
+    }
+}
+
+/// Sanity checks on upstream live prices: excludes suspended or delisted stocks, which the
+/// upstream reports with a zero price, and flags implausible daily changes
+#[derive(Debug, Clone, Copy)]
+pub struct PriceFilter {
+    /// Prices must be strictly above this value to be considered valid
+    pub min_valid_price: f64,
+    pub mode: InvalidPriceMode,
+    /// Changes larger than this percent of the previous price are treated as bad upstream data
+    pub max_change_percent: f64,
+}
+
+impl Default for PriceFilter {
+    fn default() -> Self {
+        Self {
+            min_valid_price: 0.0,
+            mode: InvalidPriceMode::default(),
+            max_change_percent: 1000.0,
+        }
+    }
+}
+
+impl PriceFilter {
+    pub fn is_valid(&self, data: &EquityLive) -> bool {
+        data.price > self.min_valid_price
+    }
+
+    /// The record's change, or `None` if it is non-finite or implausibly large
+    pub fn plausible_change(&self, data: &EquityLive) -> Option<f64> {
+        if !data.change.is_finite() {
+            return None;
+        }
+
+        if data
+            .change_percent()
+            .is_some_and(|percent| percent.abs() > self.max_change_percent)
+        {
+            return None;
+        }
+
+        Some(data.change)
+    }
+}
+
+/// Configuration for fetching and summarising stock data
+#[derive(Debug, Clone)]
+pub struct FetchConfig {
+    /// Percent move of the composite index between two summaries that records a market event
+    pub market_move_alert_percent: f64,
+    pub price_filter: PriceFilter,
+    /// Seconds a manually triggered refresh waits for a running scrape before giving up
+    pub manual_refresh_wait: u64,
+    /// How recently a symbol's live data must have updated to count towards a summary's
+    /// `data_completeness`
+    pub summary_fresh_within: chrono::Duration,
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            market_move_alert_percent: 5.0,
+            price_filter: PriceFilter::default(),
+            manual_refresh_wait: 0,
+            summary_fresh_within: chrono::Duration::minutes(30),
+        }
+    }
+}
+
+/// Configuration for reading stock data
+#[derive(Debug, Clone)]
+pub struct QueryConfig {
+    pub price_filter: PriceFilter,
+    /// Offset of the market's local time from UTC, used to decide where "today" starts and ends
+    pub market_timezone: FixedOffset,
+    /// How long stored equity details are served before an on-demand request re-fetches them
+    pub equity_cache_ttl: chrono::Duration,
+    /// Most symbols a single batch request may ask for
+    pub max_batch_symbols: usize,
+    pub market_calendar: MarketCalendar,
+    /// Expected update frequency of each symbol while trading, for stale-symbol monitoring
+    pub freshness_sla: FreshnessSla,
+    /// Spread assumed by the synthetic price ladder
+    pub ladder: LadderConfig,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            price_filter: PriceFilter::default(),
+            // The GSE trades on Accra time, which is UTC year-round
+            market_timezone: FixedOffset::east_opt(0).unwrap(),
+            equity_cache_ttl: chrono::Duration::minutes(5),
+            max_batch_symbols: 100,
+            market_calendar: MarketCalendar::default(),
+            freshness_sla: FreshnessSla::default(),
+            ladder: LadderConfig::default(),
+        }
+    }
+}
 
 /// Use case for fetching and storing stock data
 #[derive(Clone)]
 pub struct FetchStockDataUseCase {
     api_client: Arc<dyn GseApiClient + Send + Sync>,
     repository: Arc<dyn StockRepository + Send + Sync>,
+    config: FetchConfig,
+    /// Held for the duration of any scrape against the upstream API, so the worker and manual
+    /// triggers never hit it at the same time
+    scrape_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Publishes each batch of stored live data to streaming clients
+    live_updates: broadcast::Sender<Arc<LiveUpdate>>,
+    /// Publishes each stored market summary to streaming clients
+    summary_updates: broadcast::Sender<Arc<MarketSummary>>,
+    /// Invalidated whenever stored stock data changes
+    response_cache: Arc<ResponseCache>,
+    /// When the service entered degraded mode, if it has not reached the upstream since
+    degraded_since: Arc<RwLock<Option<DateTime<Utc>>>>,
 }
 
 impl FetchStockDataUseCase {
-    pub fn new(
+    pub fn with_config(
         api_client: Arc<dyn GseApiClient + Send + Sync>,
         repository: Arc<dyn StockRepository + Send + Sync>,
+        response_cache: Arc<ResponseCache>,
+        config: FetchConfig,
     ) -> Self {
         Self {
             api_client,
             repository,
+            config,
+            scrape_lock: Arc::new(tokio::sync::Mutex::new(())),
+            live_updates: broadcast::channel(LIVE_UPDATE_CAPACITY).0,
+            summary_updates: broadcast::channel(SUMMARY_UPDATE_CAPACITY).0,
+            response_cache,
+            degraded_since: Arc::new(RwLock::new(None)),
         }
     }
 
-    /// Fetch all live data from GSE API and store it
-    pub async fn fetch_and_store_all_live_data(&self) -> Result<()> {
-        let live_data = self.api_client.fetch_all_live_data().await?;
-        let count = live_data.len();
+    /// Enter degraded mode, in which stored data is served as best-effort until the next
+    /// successful live data fetch
+    pub fn mark_degraded(&self) {
+        let mut degraded_since = self.degraded_since.write().unwrap();
+        if degraded_since.is_none() {
+            *degraded_since = Some(Utc::now());
+        }
+    }
+
+    /// When the service entered degraded mode, or `None` if it is not in it
+    pub fn degraded_since(&self) -> Option<DateTime<Utc>> {
+        *self.degraded_since.read().unwrap()
+    }
+
+    /// Drop cached responses after stored data changed outside a fetch, e.g. by retention pruning
+    pub fn invalidate_responses(&self) {
+        self.response_cache.invalidate(Utc::now());
+    }
+
+    /// State of the circuit breaker in front of the upstream API
+    pub fn upstream_circuit_state(&self) -> CircuitState {
+        self.api_client.circuit_state()
+    }
+
+    /// Receive every batch of live data stored from now on. Receivers that fall more than
+    /// `LIVE_UPDATE_CAPACITY` batches behind miss the oldest ones rather than slowing the fetch.
+    pub fn subscribe_live_updates(&self) -> broadcast::Receiver<Arc<LiveUpdate>> {
+        self.live_updates.subscribe()
+    }
+
+    /// Receive every market summary stored from now on. Receivers that fall more than
+    /// `SUMMARY_UPDATE_CAPACITY` summaries behind miss the oldest ones.
+    pub fn subscribe_summary_updates(&self) -> broadcast::Receiver<Arc<MarketSummary>> {
+        self.summary_updates.subscribe()
+    }
+
+    /// Wait for any running scrape to finish, then hold the scrape lock until the guard is dropped
+    pub async fn lock_scrapes(&self) -> OwnedMutexGuard<()> {
+        self.scrape_lock.clone().lock_owned().await
+    }
+
+    /// Take the scrape lock for a manually triggered scrape, waiting at most the configured
+    /// time. Returns `None` if another scrape still holds it.
+    pub async fn try_lock_scrapes(&self) -> Option<OwnedMutexGuard<()>> {
+        let wait = std::time::Duration::from_secs(self.config.manual_refresh_wait);
+        tokio::time::timeout(wait, self.lock_scrapes()).await.ok()
+    }
+
+    /// Fetch all live data from GSE API and store it, returning the number of records stored
+    pub async fn fetch_and_store_all_live_data(&self) -> Result<usize> {
+        let mut live_data = self.api_client.fetch_all_live_data().await?;
         let timestamp = Utc::now();
+        if let Some(since) = self.degraded_since.write().unwrap().take() {
+            tracing::info!(
+                "GSE API reachable again, leaving degraded mode entered at {}",
+                since
+            );
+        }
 
-        for data in live_data {
-            self.repository
-                .store_live_data(&data.name, &data, timestamp)
-                .await?;
+        let price_filter = self.config.price_filter;
+        if price_filter.mode == InvalidPriceMode::SkipStore {
+            let before = live_data.len();
+            live_data.retain(|data| price_filter.is_valid(data));
+            if live_data.len() < before {
+                tracing::info!(
+                    "Skipped {} live data records priced at or below {}",
+                    before - live_data.len(),
+                    price_filter.min_valid_price
+                );
+            }
+        }
+        let count = live_data.len();
+
+        let mut stored = Ok(());
+        for data in &live_data {
+            stored = self
+                .repository
+                .store_live_data(&data.name, data, timestamp)
+                .await;
+            if stored.is_err() {
+                break;
+            }
         }
+        // Even a partly stored batch changes what readers see
+        self.response_cache.invalidate(timestamp);
+        stored?;
+
+        // Sending only fails when nobody is subscribed
+        let _ = self.live_updates.send(Arc::new(LiveUpdate {
+            timestamp,
+            data: live_data,
+        }));
 
         tracing::info!(
             "Successfully fetched and stored {} live data records",
             count
         );
-        Ok(())
+        Ok(count)
     }
 
     pub async fn fetch_and_store_all_equity_data(&self) -> Result<()> {
+        self.fetch_and_store_all_equity_data_with_progress(|_, _| {})
+            .await
+    }
+
+    /// Fetch and store all equity data, reporting `(processed, total)` after each equity
+    pub async fn fetch_and_store_all_equity_data_with_progress<F>(
+        &self,
+        on_progress: F,
+    ) -> Result<()>
+    where
+        F: Fn(usize, usize),
+    {
         let equity_summaries = self.api_client.fetch_all_equities().await?;
         let count = equity_summaries.len();
         let timestamp = Utc::now();
+        on_progress(0, count);
 
         // For each summary, fetch the detailed equity data
-        for summary in equity_summaries {
+        for (index, summary) in equity_summaries.into_iter().enumerate() {
             match self.api_client.fetch_equity_data(&summary.name).await {
                 Ok(equity) => {
                     self.repository
@@ -59,155 +338,2065 @@ impl FetchStockDataUseCase {
                     tracing::warn!("Failed to fetch detailed data for {}: {}", summary.name, e);
                 }
             }
+            on_progress(index + 1, count);
         }
+        self.response_cache.invalidate(timestamp);
 
         tracing::info!("Successfully processed {} equity records", count);
         Ok(())
     }
 
+    /// Whether the store holds no stock data at all (e.g. a fresh deployment)
+    pub async fn is_store_empty(&self) -> Result<bool> {
+        Ok(self.repository.get_all_symbols().await?.is_empty())
+    }
+
+    /// Mark the equity bootstrap as running if the store is empty. Returns `false`, leaving the
+    /// job untouched, when the store already holds data or a bootstrap is already running.
+    pub async fn claim_bootstrap(&self, status: &WorkerStatus) -> Result<bool> {
+        Ok(self.is_store_empty().await? && status.try_start_job(|s| &mut s.bootstrap))
+    }
+
+    /// One-time population of equity details for a fresh deployment, tracking progress in `status`.
+    /// Requests are paced by the API client's rate limiting. The caller is expected to have
+    /// claimed the job with [`Self::claim_bootstrap`].
+    pub async fn bootstrap_equities(&self, status: &WorkerStatus) -> Result<()> {
+        let _scrape = self.lock_scrapes().await;
+
+        let result = self
+            .fetch_and_store_all_equity_data_with_progress(|processed, total| {
+                status.update(|s| {
+                    s.bootstrap.processed = processed;
+                    s.bootstrap.total = total;
+                })
+            })
+            .await;
+
+        status.update(|s| {
+            s.bootstrap.state = if result.is_ok() {
+                JobState::Completed
+            } else {
+                JobState::Failed
+            }
+        });
+
+        result
+    }
+
+    /// Recompute and cache derived metrics for every symbol, tracking progress in `status`.
+    /// The caller is expected to have marked the job as running.
+    pub async fn recompute_metrics(&self, status: &WorkerStatus) -> Result<usize> {
+        let result = self.recompute_all_metrics(status).await;
+
+        status.update(|s| {
+            s.recompute_metrics.state = if result.is_ok() {
+                JobState::Completed
+            } else {
+                JobState::Failed
+            }
+        });
+
+        result
+    }
+
+    async fn recompute_all_metrics(&self, status: &WorkerStatus) -> Result<usize> {
+        let symbols = self.repository.get_all_symbols().await?;
+        let total = symbols.len();
+        status.update(|s| s.recompute_metrics.total = total);
+
+        for (index, symbol) in symbols.iter().enumerate() {
+            let metrics = compute_symbol_metrics(self.repository.as_ref(), symbol).await?;
+            self.repository.store_symbol_metrics(&metrics).await?;
+            status.update(|s| s.recompute_metrics.processed = index + 1);
+        }
+
+        tracing::info!("Recomputed derived metrics for {} symbols", total);
+        Ok(total)
+    }
+
+    /// Fetch detailed equity data for a single symbol and store it
+    pub async fn fetch_and_store_equity_data(&self, symbol: &str) -> Result<()> {
+        let equity = self.api_client.fetch_equity_data(symbol).await?;
+        let timestamp = Utc::now();
+        self.repository
+            .store_equity_data(&equity.name, &equity, timestamp)
+            .await?;
+        self.response_cache.invalidate(timestamp);
+        Ok(())
+    }
+
     /// Generate and store market summary
     pub async fn generate_and_store_market_summary(&self) -> Result<()> {
-        let all_symbols = self.repository.get_all_symbols().await?;
-        let count = all_symbols.len();
-        let mut total_market_cap = 0.0;
-        let mut total_volume = 0i64;
-        let mut top_gainers = Vec::new();
-        let mut top_losers = Vec::new();
-
-        for symbol in all_symbols {
-            if let Some(live_data) = self.repository.get_latest_live_data(&symbol).await? {
-                total_volume += live_data.volume;
-
-                // Try to calculate market cap if we have equity data
-                if let Ok(Some(equity)) = self.repository.get_latest_equity_data(&symbol).await {
-                    if let Some(shares) = equity.shares {
-                        let market_cap = live_data.price * shares as f64;
-                        total_market_cap += market_cap;
-                    }
-                }
+        let mut summary =
+            build_market_summary(self.repository.as_ref(), None, &self.config.price_filter).await?;
 
-                // Categorize as gainer or loser
-                if live_data.change > 0.0 {
-                    top_gainers.push(live_data);
-                } else if live_data.change < 0.0 {
-                    top_losers.push(live_data);
+        let last_updates = self.repository.get_last_updates().await?;
+        let completeness = data_completeness(
+            summary.total_stocks,
+            &last_updates,
+            self.config.summary_fresh_within,
+            summary.last_updated,
+        );
+        if completeness < 1.0 {
+            tracing::warn!(
+                "Market summary generated with fresh data for only {:.0}% of symbols",
+                completeness * 100.0
+            );
+        }
+        summary.data_completeness = Some(completeness);
+
+        match self.repository.get_latest_market_summary().await {
+            Ok(Some(previous)) => {
+                if let Some(level) = previous.next_index_level(&summary.prices) {
+                    summary.index_level = level;
+                }
+                if let Err(e) = self.check_market_move(&previous, &summary).await {
+                    tracing::warn!("Failed to check for market-wide move: {}", e);
                 }
             }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read the previous market summary: {}", e),
         }
 
-        // Sort and take top 5
-        top_gainers.sort_by(|a, b| b.change.partial_cmp(&a.change).unwrap());
-        top_losers.sort_by(|a, b| a.change.partial_cmp(&b.change).unwrap());
-        top_gainers.truncate(5);
-        top_losers.truncate(5);
-
-        let summary = MarketSummary {
-            total_market_cap,
-            total_volume,
-            total_stocks: count,
-            top_gainers,
-            top_losers,
-            last_updated: Utc::now(),
-        };
-
         self.repository
             .store_market_summary(&summary, Utc::now())
             .await?;
 
         tracing::info!(
             "Successfully generated and stored market summary (market cap: {:.2})",
-            total_market_cap
+            summary.total_market_cap
         );
+
+        // Sending only fails when nobody is subscribed
+        let _ = self.summary_updates.send(Arc::new(summary));
+        Ok(())
+    }
+
+    /// Record a bond's current yield, replacing any earlier record for the same code
+    pub async fn record_bond(
+        &self,
+        code: &str,
+        issuer: String,
+        maturity: Option<NaiveDate>,
+        yield_percent: f64,
+    ) -> Result<Bond> {
+        let bond = Bond {
+            code: code.to_uppercase(),
+            issuer,
+            maturity,
+            yield_percent,
+            updated_at: Utc::now(),
+        };
+        self.repository.store_bond(&bond).await?;
+        Ok(bond)
+    }
+
+    /// Record a stock split, replacing any earlier split for the same symbol and ex-date
+    pub async fn record_split(
+        &self,
+        symbol: &str,
+        ex_date: NaiveDate,
+        ratio: f64,
+    ) -> Result<StockSplit> {
+        let split = StockSplit {
+            symbol: symbol.to_uppercase(),
+            ex_date,
+            ratio,
+            recorded_at: Utc::now(),
+        };
+        self.repository.store_split(&split).await?;
+        Ok(split)
+    }
+
+    /// Record a company announcement under a new id
+    pub async fn record_announcement(
+        &self,
+        symbol: &str,
+        date: NaiveDate,
+        title: String,
+        body: String,
+        category: AnnouncementCategory,
+    ) -> Result<Announcement> {
+        let announcement = Announcement {
+            id: uuid::Uuid::new_v4().to_string(),
+            symbol: symbol.to_uppercase(),
+            date,
+            title,
+            body,
+            category,
+        };
+        self.repository.store_announcement(&announcement).await?;
+        Ok(announcement)
+    }
+
+    /// Persist a completed scrape cycle so operators can check the scraping cadence
+    pub async fn record_scrape_cycle(&self, cycle: &ScrapeCycle) -> Result<()> {
+        self.repository.store_scrape_cycle(cycle).await
+    }
+
+    /// Record a market event if the composite index moved more than the
+    /// configured percentage since the previously stored summary
+    async fn check_market_move(
+        &self,
+        previous: &MarketSummary,
+        summary: &MarketSummary,
+    ) -> Result<()> {
+        if previous.index_level <= 0.0 {
+            return Ok(());
+        }
+
+        let change_percent =
+            (summary.index_level - previous.index_level) / previous.index_level * 100.0;
+
+        if change_percent.abs() >= self.config.market_move_alert_percent {
+            tracing::warn!(
+                "Market-wide move of {:.2}% detected (index {:.2} -> {:.2})",
+                change_percent,
+                previous.index_level,
+                summary.index_level
+            );
+
+            let event = MarketEvent {
+                timestamp: summary.last_updated,
+                previous_level: previous.index_level,
+                current_level: summary.index_level,
+                change_percent,
+            };
+            self.repository.store_market_event(&event).await?;
+        }
+
         Ok(())
     }
 }
 
+/// Build a market summary from the latest live data of every symbol.
+///
+/// When `source` is given, only live records from that source are counted.
+/// Records rejected by `price_filter` are always left out.
+async fn build_market_summary(
+    repository: &(dyn StockRepository + Send + Sync),
+    source: Option<DataSource>,
+    price_filter: &PriceFilter,
+) -> Result<MarketSummary> {
+    let all_symbols = repository.get_all_symbols().await?;
+    let count = all_symbols.len();
+    let mut total_market_cap = 0.0;
+    let mut total_volume = 0i64;
+    let mut total_price = 0.0;
+    let mut priced_stocks = 0usize;
+    let mut top_gainers = Vec::new();
+    let mut top_losers = Vec::new();
+    let mut prices = BTreeMap::new();
+    let mut sector_members = Vec::new();
+
+    for symbol in all_symbols {
+        let live_data = match latest_live_data(repository, &symbol, source).await? {
+            Some(live_data) if price_filter.is_valid(&live_data) => live_data,
+            _ => continue,
+        };
+
+        total_volume += live_data.volume;
+        total_price += live_data.price;
+        priced_stocks += 1;
+        prices.insert(symbol.clone(), live_data.price);
+
+        // Market cap and sector need equity data, which not every symbol has
+        let equity = repository
+            .get_latest_equity_data(&symbol)
+            .await
+            .ok()
+            .flatten();
+        let market_cap = equity
+            .as_ref()
+            .and_then(|equity| equity.shares)
+            .map(|shares| live_data.price * shares as f64);
+        total_market_cap += market_cap.unwrap_or(0.0);
+
+        let change = price_filter.plausible_change(&live_data);
+        sector_members.push(SectorMember {
+            symbol: symbol.clone(),
+            sector: equity.and_then(|equity| equity.company.sector),
+            price: live_data.price,
+            volume: live_data.volume,
+            change_percent: change.and_then(|_| live_data.change_percent()),
+            market_cap,
+        });
+
+        // Categorize as gainer or loser; suspect changes count as neither
+        match change {
+            Some(change) if change > 0.0 => top_gainers.push(live_data),
+            Some(change) if change < 0.0 => top_losers.push(live_data),
+            Some(_) => {}
+            None => tracing::warn!(
+                "Ignoring suspect change {} for {} (price {})",
+                live_data.change,
+                symbol,
+                live_data.price
+            ),
+        }
+    }
+
+    // Rank by percent change so a move on a low-priced stock isn't outweighed by the same
+    // absolute move on a high-priced one, then take the top 5
+    let percent = |data: &EquityLive| data.change_percent().unwrap_or(f64::NAN);
+    top_gainers.sort_by(|a, b| compare_changes(percent(a), percent(b), true));
+    top_losers.sort_by(|a, b| compare_changes(percent(a), percent(b), false));
+    top_gainers.truncate(5);
+    top_losers.truncate(5);
+
+    let index_level = if priced_stocks > 0 {
+        total_price / priced_stocks as f64
+    } else {
+        0.0
+    };
+
+    Ok(MarketSummary {
+        total_market_cap,
+        total_volume,
+        total_stocks: count,
+        top_gainers,
+        top_losers,
+        index_level,
+        prices,
+        data_completeness: None,
+        sectors: sector_summaries(&sector_members),
+        last_updated: Utc::now(),
+    })
+}
+
+/// Latest live data for a symbol, from `source` when one is given
+async fn latest_live_data(
+    repository: &(dyn StockRepository + Send + Sync),
+    symbol: &str,
+    source: Option<DataSource>,
+) -> Result<Option<EquityLive>> {
+    match source {
+        Some(source) => repository.get_latest_live_data_from(symbol, source).await,
+        None => repository.get_latest_live_data(symbol).await,
+    }
+}
+
+/// Order changes by value without panicking, sorting NaN and infinities after every finite
+/// value in either direction
+fn compare_changes(a: f64, b: f64, descending: bool) -> std::cmp::Ordering {
+    match (a.is_finite(), b.is_finite()) {
+        (true, true) if descending => b.total_cmp(&a),
+        (true, true) => a.total_cmp(&b),
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        (false, false) => std::cmp::Ordering::Equal,
+    }
+}
+
+/// Compute the derived metrics of a symbol from its stored history and latest data
+async fn compute_symbol_metrics(
+    repository: &(dyn StockRepository + Send + Sync),
+    symbol: &str,
+) -> Result<SymbolMetrics> {
+    let now = Utc::now();
+    let history = repository
+        .get_historical_data(symbol, now - chrono::Duration::weeks(52), now)
+        .await?;
+    let range = analytics::metrics::fifty_two_week_range(&history, now);
+
+    let market_cap = match (
+        repository.get_latest_live_data(symbol).await?,
+        repository.get_latest_equity_data(symbol).await?,
+    ) {
+        (Some(live), Some(equity)) => equity.shares.map(|shares| live.price * shares as f64),
+        _ => None,
+    };
+
+    Ok(SymbolMetrics {
+        symbol: symbol.to_string(),
+        high_52w: range.map(|(high, _)| high),
+        low_52w: range.map(|(_, low)| low),
+        average_daily_volume: analytics::metrics::average_daily_volume(&history, 30, now),
+        market_cap,
+        computed_at: now,
+    })
+}
+
+/// Compare two market summaries symbol by symbol
+fn diff_market_summaries(from: &MarketSummary, to: &MarketSummary) -> SnapshotDiff {
+    let mut gainers = Vec::new();
+    let mut losers = Vec::new();
+
+    for (symbol, &to_price) in &to.prices {
+        if let Some(&from_price) = from.prices.get(symbol) {
+            let change = to_price - from_price;
+            let entry = SnapshotPriceChange {
+                symbol: symbol.clone(),
+                from_price,
+                to_price,
+                change,
+                change_percent: (from_price != 0.0).then(|| change / from_price * 100.0),
+            };
+            if change > 0.0 {
+                gainers.push(entry);
+            } else if change < 0.0 {
+                losers.push(entry);
+            }
+        }
+    }
+
+    let percent = |c: &SnapshotPriceChange| c.change_percent.unwrap_or(0.0);
+    gainers.sort_by(|a, b| percent(b).total_cmp(&percent(a)));
+    losers.sort_by(|a, b| percent(a).total_cmp(&percent(b)));
+
+    let market_cap_change = to.total_market_cap - from.total_market_cap;
+
+    SnapshotDiff {
+        from: from.last_updated,
+        to: to.last_updated,
+        market_cap_change,
+        market_cap_change_percent: (from.total_market_cap != 0.0)
+            .then(|| market_cap_change / from.total_market_cap * 100.0),
+        volume_change: to.total_volume - from.total_volume,
+        gainers,
+        losers,
+        new_symbols: to
+            .prices
+            .keys()
+            .filter(|symbol| !from.prices.contains_key(*symbol))
+            .cloned()
+            .collect(),
+        removed_symbols: from
+            .prices
+            .keys()
+            .filter(|symbol| !to.prices.contains_key(*symbol))
+            .cloned()
+            .collect(),
+    }
+}
+
 /// Use case for retrieving stock data
 #[derive(Clone)]
 pub struct GetStockDataUseCase {
     repository: Arc<dyn StockRepository + Send + Sync>,
     api_client: Arc<dyn GseApiClient + Send + Sync>,
+    recently_requested: Arc<RecentlyRequested>,
+    response_cache: Arc<ResponseCache>,
+    config: QueryConfig,
 }
 
 impl GetStockDataUseCase {
-    pub fn new(
+    pub fn with_config(
         repository: Arc<dyn StockRepository + Send + Sync>,
         api_client: Arc<dyn GseApiClient + Send + Sync>,
+        recently_requested: Arc<RecentlyRequested>,
+        response_cache: Arc<ResponseCache>,
+        config: QueryConfig,
     ) -> Self {
         Self {
             repository,
             api_client,
+            recently_requested,
+            response_cache,
+            config,
         }
     }
 
-    /// Get latest live data for all symbols
-    pub async fn get_all_latest_live_data(&self) -> Result<Vec<EquityLive>> {
+    /// The response cached under `key`, which names a route and its parameters, computing it
+    /// with `compute` on a miss. Concurrent misses for the same key share one computation.
+    ///
+    /// A shared computation only logs under the request that ran it, so every caller logs here,
+    /// in its own request's span, whether its response was computed for it or reused.
+    pub async fn cached_response<E, F, Fut>(
+        &self,
+        key: &str,
+        compute: F,
+    ) -> std::result::Result<Arc<CachedResponse>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<serde_json::Value, E>>,
+    {
+        let computed = std::sync::atomic::AtomicBool::new(false);
+        let result = self
+            .response_cache
+            .get_or_try_insert(key, || {
+                computed.store(true, std::sync::atomic::Ordering::Relaxed);
+                compute()
+            })
+            .await;
+
+        let computed = computed.load(std::sync::atomic::Ordering::Relaxed);
+        match (&result, computed) {
+            (Ok(_), true) => tracing::debug!("Computed response for {}", key),
+            (Ok(_), false) => tracing::debug!("Served cached response for {}", key),
+            (Err(_), _) => tracing::debug!("Computing response for {} failed", key),
+        }
+        result
+    }
+
+    /// Get latest live data for all symbols, optionally restricted to one data source
+    pub async fn get_all_latest_live_data(
+        &self,
+        source: Option<DataSource>,
+    ) -> Result<Vec<EquityLive>> {
         let symbols = self.repository.get_all_symbols().await?;
         let mut live_data = Vec::new();
 
         for symbol in symbols {
-            if let Some(data) = self.repository.get_latest_live_data(&symbol).await? {
-                live_data.push(data);
+            if let Some(data) = latest_live_data(self.repository.as_ref(), &symbol, source).await? {
+                if self.config.price_filter.is_valid(&data) {
+                    live_data.push(data);
+                }
+            }
+        }
+
+        Ok(live_data)
+    }
+
+    /// Trading status of every date from `from` to `to` inclusive
+    pub fn get_trading_calendar(&self, from: NaiveDate, to: NaiveDate) -> Vec<CalendarDay> {
+        self.config.market_calendar.days_between(from, to)
+    }
+
+    /// Most symbols a single batch request may ask for
+    pub fn max_batch_symbols(&self) -> usize {
+        self.config.max_batch_symbols
+    }
+
+    /// Get the latest live data for each of the given symbols that has any
+    pub async fn get_batch_latest_live_data(&self, symbols: &[String]) -> Result<Vec<EquityLive>> {
+        let mut live_data = Vec::with_capacity(symbols.len());
+
+        for symbol in symbols {
+            if let Some(data) = self.repository.get_latest_live_data(symbol).await? {
+                if self.config.price_filter.is_valid(&data) {
+                    live_data.push(data);
+                }
             }
         }
 
         Ok(live_data)
     }
 
-    /// Get historical data for a symbol
+    /// Get historical data for a symbol, optionally restricted to one data source
     pub async fn get_historical_data(
         &self,
         symbol: &str,
         from: DateTime<Utc>,
         to: DateTime<Utc>,
+        source: Option<DataSource>,
     ) -> Result<Vec<TimeSeriesPoint>> {
-        self.repository.get_historical_data(symbol, from, to).await
-    }
-
-    /// Get latest market summary
-    pub async fn get_latest_market_summary(&self) -> Result<Option<MarketSummary>> {
-        self.repository.get_latest_market_summary().await
+        let mut points = self
+            .repository
+            .get_historical_data(symbol, from, to)
+            .await?;
+        if let Some(source) = source {
+            points.retain(|point| point.source == source);
+        }
+        Ok(points)
     }
 
-    /// Get data for a specific symbol
-    pub async fn get_symbol_data(
+    /// Compare a symbol's daily closes against a benchmark symbol's, both rebased to 100
+    pub async fn get_benchmark_comparison(
         &self,
         symbol: &str,
-    ) -> Result<Option<(Equity, Option<EquityLive>)>> {
-        let equity = self.repository.get_latest_equity_data(symbol).await?;
-        let live_data = self.repository.get_latest_live_data(symbol).await?;
+        benchmark: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        source: Option<DataSource>,
+    ) -> Result<Vec<ComparisonPoint>> {
+        let history = self.get_historical_data(symbol, from, to, source).await?;
+        let benchmark_history = self
+            .get_historical_data(benchmark, from, to, source)
+            .await?;
 
-        if let Some(equity) = equity {
-            Ok(Some((equity, live_data)))
-        } else {
-            Ok(None)
-        }
+        Ok(rebased_comparison(
+            &analytics::daily_closes(&history),
+            &analytics::daily_closes(&benchmark_history),
+        ))
     }
 
-    /// Get latest live data for a specific symbol
-    pub async fn get_latest_live_data(&self, symbol: &str) -> Result<Option<EquityLive>> {
-        self.repository.get_latest_live_data(symbol).await
+    /// Get a symbol's daily closes within a time range next to the same closes adjusted for
+    /// its recorded splits
+    pub async fn get_adjusted_history(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        source: Option<DataSource>,
+    ) -> Result<AdjustedHistory> {
+        let history = self.get_historical_data(symbol, from, to, source).await?;
+        let splits = self.repository.get_splits(symbol).await?;
+        Ok(adjusted_history(
+            symbol,
+            &analytics::daily_closes(&history),
+            splits,
+        ))
     }
 
-    /// Fetch fresh equity data from API (on-demand)
-    pub async fn fetch_fresh_equity_data(&self, symbol: &str) -> Result<Equity> {
-        tracing::info!("Fetching fresh equity data for symbol: {}", symbol);
-        let equity = self.api_client.fetch_equity_data(symbol).await?;
+    /// Stream a symbol's historical data oldest first, reading it page by page so the whole
+    /// series is never held in memory. The stream ends early if the receiver is dropped.
+    pub fn stream_historical_data(
+        &self,
+        symbol: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        source: Option<DataSource>,
+    ) -> mpsc::Receiver<Result<TimeSeriesPoint>> {
+        let (tx, rx) = mpsc::channel(HISTORY_STREAM_PAGE_SIZE);
+        let repository = self.repository.clone();
 
-        tracing::info!(
-            "Received equity data for: {} (company: {})",
-            equity.name,
-            equity.company.name
-        );
+        tokio::spawn(async move {
+            let mut from = from;
+            loop {
+                let page = match repository
+                    .get_historical_data_page(&symbol, from, to, HISTORY_STREAM_PAGE_SIZE)
+                    .await
+                {
+                    Ok(page) => page,
+                    Err(e) => {
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                };
 
-        // Store it for future use
-        let timestamp = Utc::now();
+                let is_last_page = page.len() < HISTORY_STREAM_PAGE_SIZE;
+                // Keys have one-second resolution, so the next page starts just after this one
+                let next_from = page
+                    .last()
+                    .map(|point| point.timestamp + chrono::Duration::seconds(1));
+
+                for point in page {
+                    if source.is_none_or(|s| point.source == s) && tx.send(Ok(point)).await.is_err()
+                    {
+                        return;
+                    }
+                }
+
+                match next_from {
+                    Some(next_from) if !is_last_page => from = next_from,
+                    _ => return,
+                }
+            }
+        });
+
+        rx
+    }
+
+    /// Get a symbol's full stored history
+    async fn get_full_history(&self, symbol: &str) -> Result<Vec<TimeSeriesPoint>> {
         self.repository
-            .store_equity_data(&equity.name, &equity, timestamp)
-            .await?;
+            .get_historical_data(symbol, DateTime::<Utc>::MIN_UTC, Utc::now())
+            .await
+    }
 
-        tracing::info!("Stored equity data for: {}", equity.name);
+    /// Aggregate a symbol's points between `from` and `to` into OHLC candles
+    pub async fn get_candles(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: CandleInterval,
+    ) -> Result<Vec<Candle>> {
+        let points = self.get_historical_data(symbol, from, to, None).await?;
+        Ok(candles(&points, interval))
+    }
 
-        Ok(equity)
+    /// Get a symbol's daily closes over its full stored history, oldest first
+    pub async fn get_daily_closes(&self, symbol: &str) -> Result<Vec<(NaiveDate, f64)>> {
+        let history = self.get_full_history(symbol).await?;
+        Ok(analytics::daily_closes(&history))
+    }
+
+    /// Compute the requested figures over a symbol's daily closes up to and including `to`
+    pub async fn get_history_stats(
+        &self,
+        symbol: &str,
+        stats: &[HistoryStat],
+        to: DateTime<Utc>,
+    ) -> Result<BTreeMap<String, Option<f64>>> {
+        let mut closes = self.get_daily_closes(symbol).await?;
+        closes.retain(|(date, _)| *date <= to.date_naive());
+        Ok(history_stats(&closes, stats))
+    }
+
+    /// Estimate bid and ask levels around a symbol's latest valid price using the configured
+    /// spread, or `None` if it has no valid price
+    pub async fn get_price_ladder(&self, symbol: &str) -> Result<Option<PriceLadder>> {
+        Ok(self
+            .repository
+            .get_latest_live_data(symbol)
+            .await?
+            .filter(|data| self.config.price_filter.is_valid(data))
+            .map(|data| synthetic_ladder(symbol, data.price, &self.config.ladder)))
+    }
+
+    /// Compute a symbol's share turnover over the last `days` days from its stored volume
+    /// history and the share count of its latest equity details
+    pub async fn get_turnover(&self, symbol: &str, days: i64) -> Result<Turnover> {
+        let now = Utc::now();
+        let history = self
+            .repository
+            .get_historical_data(symbol, now - chrono::Duration::days(days), now)
+            .await?;
+        let traded_volume = traded_volume(&history, days, now);
+        let shares = self
+            .repository
+            .get_latest_equity_data(symbol)
+            .await?
+            .and_then(|equity| equity.shares);
+
+        Ok(Turnover {
+            symbol: symbol.to_string(),
+            days,
+            traded_volume,
+            shares,
+            turnover_ratio: turnover_ratio(traded_volume, shares),
+        })
+    }
+
+    /// Compute the volatility cone of a symbol's daily closes over the given window lengths
+    pub async fn get_volatility_cone(
+        &self,
+        symbol: &str,
+        windows: &[usize],
+    ) -> Result<Vec<VolatilityConeWindow>> {
+        let history = self.get_full_history(symbol).await?;
+        let closes: Vec<f64> = analytics::daily_closes(&history)
+            .into_iter()
+            .map(|(_, close)| close)
+            .collect();
+
+        Ok(volatility_cone(&closes, windows))
+    }
+
+    /// Rank every stock by its total return between `from` and `to`, using daily closes.
+    /// Also returns how many symbols were left out for lacking two closes in the window.
+    pub async fn get_relative_strength(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(Vec<RelativeStrengthEntry>, usize)> {
+        let symbols = self.repository.get_all_symbols().await?;
+        let mut series = Vec::with_capacity(symbols.len());
+
+        for symbol in symbols {
+            let history = self
+                .repository
+                .get_historical_data(&symbol, from, to)
+                .await?;
+            let closes: Vec<f64> = analytics::daily_closes(&history)
+                .into_iter()
+                .map(|(_, close)| close)
+                .collect();
+            series.push((symbol, closes));
+        }
+
+        let total = series.len();
+        let leaderboard = rank_by_total_return(series);
+        let excluded = total - leaderboard.len();
+
+        Ok((leaderboard, excluded))
+    }
+
+    /// Correlate the daily log returns of every pair of `symbols` within a time range. Pairs
+    /// sharing fewer than `MIN_CORRELATION_RETURNS` return dates are left empty.
+    pub async fn get_correlation_matrix(
+        &self,
+        symbols: &[String],
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<CorrelationMatrix> {
+        let mut series = Vec::with_capacity(symbols.len());
+        for symbol in symbols {
+            let history = self
+                .repository
+                .get_historical_data(symbol, from, to)
+                .await?;
+            let returns = dated_log_returns(&analytics::daily_closes(&history));
+            series.push((symbol.clone(), returns));
+        }
+
+        Ok(correlation_matrix(&series, MIN_CORRELATION_RETURNS))
+    }
+
+    /// Compute advancers, decliners and new 52-week highs/lows from the latest live data
+    pub async fn get_market_breadth(&self) -> Result<MarketBreadth> {
+        let now = Utc::now();
+        let symbols = self.repository.get_all_symbols().await?;
+        let mut stocks = Vec::with_capacity(symbols.len());
+
+        for symbol in symbols {
+            let Some(live) = self.repository.get_latest_live_data(&symbol).await? else {
+                continue;
+            };
+            let price_filter = &self.config.price_filter;
+            let Some(change) = price_filter
+                .plausible_change(&live)
+                .filter(|_| price_filter.is_valid(&live))
+            else {
+                continue;
+            };
+
+            let range_52w = match self.repository.get_symbol_metrics(&symbol).await? {
+                // A recent bulk recompute saves reading a year of history; widen its range with
+                // the latest price, which it may not have seen
+                Some(metrics)
+                    if now - metrics.computed_at
+                        < chrono::Duration::hours(METRICS_CACHE_MAX_AGE_HOURS) =>
+                {
+                    let (high, low) = metrics
+                        .high_52w
+                        .zip(metrics.low_52w)
+                        .unwrap_or((live.price, live.price));
+                    Some((high.max(live.price), low.min(live.price)))
+                }
+                _ => {
+                    let history = self
+                        .repository
+                        .get_historical_data(&symbol, now - chrono::Duration::weeks(52), now)
+                        .await?;
+                    analytics::metrics::fifty_two_week_range(&history, now)
+                }
+            };
+            stocks.push(BreadthInput {
+                change,
+                price: live.price,
+                range_52w,
+            });
+        }
+
+        Ok(market_breadth(&stocks))
+    }
+
+    /// Get the symbols whose latest volume is at least `threshold` times their average daily
+    /// volume over the previous `VOLUME_ALERT_ADV_DAYS` days, highest ratio first. Symbols
+    /// without volume history are left out.
+    pub async fn get_volume_alerts(&self, threshold: f64) -> Result<Vec<VolumeAlert>> {
+        let now = Utc::now();
+        // Today's volume is the one being compared, so the average stops at yesterday
+        let yesterday = now - chrono::Duration::days(1);
+        let symbols = self.repository.get_all_symbols().await?;
+        let mut alerts = Vec::new();
+
+        for symbol in symbols {
+            let Some(live) = self.repository.get_latest_live_data(&symbol).await? else {
+                continue;
+            };
+            let history = self
+                .repository
+                .get_historical_data(
+                    &symbol,
+                    yesterday - chrono::Duration::days(VOLUME_ALERT_ADV_DAYS),
+                    yesterday,
+                )
+                .await?;
+            let average = analytics::metrics::average_daily_volume(
+                &history,
+                VOLUME_ALERT_ADV_DAYS,
+                yesterday,
+            );
+            if let Some(alert) = volume_alert(&symbol, live.volume, average, threshold) {
+                alerts.push(alert);
+            }
+        }
+
+        alerts.sort_by(|a, b| b.ratio.total_cmp(&a.ratio));
+        Ok(alerts)
+    }
+
+    /// Get latest market summary
+    pub async fn get_latest_market_summary(&self) -> Result<Option<MarketSummary>> {
+        self.repository.get_latest_market_summary().await
+    }
+
+    /// Sector breakdown of the latest market summary, or `None` before one has been generated
+    pub async fn get_sector_summaries(&self) -> Result<Option<Vec<SectorSummary>>> {
+        Ok(self
+            .repository
+            .get_latest_market_summary()
+            .await?
+            .map(|summary| summary.sectors))
+    }
+
+    /// Replay the stored market summaries from `from` to `to` at one point per `step`, carrying
+    /// the previous summary forward through steps with none stored
+    pub async fn get_summary_timeline(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: chrono::Duration,
+    ) -> Result<Vec<SummaryTimelinePoint>> {
+        let summaries = self.repository.get_market_summaries(from, to).await?;
+        Ok(summary_timeline(&summaries, from, to, step))
+    }
+
+    /// Compute a market summary on the fly from records of a single data source
+    pub async fn get_market_summary_for_source(&self, source: DataSource) -> Result<MarketSummary> {
+        build_market_summary(
+            self.repository.as_ref(),
+            Some(source),
+            &self.config.price_filter,
+        )
+        .await
+    }
+
+    /// Compare the stored market summaries nearest to `from` and `to`
+    pub async fn get_snapshot_diff(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Option<SnapshotDiff>> {
+        let from_summary = self.repository.get_market_summary_nearest(from).await?;
+        let to_summary = self.repository.get_market_summary_nearest(to).await?;
+
+        Ok(match (from_summary, to_summary) {
+            (Some(from_summary), Some(to_summary)) => {
+                Some(diff_market_summaries(&from_summary, &to_summary))
+            }
+            _ => None,
+        })
+    }
+
+    /// Get recorded market-wide move events, newest first
+    pub async fn get_market_events(&self) -> Result<Vec<MarketEvent>> {
+        self.repository.get_market_events().await
+    }
+
+    /// Get the most recent completed scrape cycles, newest first
+    pub async fn get_scrape_history(&self, limit: usize) -> Result<Vec<ScrapeCycle>> {
+        self.repository.get_scrape_cycles(limit).await
+    }
+
+    /// Count the symbols with stored data
+    pub async fn count_symbols(&self) -> Result<usize> {
+        Ok(self.repository.get_all_symbols().await?.len())
+    }
+
+    /// Get symbols whose live data hasn't updated within their freshness SLA, oldest first.
+    /// Always empty outside trading hours.
+    pub async fn get_stale_symbols(&self) -> Result<Vec<StaleSymbol>> {
+        let last_updates = self.repository.get_last_updates().await?;
+        Ok(self.config.freshness_sla.stale_symbols(
+            &last_updates,
+            &self.config.market_calendar,
+            Utc::now(),
+        ))
+    }
+
+    /// Get the yield curve of every stored bond not yet matured, shortest maturity first.
+    /// Bonds without a maturity are left out.
+    pub async fn get_yield_curve(&self) -> Result<Vec<YieldCurvePoint>> {
+        let today = Utc::now().date_naive();
+        let mut curve: Vec<YieldCurvePoint> = self
+            .repository
+            .get_bonds()
+            .await?
+            .into_iter()
+            .filter_map(|bond| {
+                let maturity = bond.maturity.filter(|maturity| *maturity > today)?;
+                Some(YieldCurvePoint {
+                    code: bond.code,
+                    maturity,
+                    years_to_maturity: (maturity - today).num_days() as f64 / 365.25,
+                    yield_percent: bond.yield_percent,
+                })
+            })
+            .collect();
+        curve.sort_by(|a, b| {
+            a.maturity
+                .cmp(&b.maturity)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        Ok(curve)
+    }
+
+    /// Get announcements dated from `from` to `to` inclusive, optionally for one symbol
+    pub async fn get_announcements(
+        &self,
+        symbol: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Announcement>> {
+        self.repository.get_announcements(symbol, from, to).await
+    }
+
+    /// The `limit` symbols most often fetched on demand since startup, with their request counts
+    pub fn get_popular_symbols(&self, limit: usize) -> Vec<SymbolPopularity> {
+        self.recently_requested.popular(limit)
+    }
+
+    /// Get the webhook deliveries queued for retry or given up on
+    pub async fn get_deliveries(&self) -> Result<Vec<WebhookDelivery>> {
+        self.repository.get_deliveries().await
+    }
+
+    /// Get every stored record for a symbol
+    pub async fn get_symbol_records(&self, symbol: &str) -> Result<Vec<StoredRecord>> {
+        self.repository.get_symbol_records(symbol).await
+    }
+
+    /// Get data for a specific symbol
+    pub async fn get_symbol_data(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<(Equity, Option<EquityLive>)>> {
+        let equity = self.repository.get_latest_equity_data(symbol).await?;
+        let live_data = self.repository.get_latest_live_data(symbol).await?;
+
+        if let Some(equity) = equity {
+            Ok(Some((equity, live_data)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Get every live tick stored for a symbol since the start of the market's current day
+    pub async fn get_intraday_data(&self, symbol: &str) -> Result<Vec<TimeSeriesPoint>> {
+        let today = Utc::now()
+            .with_timezone(&self.config.market_timezone)
+            .date_naive();
+        let start_of_day = |date: chrono::NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|dt| dt.and_local_timezone(self.config.market_timezone).single())
+                .map(|dt| dt.with_timezone(&Utc))
+        };
+        let (Some(from), Some(next_day)) = (
+            start_of_day(today),
+            today.checked_add_days(Days::new(1)).and_then(start_of_day),
+        ) else {
+            anyhow::bail!("Could not determine today's boundaries");
+        };
+
+        self.repository
+            .get_intraday_data(symbol, from, next_day - chrono::Duration::seconds(1))
+            .await
+    }
+
+    /// Search symbols, sector names and company names/industries
+    pub async fn search(&self, query: &str, search_type: SearchType) -> Result<Vec<SearchResult>> {
+        let entries = self.search_entries().await?;
+        Ok(crate::domain::search(query, search_type, &entries))
+    }
+
+    /// Latest live data of the symbols classified under `sector` and `industry`, with the number
+    /// of symbols left out because no sector or industry is stored for them
+    pub async fn get_live_data_by_classification(
+        &self,
+        source: Option<DataSource>,
+        sector: Option<&str>,
+        industry: Option<&str>,
+    ) -> Result<(Vec<EquityLive>, usize)> {
+        let live_data = self.get_all_latest_live_data(source).await?;
+        let entries: HashMap<String, SearchEntry> = self
+            .search_entries()
+            .await?
+            .into_iter()
+            .map(|entry| (entry.symbol.clone(), entry))
+            .collect();
+
+        let mut matching = Vec::new();
+        let mut skipped = 0;
+        for data in live_data {
+            let classified = entries
+                .get(&data.name)
+                .and_then(|entry| matches_classification(entry, sector, industry));
+            match classified {
+                Some(true) => matching.push(data),
+                Some(false) => {}
+                None => skipped += 1,
+            }
+        }
+
+        Ok((matching, skipped))
+    }
+
+    /// Every sector with the number of symbols stored under it
+    pub async fn get_sectors(&self) -> Result<SectorBreakdown> {
+        let entries = self.search_entries().await?;
+        Ok(SectorBreakdown::of(&entries))
+    }
+
+    /// Each symbol's company name, industry and sector, from its latest stored equity data
+    async fn search_entries(&self) -> Result<Vec<SearchEntry>> {
+        let symbols = self.repository.get_all_symbols().await?;
+        let mut entries = Vec::with_capacity(symbols.len());
+
+        for symbol in symbols {
+            let company = self
+                .repository
+                .get_latest_equity_data(&symbol)
+                .await?
+                .map(|equity| equity.company);
+            entries.push(SearchEntry {
+                symbol,
+                company_name: company.as_ref().map(|c| c.name.clone()),
+                industry: company.as_ref().and_then(|c| c.industry.clone()),
+                sector: company.and_then(|c| c.sector),
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Get latest live data for a specific symbol
+    pub async fn get_latest_live_data(&self, symbol: &str) -> Result<Option<EquityLive>> {
+        self.repository.get_latest_live_data(symbol).await
+    }
+
+    /// Fetch fresh equity data from API (on-demand), unless the stored copy is still within the cache TTL
+    pub async fn fetch_fresh_equity_data(&self, symbol: &str) -> Result<Equity> {
+        let stored = self
+            .repository
+            .get_latest_equity_data_with_timestamp(symbol)
+            .await?;
+        if let Some((equity, stored_at)) = &stored {
+            if Utc::now() - *stored_at < self.config.equity_cache_ttl {
+                tracing::debug!("Serving cached equity data for symbol: {}", symbol);
+                self.recently_requested.touch(symbol);
+                return Ok(equity.clone());
+            }
+        }
+
+        tracing::info!("Fetching fresh equity data for symbol: {}", symbol);
+        let equity = match self.api_client.fetch_equity_data(symbol).await {
+            Ok(equity) => equity,
+            Err(e) if e.downcast_ref::<CircuitOpen>().is_some() => match stored {
+                // Stale details beat none while the upstream is down
+                Some((equity, stored_at)) => {
+                    tracing::warn!(
+                        "GSE API unavailable, serving equity data for {} stored at {}",
+                        symbol,
+                        stored_at
+                    );
+                    return Ok(equity);
+                }
+                None => return Err(e),
+            },
+            Err(e) => return Err(e),
+        };
+
+        tracing::info!(
+            "Received equity data for: {} (company: {})",
+            equity.name,
+            equity.company.name
+        );
+
+        // Store it for future use
+        let timestamp = Utc::now();
+        self.repository
+            .store_equity_data(&equity.name, &equity, timestamp)
+            .await?;
+
+        tracing::info!("Stored equity data for: {}", equity.name);
+
+        // Keep this symbol's details warm in the background worker
+        self.recently_requested.touch(symbol);
+
+        Ok(equity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::{equity, live, MockGseApiClient, TempDb};
+    use crate::infrastructure::RocksDbStockRepository;
+    use chrono::{Datelike, TimeZone};
+    use std::sync::atomic::Ordering;
+
+    fn fetch_use_case(temp: &TempDb, api: Arc<MockGseApiClient>) -> FetchStockDataUseCase {
+        FetchStockDataUseCase::with_config(
+            api,
+            Arc::new(RocksDbStockRepository::new(temp.db.clone())),
+            Arc::new(ResponseCache::new()),
+            FetchConfig::default(),
+        )
+    }
+
+    fn get_use_case(temp: &TempDb, api: Arc<MockGseApiClient>) -> GetStockDataUseCase {
+        GetStockDataUseCase::with_config(
+            Arc::new(RocksDbStockRepository::new(temp.db.clone())),
+            api,
+            Arc::new(RecentlyRequested::new(10)),
+            Arc::new(ResponseCache::new()),
+            QueryConfig::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn equity_within_the_ttl_is_served_from_storage() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let use_case = get_use_case(&temp, api.clone());
+        use_case
+            .repository
+            .store_equity_data("MTNGH", &equity("MTNGH", 1.5), Utc::now())
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let (equity, _) = use_case.get_symbol_data("MTNGH").await.unwrap().unwrap();
+            assert_eq!(equity.price, 1.5);
+        }
+        assert_eq!(api.equity_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn stale_equity_is_served_while_the_upstream_circuit_is_open() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let use_case = get_use_case(&temp, api.clone());
+        use_case
+            .repository
+            .store_equity_data(
+                "MTNGH",
+                &equity("MTNGH", 1.5),
+                Utc::now() - chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap();
+        api.set_circuit_open(true);
+
+        let served = use_case.fetch_fresh_equity_data("MTNGH").await.unwrap();
+        let unknown = use_case.fetch_fresh_equity_data("GCB").await.unwrap_err();
+
+        assert_eq!(served.price, 1.5);
+        assert!(unknown.downcast_ref::<CircuitOpen>().is_some());
+    }
+
+    #[tokio::test]
+    async fn equity_older_than_the_ttl_is_fetched_again() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let use_case = get_use_case(&temp, api.clone());
+        let stored_at = Utc::now() - chrono::Duration::hours(1);
+        use_case
+            .repository
+            .store_equity_data("MTNGH", &equity("MTNGH", 1.5), stored_at)
+            .await
+            .unwrap();
+
+        let equity = use_case.fetch_fresh_equity_data("MTNGH").await.unwrap();
+
+        assert_eq!(equity.price, 1.0);
+        assert_eq!(api.equity_calls.load(Ordering::SeqCst), 1);
+        let (_, refreshed_at) = use_case
+            .repository
+            .get_latest_equity_data_with_timestamp("MTNGH")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(refreshed_at > stored_at);
+    }
+
+    #[tokio::test]
+    async fn symbol_data_serves_stored_equity_without_checking_its_age() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let use_case = get_use_case(&temp, api.clone());
+        use_case
+            .repository
+            .store_equity_data(
+                "MTNGH",
+                &equity("MTNGH", 1.5),
+                Utc::now() - chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap();
+
+        let (equity, _) = use_case.get_symbol_data("MTNGH").await.unwrap().unwrap();
+
+        assert_eq!(equity.price, 1.5);
+        assert_eq!(api.equity_calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn recomputed_52_week_range_matches_a_direct_computation() {
+        let temp = TempDb::new();
+        let use_case = fetch_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let now = Utc::now();
+        for (days_ago, price) in [(400, 9.0), (200, 4.0), (100, 6.5), (1, 5.0)] {
+            use_case
+                .repository
+                .store_live_data(
+                    "MTNGH",
+                    &live("MTNGH", price, 0.0),
+                    now - chrono::Duration::days(days_ago),
+                )
+                .await
+                .unwrap();
+        }
+
+        let recomputed = use_case
+            .recompute_metrics(&WorkerStatus::new())
+            .await
+            .unwrap();
+
+        assert_eq!(recomputed, 1);
+        let cached = use_case
+            .repository
+            .get_symbol_metrics("MTNGH")
+            .await
+            .unwrap()
+            .unwrap();
+        let history = use_case
+            .repository
+            .get_historical_data("MTNGH", now - chrono::Duration::weeks(52), now)
+            .await
+            .unwrap();
+        let direct = analytics::metrics::fifty_two_week_range(&history, now);
+        assert_eq!(cached.high_52w.zip(cached.low_52w), direct);
+        assert_eq!(direct, Some((6.5, 4.0)));
+    }
+
+    #[tokio::test]
+    async fn market_breadth_reads_the_cached_52_week_range() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let now = Utc::now();
+        for (minutes_ago, price) in [(10, 2.0), (5, 3.0)] {
+            use_case
+                .repository
+                .store_live_data(
+                    "MTNGH",
+                    &live("MTNGH", price, 1.0),
+                    now - chrono::Duration::minutes(minutes_ago),
+                )
+                .await
+                .unwrap();
+        }
+        assert_eq!(use_case.get_market_breadth().await.unwrap().new_highs, 1);
+
+        use_case
+            .repository
+            .store_symbol_metrics(&SymbolMetrics {
+                symbol: "MTNGH".to_string(),
+                high_52w: Some(10.0),
+                low_52w: Some(1.0),
+                average_daily_volume: None,
+                market_cap: None,
+                computed_at: now,
+            })
+            .await
+            .unwrap();
+
+        let breadth = use_case.get_market_breadth().await.unwrap();
+        assert_eq!(breadth.advancers, 1);
+        assert_eq!(breadth.new_highs, 0);
+    }
+
+    #[tokio::test]
+    async fn a_benchmark_comparison_rebases_both_symbols_over_their_shared_days() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let day = |day: u32| Utc.with_ymd_and_hms(2024, 3, day, 15, 0, 0).unwrap();
+        let closes = [
+            ("MTNGH", 4, 1.0),
+            ("MTNGH", 5, 1.2),
+            ("MTNGH", 6, 1.5),
+            ("MTNGH", 7, 1.1),
+            ("GCB", 5, 4.0),
+            ("GCB", 7, 5.0),
+        ];
+        for (symbol, date, price) in closes {
+            use_case
+                .repository
+                .store_live_data(symbol, &live(symbol, price, 0.0), day(date))
+                .await
+                .unwrap();
+        }
+
+        let comparison = use_case
+            .get_benchmark_comparison("MTNGH", "GCB", day(1), day(8), None)
+            .await
+            .unwrap();
+
+        let points: Vec<(u32, f64, f64, bool)> = comparison
+            .iter()
+            .map(|point| {
+                (
+                    point.date.day(),
+                    (point.value * 100.0).round() / 100.0,
+                    (point.benchmark_value * 100.0).round() / 100.0,
+                    point.filled,
+                )
+            })
+            .collect();
+        assert_eq!(
+            points,
+            [
+                (5, 100.0, 100.0, false),
+                (6, 125.0, 100.0, true),
+                (7, 91.67, 125.0, false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn announcements_are_filtered_by_symbol_and_date() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let fetch = fetch_use_case(&temp, api.clone());
+        let get = get_use_case(&temp, api);
+        let date = |day: u32| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+        let announcements = [
+            ("mtngh", 4, "FY results", AnnouncementCategory::Earnings),
+            ("GCB", 5, "AGM notice", AnnouncementCategory::Agm),
+            (
+                "MTNGH",
+                12,
+                "Final dividend",
+                AnnouncementCategory::Dividend,
+            ),
+            ("MTNGH", 20, "Q1 results", AnnouncementCategory::Earnings),
+        ];
+        for (symbol, day, title, category) in announcements {
+            fetch
+                .record_announcement(
+                    symbol,
+                    date(day),
+                    title.to_string(),
+                    String::new(),
+                    category,
+                )
+                .await
+                .unwrap();
+        }
+
+        let for_mtngh = get
+            .get_announcements(Some("MTNGH"), date(1), date(15))
+            .await
+            .unwrap();
+        let market_wide = get
+            .get_announcements(None, date(5), date(20))
+            .await
+            .unwrap();
+
+        let titles = |announcements: &[Announcement]| -> Vec<String> {
+            announcements.iter().map(|a| a.title.clone()).collect()
+        };
+        assert_eq!(titles(&for_mtngh), ["FY results", "Final dividend"]);
+        assert_eq!(for_mtngh[0].symbol, "MTNGH");
+        assert_eq!(
+            titles(&market_wide),
+            ["AGM notice", "Final dividend", "Q1 results"]
+        );
+    }
+
+    #[tokio::test]
+    async fn turnover_divides_the_traded_volume_by_the_shares_outstanding() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let at = |days_ago: i64, hour: u32| {
+            (Utc::now() - chrono::Duration::days(days_ago))
+                .date_naive()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_utc()
+        };
+        // Live volumes are cumulative for the day, so each day counts its last tick
+        let ticks = [(3, 11, 1000), (3, 14, 4000), (2, 14, 6000), (1, 14, 10000)];
+        for (index, (days_ago, hour, volume)) in ticks.into_iter().enumerate() {
+            let mut data = live("MTNGH", 1.0 + index as f64 / 10.0, 0.0);
+            data.volume = volume;
+            use_case
+                .repository
+                .store_live_data("MTNGH", &data, at(days_ago, hour))
+                .await
+                .unwrap();
+        }
+        for (symbol, shares) in [("MTNGH", Some(1_000_000)), ("GCB", None)] {
+            let mut data = equity(symbol, 1.0);
+            data.shares = shares;
+            use_case
+                .repository
+                .store_equity_data(symbol, &data, at(1, 14))
+                .await
+                .unwrap();
+        }
+
+        let turnover = use_case.get_turnover("MTNGH", 30).await.unwrap();
+        let without_shares = use_case.get_turnover("GCB", 30).await.unwrap();
+
+        assert_eq!(turnover.traded_volume, 20000);
+        assert_eq!(turnover.shares, Some(1_000_000));
+        assert_eq!(turnover.turnover_ratio, Some(0.02));
+        assert_eq!(without_shares.turnover_ratio, None);
+    }
+
+    #[tokio::test]
+    async fn the_yield_curve_orders_bonds_by_maturity() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let fetch = fetch_use_case(&temp, api.clone());
+        let get = get_use_case(&temp, api);
+        let today = Utc::now().date_naive();
+        let bonds = [
+            ("GOG-5Y", Some(today + Days::new(5 * 365)), 21.0),
+            ("GOG-91D", Some(today + Days::new(91)), 25.5),
+            ("GOG-2Y", Some(today + Days::new(2 * 365)), 23.0),
+            ("UNDATED", None, 18.0),
+        ];
+        for (code, maturity, yield_percent) in bonds {
+            fetch
+                .record_bond(
+                    code,
+                    "Government of Ghana".to_string(),
+                    maturity,
+                    yield_percent,
+                )
+                .await
+                .unwrap();
+        }
+
+        let curve = get.get_yield_curve().await.unwrap();
+
+        let points: Vec<(&str, f64)> = curve
+            .iter()
+            .map(|point| (point.code.as_str(), point.yield_percent))
+            .collect();
+        assert_eq!(
+            points,
+            [("GOG-91D", 25.5), ("GOG-2Y", 23.0), ("GOG-5Y", 21.0)]
+        );
+        assert!(curve
+            .windows(2)
+            .all(|pair| pair[0].years_to_maturity < pair[1].years_to_maturity));
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_each_stored_batch_and_skip_ahead_when_lagging() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("MTNGH", 1.5, 0.1),
+            live("GCB", 5.0, -0.2),
+        ]));
+        let use_case = fetch_use_case(&temp, api);
+        let mut updates = use_case.subscribe_live_updates();
+
+        use_case.fetch_and_store_all_live_data().await.unwrap();
+        let update = updates.try_recv().unwrap();
+        for _ in 0..=LIVE_UPDATE_CAPACITY {
+            use_case.fetch_and_store_all_live_data().await.unwrap();
+        }
+
+        let names: Vec<&str> = update.data.iter().map(|data| data.name.as_str()).collect();
+        assert_eq!(names, ["MTNGH", "GCB"]);
+        assert!(matches!(
+            updates.try_recv(),
+            Err(tokio::sync::broadcast::error::TryRecvError::Lagged(1))
+        ));
+        assert!(updates.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn the_most_requested_symbol_tops_the_popularity_list() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        for symbol in ["GCB", "MTNGH", "MTNGH", "SCB", "MTNGH"] {
+            use_case.fetch_fresh_equity_data(symbol).await.unwrap();
+        }
+
+        let popular = use_case.get_popular_symbols(2);
+
+        assert_eq!(popular.len(), 2);
+        assert_eq!(popular[0].symbol, "MTNGH");
+        assert_eq!(popular[0].requests, 3);
+        assert_eq!(popular[1].requests, 1);
+    }
+
+    #[tokio::test]
+    async fn a_summary_with_half_the_symbols_stale_reports_half_completeness() {
+        let temp = TempDb::new();
+        let use_case = fetch_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let now = Utc::now();
+        for (symbol, updated) in [
+            ("MTNGH", now),
+            ("GCB", now),
+            ("SCB", now - chrono::Duration::hours(2)),
+            ("CAL", now - chrono::Duration::hours(3)),
+        ] {
+            use_case
+                .repository
+                .store_live_data(symbol, &live(symbol, 2.0, 0.1), updated)
+                .await
+                .unwrap();
+        }
+
+        use_case.generate_and_store_market_summary().await.unwrap();
+
+        let summary = use_case
+            .repository
+            .get_latest_market_summary()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.total_stocks, 4);
+        let completeness = summary.data_completeness.unwrap();
+        assert!((completeness - 0.5).abs() < 1e-9, "{}", completeness);
+    }
+
+    #[tokio::test]
+    async fn a_volume_above_the_threshold_multiple_of_its_average_is_flagged() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let now = Utc::now();
+        let mut store = Vec::new();
+        for symbol in ["MTNGH", "GCB"] {
+            for days_ago in 2..=4 {
+                store.push((symbol, 1000, now - chrono::Duration::days(days_ago)));
+            }
+        }
+        store.extend([
+            ("MTNGH", 4000, now),
+            ("GCB", 1500, now),
+            // No earlier volumes to average
+            ("CAL", 9000, now),
+        ]);
+        for (symbol, volume, timestamp) in store {
+            let mut data = live(symbol, 1.0, 0.0);
+            data.volume = volume;
+            use_case
+                .repository
+                .store_live_data(symbol, &data, timestamp)
+                .await
+                .unwrap();
+        }
+
+        let alerts = use_case.get_volume_alerts(3.0).await.unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].symbol, "MTNGH");
+        assert_eq!(alerts[0].average_daily_volume, 1000.0);
+        assert_eq!(alerts[0].ratio, 4.0);
+    }
+
+    #[tokio::test]
+    async fn degraded_mode_lasts_until_the_upstream_is_reached_again() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![live("MTNGH", 1.5, 0.1)]));
+        let use_case = fetch_use_case(&temp, api.clone());
+        api.set_failing(true);
+
+        // As at boot: an unreachable upstream puts the service in degraded mode
+        assert!(use_case.fetch_and_store_all_live_data().await.is_err());
+        use_case.mark_degraded();
+        let degraded_since = use_case.degraded_since();
+        api.set_failing(false);
+        use_case.fetch_and_store_all_live_data().await.unwrap();
+
+        assert!(degraded_since.is_some());
+        assert_eq!(use_case.degraded_since(), None);
+    }
+
+    #[tokio::test]
+    async fn the_sector_filter_skips_symbols_with_no_stored_sector() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let classified = |symbol: &str, sector: &str| {
+            let mut equity = equity(symbol, 1.0);
+            equity.company.sector = Some(sector.to_string());
+            equity
+        };
+        for (symbol, equity) in [
+            ("GCB", Some(classified("GCB", "Financials"))),
+            ("SCB", Some(classified("SCB", "Financials"))),
+            ("MTNGH", Some(classified("MTNGH", "Telecommunications"))),
+            ("CAL", Some(equity("CAL", 1.0))),
+            ("FML", None),
+        ] {
+            let data = live(symbol, 1.0, 0.0);
+            use_case
+                .repository
+                .store_live_data(symbol, &data, Utc::now())
+                .await
+                .unwrap();
+            if let Some(equity) = equity {
+                use_case
+                    .repository
+                    .store_equity_data(symbol, &equity, Utc::now())
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let (financials, skipped) = use_case
+            .get_live_data_by_classification(None, Some("FINANCIALS"), None)
+            .await
+            .unwrap();
+        let sectors = use_case.get_sectors().await.unwrap();
+
+        let mut names: Vec<&str> = financials.iter().map(|data| data.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["GCB", "SCB"]);
+        assert_eq!(skipped, 2);
+        let counts: Vec<(&str, usize)> = sectors
+            .sectors
+            .iter()
+            .map(|sector| (sector.sector.as_str(), sector.count))
+            .collect();
+        assert_eq!(counts, [("Financials", 2), ("Telecommunications", 1)]);
+        assert_eq!(sectors.unclassified, 2);
+    }
+
+    #[tokio::test]
+    async fn a_symbol_dropping_out_of_a_cycle_neither_moves_the_index_nor_records_an_event() {
+        let temp = TempDb::new();
+        let use_case = fetch_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let store = |symbol: &'static str, price: f64| {
+            let repository = use_case.repository.clone();
+            async move {
+                repository
+                    .store_live_data(symbol, &live(symbol, price, 0.0), Utc::now())
+                    .await
+                    .unwrap();
+            }
+        };
+        store("MTNGH", 2.0).await;
+        store("SCB", 20.0).await;
+        use_case.generate_and_store_market_summary().await.unwrap();
+        let first = use_case
+            .repository
+            .get_latest_market_summary()
+            .await
+            .unwrap();
+
+        // SCB's new price is rejected by the price filter, leaving only MTNGH in the summary
+        store("SCB", 0.0).await;
+        use_case.generate_and_store_market_summary().await.unwrap();
+        let second = use_case
+            .repository
+            .get_latest_market_summary()
+            .await
+            .unwrap();
+
+        assert_eq!(first.unwrap().index_level, 11.0);
+        let second = second.unwrap();
+        assert!(!second.prices.contains_key("SCB"));
+        assert_eq!(second.index_level, 11.0);
+        assert!(use_case
+            .repository
+            .get_market_events()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Store scraped and synthetic ticks: MTNGH has a scraped tick followed by a newer synthetic
+    /// one, FAKE only synthetic ticks and GCB only scraped ones
+    async fn store_mixed_sources(repository: &(dyn StockRepository + Send + Sync)) {
+        let synthetic = |symbol: &str, price: f64, change: f64| EquityLive {
+            source: DataSource::Synthetic,
+            ..live(symbol, price, change)
+        };
+        let earlier = Utc::now() - chrono::Duration::minutes(10);
+        let later = Utc::now() - chrono::Duration::minutes(5);
+        let ticks = [
+            (live("MTNGH", 2.0, 0.1), earlier),
+            (synthetic("MTNGH", 50.0, 40.0), later),
+            (synthetic("FAKE", 10.0, 5.0), later),
+            (live("GCB", 5.0, -0.5), later),
+        ];
+        for (data, timestamp) in ticks {
+            repository
+                .store_live_data(&data.name, &data, timestamp)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn listing_scraped_data_skips_synthetic_records_but_not_their_symbols() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        store_mixed_sources(use_case.repository.as_ref()).await;
+
+        let mut scraped = use_case
+            .get_all_latest_live_data(Some(DataSource::Scraped))
+            .await
+            .unwrap();
+        scraped.sort_by(|a, b| a.name.cmp(&b.name));
+        let unfiltered = use_case.get_all_latest_live_data(None).await.unwrap();
+
+        let prices: Vec<(&str, f64)> = scraped
+            .iter()
+            .map(|data| (data.name.as_str(), data.price))
+            .collect();
+        assert_eq!(prices, [("GCB", 5.0), ("MTNGH", 2.0)]);
+        assert!(scraped
+            .iter()
+            .all(|data| data.source == DataSource::Scraped));
+        assert_eq!(unfiltered.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn market_summary_for_scraped_data_excludes_synthetic_records() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        store_mixed_sources(use_case.repository.as_ref()).await;
+
+        let summary = use_case
+            .get_market_summary_for_source(DataSource::Scraped)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            summary.prices,
+            BTreeMap::from([("GCB".to_string(), 5.0), ("MTNGH".to_string(), 2.0)])
+        );
+        assert_eq!(summary.total_volume, 2000);
+        assert_eq!(summary.index_level, 3.5);
+        let gainers: Vec<&str> = summary
+            .top_gainers
+            .iter()
+            .map(|g| g.name.as_str())
+            .collect();
+        assert_eq!(gainers, ["MTNGH"]);
+        assert_eq!(summary.top_gainers[0].price, 2.0);
+    }
+
+    #[tokio::test]
+    async fn bootstrap_runs_only_when_the_store_is_empty() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("MTNGH", 1.5, 0.0),
+            live("GCB", 4.0, 0.0),
+        ]));
+        let use_case = fetch_use_case(&temp, api.clone());
+        let status = WorkerStatus::new();
+
+        assert!(use_case.claim_bootstrap(&status).await.unwrap());
+        // A second trigger while it runs is refused
+        assert!(!use_case.claim_bootstrap(&status).await.unwrap());
+        use_case.bootstrap_equities(&status).await.unwrap();
+
+        let progress = status.snapshot().bootstrap;
+        assert_eq!(progress.state, JobState::Completed);
+        assert_eq!(progress.processed, 2);
+        assert_eq!(api.equity_calls.load(Ordering::SeqCst), 2);
+
+        // On the next start the store holds data, so nothing is fetched again
+        let restarted = WorkerStatus::new();
+        assert!(!use_case.claim_bootstrap(&restarted).await.unwrap());
+        assert_eq!(restarted.snapshot().bootstrap.state, JobState::Idle);
+    }
+
+    #[tokio::test]
+    async fn a_ten_percent_index_drop_between_cycles_records_a_market_event() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("MTNGH", 2.0, 0.0),
+            live("GCB", 6.0, 0.0),
+        ]));
+        let use_case = fetch_use_case(&temp, api.clone());
+        use_case.fetch_and_store_all_live_data().await.unwrap();
+        use_case.generate_and_store_market_summary().await.unwrap();
+        *api.live.lock().unwrap() = vec![live("MTNGH", 1.8, -0.2), live("GCB", 5.4, -0.6)];
+
+        use_case.fetch_and_store_all_live_data().await.unwrap();
+        use_case.generate_and_store_market_summary().await.unwrap();
+
+        let events = use_case.repository.get_market_events().await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].previous_level, 4.0);
+        assert!((events[0].current_level - 3.6).abs() < 1e-9);
+        assert!((events[0].change_percent + 10.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn scrape_history_lists_recorded_cycles_newest_first() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let fetch = fetch_use_case(&temp, api.clone());
+        let get = get_use_case(&temp, api);
+        let start = Utc::now() - chrono::Duration::hours(3);
+        for (hour, records) in [(0, 10), (1, 20), (2, 30)] {
+            let started_at = start + chrono::Duration::hours(hour);
+            fetch
+                .record_scrape_cycle(&ScrapeCycle {
+                    started_at,
+                    completed_at: started_at + chrono::Duration::seconds(5),
+                    records,
+                })
+                .await
+                .unwrap();
+        }
+
+        let history = get.get_scrape_history(2).await.unwrap();
+
+        let records: Vec<usize> = history.iter().map(|cycle| cycle.records).collect();
+        assert_eq!(records, [30, 20]);
+        assert!(history[0].completed_at > history[1].completed_at);
+    }
+
+    #[tokio::test]
+    async fn an_on_demand_fetch_adds_the_symbol_to_the_refresh_set() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::default());
+        let use_case = get_use_case(&temp, api.clone());
+
+        use_case.fetch_fresh_equity_data("MTNGH").await.unwrap();
+
+        assert_eq!(api.equity_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(use_case.recently_requested.symbols(), ["MTNGH"]);
+    }
+
+    fn summary_at(
+        prices: &[(&str, f64)],
+        total_market_cap: f64,
+        last_updated: DateTime<Utc>,
+    ) -> MarketSummary {
+        MarketSummary {
+            total_market_cap,
+            total_volume: 1000,
+            total_stocks: prices.len(),
+            top_gainers: Vec::new(),
+            top_losers: Vec::new(),
+            index_level: 0.0,
+            prices: prices
+                .iter()
+                .map(|(symbol, price)| (symbol.to_string(), *price))
+                .collect(),
+            data_completeness: None,
+            sectors: Vec::new(),
+            last_updated,
+        }
+    }
+
+    #[tokio::test]
+    async fn snapshot_diff_compares_the_summaries_nearest_each_time() {
+        let temp = TempDb::new();
+        let use_case = get_use_case(&temp, Arc::new(MockGseApiClient::default()));
+        let week_ago = Utc::now() - chrono::Duration::days(7);
+        let now = Utc::now();
+        for summary in [
+            summary_at(
+                &[("MTNGH", 2.0), ("GCB", 5.0), ("SCB", 20.0)],
+                1000.0,
+                week_ago,
+            ),
+            summary_at(&[("MTNGH", 2.5), ("GCB", 4.0), ("CAL", 0.5)], 1100.0, now),
+        ] {
+            use_case
+                .repository
+                .store_market_summary(&summary, summary.last_updated)
+                .await
+                .unwrap();
+        }
+
+        let diff = use_case
+            .get_snapshot_diff(
+                week_ago + chrono::Duration::hours(1),
+                now - chrono::Duration::hours(1),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(diff.gainers.len(), 1);
+        assert_eq!(diff.gainers[0].symbol, "MTNGH");
+        assert_eq!(diff.gainers[0].change_percent, Some(25.0));
+        assert_eq!(diff.losers.len(), 1);
+        assert_eq!(diff.losers[0].symbol, "GCB");
+        assert_eq!(diff.losers[0].change_percent, Some(-20.0));
+        assert_eq!(diff.market_cap_change, 100.0);
+        assert_eq!(diff.market_cap_change_percent, Some(10.0));
+        assert_eq!(diff.new_symbols, ["CAL"]);
+        assert_eq!(diff.removed_symbols, ["SCB"]);
+    }
+
+    #[tokio::test]
+    async fn zero_priced_records_are_left_out_of_gainers_and_losers() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("MTNGH", 0.0, -0.5),
+            live("GCB", 5.0, -0.2),
+            live("CAL", 1.0, 0.1),
+        ]));
+        let use_case = fetch_use_case(&temp, api);
+        use_case.fetch_and_store_all_live_data().await.unwrap();
+
+        let summary =
+            build_market_summary(use_case.repository.as_ref(), None, &PriceFilter::default())
+                .await
+                .unwrap();
+
+        let names = |data: &[EquityLive]| -> Vec<String> {
+            data.iter().map(|data| data.name.clone()).collect()
+        };
+        assert_eq!(names(&summary.top_losers), ["GCB"]);
+        assert_eq!(names(&summary.top_gainers), ["CAL"]);
+        assert!(!summary.prices.contains_key("MTNGH"));
+        assert_eq!(summary.index_level, 3.0);
+    }
+
+    #[tokio::test]
+    async fn gainers_and_losers_are_ranked_by_percent_change() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("SCB", 50.5, 0.5),
+            live("CAL", 2.5, 0.5),
+            live("GCB", 5.5, 0.5),
+            live("TOTAL", 49.0, -1.0),
+            live("ACCESS", 3.0, -1.0),
+        ]));
+        let use_case = fetch_use_case(&temp, api);
+        use_case.fetch_and_store_all_live_data().await.unwrap();
+
+        let summary =
+            build_market_summary(use_case.repository.as_ref(), None, &PriceFilter::default())
+                .await
+                .unwrap();
+
+        let names = |data: &[EquityLive]| -> Vec<String> {
+            data.iter().map(|data| data.name.clone()).collect()
+        };
+        // The same 0.50 move is 25% on CAL, 10% on GCB and 1% on SCB
+        assert_eq!(names(&summary.top_gainers), ["CAL", "GCB", "SCB"]);
+        assert_eq!(names(&summary.top_losers), ["ACCESS", "TOTAL"]);
+    }
+
+    #[tokio::test]
+    async fn zero_priced_records_are_not_stored_when_skipping_on_store() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("MTNGH", 0.0, -0.5),
+            live("GCB", 5.0, -0.2),
+        ]));
+        let use_case = FetchStockDataUseCase::with_config(
+            api,
+            Arc::new(RocksDbStockRepository::new(temp.db.clone())),
+            Arc::new(ResponseCache::new()),
+            FetchConfig {
+                price_filter: PriceFilter {
+                    mode: InvalidPriceMode::SkipStore,
+                    ..PriceFilter::default()
+                },
+                ..FetchConfig::default()
+            },
+        );
+
+        let stored = use_case.fetch_and_store_all_live_data().await.unwrap();
+
+        assert_eq!(stored, 1);
+        assert_eq!(
+            use_case.repository.get_all_symbols().await.unwrap(),
+            ["GCB"]
+        );
+    }
+
+    #[test]
+    fn non_finite_changes_sort_last_without_panicking() {
+        let mut changes = [f64::NAN, 2.0, f64::INFINITY, -1.0, 5.0];
+
+        changes.sort_by(|a, b| compare_changes(*a, *b, true));
+
+        assert_eq!(changes[..3], [5.0, 2.0, -1.0]);
+        assert!(changes[3..].iter().all(|change| !change.is_finite()));
+    }
+
+    #[test]
+    fn nan_and_implausibly_large_changes_are_suspect() {
+        let filter = PriceFilter::default();
+
+        assert_eq!(filter.plausible_change(&live("MTNGH", 1.5, f64::NAN)), None);
+        assert_eq!(filter.plausible_change(&live("GCB", 11.0, 10.5)), None);
+        assert_eq!(filter.plausible_change(&live("CAL", 1.1, 0.1)), Some(0.1));
     }
 }
@@ -0,0 +1,169 @@
+use crate::domain::{EquityLive, StockRepository, Watchlist, WatchlistError, WatchlistRepository};
+use anyhow::Result;
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// One watched symbol with its latest live data, if any has been stored
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WatchedStock {
+    pub symbol: String,
+    pub live_data: Option<EquityLive>,
+}
+
+/// A watchlist with current prices joined in
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WatchlistView {
+    #[serde(flatten)]
+    pub watchlist: Watchlist,
+    pub stocks: Vec<WatchedStock>,
+}
+
+pub struct WatchlistUseCase {
+    repository: Arc<dyn WatchlistRepository + Send + Sync>,
+    /// Known symbols and the latest prices shown for watched ones
+    stock_repository: Arc<dyn StockRepository + Send + Sync>,
+}
+
+impl WatchlistUseCase {
+    pub fn new(
+        repository: Arc<dyn WatchlistRepository + Send + Sync>,
+        stock_repository: Arc<dyn StockRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            repository,
+            stock_repository,
+        }
+    }
+
+    pub async fn create_watchlist(&self, name: String) -> Result<Watchlist> {
+        let watchlist = Watchlist::new(name);
+        self.repository.create_watchlist(&watchlist).await?;
+        Ok(watchlist)
+    }
+
+    pub async fn get_all_watchlists(&self) -> Result<Vec<Watchlist>> {
+        self.repository.get_all_watchlists().await
+    }
+
+    /// Get a watchlist with the latest live data of each watched symbol
+    pub async fn get_watchlist(&self, id: &str) -> Result<Option<WatchlistView>> {
+        let Some(watchlist) = self.repository.get_watchlist(id).await? else {
+            return Ok(None);
+        };
+
+        let mut stocks = Vec::with_capacity(watchlist.symbols.len());
+        for symbol in &watchlist.symbols {
+            stocks.push(WatchedStock {
+                symbol: symbol.clone(),
+                live_data: self.stock_repository.get_latest_live_data(symbol).await?,
+            });
+        }
+
+        Ok(Some(WatchlistView { watchlist, stocks }))
+    }
+
+    /// Watch a symbol, rejecting symbols no data has been stored for. Returns `None` if the
+    /// watchlist doesn't exist.
+    pub async fn add_symbol(&self, id: &str, symbol: &str) -> Result<Option<Watchlist>> {
+        let Some(mut watchlist) = self.repository.get_watchlist(id).await? else {
+            return Ok(None);
+        };
+
+        let symbol = symbol.trim().to_uppercase();
+        let known = self.stock_repository.get_all_symbols().await?;
+        if !known.contains(&symbol) {
+            return Err(WatchlistError::UnknownSymbol { symbol }.into());
+        }
+
+        if watchlist.add_symbol(&symbol) {
+            self.repository.update_watchlist(&watchlist).await?;
+        }
+        Ok(Some(watchlist))
+    }
+
+    /// Stop watching a symbol. Returns `None` if the watchlist doesn't exist.
+    pub async fn remove_symbol(&self, id: &str, symbol: &str) -> Result<Option<Watchlist>> {
+        let Some(mut watchlist) = self.repository.get_watchlist(id).await? else {
+            return Ok(None);
+        };
+
+        if watchlist.remove_symbol(&symbol.trim().to_uppercase()) {
+            self.repository.update_watchlist(&watchlist).await?;
+        }
+        Ok(Some(watchlist))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::{live, TempDb};
+    use crate::infrastructure::{RocksDbStockRepository, RocksDbWatchlistRepository};
+    use chrono::Utc;
+
+    fn use_case(temp: &TempDb) -> WatchlistUseCase {
+        WatchlistUseCase::new(
+            Arc::new(RocksDbWatchlistRepository::new(temp.db.clone())),
+            Arc::new(RocksDbStockRepository::new(temp.db.clone())),
+        )
+    }
+
+    #[tokio::test]
+    async fn a_watchlist_shows_the_latest_price_of_each_symbol() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        RocksDbStockRepository::new(temp.db.clone())
+            .store_live_data("MTNGH", &live("MTNGH", 1.5, 0.1), Utc::now())
+            .await
+            .unwrap();
+        let watchlist = use_case
+            .create_watchlist("Telcos".to_string())
+            .await
+            .unwrap();
+
+        use_case
+            .add_symbol(&watchlist.id, " mtngh ")
+            .await
+            .unwrap()
+            .unwrap();
+        let unknown = use_case.add_symbol(&watchlist.id, "NOPE").await;
+
+        assert!(matches!(
+            unknown.unwrap_err().downcast_ref::<WatchlistError>(),
+            Some(WatchlistError::UnknownSymbol { .. })
+        ));
+        let view = use_case
+            .get_watchlist(&watchlist.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(view.watchlist.symbols, ["MTNGH"]);
+        assert_eq!(view.stocks[0].live_data.as_ref().unwrap().price, 1.5);
+    }
+
+    #[tokio::test]
+    async fn a_removed_symbol_is_no_longer_watched() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        RocksDbStockRepository::new(temp.db.clone())
+            .store_live_data("GCB", &live("GCB", 5.0, 0.0), Utc::now())
+            .await
+            .unwrap();
+        let watchlist = use_case
+            .create_watchlist("Banks".to_string())
+            .await
+            .unwrap();
+        use_case.add_symbol(&watchlist.id, "GCB").await.unwrap();
+
+        use_case.remove_symbol(&watchlist.id, "gcb").await.unwrap();
+
+        let view = use_case
+            .get_watchlist(&watchlist.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(view.stocks.is_empty());
+        assert!(use_case.get_watchlist("missing").await.unwrap().is_none());
+    }
+}
@@ -1,10 +1,17 @@
 use crate::application::use_cases::FetchStockDataUseCase;
+use crate::application::{
+    AlertUseCase, DeliveryQueue, PortfolioUseCase, RecentlyRequested, WorkerStatus,
+};
+use crate::domain::{
+    DataPruner, DiskSpaceProbe, MarketCalendar, MarketStatus, MetricsRecorder, PauseWindow,
+    ScrapeCycle, DEFAULT_CLOSE_HOUR, DEFAULT_OPEN_HOUR, ERRORS_METRIC, MARKET_TIMEZONE,
+};
 use anyhow::Result;
-use chrono::{Datelike, Timelike, Utc, Weekday};
-use std::sync::Arc;
-use std::time::Duration;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tokio::time::{interval, sleep};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 /// Configuration for the data scraping worker
 #[derive(Debug, Clone)]
@@ -19,6 +26,23 @@ pub struct WorkerConfig {
     pub fetch_equity_data: bool,
     /// Whether to generate market summary
     pub generate_market_summary: bool,
+    /// Whether to refresh details of symbols recently fetched on demand
+    pub refresh_requested_symbols: bool,
+    /// Daily windows (market time) during which trading pauses and scraping is skipped
+    pub pause_windows: Vec<PauseWindow>,
+    /// Hour the market opens, in Accra time
+    pub market_open_hour: u32,
+    /// Hour the market closes, in Accra time
+    pub market_close_hour: u32,
+    /// Market holidays on which scraping is skipped
+    pub holidays: Vec<NaiveDate>,
+    /// URL notified after each completed scrape cycle
+    pub scrape_webhook_url: Option<String>,
+    /// Scraping pauses while free disk space is below this many bytes (0 disables the check)
+    pub min_free_disk_bytes: u64,
+    /// Live ticks and equity details older than this many days are deleted, keeping each
+    /// symbol's latest record; `None` keeps everything
+    pub retention_days: Option<i64>,
 }
 
 impl Default for WorkerConfig {
@@ -29,39 +53,89 @@ impl Default for WorkerConfig {
             retry_delay: 5,
             fetch_equity_data: true,
             generate_market_summary: true,
+            refresh_requested_symbols: true,
+            pause_windows: Vec::new(),
+            market_open_hour: DEFAULT_OPEN_HOUR,
+            market_close_hour: DEFAULT_CLOSE_HOUR,
+            holidays: Vec::new(),
+            scrape_webhook_url: None,
+            min_free_disk_bytes: 0,
+            retention_days: None,
         }
     }
 }
 
+impl WorkerConfig {
+    /// Build the market calendar used to decide when to scrape
+    pub fn market_calendar(&self) -> MarketCalendar {
+        MarketCalendar::new(self.pause_windows.clone(), self.holidays.clone())
+            .with_trading_hours(self.market_open_hour, self.market_close_hour)
+    }
+}
+
+/// Services the worker calls on besides the scraping use case
+pub struct WorkerDependencies {
+    pub recently_requested: Arc<RecentlyRequested>,
+    pub deliveries: Arc<DeliveryQueue>,
+    pub portfolio_use_case: Arc<PortfolioUseCase>,
+    pub alert_use_case: Arc<AlertUseCase>,
+    pub disk_probe: Arc<dyn DiskSpaceProbe + Send + Sync>,
+    pub pruner: Arc<dyn DataPruner + Send + Sync>,
+    pub status: Arc<WorkerStatus>,
+    pub metrics: Arc<dyn MetricsRecorder + Send + Sync>,
+}
+
 /// Background worker for scraping GSE data
 pub struct DataScrapingWorker {
     use_case: Arc<FetchStockDataUseCase>,
     config: WorkerConfig,
+    recently_requested: Arc<RecentlyRequested>,
+    deliveries: Arc<DeliveryQueue>,
+    portfolio_use_case: Arc<PortfolioUseCase>,
+    alert_use_case: Arc<AlertUseCase>,
+    disk_probe: Arc<dyn DiskSpaceProbe + Send + Sync>,
+    pruner: Arc<dyn DataPruner + Send + Sync>,
+    status: Arc<WorkerStatus>,
+    metrics: Arc<dyn MetricsRecorder + Send + Sync>,
+    /// Market status seen on the previous tick, so skips are logged once per closure
+    last_status: Mutex<Option<MarketStatus>>,
+    /// When records past the retention window were last pruned
+    last_pruned: Mutex<Option<DateTime<Utc>>>,
 }
 
-impl DataScrapingWorker {
-    pub fn new(use_case: Arc<FetchStockDataUseCase>, config: WorkerConfig) -> Self {
-        Self { use_case, config }
-    }
-
-    /// Check if current time is within GSE trading hours
-    /// Trading hours: Monday-Friday, 10:00 AM - 3:00 PM GMT
-    fn is_trading_hours() -> bool {
-        let now = Utc::now();
-
-        // Check if it's a weekday (Monday = 0, Sunday = 6)
-        let is_weekday = matches!(
-            now.weekday(),
-            Weekday::Mon | Weekday::Tue | Weekday::Wed | Weekday::Thu | Weekday::Fri
-        );
+/// Minimum time between two retention prunes
+const PRUNE_INTERVAL_HOURS: i64 = 24;
 
-        if !is_weekday {
-            return false;
+impl DataScrapingWorker {
+    pub fn new(
+        use_case: Arc<FetchStockDataUseCase>,
+        config: WorkerConfig,
+        dependencies: WorkerDependencies,
+    ) -> Self {
+        let WorkerDependencies {
+            recently_requested,
+            deliveries,
+            portfolio_use_case,
+            alert_use_case,
+            disk_probe,
+            pruner,
+            status,
+            metrics,
+        } = dependencies;
+        Self {
+            use_case,
+            config,
+            recently_requested,
+            deliveries,
+            portfolio_use_case,
+            alert_use_case,
+            disk_probe,
+            pruner,
+            status,
+            metrics,
+            last_status: Mutex::new(None),
+            last_pruned: Mutex::new(None),
         }
-
-        // Check if time is between 10:00 and 15:00 (3:00 PM)
-        let hour = now.hour();
-        hour >= 10 && hour < 15
     }
 
     /// Start the worker with the configured interval
@@ -85,32 +159,209 @@ impl DataScrapingWorker {
         }
     }
 
-    /// Run a complete scrape cycle
-    async fn run_scrape_cycle(&self) -> Result<()> {
+    /// Store the end-of-day valuation of each portfolio missing one for the last closed session,
+    /// logging rather than failing the tick on error
+    async fn snapshot_portfolios(&self, now: DateTime<Utc>) {
+        match self
+            .portfolio_use_case
+            .snapshot_missing_portfolios(now)
+            .await
+        {
+            Ok(0) => {}
+            Ok(count) => info!("Stored end-of-day valuation for {} portfolios", count),
+            Err(e) => error!("Failed to snapshot portfolio valuations: {}", e),
+        }
+    }
+
+    /// Delete records older than the retention window, at most once per prune interval.
+    /// Failures are logged and retried on the next tick.
+    async fn prune_expired(&self) {
+        let Some(retention_days) = self.config.retention_days else {
+            return;
+        };
         let now = Utc::now();
-        info!("Starting scrape cycle at {}", now);
+        let due = self
+            .last_pruned
+            .lock()
+            .unwrap()
+            .is_none_or(|last| now - last >= chrono::Duration::hours(PRUNE_INTERVAL_HOURS));
+        if !due {
+            return;
+        }
 
-        // Check if we're within trading hours
-        if !Self::is_trading_hours() {
+        let cutoff = now - chrono::Duration::days(retention_days);
+        match self.pruner.prune_older_than(cutoff).await {
+            Ok(summary) => {
+                info!(
+                    "Pruned {} records older than {} across {} symbols",
+                    summary.records_deleted, cutoff, summary.symbols
+                );
+                *self.last_pruned.lock().unwrap() = Some(now);
+                if summary.records_deleted > 0 {
+                    self.use_case.invalidate_responses();
+                }
+            }
+            Err(e) => error!("Failed to prune expired records: {}", e),
+        }
+    }
+
+    /// Check free disk space, recording it in the worker status.
+    ///
+    /// Returns `true` when space is below the configured minimum and writes should be skipped.
+    /// A failing probe never pauses scraping.
+    fn disk_space_low(&self) -> bool {
+        if self.config.min_free_disk_bytes == 0 {
+            return false;
+        }
+
+        let available = match self.disk_probe.available_bytes() {
+            Ok(available) => available,
+            Err(e) => {
+                warn!("Failed to check free disk space: {}", e);
+                return false;
+            }
+        };
+        let low = available < self.config.min_free_disk_bytes;
+
+        let was_low = self.status.snapshot().disk.low_space;
+        if low && !was_low {
+            warn!(
+                "Free disk space ({} bytes) is below the configured minimum of {} bytes. Pausing scrapes until space is reclaimed.",
+                available, self.config.min_free_disk_bytes
+            );
+        } else if !low && was_low {
             info!(
-                "Outside trading hours (Monday-Friday 10:00-15:00 GMT). Current time: {} ({}). Skipping scrape.",
-                now.format("%Y-%m-%d %H:%M:%S GMT"),
-                now.weekday()
+                "Free disk space recovered ({} bytes). Resuming scrapes.",
+                available
             );
+        }
+
+        self.status.update(|s| {
+            s.disk.available_bytes = Some(available);
+            s.disk.low_space = low;
+        });
+        low
+    }
+
+    /// Run a complete scrape cycle
+    async fn run_scrape_cycle(&self) -> Result<()> {
+        self.run_scrape_cycle_at(Utc::now()).await
+    }
+
+    /// Run a scrape cycle as of `now`, which decides whether the market is trading
+    async fn run_scrape_cycle_at(&self, now: DateTime<Utc>) -> Result<()> {
+        // Failed webhooks are retried whether or not the market is open
+        if let Err(e) = self.deliveries.retry_due().await {
+            error!("Failed to retry queued webhook deliveries: {}", e);
+        }
+
+        // Retention is enforced whether or not the market is open, and even while disk space is
+        // low, since pruning is what frees the space that lets scraping resume
+        self.prune_expired().await;
+
+        let status = self.config.market_calendar().status_at(now);
+        // Value portfolios at the close whenever the last session's snapshot is missing, so a
+        // close missed while the worker was down, paused for disk space, or in a trading pause
+        // is caught up on the next closed tick. Snapshots are small enough to write on low disk.
+        if matches!(
+            status,
+            MarketStatus::Weekend | MarketStatus::Holiday | MarketStatus::OffHours
+        ) {
+            self.snapshot_portfolios(now).await;
+        }
+
+        // Stop writing before the disk fills up
+        if self.disk_space_low() {
             return Ok(());
         }
 
+        let previous = self.last_status.lock().unwrap().replace(status);
+        // Log skips only on the first tick after trading stops, not on every tick while closed
+        let was_trading = previous.is_none_or(|previous| previous == MarketStatus::Open);
+
+        // Check if we're within trading hours
+        match status {
+            MarketStatus::Open => {
+                if !was_trading {
+                    info!(
+                        "Trading resumed. Current time: {}",
+                        now.format("%Y-%m-%d %H:%M:%S GMT")
+                    );
+                }
+            }
+            MarketStatus::Paused if was_trading => {
+                info!(
+                    "Within a configured trading pause. Current time: {}. Skipping scrapes until trading resumes.",
+                    now.format("%Y-%m-%d %H:%M:%S GMT")
+                );
+                return Ok(());
+            }
+            MarketStatus::Holiday if was_trading => {
+                info!(
+                    "Market holiday. Current time: {}. Skipping scrapes until trading resumes.",
+                    now.format("%Y-%m-%d %H:%M:%S GMT")
+                );
+                return Ok(());
+            }
+            MarketStatus::Weekend | MarketStatus::OffHours if was_trading => {
+                if status == MarketStatus::Weekend {
+                    info!(
+                        "Weekend. Current time: {} ({}). Skipping scrapes until trading resumes.",
+                        now.format("%Y-%m-%d %H:%M:%S GMT"),
+                        now.weekday()
+                    );
+                } else {
+                    info!(
+                        "Outside trading hours ({:02}:00-{:02}:00 Accra time). Current time: {}. Skipping scrapes until trading resumes.",
+                        self.config.market_open_hour,
+                        self.config.market_close_hour,
+                        now.with_timezone(&MARKET_TIMEZONE).format("%Y-%m-%d %H:%M:%S %Z")
+                    );
+                }
+                return Ok(());
+            }
+            MarketStatus::Paused
+            | MarketStatus::Weekend
+            | MarketStatus::Holiday
+            | MarketStatus::OffHours => {
+                debug!("Market closed ({:?}), skipping scrape", status);
+                return Ok(());
+            }
+        }
+
+        info!("Starting scrape cycle at {}", now);
+
         info!("Within trading hours. Proceeding with data scrape.");
 
+        // Wait out any manually triggered scrape rather than hitting the upstream alongside it
+        let _scrape = self.use_case.lock_scrapes().await;
+        let started = Instant::now();
+
         // Fetch live data
-        if let Err(e) = self
+        let records = match self
             .fetch_with_retry("live data", || {
                 self.use_case.fetch_and_store_all_live_data()
             })
             .await
         {
-            error!("Failed to fetch live data: {}", e);
-            return Err(e);
+            Ok(records) => records,
+            Err(e) => {
+                error!("Failed to fetch live data: {}", e);
+                self.record_cycle_metrics(started, false);
+                return Err(e);
+            }
+        };
+
+        // Notify alerts crossed by the prices just stored
+        match self.alert_use_case.check_alerts().await {
+            Ok(0) => {}
+            Ok(fired) => info!("{} price alerts fired", fired),
+            Err(e) => warn!("Failed to check price alerts: {}", e),
+        }
+
+        // Chart each portfolio's value at the prices just stored
+        if let Err(e) = self.portfolio_use_case.record_all_values(now).await {
+            warn!("Failed to record portfolio values: {}", e);
         }
 
         // Fetch equity data if enabled (but less frequently to avoid rate limits)
@@ -120,6 +371,15 @@ impl DataScrapingWorker {
             info!("Skipping equity data fetch to avoid rate limits");
         }
 
+        // Refresh details of symbols recently fetched on demand so popular symbols stay warm
+        if self.config.refresh_requested_symbols {
+            for symbol in self.recently_requested.symbols() {
+                if let Err(e) = self.use_case.fetch_and_store_equity_data(&symbol).await {
+                    warn!("Failed to refresh equity data for {}: {}", symbol, e);
+                }
+            }
+        }
+
         // Generate market summary if enabled
         if self.config.generate_market_summary {
             if let Err(e) = self
@@ -133,23 +393,74 @@ impl DataScrapingWorker {
             }
         }
 
-        info!("Completed scrape cycle at {}", Utc::now());
+        let cycle = ScrapeCycle {
+            started_at: now,
+            completed_at: Utc::now(),
+            records,
+        };
+        if let Err(e) = self.use_case.record_scrape_cycle(&cycle).await {
+            warn!("Failed to record scrape cycle: {}", e);
+        }
+
+        self.notify_scrape_completed(&cycle);
+        self.record_cycle_metrics(started, true);
+
+        info!("Completed scrape cycle at {}", cycle.completed_at);
         Ok(())
     }
 
+    /// Count a finished scrape cycle and record how long it took
+    fn record_cycle_metrics(&self, started: Instant, succeeded: bool) {
+        let outcome = if succeeded { "success" } else { "failure" };
+        self.metrics
+            .increment("gse_scrape_cycles_total", &[("outcome", outcome)]);
+        self.metrics.observe(
+            "gse_scrape_cycle_duration_seconds",
+            &[],
+            started.elapsed().as_secs_f64(),
+        );
+        if !succeeded {
+            self.metrics
+                .increment(ERRORS_METRIC, &[("type", "scrape_cycle")]);
+        }
+    }
+
+    /// Best-effort notification of the configured webhook, without blocking the cycle
+    fn notify_scrape_completed(&self, cycle: &ScrapeCycle) {
+        let url = match &self.config.scrape_webhook_url {
+            Some(url) => url.clone(),
+            None => return,
+        };
+
+        let payload = serde_json::json!({
+            "event": "scrape_completed",
+            "cycle_timestamp": cycle.completed_at,
+            "started_at": cycle.started_at,
+            "record_count": cycle.records,
+            "market_open": self.config.market_calendar().is_trading_time(cycle.started_at),
+        });
+
+        let deliveries = self.deliveries.clone();
+        tokio::spawn(async move {
+            if let Err(e) = deliveries.deliver(&url, payload).await {
+                warn!("Failed to queue scrape webhook to {}: {}", url, e);
+            }
+        });
+    }
+
     /// Execute an operation with retry logic
-    async fn fetch_with_retry<F, Fut>(&self, operation_name: &str, operation: F) -> Result<()>
+    async fn fetch_with_retry<F, Fut, T>(&self, operation_name: &str, operation: F) -> Result<T>
     where
         F: Fn() -> Fut,
-        Fut: std::future::Future<Output = Result<()>>,
+        Fut: std::future::Future<Output = Result<T>>,
     {
         let mut retries = 0;
 
-        while retries <= self.config.max_retries {
+        loop {
             match operation().await {
-                Ok(()) => {
+                Ok(value) => {
                     info!("Successfully completed {}", operation_name);
-                    return Ok(());
+                    return Ok(value);
                 }
                 Err(e) if retries < self.config.max_retries => {
                     retries += 1;
@@ -165,7 +476,244 @@ impl DataScrapingWorker {
                 }
             }
         }
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{DeliveryConfig, FetchConfig, ResponseCache};
+    use crate::infrastructure::test_support::{live, MockGseApiClient, MockWebhookServer, TempDb};
+    use crate::infrastructure::{
+        FsDiskSpaceProbe, PrometheusMetrics, PublicWebhookTargets, RocksDbAlertRepository,
+        RocksDbPortfolioRepository, RocksDbStockRepository, StaticFxRateProvider,
+        WebhookClientImpl,
+    };
+    use chrono::TimeZone;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    /// Collects the message of every event logged while it is the default subscriber
+    #[derive(Clone, Default)]
+    struct LoggedMessages(Arc<Mutex<Vec<String>>>);
+
+    struct MessageVisitor<'a>(&'a mut String);
+
+    impl Visit for MessageVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{:?}", value);
+            }
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for LoggedMessages {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            let mut message = String::new();
+            event.record(&mut MessageVisitor(&mut message));
+            self.0.lock().unwrap().push(message);
+        }
+    }
+
+    impl LoggedMessages {
+        fn containing(&self, text: &str) -> usize {
+            self.0
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|message| message.contains(text))
+                .count()
+        }
+    }
+
+    fn worker(
+        temp: &TempDb,
+        api: Arc<MockGseApiClient>,
+        config: WorkerConfig,
+    ) -> DataScrapingWorker {
+        worker_with_probe(
+            temp,
+            api,
+            config,
+            Arc::new(FsDiskSpaceProbe::new(std::env::temp_dir())),
+        )
+    }
+
+    fn worker_with_probe(
+        temp: &TempDb,
+        api: Arc<MockGseApiClient>,
+        config: WorkerConfig,
+        disk_probe: Arc<dyn DiskSpaceProbe + Send + Sync>,
+    ) -> DataScrapingWorker {
+        let repository = Arc::new(RocksDbStockRepository::new(temp.db.clone()));
+        let deliveries = Arc::new(DeliveryQueue::new(
+            Arc::new(WebhookClientImpl::new().allowing_private_targets()),
+            repository.clone(),
+            DeliveryConfig::default(),
+        ));
+        let portfolio_use_case = Arc::new(PortfolioUseCase::new(
+            Arc::new(RocksDbPortfolioRepository::new(temp.db.clone())),
+            repository.clone(),
+            Arc::new(StaticFxRateProvider::new(Default::default())),
+            config.market_calendar(),
+        ));
+        let alert_use_case = Arc::new(AlertUseCase::new(
+            Arc::new(RocksDbAlertRepository::new(temp.db.clone())),
+            repository.clone(),
+            deliveries.clone(),
+            Arc::new(PublicWebhookTargets),
+            chrono::Duration::hours(1),
+        ));
+
+        DataScrapingWorker::new(
+            Arc::new(FetchStockDataUseCase::with_config(
+                api,
+                repository.clone(),
+                Arc::new(ResponseCache::new()),
+                FetchConfig::default(),
+            )),
+            config,
+            WorkerDependencies {
+                recently_requested: Arc::new(RecentlyRequested::new(10)),
+                deliveries,
+                portfolio_use_case,
+                alert_use_case,
+                disk_probe,
+                pruner: repository,
+                status: Arc::new(WorkerStatus::new()),
+                metrics: Arc::new(PrometheusMetrics::new()),
+            },
+        )
+    }
+
+    /// Disk whose free space the test sets
+    #[derive(Default)]
+    struct FakeDisk {
+        available: AtomicU64,
+    }
+
+    impl DiskSpaceProbe for FakeDisk {
+        fn available_bytes(&self) -> Result<u64> {
+            Ok(self.available.load(Ordering::SeqCst))
+        }
+    }
+
+    /// A Wednesday in market time
+    fn wednesday_at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 6, hour, minute, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn scrapes_are_skipped_during_a_midday_pause() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![live("MTNGH", 1.5, 0.0)]));
+        let worker = worker(
+            &temp,
+            api.clone(),
+            WorkerConfig {
+                pause_windows: PauseWindow::parse_list("12:00-12:30"),
+                ..WorkerConfig::default()
+            },
+        );
+
+        worker
+            .run_scrape_cycle_at(wednesday_at(12, 15))
+            .await
+            .unwrap();
+        let calls_in_pause = api.live_calls.load(Ordering::SeqCst);
+        worker
+            .run_scrape_cycle_at(wednesday_at(12, 45))
+            .await
+            .unwrap();
+
+        assert_eq!(calls_in_pause, 0);
+        assert_eq!(api.live_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn a_completed_cycle_posts_to_the_scrape_webhook() {
+        let temp = TempDb::new();
+        let mut server = MockWebhookServer::start().await;
+        let api = Arc::new(MockGseApiClient::with_live(vec![
+            live("MTNGH", 1.5, 0.0),
+            live("GCB", 4.0, 0.0),
+        ]));
+        let worker = worker(
+            &temp,
+            api,
+            WorkerConfig {
+                scrape_webhook_url: Some(server.url.clone()),
+                ..WorkerConfig::default()
+            },
+        );
+
+        worker
+            .run_scrape_cycle_at(wednesday_at(11, 0))
+            .await
+            .unwrap();
+
+        let payload = server.next_payload().await;
+        assert_eq!(payload["event"], "scrape_completed");
+        assert_eq!(payload["record_count"], 2);
+        assert_eq!(payload["market_open"], true);
+    }
+
+    #[tokio::test]
+    async fn a_closed_market_is_logged_once_until_trading_resumes() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![live("MTNGH", 1.5, 0.0)]));
+        let worker = worker(&temp, api, WorkerConfig::default());
+        let logs = LoggedMessages::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(logs.clone()));
+
+        for hour in [18, 19, 20, 21] {
+            worker
+                .run_scrape_cycle_at(wednesday_at(hour, 0))
+                .await
+                .unwrap();
+        }
+        let thursday_open = wednesday_at(11, 0) + chrono::Duration::days(1);
+        worker.run_scrape_cycle_at(thursday_open).await.unwrap();
+
+        assert_eq!(logs.containing("Skipping scrapes"), 1);
+        assert_eq!(logs.containing("Trading resumed"), 1);
+    }
+
+    #[tokio::test]
+    async fn writes_are_skipped_while_disk_space_is_low() {
+        let temp = TempDb::new();
+        let api = Arc::new(MockGseApiClient::with_live(vec![live("MTNGH", 1.5, 0.0)]));
+        let disk = Arc::new(FakeDisk::default());
+        disk.available.store(1_000, Ordering::SeqCst);
+        let worker = worker_with_probe(
+            &temp,
+            api.clone(),
+            WorkerConfig {
+                min_free_disk_bytes: 10_000,
+                ..WorkerConfig::default()
+            },
+            disk.clone(),
+        );
+
+        worker
+            .run_scrape_cycle_at(wednesday_at(11, 0))
+            .await
+            .unwrap();
+        let calls_while_low = api.live_calls.load(Ordering::SeqCst);
+        let status_while_low = worker.status.snapshot().disk;
+        disk.available.store(50_000, Ordering::SeqCst);
+        worker
+            .run_scrape_cycle_at(wednesday_at(12, 0))
+            .await
+            .unwrap();
+
+        assert_eq!(calls_while_low, 0);
+        assert!(status_while_low.low_space);
+        assert_eq!(status_while_low.available_bytes, Some(1_000));
+        assert_eq!(api.live_calls.load(Ordering::SeqCst), 1);
+        assert!(!worker.status.snapshot().disk.low_space);
     }
 }
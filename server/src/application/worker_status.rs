@@ -0,0 +1,86 @@
+use serde::Serialize;
+use std::sync::RwLock;
+
+/// State of a one-off background job
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    #[default]
+    Idle,
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of a one-off background job
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct JobProgress {
+    pub state: JobState,
+    /// Number of items to process
+    pub total: usize,
+    /// Number of items processed so far
+    pub processed: usize,
+}
+
+/// Free space on the database volume, as of the last scrape cycle
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiskStatus {
+    pub available_bytes: Option<u64>,
+    /// Whether scraping is paused until space is reclaimed
+    pub low_space: bool,
+}
+
+/// Point-in-time view of the background worker's state
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkerStatusSnapshot {
+    /// One-time equity bootstrap of a fresh deployment
+    pub bootstrap: JobProgress,
+    /// Bulk recompute of derived per-symbol metrics
+    pub recompute_metrics: JobProgress,
+    pub disk: DiskStatus,
+}
+
+/// Shared worker state, updated by background tasks and read by the status endpoint
+#[derive(Debug, Default)]
+pub struct WorkerStatus {
+    inner: RwLock<WorkerStatusSnapshot>,
+}
+
+impl WorkerStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a copy of the current status
+    pub fn snapshot(&self) -> WorkerStatusSnapshot {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// Mark a job as running unless it already is.
+    ///
+    /// Returns `false` when the job was already running, so callers can reject duplicate triggers.
+    #[must_use]
+    pub fn try_start_job<F>(&self, job: F) -> bool
+    where
+        F: FnOnce(&mut WorkerStatusSnapshot) -> &mut JobProgress,
+    {
+        let mut inner = self.inner.write().unwrap();
+        let progress = job(&mut inner);
+        if progress.state == JobState::Running {
+            return false;
+        }
+        *progress = JobProgress {
+            state: JobState::Running,
+            ..JobProgress::default()
+        };
+        true
+    }
+
+    /// Apply a change to the current status
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut WorkerStatusSnapshot),
+    {
+        f(&mut self.inner.write().unwrap());
+    }
+}
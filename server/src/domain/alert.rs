@@ -0,0 +1,201 @@
+use crate::domain::EquityLive;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// What a symbol's live data must do for an alert to fire
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Price at or above the threshold
+    Above { price: f64 },
+    /// Price at or below the threshold
+    Below { price: f64 },
+    /// Change from the previous close of at least this percent, either way
+    PercentMove { percent: f64 },
+}
+
+impl AlertCondition {
+    pub fn is_met(&self, data: &EquityLive) -> bool {
+        match *self {
+            AlertCondition::Above { price } => data.price >= price,
+            AlertCondition::Below { price } => data.price <= price,
+            AlertCondition::PercentMove { percent } => data
+                .change_percent()
+                .is_some_and(|change| change.abs() >= percent),
+        }
+    }
+}
+
+/// A condition on one symbol's live data, notifying a webhook when it starts to hold
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Alert {
+    pub id: String,
+    pub symbol: String,
+    pub condition: AlertCondition,
+    pub webhook_url: String,
+    pub created_at: DateTime<Utc>,
+    /// When the alert last fired
+    pub fired_at: Option<DateTime<Utc>>,
+    /// Whether the condition held at the last evaluation; the alert only fires again once it has
+    /// stopped holding in between
+    #[serde(default)]
+    pub triggered: bool,
+}
+
+impl Alert {
+    pub fn new(symbol: String, condition: AlertCondition, webhook_url: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            symbol,
+            condition,
+            webhook_url,
+            created_at: Utc::now(),
+            fired_at: None,
+            triggered: false,
+        }
+    }
+
+    /// Evaluate the alert against the symbol's latest live data, returning whether it fires.
+    ///
+    /// It fires when the condition starts to hold, unless it already fired less than `cooldown`
+    /// before `now`, so prices hovering around a threshold don't fire it on every cycle.
+    pub fn evaluate(
+        &mut self,
+        data: &EquityLive,
+        now: DateTime<Utc>,
+        cooldown: chrono::Duration,
+    ) -> bool {
+        let met = self.condition.is_met(data);
+        let newly_met = met && !self.triggered;
+        self.triggered = met;
+
+        let cooling_down = self
+            .fired_at
+            .is_some_and(|fired_at| now - fired_at < cooldown);
+        if newly_met && !cooling_down {
+            self.fired_at = Some(now);
+            return true;
+        }
+        false
+    }
+}
+
+/// A webhook URL that alerts may not deliver to
+#[derive(Debug)]
+pub struct RejectedWebhookUrl {
+    pub reason: String,
+}
+
+impl std::fmt::Display for RejectedWebhookUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "webhook_url not allowed: {}", self.reason)
+    }
+}
+
+impl std::error::Error for RejectedWebhookUrl {}
+
+/// Decides which user-supplied webhook URLs alerts may deliver to, so the service can't be
+/// pointed at hosts inside its own network
+#[async_trait::async_trait]
+pub trait WebhookTargetPolicy {
+    /// `Ok` if the URL may receive deliveries, a [`RejectedWebhookUrl`] saying why not otherwise
+    async fn check(&self, url: &str) -> anyhow::Result<()>;
+}
+
+#[async_trait::async_trait]
+pub trait AlertRepository {
+    async fn store_alert(&self, alert: &Alert) -> anyhow::Result<()>;
+    async fn get_alerts(&self) -> anyhow::Result<Vec<Alert>>;
+    /// Delete an alert, returning whether it existed
+    async fn delete_alert(&self, id: &str) -> anyhow::Result<bool>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn live(price: f64, change: f64) -> EquityLive {
+        EquityLive {
+            change,
+            name: "MTNGH".to_string(),
+            price,
+            volume: 1000,
+            source: Default::default(),
+        }
+    }
+
+    fn alert(condition: AlertCondition) -> Alert {
+        Alert::new(
+            "MTNGH".to_string(),
+            condition,
+            "https://example.com/hook".to_string(),
+        )
+    }
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn above_fires_once_the_price_reaches_the_threshold() {
+        let mut alert = alert(AlertCondition::Above { price: 3.0 });
+
+        assert!(!alert.evaluate(&live(2.9, 0.0), at(0), Duration::zero()));
+        assert!(alert.evaluate(&live(3.0, 0.0), at(1), Duration::zero()));
+        assert_eq!(alert.fired_at, Some(at(1)));
+    }
+
+    #[test]
+    fn below_fires_once_the_price_falls_to_the_threshold() {
+        let mut alert = alert(AlertCondition::Below { price: 2.5 });
+
+        assert!(!alert.evaluate(&live(2.6, 0.0), at(0), Duration::zero()));
+        assert!(alert.evaluate(&live(2.4, 0.0), at(1), Duration::zero()));
+    }
+
+    #[test]
+    fn percent_move_fires_on_a_large_move_either_way() {
+        let mut rise = alert(AlertCondition::PercentMove { percent: 5.0 });
+        let mut fall = alert(AlertCondition::PercentMove { percent: 5.0 });
+
+        // 0.04 on a previous close of 2.00 is a 2% move
+        assert!(!rise.evaluate(&live(2.04, 0.04), at(0), Duration::zero()));
+        // 0.12 on a previous close of 2.00 is a 6% move
+        assert!(rise.evaluate(&live(2.12, 0.12), at(1), Duration::zero()));
+        assert!(fall.evaluate(&live(1.88, -0.12), at(1), Duration::zero()));
+    }
+
+    #[test]
+    fn a_held_condition_fires_again_only_after_clearing() {
+        let mut alert = alert(AlertCondition::Below { price: 2.5 });
+
+        assert!(alert.evaluate(&live(2.4, 0.0), at(0), Duration::zero()));
+        assert!(!alert.evaluate(&live(2.3, 0.0), at(1), Duration::zero()));
+        assert!(alert.triggered);
+
+        assert!(!alert.evaluate(&live(2.6, 0.0), at(2), Duration::zero()));
+        assert!(!alert.triggered);
+        assert!(alert.evaluate(&live(2.4, 0.0), at(3), Duration::zero()));
+        assert_eq!(alert.fired_at, Some(at(3)));
+    }
+
+    #[test]
+    fn re_arming_within_the_cooldown_does_not_fire() {
+        let mut alert = alert(AlertCondition::Below { price: 2.5 });
+        let cooldown = Duration::minutes(30);
+
+        assert!(alert.evaluate(&live(2.4, 0.0), at(0), cooldown));
+        assert!(!alert.evaluate(&live(2.6, 0.0), at(5), cooldown));
+        assert!(!alert.evaluate(&live(2.4, 0.0), at(10), cooldown));
+        assert_eq!(alert.fired_at, Some(at(0)));
+
+        // Still held when the cooldown ends, so it waits for the condition to clear again
+        assert!(!alert.evaluate(&live(2.4, 0.0), at(40), cooldown));
+        assert!(!alert.evaluate(&live(2.6, 0.0), at(41), cooldown));
+        assert!(alert.evaluate(&live(2.4, 0.0), at(42), cooldown));
+        assert_eq!(alert.fired_at, Some(at(42)));
+    }
+}
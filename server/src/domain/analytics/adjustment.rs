@@ -0,0 +1,120 @@
+use crate::domain::StockSplit;
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// One day's close
+#[derive(Debug, Clone, Serialize)]
+pub struct ClosePoint {
+    pub date: NaiveDate,
+    pub close: f64,
+}
+
+/// A symbol's raw closes next to the same closes adjusted for its splits
+#[derive(Debug, Clone, Serialize)]
+pub struct AdjustedHistory {
+    pub symbol: String,
+    /// Whether any splits are recorded for the symbol; the adjusted series is omitted otherwise
+    pub has_adjustments: bool,
+    pub splits: Vec<StockSplit>,
+    pub raw: Vec<ClosePoint>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjusted: Option<Vec<ClosePoint>>,
+}
+
+/// Factor dividing a price on `date` to put it on the basis of the latest split: the product of
+/// the ratios of every split with a later ex-date. Splits with a non-positive ratio are ignored.
+pub fn split_factor(date: NaiveDate, splits: &[StockSplit]) -> f64 {
+    splits
+        .iter()
+        .filter(|split| split.ex_date > date && split.ratio > 0.0)
+        .map(|split| split.ratio)
+        .product()
+}
+
+/// Restate daily closes on the post-split basis so prices before a split are comparable to
+/// those after it
+pub fn split_adjusted_closes(
+    closes: &[(NaiveDate, f64)],
+    splits: &[StockSplit],
+) -> Vec<(NaiveDate, f64)> {
+    closes
+        .iter()
+        .map(|(date, close)| (*date, close / split_factor(*date, splits)))
+        .collect()
+}
+
+/// Pair a symbol's raw closes with their split-adjusted counterparts
+pub fn adjusted_history(
+    symbol: &str,
+    closes: &[(NaiveDate, f64)],
+    splits: Vec<StockSplit>,
+) -> AdjustedHistory {
+    let to_points = |series: &[(NaiveDate, f64)]| {
+        series
+            .iter()
+            .map(|(date, close)| ClosePoint {
+                date: *date,
+                close: *close,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let adjusted = (!splits.is_empty()).then(|| to_points(&split_adjusted_closes(closes, &splits)));
+    AdjustedHistory {
+        symbol: symbol.to_string(),
+        has_adjustments: adjusted.is_some(),
+        splits,
+        raw: to_points(closes),
+        adjusted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn closes() -> Vec<(NaiveDate, f64)> {
+        vec![
+            (date(4), 10.0),
+            (date(5), 10.4),
+            (date(6), 5.1),
+            (date(7), 5.3),
+        ]
+    }
+
+    #[test]
+    fn a_split_makes_the_series_diverge_before_its_ex_date() {
+        let split = StockSplit {
+            symbol: "MTNGH".to_string(),
+            ex_date: date(6),
+            ratio: 2.0,
+            recorded_at: Utc::now(),
+        };
+
+        let history = adjusted_history("MTNGH", &closes(), vec![split]);
+
+        let closes_of = |series: &[ClosePoint]| -> Vec<f64> {
+            series.iter().map(|point| point.close).collect()
+        };
+        assert!(history.has_adjustments);
+        assert_eq!(closes_of(&history.raw), [10.0, 10.4, 5.1, 5.3]);
+        assert_eq!(
+            closes_of(history.adjusted.as_ref().unwrap()),
+            [5.0, 5.2, 5.1, 5.3]
+        );
+    }
+
+    #[test]
+    fn the_adjusted_series_is_omitted_without_splits() {
+        let history = adjusted_history("MTNGH", &closes(), Vec::new());
+
+        assert!(!history.has_adjustments);
+        assert!(history.adjusted.is_none());
+        assert_eq!(history.raw.len(), 4);
+    }
+}
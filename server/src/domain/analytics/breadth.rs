@@ -0,0 +1,92 @@
+use serde::Serialize;
+
+/// What breadth needs to know about one stock
+#[derive(Debug, Clone, Copy)]
+pub struct BreadthInput {
+    pub change: f64,
+    pub price: f64,
+    /// 52-week (high, low), including the latest price
+    pub range_52w: Option<(f64, f64)>,
+}
+
+/// Market breadth: how many stocks are moving in each direction
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MarketBreadth {
+    pub advancers: usize,
+    pub decliners: usize,
+    pub unchanged: usize,
+    /// Advancers per decliner; `None` when nothing declined
+    pub advance_decline_ratio: Option<f64>,
+    /// Stocks trading at their 52-week high
+    pub new_highs: usize,
+    /// Stocks trading at their 52-week low
+    pub new_lows: usize,
+}
+
+pub fn market_breadth(stocks: &[BreadthInput]) -> MarketBreadth {
+    let mut breadth = MarketBreadth::default();
+
+    for stock in stocks {
+        if stock.change > 0.0 {
+            breadth.advancers += 1;
+        } else if stock.change < 0.0 {
+            breadth.decliners += 1;
+        } else {
+            breadth.unchanged += 1;
+        }
+
+        // A stock with a flat year is at both its high and its low; count it as neither
+        if let Some((high, low)) = stock.range_52w.filter(|(high, low)| high > low) {
+            if stock.price >= high {
+                breadth.new_highs += 1;
+            } else if stock.price <= low {
+                breadth.new_lows += 1;
+            }
+        }
+    }
+
+    breadth.advance_decline_ratio =
+        (breadth.decliners > 0).then(|| breadth.advancers as f64 / breadth.decliners as f64);
+    breadth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(change: f64, price: f64, range_52w: Option<(f64, f64)>) -> BreadthInput {
+        BreadthInput {
+            change,
+            price,
+            range_52w,
+        }
+    }
+
+    #[test]
+    fn breadth_counts_each_direction_and_the_advance_decline_ratio() {
+        let stocks = [
+            stock(0.2, 5.0, Some((5.0, 3.0))),
+            stock(0.1, 2.0, Some((4.0, 1.0))),
+            stock(0.3, 1.5, None),
+            stock(-0.4, 1.0, Some((4.0, 1.0))),
+            stock(-0.1, 3.0, Some((4.0, 1.0))),
+            stock(0.0, 2.0, Some((2.0, 2.0))),
+        ];
+
+        let breadth = market_breadth(&stocks);
+
+        assert_eq!(
+            (breadth.advancers, breadth.decliners, breadth.unchanged),
+            (3, 2, 1)
+        );
+        assert_eq!(breadth.advance_decline_ratio, Some(1.5));
+        assert_eq!((breadth.new_highs, breadth.new_lows), (1, 1));
+    }
+
+    #[test]
+    fn the_ratio_is_omitted_when_nothing_declined() {
+        let breadth = market_breadth(&[stock(0.2, 5.0, None), stock(0.0, 2.0, None)]);
+
+        assert_eq!(breadth.advance_decline_ratio, None);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::domain::TimeSeriesPoint;
+use chrono::{DateTime, Datelike, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Length of each candle, aligned to UTC hours, days and ISO weeks starting on Monday
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CandleInterval {
+    #[serde(rename = "1h")]
+    Hour,
+    #[default]
+    #[serde(rename = "1d")]
+    Day,
+    #[serde(rename = "1w")]
+    Week,
+}
+
+impl CandleInterval {
+    /// Start of the candle containing `timestamp`
+    fn bucket_start(self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let start = match self {
+            Self::Hour => timestamp.duration_trunc(Duration::hours(1)),
+            Self::Day | Self::Week => timestamp.duration_trunc(Duration::days(1)),
+        }
+        .unwrap_or(timestamp);
+
+        match self {
+            Self::Week => start - Duration::days(start.weekday().num_days_from_monday() as i64),
+            Self::Hour | Self::Day => start,
+        }
+    }
+}
+
+/// Open, high, low and close prices of the points in one interval, with their summed volume
+#[derive(Debug, Clone, Serialize)]
+pub struct Candle {
+    /// Start of the interval
+    pub timestamp: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: i64,
+}
+
+/// Bucket points into one candle per interval that has any, oldest first
+pub fn candles(points: &[TimeSeriesPoint], interval: CandleInterval) -> Vec<Candle> {
+    let mut sorted: Vec<&TimeSeriesPoint> = points.iter().collect();
+    sorted.sort_by_key(|point| point.timestamp);
+
+    let mut candles: Vec<Candle> = Vec::new();
+    for point in sorted {
+        let start = interval.bucket_start(point.timestamp);
+        let volume = point.volume.unwrap_or(0);
+        match candles.last_mut() {
+            Some(candle) if candle.timestamp == start => {
+                candle.high = candle.high.max(point.value);
+                candle.low = candle.low.min(point.value);
+                candle.close = point.value;
+                candle.volume += volume;
+            }
+            _ => candles.push(Candle {
+                timestamp: start,
+                open: point.value,
+                high: point.value,
+                low: point.value,
+                close: point.value,
+                volume,
+            }),
+        }
+    }
+
+    candles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DataSource;
+    use chrono::TimeZone;
+
+    fn point(day: u32, hour: u32, value: f64, volume: i64) -> TimeSeriesPoint {
+        TimeSeriesPoint {
+            timestamp: Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap(),
+            value,
+            volume: Some(volume),
+            source: DataSource::Scraped,
+        }
+    }
+
+    /// Ticks from Monday 4 to Friday 8 March 2024, with nothing on the Wednesday, given out of
+    /// order
+    fn week_of_points() -> Vec<TimeSeriesPoint> {
+        vec![
+            point(4, 14, 1.2, 300),
+            point(4, 10, 1.0, 100),
+            point(4, 12, 1.5, 200),
+            point(5, 10, 1.1, 50),
+            point(5, 14, 0.9, 50),
+            point(7, 11, 1.3, 10),
+            point(8, 10, 1.4, 20),
+            point(8, 14, 1.6, 30),
+        ]
+    }
+
+    #[test]
+    fn daily_candles_over_a_utc_week_skip_empty_days() {
+        let points = week_of_points();
+
+        let candles = candles(&points, CandleInterval::Day);
+
+        let days: Vec<u32> = candles.iter().map(|c| c.timestamp.day()).collect();
+        assert_eq!(days, vec![4, 5, 7, 8]);
+        let monday = &candles[0];
+        assert_eq!(
+            monday.timestamp,
+            Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            (monday.open, monday.high, monday.low, monday.close),
+            (1.0, 1.5, 1.0, 1.2)
+        );
+        assert_eq!(monday.volume, 600);
+        assert_eq!((candles[1].open, candles[1].close), (1.1, 0.9));
+        assert_eq!(candles[2].volume, 10);
+    }
+
+    #[test]
+    fn a_weekly_candle_starts_on_monday() {
+        let points = week_of_points();
+
+        let candles = candles(&points, CandleInterval::Week);
+
+        assert_eq!(candles.len(), 1);
+        assert_eq!(
+            candles[0].timestamp,
+            Utc.with_ymd_and_hms(2024, 3, 4, 0, 0, 0).unwrap()
+        );
+        assert_eq!((candles[0].open, candles[0].close), (1.0, 1.6));
+        assert_eq!((candles[0].high, candles[0].low), (1.6, 0.9));
+        assert_eq!(candles[0].volume, 760);
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// One day of a two-series comparison, both rebased to 100 on the first shared day
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparisonPoint {
+    pub date: NaiveDate,
+    pub value: f64,
+    pub benchmark_value: f64,
+    /// Whether either value was carried forward from an earlier day with no close
+    pub filled: bool,
+}
+
+/// Align two daily close series on their combined dates and rebase both to 100.
+///
+/// Days missing from one series carry its previous close forward. The comparison starts on
+/// the first day both series have a close, so it is empty if they never overlap.
+pub fn rebased_comparison(
+    closes: &[(NaiveDate, f64)],
+    benchmark_closes: &[(NaiveDate, f64)],
+) -> Vec<ComparisonPoint> {
+    let mut dates: Vec<NaiveDate> = closes
+        .iter()
+        .chain(benchmark_closes)
+        .map(|(date, _)| *date)
+        .collect();
+    dates.sort();
+    dates.dedup();
+
+    let close_on = |series: &[(NaiveDate, f64)], date: NaiveDate| {
+        series
+            .binary_search_by_key(&date, |(d, _)| *d)
+            .ok()
+            .map(|index| series[index].1)
+    };
+
+    let mut points = Vec::new();
+    let mut last = (None, None);
+    let mut base = None;
+
+    for date in dates {
+        let (close, benchmark_close) = (close_on(closes, date), close_on(benchmark_closes, date));
+        last = (close.or(last.0), benchmark_close.or(last.1));
+        let (Some(value), Some(benchmark_value)) = last else {
+            continue;
+        };
+        if value <= 0.0 || benchmark_value <= 0.0 {
+            continue;
+        }
+
+        let (base_value, base_benchmark) = *base.get_or_insert((value, benchmark_value));
+        points.push(ComparisonPoint {
+            date,
+            value: value / base_value * 100.0,
+            benchmark_value: benchmark_value / base_benchmark * 100.0,
+            filled: close.is_none() || benchmark_close.is_none(),
+        });
+    }
+
+    points
+}
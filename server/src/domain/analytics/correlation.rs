@@ -0,0 +1,103 @@
+use crate::domain::analytics::mean;
+use chrono::NaiveDate;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Pairwise correlations of several symbols' daily returns, in the order the symbols were given
+#[derive(Debug, Clone, Serialize)]
+pub struct CorrelationMatrix {
+    pub symbols: Vec<String>,
+    /// `matrix[i][j]` correlates `symbols[i]` with `symbols[j]`; `None` when the pair shares too
+    /// few return dates or either series is flat over them
+    pub matrix: Vec<Vec<Option<f64>>>,
+}
+
+/// Pearson correlation of two return series over the dates both have a return.
+///
+/// `None` with fewer than `min_points` shared dates or a flat series.
+pub fn correlation(
+    a: &BTreeMap<NaiveDate, f64>,
+    b: &BTreeMap<NaiveDate, f64>,
+    min_points: usize,
+) -> Option<f64> {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = a
+        .iter()
+        .filter_map(|(date, x)| Some((*x, *b.get(date)?)))
+        .unzip();
+    if xs.len() < min_points.max(2) {
+        return None;
+    }
+
+    let (mean_x, mean_y) = (mean(&xs)?, mean(&ys)?);
+    let covariance: f64 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance_x: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let variance_y: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    if variance_x <= 0.0 || variance_y <= 0.0 {
+        return None;
+    }
+
+    // Rounding can push perfectly correlated series fractionally past 1
+    Some((covariance / (variance_x * variance_y).sqrt()).clamp(-1.0, 1.0))
+}
+
+/// Correlate every pair of return series, each aligned with the other on their shared dates
+pub fn correlation_matrix(
+    series: &[(String, BTreeMap<NaiveDate, f64>)],
+    min_points: usize,
+) -> CorrelationMatrix {
+    let mut matrix = vec![vec![None; series.len()]; series.len()];
+    for i in 0..series.len() {
+        for j in i..series.len() {
+            let value = correlation(&series[i].1, &series[j].1, min_points);
+            matrix[i][j] = value;
+            matrix[j][i] = value;
+        }
+    }
+
+    CorrelationMatrix {
+        symbols: series.iter().map(|(symbol, _)| symbol.clone()).collect(),
+        matrix,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns on consecutive March 2024 dates from day `first_day`
+    fn returns(first_day: u32, values: &[f64]) -> BTreeMap<NaiveDate, f64> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(offset, value)| {
+                let date = NaiveDate::from_ymd_opt(2024, 3, first_day + offset as u32).unwrap();
+                (date, *value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn perfectly_correlated_series_correlate_near_one() {
+        let base = [0.01, -0.02, 0.015, 0.0, -0.005, 0.02];
+        let doubled: Vec<f64> = base.iter().map(|r| r * 2.0).collect();
+        let series = vec![
+            ("MTNGH".to_string(), returns(1, &base)),
+            ("GCB".to_string(), returns(1, &doubled)),
+            // Shares only two dates with the others
+            ("CAL".to_string(), returns(5, &[0.01, 0.03, -0.01, 0.02])),
+        ];
+
+        let matrix = correlation_matrix(&series, 5);
+
+        assert_eq!(matrix.symbols, ["MTNGH", "GCB", "CAL"]);
+        let mtngh_gcb = matrix.matrix[0][1].unwrap();
+        assert!((mtngh_gcb - 1.0).abs() < 1e-9, "{}", mtngh_gcb);
+        assert_eq!(matrix.matrix[1][0], matrix.matrix[0][1]);
+        assert_eq!(matrix.matrix[0][2], None);
+        assert_eq!(matrix.matrix[2][1], None);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::domain::analytics::indicators::sma;
+use chrono::{Days, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Calendar days covered by the 52-week high and low
+const FIFTY_TWO_WEEK_DAYS: u64 = 52 * 7;
+
+/// A single figure summarising a stock's daily closes as of the latest one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryStat {
+    /// Simple moving average of the last `n` closes
+    Sma(usize),
+    High52w,
+    Low52w,
+}
+
+impl std::str::FromStr for HistoryStat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "high52w" => Ok(Self::High52w),
+            "low52w" => Ok(Self::Low52w),
+            other => other
+                .strip_prefix("sma")
+                .and_then(|period| period.parse().ok())
+                .filter(|period| *period > 0)
+                .map(Self::Sma)
+                .ok_or_else(|| format!("unknown indicator: {}", s.trim())),
+        }
+    }
+}
+
+impl std::fmt::Display for HistoryStat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sma(period) => write!(f, "sma{}", period),
+            Self::High52w => write!(f, "high52w"),
+            Self::Low52w => write!(f, "low52w"),
+        }
+    }
+}
+
+impl HistoryStat {
+    /// Compute the figure over daily closes, oldest first. `None` when there are no closes or,
+    /// for a moving average, fewer closes than its window.
+    pub fn compute(self, closes: &[(NaiveDate, f64)]) -> Option<f64> {
+        match self {
+            Self::Sma(period) => {
+                let values: Vec<f64> = closes.iter().map(|(_, close)| *close).collect();
+                sma(&values, period).last().copied()
+            }
+            Self::High52w => fifty_two_weeks(closes).reduce(f64::max),
+            Self::Low52w => fifty_two_weeks(closes).reduce(f64::min),
+        }
+    }
+}
+
+/// Closes within 52 weeks of the latest one
+fn fifty_two_weeks(closes: &[(NaiveDate, f64)]) -> impl Iterator<Item = f64> + '_ {
+    let start = closes
+        .last()
+        .and_then(|(latest, _)| latest.checked_sub_days(Days::new(FIFTY_TWO_WEEK_DAYS)));
+    closes
+        .iter()
+        .filter(move |(date, _)| start.is_some_and(|start| *date > start))
+        .map(|(_, close)| *close)
+}
+
+/// Compute each requested figure, keyed by its name, e.g. `sma50`
+pub fn history_stats(
+    closes: &[(NaiveDate, f64)],
+    stats: &[HistoryStat],
+) -> BTreeMap<String, Option<f64>> {
+    stats
+        .iter()
+        .map(|stat| (stat.to_string(), stat.compute(closes)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_over_a_known_series() {
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        // An old spike more than 52 weeks before the latest close, then closes of 1 to 10
+        let mut closes = vec![(NaiveDate::from_ymd_opt(2022, 6, 1).unwrap(), 100.0)];
+        closes.extend((0..10).map(|day| (start + Days::new(day), day as f64 + 1.0)));
+        let stats: Vec<HistoryStat> = ["sma3", "sma50", "high52w", "low52w"]
+            .iter()
+            .map(|name| name.parse().unwrap())
+            .collect();
+
+        let computed = history_stats(&closes, &stats);
+
+        assert_eq!(computed["sma3"], Some(9.0));
+        assert_eq!(computed["sma50"], None);
+        assert_eq!(computed["high52w"], Some(10.0));
+        assert_eq!(computed["low52w"], Some(1.0));
+    }
+
+    #[test]
+    fn unknown_indicators_are_rejected() {
+        assert!("sma0".parse::<HistoryStat>().is_err());
+        assert!("ema20".parse::<HistoryStat>().is_err());
+        assert_eq!(" SMA200 ".parse::<HistoryStat>(), Ok(HistoryStat::Sma(200)));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::domain::analytics::{mean, std_dev};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// MACD fast, slow and signal EMA periods
+const MACD_PERIODS: (usize, usize, usize) = (12, 26, 9);
+
+/// Bollinger band width in standard deviations
+const BOLLINGER_WIDTH: f64 = 2.0;
+
+/// A technical indicator computed over daily closes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Indicator {
+    Sma,
+    Ema,
+    Rsi,
+    Macd,
+    Bollinger,
+}
+
+/// One day of an indicator, e.g. `{"date": ..., "value": ...}` or the MACD/Bollinger components
+#[derive(Debug, Clone, Serialize)]
+pub struct IndicatorPoint {
+    pub date: NaiveDate,
+    #[serde(flatten)]
+    pub values: BTreeMap<&'static str, f64>,
+}
+
+impl Indicator {
+    /// Lookback period used when the request doesn't give one; MACD's periods are fixed
+    pub fn default_period(self) -> usize {
+        match self {
+            Indicator::Sma | Indicator::Ema | Indicator::Bollinger => 20,
+            Indicator::Rsi => 14,
+            Indicator::Macd => MACD_PERIODS.1,
+        }
+    }
+
+    /// Fewest closes that yield at least one indicator value
+    pub fn min_points(self, period: usize) -> usize {
+        match self {
+            Indicator::Sma | Indicator::Ema | Indicator::Bollinger => period,
+            // Each RSI value needs `period` changes
+            Indicator::Rsi => period + 1,
+            // The signal line is an EMA of the MACD line, which starts once the slow EMA does
+            Indicator::Macd => MACD_PERIODS.1 + MACD_PERIODS.2 - 1,
+        }
+    }
+
+    /// Compute the indicator over daily closes, oldest first. Days before the indicator has
+    /// enough history are omitted.
+    pub fn compute(self, closes: &[(NaiveDate, f64)], period: usize) -> Vec<IndicatorPoint> {
+        let values: Vec<f64> = closes.iter().map(|(_, close)| *close).collect();
+        let point = |index: usize, values: &[(&'static str, f64)]| IndicatorPoint {
+            date: closes[index].0,
+            values: values.iter().copied().collect(),
+        };
+
+        match self {
+            Indicator::Sma => aligned(&sma(&values, period), closes.len())
+                .map(|(i, v)| point(i, &[("value", v)]))
+                .collect(),
+            Indicator::Ema => aligned(&ema(&values, period), closes.len())
+                .map(|(i, v)| point(i, &[("value", v)]))
+                .collect(),
+            Indicator::Rsi => aligned(&rsi(&values, period), closes.len())
+                .map(|(i, v)| point(i, &[("value", v)]))
+                .collect(),
+            Indicator::Macd => {
+                let (fast, slow, signal_period) = MACD_PERIODS;
+                let fast = ema(&values, fast);
+                let slow = ema(&values, slow);
+                // Align the fast EMA with the later-starting slow one
+                let line: Vec<f64> = fast[fast.len() - slow.len()..]
+                    .iter()
+                    .zip(&slow)
+                    .map(|(f, s)| f - s)
+                    .collect();
+                let signal = ema(&line, signal_period);
+                let line = &line[line.len() - signal.len()..];
+
+                aligned(&signal, closes.len())
+                    .zip(line)
+                    .map(|((i, signal), macd)| {
+                        point(
+                            i,
+                            &[
+                                ("macd", *macd),
+                                ("signal", signal),
+                                ("histogram", macd - signal),
+                            ],
+                        )
+                    })
+                    .collect()
+            }
+            Indicator::Bollinger => {
+                let bands: Vec<(f64, f64)> = if period == 0 {
+                    Vec::new()
+                } else {
+                    values
+                        .windows(period)
+                        .filter_map(|window| Some((mean(window)?, std_dev(window).unwrap_or(0.0))))
+                        .collect()
+                };
+                let middles: Vec<f64> = bands.iter().map(|(middle, _)| *middle).collect();
+
+                aligned(&middles, closes.len())
+                    .zip(&bands)
+                    .map(|((i, middle), (_, sd))| {
+                        point(
+                            i,
+                            &[
+                                ("middle", middle),
+                                ("upper", middle + BOLLINGER_WIDTH * sd),
+                                ("lower", middle - BOLLINGER_WIDTH * sd),
+                            ],
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Pair each value of a series that ends on the last close with the index of its close
+fn aligned(series: &[f64], len: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+    series
+        .iter()
+        .enumerate()
+        .map(move |(i, v)| (len - series.len() + i, *v))
+}
+
+/// Simple moving average of every full `period`-value window
+pub fn sma(values: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 {
+        return Vec::new();
+    }
+    values.windows(period).filter_map(mean).collect()
+}
+
+/// Exponential moving average, seeded with the simple average of the first `period` values
+pub fn ema(values: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || values.len() < period {
+        return Vec::new();
+    }
+
+    let alpha = 2.0 / (period as f64 + 1.0);
+    let mut current = values[..period].iter().sum::<f64>() / period as f64;
+    let mut result = vec![current];
+    for value in &values[period..] {
+        current += alpha * (value - current);
+        result.push(current);
+    }
+
+    result
+}
+
+/// Wilder's relative strength index over `period` changes
+pub fn rsi(values: &[f64], period: usize) -> Vec<f64> {
+    if period == 0 || values.len() <= period {
+        return Vec::new();
+    }
+
+    let changes: Vec<f64> = values.windows(2).map(|pair| pair[1] - pair[0]).collect();
+    let gains = |changes: &[f64]| changes.iter().map(|c| c.max(0.0)).sum::<f64>();
+    let losses = |changes: &[f64]| changes.iter().map(|c| (-c).max(0.0)).sum::<f64>();
+    let index = |gain: f64, loss: f64| {
+        if loss == 0.0 {
+            100.0
+        } else {
+            100.0 - 100.0 / (1.0 + gain / loss)
+        }
+    };
+
+    let n = period as f64;
+    let mut avg_gain = gains(&changes[..period]) / n;
+    let mut avg_loss = losses(&changes[..period]) / n;
+    let mut result = vec![index(avg_gain, avg_loss)];
+    for change in &changes[period..] {
+        avg_gain = (avg_gain * (n - 1.0) + change.max(0.0)) / n;
+        avg_loss = (avg_loss * (n - 1.0) + (-change).max(0.0)) / n;
+        result.push(index(avg_gain, avg_loss));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Days;
+
+    fn closes(values: impl IntoIterator<Item = f64>) -> Vec<(NaiveDate, f64)> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        values
+            .into_iter()
+            .enumerate()
+            .map(|(day, value)| (start + Days::new(day as u64), value))
+            .collect()
+    }
+
+    #[test]
+    fn rsi_follows_wilder_smoothing() {
+        let values = [1.0, 2.0, 1.0, 2.0, 1.0];
+
+        let rsi = rsi(&values, 2);
+
+        assert_eq!(rsi, vec![50.0, 75.0, 37.5]);
+    }
+
+    #[test]
+    fn macd_points_are_aligned_with_the_last_closes() {
+        let closes = closes((0..40).map(|day| 10.0 + day as f64));
+
+        let macd = Indicator::Macd.compute(&closes, Indicator::Macd.default_period());
+
+        assert_eq!(macd.len(), 40 - Indicator::Macd.min_points(26) + 1);
+        assert_eq!(macd.first().unwrap().date, closes[33].0);
+        assert_eq!(macd.last().unwrap().date, closes[39].0);
+        // A steady rise keeps the fast EMA above the slow one
+        assert!(macd.iter().all(|point| point.values["macd"] > 0.0));
+    }
+
+    #[test]
+    fn too_little_history_gives_an_empty_series() {
+        let closes = closes((0..14).map(f64::from));
+
+        assert!(Indicator::Rsi.compute(&closes, 14).is_empty());
+        assert!(Indicator::Macd.compute(&closes, 26).is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+/// Configuration of the synthetic price ladder served while no order-book data is available
+#[derive(Debug, Clone, Copy)]
+pub struct LadderConfig {
+    /// Assumed bid/ask spread as a percent of the last price
+    pub spread_percent: f64,
+    /// Price levels shown on each side
+    pub levels: usize,
+}
+
+impl Default for LadderConfig {
+    fn default() -> Self {
+        Self {
+            spread_percent: 1.0,
+            levels: 5,
+        }
+    }
+}
+
+/// Bid and ask prices estimated around a last traded price. Not real market depth.
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceLadder {
+    pub symbol: String,
+    /// Always `true`: the prices come from an assumed spread, not from an order book
+    pub synthetic: bool,
+    pub last_price: f64,
+    pub spread_percent: f64,
+    pub bid: f64,
+    pub ask: f64,
+    /// Bid levels, best (highest) first
+    pub bids: Vec<f64>,
+    /// Ask levels, best (lowest) first
+    pub asks: Vec<f64>,
+}
+
+/// Place the best bid and ask half the spread either side of `last_price`, with further levels
+/// stepping away by another half spread each
+pub fn synthetic_ladder(symbol: &str, last_price: f64, config: &LadderConfig) -> PriceLadder {
+    let half_spread = last_price * config.spread_percent / 200.0;
+    let bids: Vec<f64> = (1..=config.levels.max(1))
+        .map(|level| (last_price - half_spread * level as f64).max(0.0))
+        .collect();
+    let asks: Vec<f64> = (1..=config.levels.max(1))
+        .map(|level| last_price + half_spread * level as f64)
+        .collect();
+
+    PriceLadder {
+        symbol: symbol.to_string(),
+        synthetic: true,
+        last_price,
+        spread_percent: config.spread_percent,
+        bid: bids[0],
+        ask: asks[0],
+        bids,
+        asks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bid_and_ask_bracket_the_last_price_by_the_configured_spread() {
+        let config = LadderConfig {
+            spread_percent: 2.0,
+            levels: 3,
+        };
+
+        let ladder = synthetic_ladder("MTNGH", 10.0, &config);
+
+        assert!(ladder.synthetic);
+        assert!(ladder.bid < ladder.last_price && ladder.last_price < ladder.ask);
+        assert!((ladder.ask - ladder.bid - 0.2).abs() < 1e-9);
+        assert!((ladder.bid - 9.9).abs() < 1e-9);
+        assert!((ladder.ask - 10.1).abs() < 1e-9);
+        assert_eq!(ladder.bids.len(), 3);
+        assert!(ladder.bids.windows(2).all(|pair| pair[0] > pair[1]));
+        assert!(ladder.asks.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::domain::analytics::mean;
+use crate::domain::TimeSeriesPoint;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+/// Highest and lowest price over the 52 weeks up to `now`
+pub fn fifty_two_week_range(points: &[TimeSeriesPoint], now: DateTime<Utc>) -> Option<(f64, f64)> {
+    let start = now - Duration::weeks(52);
+    let prices = points
+        .iter()
+        .filter(|point| point.timestamp >= start && point.timestamp <= now)
+        .map(|point| point.value);
+
+    prices.fold(None, |range, price| match range {
+        None => Some((price, price)),
+        Some((high, low)) => Some((high.max(price), low.min(price))),
+    })
+}
+
+/// Last reported volume of each UTC calendar day, oldest first.
+///
+/// Live records carry the day's cumulative volume, so the last one of a day is its total.
+pub fn daily_volumes(points: &[TimeSeriesPoint]) -> Vec<(NaiveDate, i64)> {
+    let mut sorted: Vec<&TimeSeriesPoint> = points.iter().collect();
+    sorted.sort_by_key(|point| point.timestamp);
+
+    let mut volumes: Vec<(NaiveDate, i64)> = Vec::new();
+    for point in sorted {
+        let Some(volume) = point.volume else {
+            continue;
+        };
+        let date = point.timestamp.date_naive();
+        match volumes.last_mut() {
+            Some((last_date, last_volume)) if *last_date == date => *last_volume = volume,
+            _ => volumes.push((date, volume)),
+        }
+    }
+
+    volumes
+}
+
+/// Average daily volume over the trading days within the last `days` calendar days
+pub fn average_daily_volume(
+    points: &[TimeSeriesPoint],
+    days: i64,
+    now: DateTime<Utc>,
+) -> Option<f64> {
+    let start = (now - Duration::days(days)).date_naive();
+    let volumes: Vec<f64> = daily_volumes(points)
+        .into_iter()
+        .filter(|(date, _)| *date >= start && *date <= now.date_naive())
+        .map(|(_, volume)| volume as f64)
+        .collect();
+
+    mean(&volumes)
+}
+
+/// A symbol trading at a multiple of its average daily volume
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeAlert {
+    pub symbol: String,
+    /// Latest reported volume for the day
+    pub volume: i64,
+    pub average_daily_volume: f64,
+    /// `volume / average_daily_volume`
+    pub ratio: f64,
+}
+
+/// Flag `volume` when it is at least `threshold` times the average daily volume. `None` below
+/// the threshold or without a positive average.
+pub fn volume_alert(
+    symbol: &str,
+    volume: i64,
+    average_daily_volume: Option<f64>,
+    threshold: f64,
+) -> Option<VolumeAlert> {
+    let average_daily_volume = average_daily_volume.filter(|adv| *adv > 0.0)?;
+    let ratio = volume as f64 / average_daily_volume;
+    (ratio >= threshold).then(|| VolumeAlert {
+        symbol: symbol.to_string(),
+        volume,
+        average_daily_volume,
+        ratio,
+    })
+}
+
+/// Shares traded over a window relative to the shares outstanding
+#[derive(Debug, Clone, Serialize)]
+pub struct Turnover {
+    pub symbol: String,
+    pub days: i64,
+    /// Total volume of the trading days within the window
+    pub traded_volume: i64,
+    /// Shares outstanding per the latest equity details, if known
+    pub shares: Option<i64>,
+    /// `traded_volume / shares`, or `None` when the share count is unknown
+    pub turnover_ratio: Option<f64>,
+}
+
+/// Total volume of the trading days within the last `days` calendar days
+pub fn traded_volume(points: &[TimeSeriesPoint], days: i64, now: DateTime<Utc>) -> i64 {
+    let start = (now - Duration::days(days)).date_naive();
+    daily_volumes(points)
+        .into_iter()
+        .filter(|(date, _)| *date >= start && *date <= now.date_naive())
+        .map(|(_, volume)| volume)
+        .sum()
+}
+
+/// Share turnover ratio, or `None` without a positive share count
+pub fn turnover_ratio(traded_volume: i64, shares: Option<i64>) -> Option<f64> {
+    shares
+        .filter(|shares| *shares > 0)
+        .map(|shares| traded_volume as f64 / shares as f64)
+}
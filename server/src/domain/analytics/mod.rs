@@ -0,0 +1,79 @@
+//! Pure computations over stored price series, shared by the stock and market endpoints.
+
+pub mod adjustment;
+pub mod breadth;
+pub mod candles;
+pub mod comparison;
+pub mod correlation;
+pub mod history;
+pub mod indicators;
+pub mod ladder;
+pub mod metrics;
+pub mod relative_strength;
+pub mod risk;
+pub mod sectors;
+pub mod timeline;
+pub mod volatility;
+
+use crate::domain::TimeSeriesPoint;
+use chrono::NaiveDate;
+
+/// Collapse intraday points into the last value seen on each UTC calendar day, oldest first
+pub fn daily_closes(points: &[TimeSeriesPoint]) -> Vec<(NaiveDate, f64)> {
+    let mut sorted: Vec<&TimeSeriesPoint> = points.iter().collect();
+    sorted.sort_by_key(|point| point.timestamp);
+
+    let mut closes: Vec<(NaiveDate, f64)> = Vec::new();
+    for point in sorted {
+        let date = point.timestamp.date_naive();
+        match closes.last_mut() {
+            Some((last_date, close)) if *last_date == date => *close = point.value,
+            _ => closes.push((date, point.value)),
+        }
+    }
+
+    closes
+}
+
+/// Log returns between consecutive values, skipping pairs with a non-positive price
+pub fn log_returns(values: &[f64]) -> Vec<f64> {
+    values
+        .windows(2)
+        .filter(|pair| pair[0] > 0.0 && pair[1] > 0.0)
+        .map(|pair| (pair[1] / pair[0]).ln())
+        .collect()
+}
+
+/// Arithmetic mean, or `None` for an empty slice
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Sample standard deviation, or `None` with fewer than two values
+pub fn std_dev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values)?;
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Median of the values, or `None` for an empty slice
+pub fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mid = sorted.len() / 2;
+    Some(if sorted.len() % 2 == 0 {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    })
+}
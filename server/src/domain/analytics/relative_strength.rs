@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+/// One row of the relative strength leaderboard
+#[derive(Debug, Clone, Serialize)]
+pub struct RelativeStrengthEntry {
+    pub symbol: String,
+    pub start_close: f64,
+    pub end_close: f64,
+    pub total_return_percent: f64,
+    /// 1 is the best performer
+    pub rank: usize,
+    /// Share of the other ranked stocks this one outperformed, 0-100
+    pub percentile_rank: f64,
+}
+
+/// Simple return from the first to the last close, or `None` with fewer than two usable closes
+pub fn total_return(closes: &[f64]) -> Option<f64> {
+    match (closes.first(), closes.last()) {
+        (Some(first), Some(last)) if closes.len() >= 2 && *first > 0.0 => Some(last / first - 1.0),
+        _ => None,
+    }
+}
+
+/// Rank symbols by the total return of their closes, best first. Symbols whose closes don't yield
+/// a return are left out of the leaderboard.
+pub fn rank_by_total_return(series: Vec<(String, Vec<f64>)>) -> Vec<RelativeStrengthEntry> {
+    let mut returns: Vec<(String, f64, f64, f64)> = series
+        .into_iter()
+        .filter_map(|(symbol, closes)| {
+            let total = total_return(&closes)?;
+            Some((symbol, closes[0], closes[closes.len() - 1], total))
+        })
+        .collect();
+    returns.sort_by(|a, b| b.3.total_cmp(&a.3).then_with(|| a.0.cmp(&b.0)));
+
+    let count = returns.len();
+    returns
+        .into_iter()
+        .enumerate()
+        .map(|(index, (symbol, start_close, end_close, total))| {
+            let outperformed = count - index - 1;
+            RelativeStrengthEntry {
+                symbol,
+                start_close,
+                end_close,
+                total_return_percent: total * 100.0,
+                rank: index + 1,
+                percentile_rank: if count > 1 {
+                    outperformed as f64 / (count - 1) as f64 * 100.0
+                } else {
+                    100.0
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_are_ranked_by_total_return() {
+        let series = vec![
+            ("GCB".to_string(), vec![4.0, 4.2, 4.4]),
+            ("MTNGH".to_string(), vec![1.0, 1.1, 1.5]),
+            ("SCB".to_string(), vec![20.0, 19.0, 18.0]),
+            ("CAL".to_string(), vec![0.5]),
+        ];
+
+        let leaderboard = rank_by_total_return(series);
+
+        let ranked: Vec<(&str, usize, f64)> = leaderboard
+            .iter()
+            .map(|entry| (entry.symbol.as_str(), entry.rank, entry.percentile_rank))
+            .collect();
+        assert_eq!(
+            ranked,
+            [("MTNGH", 1, 100.0), ("GCB", 2, 50.0), ("SCB", 3, 0.0)]
+        );
+        assert!((leaderboard[0].total_return_percent - 50.0).abs() < 1e-9);
+        assert!((leaderboard[2].total_return_percent + 10.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::domain::analytics::mean;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Daily log returns keyed by the date each return ends on
+pub fn dated_log_returns(closes: &[(NaiveDate, f64)]) -> BTreeMap<NaiveDate, f64> {
+    closes
+        .windows(2)
+        .filter(|pair| pair[0].1 > 0.0 && pair[1].1 > 0.0)
+        .map(|pair| (pair[1].0, (pair[1].1 / pair[0].1).ln()))
+        .collect()
+}
+
+/// Beta of a return series against a benchmark over the dates both have a return.
+///
+/// `None` with fewer than `min_points` shared dates or a flat benchmark.
+pub fn beta(
+    returns: &BTreeMap<NaiveDate, f64>,
+    benchmark_returns: &BTreeMap<NaiveDate, f64>,
+    min_points: usize,
+) -> Option<f64> {
+    let (xs, ys): (Vec<f64>, Vec<f64>) = returns
+        .iter()
+        .filter_map(|(date, r)| Some((*benchmark_returns.get(date)?, *r)))
+        .unzip();
+    if xs.len() < min_points.max(2) {
+        return None;
+    }
+
+    let (mean_x, mean_y) = (mean(&xs)?, mean(&ys)?);
+    let covariance: f64 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if variance <= 0.0 {
+        return None;
+    }
+
+    Some(covariance / variance)
+}
+
+/// Weighted sum of each series' return on the dates every series has one, oldest first
+pub fn weighted_returns(series: &[(f64, &BTreeMap<NaiveDate, f64>)]) -> Vec<f64> {
+    let Some((_, first)) = series.first() else {
+        return Vec::new();
+    };
+
+    first
+        .keys()
+        .filter_map(|date| {
+            series
+                .iter()
+                .map(|(weight, returns)| Some(weight * returns.get(date)?))
+                .sum::<Option<f64>>()
+        })
+        .collect()
+}
+
+/// Historical value at risk: the loss, as a positive fraction, exceeded on only
+/// `1 - confidence` of the observed returns. `None` for an empty series.
+pub fn historical_var(returns: &[f64], confidence: f64) -> Option<f64> {
+    if returns.is_empty() {
+        return None;
+    }
+
+    let mut sorted = returns.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let index = (((1.0 - confidence) * sorted.len() as f64).floor() as usize).min(sorted.len() - 1);
+    // Convert the log return at the cutoff into a simple loss
+    Some((1.0 - sorted[index].exp()).max(0.0))
+}
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
+
+/// Sector bucket for symbols without a stored sector
+pub const UNCLASSIFIED_SECTOR: &str = "Unclassified";
+
+/// One symbol's figures as they feed into its sector's summary
+#[derive(Debug, Clone)]
+pub struct SectorMember {
+    pub symbol: String,
+    /// `None` when no sector is stored for the symbol
+    pub sector: Option<String>,
+    pub price: f64,
+    pub volume: i64,
+    /// `None` without a previous close or when the change looks suspect
+    pub change_percent: Option<f64>,
+    /// `None` without a known share count
+    pub market_cap: Option<f64>,
+}
+
+/// The symbol that moved furthest, either way, within a sector
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SectorMover {
+    pub symbol: String,
+    pub price: f64,
+    pub change_percent: f64,
+}
+
+/// Aggregate activity of the symbols in one sector
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SectorSummary {
+    pub sector: String,
+    pub stocks: usize,
+    pub total_volume: i64,
+    /// Mean percent change of the symbols with a usable change, `None` when there are none
+    pub average_change_percent: Option<f64>,
+    /// Market cap of the symbols with a known share count
+    pub total_market_cap: f64,
+    /// Largest absolute percent change in the sector
+    pub top_mover: Option<SectorMover>,
+}
+
+/// Group symbols by sector, in sector order with the unclassified bucket last.
+///
+/// Sector names are trimmed; blank ones count as unclassified.
+pub fn sector_summaries(members: &[SectorMember]) -> Vec<SectorSummary> {
+    let mut groups: BTreeMap<&str, Vec<&SectorMember>> = BTreeMap::new();
+    let mut unclassified = Vec::new();
+    for member in members {
+        match member.sector.as_deref().map(str::trim) {
+            Some(sector) if !sector.is_empty() => groups.entry(sector).or_default().push(member),
+            _ => unclassified.push(member),
+        }
+    }
+
+    let mut summaries: Vec<SectorSummary> = groups
+        .into_iter()
+        .map(|(sector, members)| summarize(sector, &members))
+        .collect();
+    if !unclassified.is_empty() {
+        summaries.push(summarize(UNCLASSIFIED_SECTOR, &unclassified));
+    }
+    summaries
+}
+
+fn summarize(sector: &str, members: &[&SectorMember]) -> SectorSummary {
+    let changes: Vec<f64> = members
+        .iter()
+        .filter_map(|m| m.change_percent)
+        .filter(|change| change.is_finite())
+        .collect();
+    let top_mover = members
+        .iter()
+        .filter_map(|m| {
+            m.change_percent
+                .filter(|change| change.is_finite())
+                .map(|change| (m, change))
+        })
+        .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+        .map(|(m, change_percent)| SectorMover {
+            symbol: m.symbol.clone(),
+            price: m.price,
+            change_percent,
+        });
+
+    SectorSummary {
+        sector: sector.to_string(),
+        stocks: members.len(),
+        total_volume: members.iter().map(|m| m.volume).sum(),
+        average_change_percent: super::mean(&changes),
+        total_market_cap: members.iter().filter_map(|m| m.market_cap).sum(),
+        top_mover,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(
+        symbol: &str,
+        sector: Option<&str>,
+        volume: i64,
+        change_percent: Option<f64>,
+        market_cap: Option<f64>,
+    ) -> SectorMember {
+        SectorMember {
+            symbol: symbol.to_string(),
+            sector: sector.map(str::to_string),
+            price: 1.0,
+            volume,
+            change_percent,
+            market_cap,
+        }
+    }
+
+    #[test]
+    fn symbols_are_grouped_by_sector_with_the_unclassified_bucket_last() {
+        let members = [
+            member(
+                "MTNGH",
+                Some("Telecommunications"),
+                500,
+                Some(1.0),
+                Some(100.0),
+            ),
+            member("GCB", Some("Financials"), 100, Some(2.0), Some(50.0)),
+            member("SCB", Some(" Financials "), 300, Some(-5.0), None),
+            member("EGH", Some("Financials"), 200, None, Some(25.0)),
+            member("CAL", None, 40, Some(3.0), None),
+            member("FML", Some(""), 60, None, None),
+        ];
+
+        let summaries = sector_summaries(&members);
+
+        let sectors: Vec<&str> = summaries.iter().map(|s| s.sector.as_str()).collect();
+        assert_eq!(
+            sectors,
+            ["Financials", "Telecommunications", UNCLASSIFIED_SECTOR]
+        );
+        let financials = &summaries[0];
+        assert_eq!(financials.stocks, 3);
+        assert_eq!(financials.total_volume, 600);
+        assert_eq!(financials.average_change_percent, Some(-1.5));
+        assert_eq!(financials.total_market_cap, 75.0);
+        let top_mover = financials.top_mover.as_ref().unwrap();
+        assert_eq!(
+            (top_mover.symbol.as_str(), top_mover.change_percent),
+            ("SCB", -5.0)
+        );
+        let unclassified = &summaries[2];
+        assert_eq!(unclassified.stocks, 2);
+        assert_eq!(unclassified.total_volume, 100);
+        assert_eq!(unclassified.total_market_cap, 0.0);
+        assert_eq!(unclassified.top_mover.as_ref().unwrap().symbol, "CAL");
+    }
+}
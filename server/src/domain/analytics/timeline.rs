@@ -0,0 +1,109 @@
+use crate::domain::MarketSummary;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Market totals at one step of a summary timeline
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryTimelinePoint {
+    pub timestamp: DateTime<Utc>,
+    /// When the summary shown at this step was stored
+    pub summary_at: DateTime<Utc>,
+    /// Set when no summary was stored during the step and the previous one is repeated
+    pub carried_forward: bool,
+    pub total_market_cap: f64,
+    pub total_volume: i64,
+    pub total_stocks: usize,
+    pub index_level: f64,
+}
+
+/// Down-sample stored summaries to one point every `step` from `from` to `to`, each showing the
+/// latest summary stored at or before it. Steps before the first summary are left out.
+///
+/// `summaries` must be oldest first.
+pub fn summary_timeline(
+    summaries: &[(DateTime<Utc>, MarketSummary)],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    step: Duration,
+) -> Vec<SummaryTimelinePoint> {
+    let mut points = Vec::new();
+    if step <= Duration::zero() {
+        return points;
+    }
+
+    let mut next = 0;
+    let mut timestamp = from;
+    while timestamp <= to {
+        let step_start = next;
+        while next < summaries.len() && summaries[next].0 <= timestamp {
+            next += 1;
+        }
+
+        if let Some((summary_at, summary)) = next.checked_sub(1).map(|index| &summaries[index]) {
+            points.push(SummaryTimelinePoint {
+                timestamp,
+                summary_at: *summary_at,
+                carried_forward: next == step_start,
+                total_market_cap: summary.total_market_cap,
+                total_volume: summary.total_volume,
+                total_stocks: summary.total_stocks,
+                index_level: summary.index_level,
+            });
+        }
+        timestamp += step;
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn summary(index_level: f64) -> MarketSummary {
+        MarketSummary {
+            total_market_cap: index_level * 1000.0,
+            total_volume: 500,
+            total_stocks: 2,
+            top_gainers: Vec::new(),
+            top_losers: Vec::new(),
+            index_level,
+            prices: Default::default(),
+            data_completeness: None,
+            sectors: Vec::new(),
+            last_updated: Utc::now(),
+        }
+    }
+
+    fn march(day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn a_daily_timeline_carries_the_last_summary_through_empty_days() {
+        let summaries = vec![
+            (march(1, 12), summary(100.0)),
+            (march(2, 12), summary(101.0)),
+            (march(4, 12), summary(103.0)),
+        ];
+
+        let timeline = summary_timeline(&summaries, march(1, 0), march(5, 0), Duration::days(1));
+
+        let steps: Vec<(DateTime<Utc>, f64, bool)> = timeline
+            .iter()
+            .map(|point| (point.timestamp, point.index_level, point.carried_forward))
+            .collect();
+        // March 1st midnight comes before the first summary and is left out
+        assert_eq!(
+            steps,
+            vec![
+                (march(2, 0), 100.0, false),
+                (march(3, 0), 101.0, false),
+                (march(4, 0), 101.0, true),
+                (march(5, 0), 103.0, false),
+            ]
+        );
+        assert_eq!(timeline[2].summary_at, march(2, 12));
+    }
+}
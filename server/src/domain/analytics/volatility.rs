@@ -0,0 +1,94 @@
+use crate::domain::analytics::{log_returns, median, std_dev};
+use serde::Serialize;
+
+/// Trading days used to annualize daily volatility
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Annualized realized volatility of a series of daily log returns
+pub fn realized_volatility(returns: &[f64]) -> Option<f64> {
+    std_dev(returns).map(|sd| sd * TRADING_DAYS_PER_YEAR.sqrt())
+}
+
+/// Annualized volatility of every rolling `window`-day span of returns, oldest first
+pub fn rolling_volatility(returns: &[f64], window: usize) -> Vec<f64> {
+    if window < 2 || returns.len() < window {
+        return Vec::new();
+    }
+    returns
+        .windows(window)
+        .filter_map(realized_volatility)
+        .collect()
+}
+
+/// Distribution of realized volatility over one rolling window length
+#[derive(Debug, Clone, Serialize)]
+pub struct VolatilityConeWindow {
+    pub window_days: usize,
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+    /// Volatility of the most recent window
+    pub current: f64,
+    /// Number of rolling windows the distribution was computed from
+    pub samples: usize,
+}
+
+/// Volatility cone over daily closes: min/median/max realized volatility per window length.
+///
+/// Windows without enough history for at least one full span are omitted.
+pub fn volatility_cone(closes: &[f64], windows: &[usize]) -> Vec<VolatilityConeWindow> {
+    let returns = log_returns(closes);
+
+    windows
+        .iter()
+        .filter_map(|&window| {
+            let vols = rolling_volatility(&returns, window);
+            Some(VolatilityConeWindow {
+                window_days: window,
+                min: vols.iter().copied().reduce(f64::min)?,
+                median: median(&vols)?,
+                max: vols.iter().copied().reduce(f64::max)?,
+                current: *vols.last()?,
+                samples: vols.len(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Daily closes whose swings widen over time, so each window's volatility varies
+    fn widening_series(days: usize) -> Vec<f64> {
+        (0..days)
+            .map(|day| {
+                let swing = 0.002 * (1.0 + day as f64 / 10.0);
+                let direction = if day % 2 == 0 { 1.0 } else { -1.0 };
+                10.0 * (1.0 + direction * swing)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cone_windows_are_ordered_min_median_max() {
+        let cone = volatility_cone(&widening_series(90), &[10, 20, 30, 60]);
+
+        let windows: Vec<usize> = cone.iter().map(|window| window.window_days).collect();
+        assert_eq!(windows, [10, 20, 30, 60]);
+        for window in &cone {
+            assert!(window.min <= window.median, "{:?}", window);
+            assert!(window.median <= window.max, "{:?}", window);
+            assert!(window.min < window.max, "{:?}", window);
+            assert_eq!(window.samples, 89 - window.window_days + 1);
+        }
+    }
+
+    #[test]
+    fn windows_longer_than_the_history_are_omitted() {
+        let cone = volatility_cone(&widening_series(40), &[10, 60]);
+
+        let windows: Vec<usize> = cone.iter().map(|window| window.window_days).collect();
+        assert_eq!(windows, [10]);
+    }
+}
@@ -1,15 +1,19 @@
-use chrono::{DateTime, Utc};
+use crate::domain::analytics::sectors::SectorSummary;
+use crate::domain::serde_helpers::f64_from_number_or_string;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
 
 /// Represents a director of a company
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Director {
     pub name: String,
     pub position: Option<String>,
 }
 
 /// Represents company information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Company {
     pub address: Option<String>,
     pub directors: Vec<Director>,
@@ -22,27 +26,104 @@ pub struct Company {
     pub website: Option<String>,
 }
 
+/// Origin of a stored record, so backfilled or synthetic data can be kept apart from real scrapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DataSource {
+    #[default]
+    Scraped,
+    Backfill,
+    Synthetic,
+}
+
 /// Represents live trading data for a stock
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct EquityLive {
+    #[serde(deserialize_with = "f64_from_number_or_string")]
     pub change: f64,
     pub name: String,
+    #[serde(deserialize_with = "f64_from_number_or_string")]
     pub price: f64,
     pub volume: i64,
+    #[serde(default)]
+    pub source: DataSource,
+}
+
+impl EquityLive {
+    /// Change as a percent of the previous close (`price - change`), or `None` when the
+    /// previous close is not positive
+    pub fn change_percent(&self) -> Option<f64> {
+        let previous_close = self.price - self.change;
+        (previous_close > 0.0).then(|| self.change / previous_close * 100.0)
+    }
+
+    /// Whether `other` carries the same price, change, volume and source, treating prices
+    /// that differ only by float rounding as equal
+    pub fn is_unchanged_from(&self, other: &EquityLive) -> bool {
+        const TOLERANCE: f64 = 1e-9;
+        (self.price - other.price).abs() <= TOLERANCE
+            && (self.change - other.change).abs() <= TOLERANCE
+            && self.volume == other.volume
+            && self.source == other.source
+    }
+}
+
+/// Live data stored by one fetch, as pushed to streaming clients
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveUpdate {
+    pub timestamp: DateTime<Utc>,
+    pub data: Vec<EquityLive>,
 }
 
 /// Represents detailed equity information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Equity {
     pub capital: Option<f64>,
     pub company: Company,
     pub dps: Option<f64>, // Dividend per share
     pub eps: Option<f64>, // Earnings per share
     pub name: String,
+    #[serde(deserialize_with = "f64_from_number_or_string")]
     pub price: f64,
     pub shares: Option<i64>,
 }
 
+/// Valuation figures derived from an equity's price and per-share data
+#[derive(Debug, Clone, Serialize)]
+pub struct ValuationMetrics {
+    pub dividend_yield: Option<f64>,
+    pub pe_ratio: Option<f64>,
+    pub market_cap: Option<f64>,
+}
+
+impl Equity {
+    /// Dividend per share over price, or `None` without a dividend or a positive price
+    pub fn dividend_yield(&self) -> Option<f64> {
+        let dps = self.dps?;
+        (self.price > 0.0).then(|| dps / self.price)
+    }
+
+    /// Price over earnings per share, or `None` without earnings or when they are zero
+    pub fn pe_ratio(&self) -> Option<f64> {
+        self.eps
+            .filter(|eps| *eps != 0.0)
+            .map(|eps| self.price / eps)
+    }
+
+    /// Price times shares outstanding, or `None` when the share count is unknown
+    pub fn market_cap(&self) -> Option<f64> {
+        self.shares.map(|shares| self.price * shares as f64)
+    }
+
+    pub fn valuation_metrics(&self) -> ValuationMetrics {
+        ValuationMetrics {
+            dividend_yield: self.dividend_yield(),
+            pe_ratio: self.pe_ratio(),
+            market_cap: self.market_cap(),
+        }
+    }
+}
+
 /// Represents simplified equity information (from /equities endpoint)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EquitySummary {
@@ -51,23 +132,223 @@ pub struct EquitySummary {
 }
 
 /// Represents market summary data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MarketSummary {
     pub total_market_cap: f64,
     pub total_volume: i64,
     pub total_stocks: usize,
+    /// Five largest rises by percent change from the previous close
     pub top_gainers: Vec<EquityLive>,
+    /// Five largest falls by percent change from the previous close
     pub top_losers: Vec<EquityLive>,
+    /// Composite index level. It starts at the mean price of the summarized symbols, and each
+    /// stored summary moves it by the mean percent change of the symbols priced in both it and
+    /// the previous one, so symbols joining or dropping out of a cycle don't move it.
+    #[serde(default)]
+    pub index_level: f64,
+    /// Latest price of every symbol included in the summary
+    #[serde(default)]
+    pub prices: BTreeMap<String, f64>,
+    /// Fraction of known symbols whose live data was fresh when the summary was generated,
+    /// below 1 during a partial upstream outage. `None` on summaries stored before it existed.
+    #[serde(default)]
+    pub data_completeness: Option<f64>,
+    /// Activity grouped by company sector; empty on summaries stored before it existed
+    #[serde(default)]
+    pub sectors: Vec<SectorSummary>,
     pub last_updated: DateTime<Utc>,
 }
 
-/// Represents a stock with its historical data
+impl MarketSummary {
+    /// The index level following this summary's, for a cycle with the given `prices`: this
+    /// level moved by the mean percent change of the symbols priced in both. `None` when this
+    /// summary has no level or no symbol in common with `prices`.
+    pub fn next_index_level(&self, prices: &BTreeMap<String, f64>) -> Option<f64> {
+        if !(self.index_level.is_finite() && self.index_level > 0.0) {
+            return None;
+        }
+        let changes: Vec<f64> = prices
+            .iter()
+            .filter_map(|(symbol, price)| {
+                let previous = *self.prices.get(symbol)?;
+                (previous > 0.0).then(|| price / previous - 1.0)
+            })
+            .collect();
+        if changes.is_empty() {
+            return None;
+        }
+        let mean_change = changes.iter().sum::<f64>() / changes.len() as f64;
+        Some(self.index_level * (1.0 + mean_change))
+    }
+}
+
+/// Price movement of a single symbol between two market snapshots
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Stock {
+pub struct SnapshotPriceChange {
     pub symbol: String,
-    pub equity: Equity,
-    pub live_data: Option<EquityLive>,
-    pub last_updated: DateTime<Utc>,
+    pub from_price: f64,
+    pub to_price: f64,
+    pub change: f64,
+    pub change_percent: Option<f64>,
+}
+
+/// Difference between two stored market summaries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub market_cap_change: f64,
+    pub market_cap_change_percent: Option<f64>,
+    pub volume_change: i64,
+    pub gainers: Vec<SnapshotPriceChange>,
+    pub losers: Vec<SnapshotPriceChange>,
+    pub new_symbols: Vec<String>,
+    pub removed_symbols: Vec<String>,
+}
+
+/// Represents a market-wide move that exceeded the configured alert threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketEvent {
+    pub timestamp: DateTime<Utc>,
+    pub previous_level: f64,
+    pub current_level: f64,
+    pub change_percent: f64,
+}
+
+/// Represents a completed scrape cycle of the background worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrapeCycle {
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    /// Number of live data records stored during the cycle
+    pub records: usize,
+}
+
+/// Cached derived metrics for a symbol, recomputed in bulk by the admin endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolMetrics {
+    pub symbol: String,
+    pub high_52w: Option<f64>,
+    pub low_52w: Option<f64>,
+    /// Average daily volume over the last 30 days
+    pub average_daily_volume: Option<f64>,
+    pub market_cap: Option<f64>,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// State of a queued webhook delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Waiting for its next retry
+    Pending,
+    /// Gave up after the maximum number of attempts; kept for inspection
+    Failed,
+}
+
+/// A webhook delivery that failed and is queued for retry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub url: String,
+    pub payload: serde_json::Value,
+    pub attempts: u32,
+    pub status: DeliveryStatus,
+    pub next_retry_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Kind of company announcement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnouncementCategory {
+    Earnings,
+    Agm,
+    Dividend,
+    Other,
+}
+
+/// A company announcement recorded by an operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Announcement {
+    pub id: String,
+    pub symbol: String,
+    pub date: NaiveDate,
+    pub title: String,
+    pub body: String,
+    pub category: AnnouncementCategory,
+}
+
+/// A government or corporate bond recorded by an operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bond {
+    /// Issue code, e.g. `GOG-2Y-2026`
+    pub code: String,
+    pub issuer: String,
+    pub maturity: Option<NaiveDate>,
+    /// Annual yield in percent
+    pub yield_percent: f64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One point of a yield curve
+#[derive(Debug, Clone, Serialize)]
+pub struct YieldCurvePoint {
+    pub code: String,
+    pub maturity: NaiveDate,
+    /// Years from today to maturity
+    pub years_to_maturity: f64,
+    pub yield_percent: f64,
+}
+
+/// A stock split or bonus issue recorded by an operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StockSplit {
+    pub symbol: String,
+    /// First trading day on the post-split basis
+    pub ex_date: NaiveDate,
+    /// Shares held after the split for each share held before, e.g. `2.0` for a 2-for-1 split
+    pub ratio: f64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Outcome of compacting old live ticks into monthly archives
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchiveSummary {
+    /// Symbols that had ticks archived
+    pub symbols: usize,
+    /// Monthly archives written or extended
+    pub months: usize,
+    /// Live tick records moved into archives and deleted
+    pub records_archived: usize,
+}
+
+/// Outcome of deleting live ticks and equity details older than the retention window
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneSummary {
+    /// Symbols that had records deleted
+    pub symbols: usize,
+    pub records_deleted: usize,
+}
+
+/// A consistent copy of the database
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    /// Name to restore the backup by
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A raw stored record for a symbol, as returned by the admin dump endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredRecord {
+    /// Record type taken from the key (e.g. `live`, `detail`)
+    pub record_type: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub value: serde_json::Value,
 }
 
 /// Represents time series data point
@@ -76,11 +357,80 @@ pub struct TimeSeriesPoint {
     pub timestamp: DateTime<Utc>,
     pub value: f64,
     pub volume: Option<i64>,
+    #[serde(default)]
+    pub source: DataSource,
 }
 
-/// Represents historical data for a stock
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StockHistory {
-    pub symbol: String,
-    pub data_points: Vec<TimeSeriesPoint>,
+#[cfg(test)]
+mod tests {
+    use super::{MarketSummary, Utc};
+    use crate::infrastructure::test_support::equity;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn valuation_metrics_are_derived_from_per_share_figures() {
+        let mut equity = equity("MTNGH", 2.0);
+        equity.dps = Some(0.1);
+        equity.eps = Some(0.25);
+        equity.shares = Some(1_000);
+
+        let metrics = equity.valuation_metrics();
+
+        assert_eq!(metrics.dividend_yield, Some(0.05));
+        assert_eq!(metrics.pe_ratio, Some(8.0));
+        assert_eq!(metrics.market_cap, Some(2_000.0));
+    }
+
+    #[test]
+    fn missing_per_share_figures_leave_the_metrics_empty() {
+        let equity = equity("MTNGH", 2.0);
+
+        let metrics = equity.valuation_metrics();
+
+        assert_eq!(metrics.dividend_yield, None);
+        assert_eq!(metrics.pe_ratio, None);
+        assert_eq!(metrics.market_cap, None);
+    }
+
+    #[test]
+    fn zero_price_or_earnings_do_not_divide_by_zero() {
+        let mut unpriced = equity("MTNGH", 0.0);
+        unpriced.dps = Some(0.1);
+        let mut no_earnings = equity("GCB", 5.0);
+        no_earnings.eps = Some(0.0);
+
+        assert_eq!(unpriced.dividend_yield(), None);
+        assert_eq!(no_earnings.pe_ratio(), None);
+    }
+
+    fn prices(entries: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        entries
+            .iter()
+            .map(|(symbol, price)| (symbol.to_string(), *price))
+            .collect()
+    }
+
+    #[test]
+    fn the_index_moves_by_the_mean_change_of_symbols_priced_in_both_cycles() {
+        let previous = MarketSummary {
+            total_market_cap: 0.0,
+            total_volume: 0,
+            total_stocks: 3,
+            top_gainers: Vec::new(),
+            top_losers: Vec::new(),
+            index_level: 100.0,
+            prices: prices(&[("MTNGH", 2.0), ("GCB", 5.0), ("SCB", 20.0)]),
+            data_completeness: None,
+            sectors: Vec::new(),
+            last_updated: Utc::now(),
+        };
+
+        // SCB dropped out and CAL is new; MTNGH rose 10% and GCB fell 2%
+        let next =
+            previous.next_index_level(&prices(&[("MTNGH", 2.2), ("GCB", 4.9), ("CAL", 1.0)]));
+        let unrelated = previous.next_index_level(&prices(&[("CAL", 1.0)]));
+
+        assert!((next.unwrap() - 104.0).abs() < 1e-9);
+        assert_eq!(unrelated, None);
+    }
 }
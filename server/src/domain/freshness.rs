@@ -0,0 +1,162 @@
+use crate::domain::MarketCalendar;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// How often each symbol's live data is expected to update while the market is trading
+#[derive(Debug, Clone)]
+pub struct FreshnessSla {
+    pub default: Duration,
+    /// Per-symbol SLAs, keyed by uppercase symbol
+    pub overrides: HashMap<String, Duration>,
+}
+
+impl Default for FreshnessSla {
+    fn default() -> Self {
+        Self {
+            default: Duration::minutes(15),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+/// A symbol whose latest live record is older than its SLA allows
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleSymbol {
+    pub symbol: String,
+    pub last_updated: DateTime<Utc>,
+    /// Seconds of trading time since the last update
+    pub age_seconds: i64,
+    pub sla_seconds: i64,
+}
+
+impl FreshnessSla {
+    /// Parse a comma-separated list of per-symbol SLAs in seconds, such as `MTNGH=600,GCB=1800`,
+    /// skipping invalid entries
+    pub fn parse_overrides(value: &str) -> HashMap<String, Duration> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let sla = entry
+                    .split_once('=')
+                    .and_then(|(symbol, secs)| Some((symbol, secs.trim().parse::<i64>().ok()?)))
+                    .filter(|(_, secs)| *secs > 0);
+                if sla.is_none() {
+                    tracing::warn!("Ignoring invalid freshness SLA {:?}", entry);
+                }
+                sla.map(|(symbol, secs)| (symbol.trim().to_uppercase(), Duration::seconds(secs)))
+            })
+            .collect()
+    }
+
+    /// SLA that applies to a symbol
+    pub fn sla_for(&self, symbol: &str) -> Duration {
+        self.overrides
+            .get(&symbol.to_uppercase())
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Symbols whose last update is older than their SLA, oldest first.
+    ///
+    /// Nothing is stale outside trading hours. Only time since today's open counts, so updates
+    /// from a previous session don't flag every symbol the moment the market opens.
+    pub fn stale_symbols(
+        &self,
+        last_updates: &BTreeMap<String, DateTime<Utc>>,
+        calendar: &MarketCalendar,
+        now: DateTime<Utc>,
+    ) -> Vec<StaleSymbol> {
+        if !calendar.is_trading_time(now) {
+            return Vec::new();
+        }
+        let Some(session_open) = now.date_naive().and_hms_opt(10, 0, 0) else {
+            return Vec::new();
+        };
+        let session_open = session_open.and_utc();
+
+        let mut stale: Vec<StaleSymbol> = last_updates
+            .iter()
+            .filter_map(|(symbol, last_updated)| {
+                let age = now - (*last_updated).max(session_open);
+                let sla = self.sla_for(symbol);
+                (age > sla).then(|| StaleSymbol {
+                    symbol: symbol.clone(),
+                    last_updated: *last_updated,
+                    age_seconds: age.num_seconds(),
+                    sla_seconds: sla.num_seconds(),
+                })
+            })
+            .collect();
+        stale.sort_by_key(|s| s.last_updated);
+        stale
+    }
+}
+
+/// Fraction of `symbols` known symbols whose last live update is no older than `fresh_within`,
+/// or 1 when no symbols are known
+pub fn data_completeness(
+    symbols: usize,
+    last_updates: &BTreeMap<String, DateTime<Utc>>,
+    fresh_within: Duration,
+    now: DateTime<Utc>,
+) -> f64 {
+    if symbols == 0 {
+        return 1.0;
+    }
+    let fresh = last_updates
+        .values()
+        .filter(|last_updated| now - **last_updated <= fresh_within)
+        .count();
+    fresh.min(symbols) as f64 / symbols as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// A Wednesday in market time
+    fn wednesday_at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 6, hour, minute, 0).unwrap()
+    }
+
+    fn last_updates() -> BTreeMap<String, DateTime<Utc>> {
+        BTreeMap::from([
+            ("MTNGH".to_string(), wednesday_at(11, 30)),
+            ("GCB".to_string(), wednesday_at(11, 55)),
+        ])
+    }
+
+    #[test]
+    fn a_symbol_past_its_sla_during_trading_hours_is_flagged() {
+        let sla = FreshnessSla {
+            overrides: FreshnessSla::parse_overrides("MTNGH=600"),
+            ..FreshnessSla::default()
+        };
+
+        let stale = sla.stale_symbols(
+            &last_updates(),
+            &MarketCalendar::default(),
+            wednesday_at(12, 0),
+        );
+
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].symbol, "MTNGH");
+        assert_eq!(stale[0].age_seconds, 1800);
+        assert_eq!(stale[0].sla_seconds, 600);
+    }
+
+    #[test]
+    fn nothing_is_stale_outside_trading_hours() {
+        let stale = FreshnessSla::default().stale_symbols(
+            &last_updates(),
+            &MarketCalendar::default(),
+            wednesday_at(18, 0),
+        );
+
+        assert!(stale.is_empty());
+    }
+}
@@ -0,0 +1,345 @@
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// The GSE trades on Accra time
+pub const MARKET_TIMEZONE: Tz = chrono_tz::Africa::Accra;
+
+/// Hour the market opens, in market time
+pub const DEFAULT_OPEN_HOUR: u32 = 10;
+
+/// Hour the market closes, in market time
+pub const DEFAULT_CLOSE_HOUR: u32 = 15;
+
+/// A daily window during which the market pauses trading (e.g. a midday auction)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PauseWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl PauseWindow {
+    /// Whether `time` falls inside the window (start inclusive, end exclusive)
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        time >= self.start && time < self.end
+    }
+
+    /// Parse a comma-separated list such as `12:00-12:30,14:00-14:15`, skipping invalid entries
+    pub fn parse_list(value: &str) -> Vec<PauseWindow> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| match entry.parse() {
+                Ok(window) => Some(window),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid pause window {:?}: {}", entry, e);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl FromStr for PauseWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| "expected HH:MM-HH:MM".to_string())?;
+        let parse =
+            |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").map_err(|e| e.to_string());
+        let (start, end) = (parse(start)?, parse(end)?);
+
+        if start >= end {
+            return Err("window start must be before its end".to_string());
+        }
+
+        Ok(Self { start, end })
+    }
+}
+
+/// Ghanaian public holidays on which the GSE closed in 2024 and 2025, as observed on weekdays
+const GSE_HOLIDAYS: &[(i32, u32, u32)] = &[
+    (2024, 1, 1),
+    (2024, 1, 8),
+    (2024, 3, 6),
+    (2024, 3, 29),
+    (2024, 4, 1),
+    (2024, 4, 10),
+    (2024, 5, 1),
+    (2024, 6, 17),
+    (2024, 8, 5),
+    (2024, 9, 23),
+    (2024, 12, 6),
+    (2024, 12, 25),
+    (2024, 12, 26),
+    (2025, 1, 1),
+    (2025, 1, 7),
+    (2025, 3, 6),
+    (2025, 3, 31),
+    (2025, 4, 18),
+    (2025, 4, 21),
+    (2025, 5, 1),
+    (2025, 6, 6),
+    (2025, 7, 1),
+    (2025, 8, 4),
+    (2025, 9, 22),
+    (2025, 12, 5),
+    (2025, 12, 25),
+    (2025, 12, 26),
+];
+
+/// Whether the market is trading at a given instant, and why not if it isn't
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketStatus {
+    Open,
+    Weekend,
+    Holiday,
+    OffHours,
+    Paused,
+}
+
+/// Whether a calendar date is a trading day, and why not if it isn't
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayStatus {
+    Open,
+    Weekend,
+    Holiday,
+}
+
+/// One date of the trading calendar
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub is_trading_day: bool,
+    pub reason: DayStatus,
+}
+
+/// GSE trading calendar: Monday-Friday, 10:00 AM - 3:00 PM Accra time by default, minus
+/// holidays and any pause windows
+#[derive(Debug, Clone)]
+pub struct MarketCalendar {
+    /// Daily windows in market time
+    pub pause_windows: Vec<PauseWindow>,
+    /// Weekdays on which the market is closed
+    pub holidays: Vec<NaiveDate>,
+    pub timezone: Tz,
+    pub open_hour: u32,
+    pub close_hour: u32,
+}
+
+impl Default for MarketCalendar {
+    fn default() -> Self {
+        Self::new(Vec::new(), Vec::new())
+    }
+}
+
+impl MarketCalendar {
+    pub fn new(pause_windows: Vec<PauseWindow>, holidays: Vec<NaiveDate>) -> Self {
+        Self {
+            pause_windows,
+            holidays,
+            timezone: MARKET_TIMEZONE,
+            open_hour: DEFAULT_OPEN_HOUR,
+            close_hour: DEFAULT_CLOSE_HOUR,
+        }
+    }
+
+    /// Trade from `open_hour` until `close_hour` instead of the default hours
+    pub fn with_trading_hours(mut self, open_hour: u32, close_hour: u32) -> Self {
+        self.open_hour = open_hour;
+        self.close_hour = close_hour;
+        self
+    }
+
+    /// Fixed GSE holidays used when none are configured
+    pub fn default_holidays() -> Vec<NaiveDate> {
+        GSE_HOLIDAYS
+            .iter()
+            .filter_map(|(year, month, day)| NaiveDate::from_ymd_opt(*year, *month, *day))
+            .collect()
+    }
+
+    /// Parse a comma-separated list of `YYYY-MM-DD` dates, skipping invalid entries
+    pub fn parse_holidays(value: &str) -> Vec<NaiveDate> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| match NaiveDate::parse_from_str(entry, "%Y-%m-%d") {
+                Ok(date) => Some(date),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid holiday {:?}: {}", entry, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Whether the given date is a trading day
+    pub fn day_status(&self, date: NaiveDate) -> DayStatus {
+        if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            DayStatus::Weekend
+        } else if self.holidays.contains(&date) {
+            DayStatus::Holiday
+        } else {
+            DayStatus::Open
+        }
+    }
+
+    /// Every date from `from` to `to` inclusive with its trading status
+    pub fn days_between(&self, from: NaiveDate, to: NaiveDate) -> Vec<CalendarDay> {
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| {
+                let reason = self.day_status(date);
+                CalendarDay {
+                    date,
+                    is_trading_day: reason == DayStatus::Open,
+                    reason,
+                }
+            })
+            .collect()
+    }
+
+    /// Trading status at the given instant
+    pub fn status_at(&self, now: DateTime<Utc>) -> MarketStatus {
+        let local = now.with_timezone(&self.timezone);
+        match self.day_status(local.date_naive()) {
+            DayStatus::Weekend => return MarketStatus::Weekend,
+            DayStatus::Holiday => return MarketStatus::Holiday,
+            DayStatus::Open => {}
+        }
+
+        if !(self.open_hour..self.close_hour).contains(&local.hour()) {
+            return MarketStatus::OffHours;
+        }
+
+        let time = local.time();
+        if self
+            .pause_windows
+            .iter()
+            .any(|window| window.contains(time))
+        {
+            return MarketStatus::Paused;
+        }
+
+        MarketStatus::Open
+    }
+
+    /// Whether the market is trading at the given instant
+    pub fn is_trading_time(&self, now: DateTime<Utc>) -> bool {
+        self.status_at(now) == MarketStatus::Open
+    }
+
+    /// Open and close of the most recent session that ended at or before `now`.
+    ///
+    /// Looks back at most a year, so a calendar with no trading days yields `None`.
+    pub fn last_completed_session(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let date = self.last_completed_trading_day(now)?;
+        Some((
+            self.utc_at(date, self.open_hour)?,
+            self.utc_at(date, self.close_hour)?,
+        ))
+    }
+
+    /// Market date of the most recent session that ended at or before `now`, looking back at
+    /// most a year
+    pub fn last_completed_trading_day(&self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let local = now.with_timezone(&self.timezone);
+        let mut date = local.date_naive();
+        // Today's session only counts once it has closed
+        if local.hour() < self.close_hour {
+            date = date.pred_opt()?;
+        }
+
+        for _ in 0..366 {
+            if self.day_status(date) == DayStatus::Open {
+                return Some(date);
+            }
+            date = date.pred_opt()?;
+        }
+
+        None
+    }
+
+    /// The instant `hour` o'clock in market time falls on `date`
+    fn utc_at(&self, date: NaiveDate, hour: u32) -> Option<DateTime<Utc>> {
+        self.timezone
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0)?)
+            .earliest()
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    #[test]
+    fn calendar_days_flag_weekends_and_configured_holidays() {
+        let calendar = MarketCalendar::new(Vec::new(), vec![date(11)]);
+
+        let days = calendar.days_between(date(8), date(12));
+
+        let flags: Vec<(u32, bool, DayStatus)> = days
+            .iter()
+            .map(|day| (day.date.day(), day.is_trading_day, day.reason))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                (8, true, DayStatus::Open),
+                (9, false, DayStatus::Weekend),
+                (10, false, DayStatus::Weekend),
+                (11, false, DayStatus::Holiday),
+                (12, true, DayStatus::Open),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_default_holiday_is_closed_during_weekday_trading_hours() {
+        let calendar = MarketCalendar::new(Vec::new(), MarketCalendar::default_holidays());
+        // Independence Day fell on a Wednesday in 2024
+        let independence_day = date(6).and_hms_opt(12, 0, 0).unwrap().and_utc();
+        let next_day = date(7).and_hms_opt(12, 0, 0).unwrap().and_utc();
+
+        assert_eq!(calendar.status_at(independence_day), MarketStatus::Holiday);
+        assert!(!calendar.is_trading_time(independence_day));
+        assert!(calendar.is_trading_time(next_day));
+    }
+
+    #[test]
+    fn trading_hours_are_checked_in_accra_time_against_the_configured_hours() {
+        let calendar = MarketCalendar::default().with_trading_hours(9, 16);
+        let at = |hour: u32, minute: u32| date(7).and_hms_opt(hour, minute, 0).unwrap().and_utc();
+
+        let statuses: Vec<MarketStatus> = [(8, 59), (9, 0), (15, 59), (16, 0)]
+            .into_iter()
+            .map(|(hour, minute)| calendar.status_at(at(hour, minute)))
+            .collect();
+
+        assert_eq!(
+            statuses,
+            [
+                MarketStatus::OffHours,
+                MarketStatus::Open,
+                MarketStatus::Open,
+                MarketStatus::OffHours,
+            ]
+        );
+        assert!(!MarketCalendar::default().is_trading_time(at(9, 30)));
+    }
+}
@@ -1,7 +1,19 @@
+pub mod alert;
+pub mod analytics;
 pub mod entities;
+pub mod freshness;
+pub mod market_calendar;
 pub mod portfolio;
 pub mod repository;
+pub mod search;
+pub mod serde_helpers;
+pub mod watchlist;
 
+pub use alert::*;
 pub use entities::*;
+pub use freshness::*;
+pub use market_calendar::*;
 pub use portfolio::*;
 pub use repository::*;
+pub use search::*;
+pub use watchlist::*;
@@ -1,94 +1,575 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum TransactionType {
     Buy,
     Sell,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Transaction {
     pub id: String,
     pub symbol: String,
     pub transaction_type: TransactionType,
     pub quantity: i64,
     pub price_per_share: f64,
+    /// Brokerage fees and levies paid on the trade
+    #[serde(default)]
+    pub fee: f64,
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Transaction {
+    /// Reject a trade no holding could come from: a quantity below one share, or a price or
+    /// fee that is negative or not a number
+    pub fn validate(&self) -> Result<(), TransactionError> {
+        if self.quantity <= 0 {
+            return Err(TransactionError::InvalidQuantity {
+                quantity: self.quantity,
+            });
+        }
+        for (field, value) in [("price_per_share", self.price_per_share), ("fee", self.fee)] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(TransactionError::InvalidAmount { field, value });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How sells are matched against earlier buys to work out the cost of the shares sold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CostBasisMethod {
+    /// Sold shares cost the average price of all shares held
+    #[default]
+    Average,
+    /// Sold shares come from the oldest purchase lots first
+    Fifo,
+    /// Sold shares come from the newest purchase lots first
+    Lifo,
+}
+
+impl CostBasisMethod {
+    fn tracks_lots(self) -> bool {
+        !matches!(self, CostBasisMethod::Average)
+    }
+}
+
+/// Shares bought in one transaction that are still held
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PurchaseLot {
+    pub quantity: i64,
+    pub price_per_share: f64,
+    pub acquired_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PortfolioItem {
     pub symbol: String,
     pub quantity: i64,
+    /// Average cost of the shares still held
     pub average_buy_price: f64,
+    /// Open purchase lots, oldest first; only tracked for FIFO and LIFO portfolios
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lots: Vec<PurchaseLot>,
+}
+
+impl PortfolioItem {
+    fn empty(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            quantity: 0,
+            average_buy_price: 0.0,
+            lots: Vec::new(),
+        }
+    }
+
+    fn buy(&mut self, transaction: &Transaction, method: CostBasisMethod) {
+        let total_cost = (self.quantity as f64 * self.average_buy_price)
+            + (transaction.quantity as f64 * transaction.price_per_share);
+        self.quantity += transaction.quantity;
+        if self.quantity > 0 {
+            self.average_buy_price = total_cost / self.quantity as f64;
+        }
+
+        if method.tracks_lots() {
+            // Backdated buys are slotted in by acquisition time
+            let index = self
+                .lots
+                .partition_point(|lot| lot.acquired_at <= transaction.timestamp);
+            self.lots.insert(
+                index,
+                PurchaseLot {
+                    quantity: transaction.quantity,
+                    price_per_share: transaction.price_per_share,
+                    acquired_at: transaction.timestamp,
+                },
+            );
+        }
+    }
+
+    /// Remove up to `quantity` shares, returning how many were removed and their total cost.
+    /// Shares sold beyond the position have no matching buy and are ignored.
+    fn sell(&mut self, quantity: i64, method: CostBasisMethod) -> (i64, f64) {
+        let matched = quantity.min(self.quantity).max(0);
+        let cost = match method {
+            CostBasisMethod::Average => matched as f64 * self.average_buy_price,
+            CostBasisMethod::Fifo | CostBasisMethod::Lifo => {
+                let mut remaining = matched;
+                let mut cost = 0.0;
+                while remaining > 0 {
+                    let lot = match method {
+                        CostBasisMethod::Fifo => self.lots.first_mut(),
+                        _ => self.lots.last_mut(),
+                    };
+                    let Some(lot) = lot else { break };
+
+                    // A sell larger than the lot closes it and carries on into the next one
+                    let taken = remaining.min(lot.quantity);
+                    cost += taken as f64 * lot.price_per_share;
+                    lot.quantity -= taken;
+                    remaining -= taken;
+                    self.lots.retain(|lot| lot.quantity > 0);
+                }
+                cost
+            }
+        };
+
+        self.quantity -= matched;
+        if method.tracks_lots() && self.quantity > 0 {
+            // Keep the summary consistent with the lots left open
+            let open_cost: f64 = self
+                .lots
+                .iter()
+                .map(|lot| lot.quantity as f64 * lot.price_per_share)
+                .sum();
+            self.average_buy_price = open_cost / self.quantity as f64;
+        }
+        (matched, cost)
+    }
+}
+
+/// A cash dividend on a symbol, paid on the shares held going into its ex-date
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DividendRecord {
+    pub id: String,
+    pub symbol: String,
+    /// Dividend per share in GHS
+    pub amount_per_share: f64,
+    /// First day the shares trade without the dividend; holders at the end of the day before are paid
+    pub ex_date: NaiveDate,
+    pub pay_date: NaiveDate,
+}
+
+/// What one recorded dividend paid the portfolio
+#[derive(Debug, Clone, Serialize)]
+pub struct DividendIncome {
+    pub dividend_id: String,
+    pub symbol: String,
+    /// Shares held at the end of the day before the ex-date
+    pub quantity: i64,
+    pub amount_per_share: f64,
+    pub income: f64,
+    pub pay_date: NaiveDate,
+}
+
+/// Gain realized by one sell, matched against earlier buys by the portfolio's cost-basis method
+#[derive(Debug, Clone, Serialize)]
+pub struct RealizedSale {
+    pub transaction_id: String,
+    pub symbol: String,
+    /// Shares matched against earlier buys
+    pub quantity: i64,
+    pub proceeds: f64,
+    pub cost_basis: f64,
+    /// Proceeds less cost basis, before fees
+    pub gain: f64,
+    pub timestamp: DateTime<Utc>,
 }
 
+/// Capital deployed by a portfolio according to its transaction log
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CostSummary {
+    /// Sum of quantity * price over buys
+    pub total_invested: f64,
+    /// Sum of quantity * price over sells
+    pub total_proceeds: f64,
+    pub total_fees: f64,
+    /// Invested plus fees, less proceeds
+    pub net_invested: f64,
+}
+
+/// A portfolio's valuation at the end of one trading day
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub portfolio_id: String,
+    pub date: NaiveDate,
+    /// Holdings valued at the latest stored price, or at cost when no price is known
+    pub market_value: f64,
+    pub cost_basis: f64,
+    /// Currency of `market_value` and `cost_basis`, the portfolio's base currency
+    #[serde(default = "default_base_currency")]
+    pub currency: String,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// A portfolio's total value at one point during the trading day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PortfolioValuePoint {
+    pub timestamp: DateTime<Utc>,
+    /// Holdings valued at the latest stored price, or at cost when no price is known;
+    /// zero while the portfolio holds nothing
+    pub market_value: f64,
+    pub cost_basis: f64,
+    /// Number of symbols held when the point was taken
+    pub holdings: usize,
+    /// Currency of `market_value` and `cost_basis`, the portfolio's base currency
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Portfolio {
     pub id: String,
     pub name: String,
+    /// Currency valuations are reported in; holdings are priced in GHS and converted
+    #[serde(default = "default_base_currency")]
+    pub base_currency: String,
+    #[serde(default)]
+    pub cost_basis_method: CostBasisMethod,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub items: Vec<PortfolioItem>,
     pub transactions: Vec<Transaction>,
+    #[serde(default)]
+    pub dividends: Vec<DividendRecord>,
+}
+
+fn default_base_currency() -> String {
+    crate::domain::PRICE_CURRENCY.to_string()
 }
 
 impl Portfolio {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, base_currency: String, cost_basis_method: CostBasisMethod) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
             name,
+            base_currency,
+            cost_basis_method,
             created_at: Utc::now(),
             updated_at: Utc::now(),
             items: Vec::new(),
             transactions: Vec::new(),
+            dividends: Vec::new(),
+        }
+    }
+
+    pub fn cost_summary(&self) -> CostSummary {
+        let mut summary = CostSummary::default();
+
+        for transaction in &self.transactions {
+            let value = transaction.quantity as f64 * transaction.price_per_share;
+            match transaction.transaction_type {
+                TransactionType::Buy => summary.total_invested += value,
+                TransactionType::Sell => summary.total_proceeds += value,
+            }
+            summary.total_fees += transaction.fee;
+        }
+
+        summary.net_invested = summary.total_invested + summary.total_fees - summary.total_proceeds;
+        summary
+    }
+
+    /// Every sell's realized gain, replaying the transaction log in time order and matching
+    /// sells against earlier buys by the portfolio's cost-basis method. Fees are not included.
+    pub fn realized_sales(&self) -> Vec<RealizedSale> {
+        let mut transactions: Vec<&Transaction> = self.transactions.iter().collect();
+        transactions.sort_by_key(|t| t.timestamp);
+
+        let mut positions: HashMap<&str, PortfolioItem> = HashMap::new();
+        let mut sales = Vec::new();
+        for transaction in transactions {
+            let position = positions
+                .entry(&transaction.symbol)
+                .or_insert_with(|| PortfolioItem::empty(&transaction.symbol));
+            match transaction.transaction_type {
+                TransactionType::Buy => position.buy(transaction, self.cost_basis_method),
+                TransactionType::Sell => {
+                    let (quantity, cost_basis) =
+                        position.sell(transaction.quantity, self.cost_basis_method);
+                    let proceeds = quantity as f64 * transaction.price_per_share;
+                    sales.push(RealizedSale {
+                        transaction_id: transaction.id.clone(),
+                        symbol: transaction.symbol.clone(),
+                        quantity,
+                        proceeds,
+                        cost_basis,
+                        gain: proceeds - cost_basis,
+                        timestamp: transaction.timestamp,
+                    });
+                }
+            }
+        }
+
+        sales
+    }
+
+    /// Gain realized by each symbol's sells; see `realized_sales`
+    pub fn realized_gains(&self) -> BTreeMap<String, f64> {
+        let mut gains = BTreeMap::new();
+        for sale in self.realized_sales() {
+            *gains.entry(sale.symbol).or_insert(0.0) += sale.gain;
+        }
+        gains
+    }
+
+    /// Record a dividend; income follows from the transaction log, so later edits to it are reflected
+    pub fn add_dividend(&mut self, dividend: DividendRecord) {
+        self.dividends.push(dividend);
+        self.updated_at = Utc::now();
+    }
+
+    /// Shares of `symbol` held at the end of the day before `date`
+    pub fn quantity_held_before(&self, symbol: &str, date: NaiveDate) -> i64 {
+        let cutoff = date.and_time(NaiveTime::MIN).and_utc();
+        self.transactions
+            .iter()
+            .filter(|t| t.timestamp < cutoff && t.symbol.eq_ignore_ascii_case(symbol))
+            .map(|t| match t.transaction_type {
+                TransactionType::Buy => t.quantity,
+                TransactionType::Sell => -t.quantity,
+            })
+            .sum()
+    }
+
+    /// Income from every recorded dividend, by pay date: the dividend per share times the
+    /// shares held going into its ex-date
+    pub fn dividend_income(&self) -> Vec<DividendIncome> {
+        let mut income: Vec<DividendIncome> = self
+            .dividends
+            .iter()
+            .map(|dividend| {
+                let quantity = self
+                    .quantity_held_before(&dividend.symbol, dividend.ex_date)
+                    .max(0);
+                DividendIncome {
+                    dividend_id: dividend.id.clone(),
+                    symbol: dividend.symbol.clone(),
+                    quantity,
+                    amount_per_share: dividend.amount_per_share,
+                    income: quantity as f64 * dividend.amount_per_share,
+                    pay_date: dividend.pay_date,
+                }
+            })
+            .collect();
+        income.sort_by_key(|i| i.pay_date);
+        income
+    }
+
+    /// The portfolio as it stood at `cutoff`: holdings rebuilt by replaying, in time order, only
+    /// the transactions made at or before it
+    pub fn as_of(&self, cutoff: DateTime<Utc>) -> Portfolio {
+        let mut transactions: Vec<Transaction> = self
+            .transactions
+            .iter()
+            .filter(|t| t.timestamp <= cutoff)
+            .cloned()
+            .collect();
+        transactions.sort_by_key(|t| t.timestamp);
+
+        let mut portfolio = Portfolio {
+            items: Vec::new(),
+            transactions: Vec::new(),
+            updated_at: cutoff,
+            ..self.clone()
+        };
+        for transaction in transactions {
+            portfolio.update_holdings(&transaction);
+            portfolio.transactions.push(transaction);
+        }
+        portfolio
+    }
+
+    /// Apply a transaction, rejecting sells of more shares than the portfolio holds
+    pub fn add_transaction(&mut self, transaction: Transaction) -> Result<(), TransactionError> {
+        transaction.validate()?;
+        self.transactions.push(transaction);
+        if let Err(e) = self.recalculate_holdings() {
+            self.transactions.pop();
+            return Err(e);
         }
+        self.updated_at = Utc::now();
+        Ok(())
     }
 
-    pub fn add_transaction(&mut self, transaction: Transaction) {
-        self.transactions.push(transaction.clone());
-        self.update_holdings(&transaction);
+    /// Replace the transaction with the given id, keeping its position in the log
+    pub fn replace_transaction(
+        &mut self,
+        id: &str,
+        transaction: Transaction,
+    ) -> Result<(), TransactionError> {
+        transaction.validate()?;
+        let index = self.transaction_index(id)?;
+        let previous = std::mem::replace(&mut self.transactions[index], transaction);
+        if let Err(e) = self.recalculate_holdings() {
+            self.transactions[index] = previous;
+            return Err(e);
+        }
         self.updated_at = Utc::now();
+        Ok(())
     }
 
-    fn update_holdings(&mut self, transaction: &Transaction) {
-        let item_opt = self.items.iter_mut().find(|i| i.symbol == transaction.symbol);
-
-        match item_opt {
-            Some(item) => {
-                match transaction.transaction_type {
-                    TransactionType::Buy => {
-                        let total_cost = (item.quantity as f64 * item.average_buy_price)
-                            + (transaction.quantity as f64 * transaction.price_per_share);
-                        item.quantity += transaction.quantity;
-                        item.average_buy_price = total_cost / item.quantity as f64;
+    /// Delete the transaction with the given id, rejecting the deletion if a later sell would
+    /// then exceed the shares held
+    pub fn remove_transaction(&mut self, id: &str) -> Result<Transaction, TransactionError> {
+        let index = self.transaction_index(id)?;
+        let removed = self.transactions.remove(index);
+        if let Err(e) = self.recalculate_holdings() {
+            self.transactions.insert(index, removed);
+            return Err(e);
+        }
+        self.updated_at = Utc::now();
+        Ok(removed)
+    }
+
+    fn transaction_index(&self, id: &str) -> Result<usize, TransactionError> {
+        self.transactions
+            .iter()
+            .position(|t| t.id == id)
+            .ok_or_else(|| TransactionError::UnknownTransaction { id: id.to_string() })
+    }
+
+    /// Rebuild `items` from scratch by replaying the whole transaction log in time order, so a
+    /// corrected or deleted entry leaves no trace in the holdings. Rejects a log in which a sell
+    /// exceeds the shares held at the time, leaving `items` untouched.
+    pub fn recalculate_holdings(&mut self) -> Result<(), TransactionError> {
+        let method = self.cost_basis_method;
+        let mut transactions: Vec<&Transaction> = self.transactions.iter().collect();
+        transactions.sort_by_key(|t| t.timestamp);
+
+        let mut items: Vec<PortfolioItem> = Vec::new();
+        for transaction in transactions {
+            let index = match items.iter().position(|i| i.symbol == transaction.symbol) {
+                Some(index) => index,
+                None => {
+                    items.push(PortfolioItem::empty(&transaction.symbol));
+                    items.len() - 1
+                }
+            };
+            let item = &mut items[index];
+
+            match transaction.transaction_type {
+                TransactionType::Buy => item.buy(transaction, method),
+                TransactionType::Sell => {
+                    if item.quantity == 0 {
+                        return Err(TransactionError::NoPosition {
+                            symbol: transaction.symbol.clone(),
+                        });
                     }
-                    TransactionType::Sell => {
-                        // When selling, average buy price doesn't change, only quantity reduces
-                        item.quantity -= transaction.quantity;
+                    if transaction.quantity > item.quantity {
+                        return Err(TransactionError::InsufficientQuantity {
+                            symbol: transaction.symbol.clone(),
+                            held: item.quantity,
+                            requested: transaction.quantity,
+                        });
                     }
+                    item.sell(transaction.quantity, method);
                 }
             }
+        }
+
+        items.retain(|i| i.quantity > 0);
+        self.items = items;
+        Ok(())
+    }
+
+    fn update_holdings(&mut self, transaction: &Transaction) {
+        let method = self.cost_basis_method;
+        let item = match self
+            .items
+            .iter()
+            .position(|i| i.symbol == transaction.symbol)
+        {
+            Some(index) => &mut self.items[index],
             None => {
-                if let TransactionType::Buy = transaction.transaction_type {
-                    self.items.push(PortfolioItem {
-                        symbol: transaction.symbol.clone(),
-                        quantity: transaction.quantity,
-                        average_buy_price: transaction.price_per_share,
-                    });
-                }
-                // If selling something we don't have, we ignore it for now or handle error elsewhere.
-                // For simplicity in this domain logic, we assume valid transactions.
+                // Sells without a position are rejected by `recalculate_holdings`
+                self.items.push(PortfolioItem::empty(&transaction.symbol));
+                self.items.last_mut().unwrap()
+            }
+        };
+
+        match transaction.transaction_type {
+            TransactionType::Buy => item.buy(transaction, method),
+            TransactionType::Sell => {
+                item.sell(transaction.quantity, method);
             }
         }
-        
+
         // Remove items with 0 quantity
         self.items.retain(|i| i.quantity > 0);
     }
 }
 
+/// A transaction that can't be applied to a portfolio's current holdings
+#[derive(Debug)]
+pub enum TransactionError {
+    /// No transaction in the portfolio has this id
+    UnknownTransaction { id: String },
+    /// Sell of a symbol the portfolio doesn't hold
+    NoPosition { symbol: String },
+    /// Dividend recorded without an amount for a symbol with no stored dividend per share
+    UnknownDividendAmount { symbol: String },
+    /// Sell of more shares than the portfolio holds
+    InsufficientQuantity {
+        symbol: String,
+        held: i64,
+        requested: i64,
+    },
+    /// Trade of zero or a negative number of shares
+    InvalidQuantity { quantity: i64 },
+    /// Negative or non-finite price or fee
+    InvalidAmount { field: &'static str, value: f64 },
+}
+
+impl std::fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransactionError::UnknownTransaction { id } => {
+                write!(f, "Transaction not found: {}", id)
+            }
+            TransactionError::NoPosition { symbol } => {
+                write!(f, "Cannot sell {}: no position held", symbol)
+            }
+            TransactionError::UnknownDividendAmount { symbol } => write!(
+                f,
+                "No dividend per share stored for {}; give amount_per_share",
+                symbol
+            ),
+            TransactionError::InsufficientQuantity {
+                symbol,
+                held,
+                requested,
+            } => write!(
+                f,
+                "Cannot sell {} shares of {}: only {} held",
+                requested, symbol, held
+            ),
+            TransactionError::InvalidQuantity { quantity } => {
+                write!(f, "quantity must be at least 1, got {}", quantity)
+            }
+            TransactionError::InvalidAmount { field, value } => {
+                write!(f, "{} must be zero or more, got {}", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
 #[async_trait::async_trait]
 pub trait PortfolioRepository {
     async fn create_portfolio(&self, portfolio: &Portfolio) -> anyhow::Result<()>;
@@ -96,4 +577,310 @@ pub trait PortfolioRepository {
     async fn get_all_portfolios(&self) -> anyhow::Result<Vec<Portfolio>>;
     async fn update_portfolio(&self, portfolio: &Portfolio) -> anyhow::Result<()>;
     async fn delete_portfolio(&self, id: &str) -> anyhow::Result<()>;
+    /// Store a daily valuation snapshot, replacing any earlier one for the same date
+    async fn store_snapshot(&self, snapshot: &PortfolioSnapshot) -> anyhow::Result<()>;
+    /// Get a portfolio's valuation snapshots, oldest first
+    async fn get_snapshots(&self, id: &str) -> anyhow::Result<Vec<PortfolioSnapshot>>;
+    /// Date of a portfolio's most recent valuation snapshot, if it has any
+    async fn get_last_snapshot_date(&self, id: &str) -> anyhow::Result<Option<NaiveDate>>;
+    /// Store a point in a portfolio's value time series
+    async fn store_value_point(&self, id: &str, point: &PortfolioValuePoint) -> anyhow::Result<()>;
+    /// Get a portfolio's value points taken within `from..=to`, oldest first
+    async fn get_value_points(
+        &self,
+        id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<PortfolioValuePoint>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Datelike, TimeZone};
+
+    fn transaction(
+        day: u32,
+        transaction_type: TransactionType,
+        quantity: i64,
+        price_per_share: f64,
+        fee: f64,
+    ) -> Transaction {
+        Transaction {
+            id: Uuid::new_v4().to_string(),
+            symbol: "MTNGH".to_string(),
+            transaction_type,
+            quantity,
+            price_per_share,
+            fee,
+            timestamp: Utc.with_ymd_and_hms(2024, 3, day, 10, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn cost_summary_totals_buys_sells_and_fees() {
+        let mut portfolio = Portfolio::new(
+            "Growth".to_string(),
+            "GHS".to_string(),
+            CostBasisMethod::Average,
+        );
+        portfolio
+            .add_transaction(transaction(4, TransactionType::Buy, 100, 1.5, 2.0))
+            .unwrap();
+        portfolio
+            .add_transaction(transaction(5, TransactionType::Sell, 40, 2.0, 1.0))
+            .unwrap();
+        portfolio
+            .add_transaction(transaction(6, TransactionType::Buy, 50, 1.8, 1.5))
+            .unwrap();
+
+        let summary = portfolio.cost_summary();
+
+        assert_eq!(summary.total_invested, 240.0);
+        assert_eq!(summary.total_proceeds, 80.0);
+        assert_eq!(summary.total_fees, 4.5);
+        assert_eq!(summary.net_invested, 164.5);
+    }
+
+    fn held_portfolio() -> Portfolio {
+        let mut portfolio = Portfolio::new(
+            "Growth".to_string(),
+            "GHS".to_string(),
+            CostBasisMethod::Average,
+        );
+        portfolio
+            .add_transaction(transaction(4, TransactionType::Buy, 100, 1.5, 0.0))
+            .unwrap();
+        portfolio
+    }
+
+    #[test]
+    fn selling_more_than_is_held_is_rejected() {
+        let mut portfolio = held_portfolio();
+
+        let result =
+            portfolio.add_transaction(transaction(5, TransactionType::Sell, 150, 2.0, 0.0));
+
+        assert!(matches!(
+            result,
+            Err(TransactionError::InsufficientQuantity {
+                held: 100,
+                requested: 150,
+                ..
+            })
+        ));
+        assert_eq!(portfolio.items[0].quantity, 100);
+        assert_eq!(portfolio.transactions.len(), 1);
+    }
+
+    #[test]
+    fn selling_a_symbol_not_held_is_rejected() {
+        let mut portfolio = held_portfolio();
+        let mut sell = transaction(5, TransactionType::Sell, 10, 4.0, 0.0);
+        sell.symbol = "GCB".to_string();
+
+        let result = portfolio.add_transaction(sell);
+
+        let Err(error) = result else {
+            panic!("sell of an unheld symbol was accepted");
+        };
+        assert_eq!(error.to_string(), "Cannot sell GCB: no position held");
+        assert_eq!(portfolio.transactions.len(), 1);
+    }
+
+    #[test]
+    fn selling_exactly_the_held_quantity_closes_the_position() {
+        let mut portfolio = held_portfolio();
+
+        portfolio
+            .add_transaction(transaction(5, TransactionType::Sell, 100, 2.0, 0.0))
+            .unwrap();
+
+        assert!(portfolio.items.is_empty());
+        assert_eq!(portfolio.realized_gains()["MTNGH"], 50.0);
+    }
+
+    /// Buys of 100 at 1.0 then 100 at 2.0, then a sell of 150 at 3.0 spanning both lots
+    fn lots_sold_by(method: CostBasisMethod) -> Portfolio {
+        let mut portfolio = Portfolio::new("Lots".to_string(), "GHS".to_string(), method);
+        for transaction in [
+            transaction(4, TransactionType::Buy, 100, 1.0, 0.0),
+            transaction(5, TransactionType::Buy, 100, 2.0, 0.0),
+            transaction(6, TransactionType::Sell, 150, 3.0, 0.0),
+        ] {
+            portfolio.add_transaction(transaction).unwrap();
+        }
+        portfolio
+    }
+
+    #[test]
+    fn fifo_sells_the_oldest_lots_first() {
+        let portfolio = lots_sold_by(CostBasisMethod::Fifo);
+
+        let sales = portfolio.realized_sales();
+
+        assert_eq!(sales[0].cost_basis, 200.0);
+        assert_eq!(sales[0].gain, 250.0);
+        let item = &portfolio.items[0];
+        assert_eq!(item.quantity, 50);
+        assert_eq!(item.average_buy_price, 2.0);
+    }
+
+    #[test]
+    fn lifo_sells_the_newest_lots_first() {
+        let portfolio = lots_sold_by(CostBasisMethod::Lifo);
+
+        let sales = portfolio.realized_sales();
+
+        assert_eq!(sales[0].cost_basis, 250.0);
+        assert_eq!(sales[0].gain, 200.0);
+        let item = &portfolio.items[0];
+        assert_eq!(item.quantity, 50);
+        assert_eq!(item.average_buy_price, 1.0);
+    }
+
+    /// Symbol, quantity and average price of each holding
+    fn holdings(portfolio: &Portfolio) -> Vec<(String, i64, f64)> {
+        portfolio
+            .items
+            .iter()
+            .map(|item| (item.symbol.clone(), item.quantity, item.average_buy_price))
+            .collect()
+    }
+
+    #[test]
+    fn deleting_a_mistaken_buy_leaves_holdings_as_if_it_never_happened() {
+        let mut portfolio = held_portfolio();
+        let mistake = transaction(5, TransactionType::Buy, 100, 15.0, 0.0);
+        let mistake_id = mistake.id.clone();
+        portfolio.add_transaction(mistake).unwrap();
+        portfolio
+            .add_transaction(transaction(6, TransactionType::Buy, 100, 2.5, 0.0))
+            .unwrap();
+
+        portfolio.remove_transaction(&mistake_id).unwrap();
+
+        assert_eq!(holdings(&portfolio), [("MTNGH".to_string(), 200, 2.0)]);
+    }
+
+    #[test]
+    fn editing_a_buy_rebuilds_the_average_price() {
+        let mut portfolio = held_portfolio();
+        let id = portfolio.transactions[0].id.clone();
+        let mut corrected = transaction(4, TransactionType::Buy, 100, 1.0, 0.0);
+        corrected.id = id.clone();
+        portfolio
+            .add_transaction(transaction(6, TransactionType::Buy, 100, 2.0, 0.0))
+            .unwrap();
+
+        portfolio.replace_transaction(&id, corrected).unwrap();
+
+        assert_eq!(holdings(&portfolio), [("MTNGH".to_string(), 200, 1.5)]);
+        assert_eq!(portfolio.transactions[0].price_per_share, 1.0);
+    }
+
+    #[test]
+    fn deleting_a_buy_a_later_sell_depends_on_is_rejected() {
+        let mut portfolio = held_portfolio();
+        let id = portfolio.transactions[0].id.clone();
+        portfolio
+            .add_transaction(transaction(5, TransactionType::Sell, 60, 2.0, 0.0))
+            .unwrap();
+
+        let result = portfolio.remove_transaction(&id);
+
+        assert!(result.is_err());
+        assert_eq!(portfolio.transactions.len(), 2);
+        assert_eq!(holdings(&portfolio), [("MTNGH".to_string(), 40, 1.5)]);
+    }
+
+    fn dividend(ex_day: u32, pay_day: u32, amount_per_share: f64) -> DividendRecord {
+        DividendRecord {
+            id: Uuid::new_v4().to_string(),
+            symbol: "MTNGH".to_string(),
+            amount_per_share,
+            ex_date: NaiveDate::from_ymd_opt(2024, 3, ex_day).unwrap(),
+            pay_date: NaiveDate::from_ymd_opt(2024, 3, pay_day).unwrap(),
+        }
+    }
+
+    #[test]
+    fn dividend_income_uses_the_shares_held_going_into_each_ex_date() {
+        // 100 bought on the 4th, 40 of them sold on the 6th
+        let mut portfolio = held_portfolio();
+        portfolio
+            .add_transaction(transaction(6, TransactionType::Sell, 40, 2.0, 0.0))
+            .unwrap();
+        portfolio.add_dividend(dividend(7, 25, 0.25));
+        portfolio.add_dividend(dividend(4, 10, 0.5));
+        portfolio.add_dividend(dividend(5, 15, 0.125));
+        portfolio.add_dividend(dividend(6, 20, 0.75));
+
+        let income = portfolio.dividend_income();
+
+        let paid: Vec<(u32, i64, f64)> = income
+            .iter()
+            .map(|i| (i.pay_date.day(), i.quantity, i.income))
+            .collect();
+        // Bought on the ex-date: not entitled. Sold on the ex-date: still entitled.
+        assert_eq!(
+            paid,
+            [
+                (10, 0, 0.0),
+                (15, 100, 12.5),
+                (20, 100, 75.0),
+                (25, 60, 15.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn trades_of_no_or_negative_shares_are_rejected() {
+        let mut portfolio = held_portfolio();
+
+        let negative_buy =
+            portfolio.add_transaction(transaction(5, TransactionType::Buy, -10, 1.5, 0.0));
+        let negative_sell =
+            portfolio.add_transaction(transaction(5, TransactionType::Sell, -5, 2.0, 0.0));
+        let empty_buy =
+            portfolio.add_transaction(transaction(5, TransactionType::Buy, 0, 1.5, 0.0));
+
+        assert!(matches!(
+            negative_buy,
+            Err(TransactionError::InvalidQuantity { quantity: -10 })
+        ));
+        assert!(matches!(
+            negative_sell,
+            Err(TransactionError::InvalidQuantity { quantity: -5 })
+        ));
+        assert!(empty_buy.is_err());
+        assert_eq!(portfolio.transactions.len(), 1);
+        assert_eq!(holdings(&portfolio), [("MTNGH".to_string(), 100, 1.5)]);
+        assert_eq!(portfolio.cost_summary().total_proceeds, 0.0);
+    }
+
+    #[test]
+    fn negative_or_non_finite_prices_and_fees_are_rejected() {
+        let mut portfolio = held_portfolio();
+        let id = portfolio.transactions[0].id.clone();
+        let mut edit = transaction(4, TransactionType::Buy, 100, f64::NAN, 0.0);
+        edit.id = id.clone();
+
+        let negative_fee =
+            portfolio.add_transaction(transaction(5, TransactionType::Buy, 10, 1.5, -1.0));
+        let nan_price = portfolio.replace_transaction(&id, edit);
+
+        let Err(error) = negative_fee else {
+            panic!("a negative fee was accepted");
+        };
+        assert_eq!(error.to_string(), "fee must be zero or more, got -1");
+        assert!(matches!(
+            nan_price,
+            Err(TransactionError::InvalidAmount {
+                field: "price_per_share",
+                ..
+            })
+        ));
+        assert_eq!(portfolio.transactions[0].price_per_share, 1.5);
+    }
 }
@@ -1,6 +1,8 @@
 use crate::domain::entities::*;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Repository trait for stock data operations
 #[async_trait::async_trait]
@@ -24,12 +26,50 @@ pub trait StockRepository {
     /// Get the latest live data for a symbol
     async fn get_latest_live_data(&self, symbol: &str) -> Result<Option<EquityLive>>;
 
+    /// Get the latest live data for a symbol from one source, passing over newer records from
+    /// other sources
+    async fn get_latest_live_data_from(
+        &self,
+        symbol: &str,
+        source: DataSource,
+    ) -> Result<Option<EquityLive>>;
+
     /// Get the latest equity data for a symbol
     async fn get_latest_equity_data(&self, symbol: &str) -> Result<Option<Equity>>;
 
+    /// Get the latest equity data for a symbol along with when it was stored
+    async fn get_latest_equity_data_with_timestamp(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<(Equity, DateTime<Utc>)>>;
+
+    /// Get every stored record for a symbol, ordered by key
+    async fn get_symbol_records(&self, symbol: &str) -> Result<Vec<StoredRecord>>;
+
     /// Get all available symbols
     async fn get_all_symbols(&self) -> Result<Vec<String>>;
 
+    /// Get when each symbol's live data was last stored
+    async fn get_last_updates(&self) -> Result<BTreeMap<String, DateTime<Utc>>>;
+
+    /// Get every stored live tick for a symbol within a time range, without aggregation
+    async fn get_intraday_data(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TimeSeriesPoint>>;
+
+    /// Get at most `limit` live ticks for a symbol within a time range, oldest first.
+    /// Used to read long ranges page by page.
+    async fn get_historical_data_page(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<TimeSeriesPoint>>;
+
     /// Get historical data for a symbol within a time range
     async fn get_historical_data(
         &self,
@@ -47,8 +87,130 @@ pub trait StockRepository {
 
     /// Get the latest market summary
     async fn get_latest_market_summary(&self) -> Result<Option<MarketSummary>>;
+
+    /// Get the composite index level of every market summary within a time range, oldest first
+    async fn get_index_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TimeSeriesPoint>>;
+
+    /// Get every market summary stored within a time range with the time it was stored, oldest
+    /// first
+    async fn get_market_summaries(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, MarketSummary)>>;
+
+    /// Get the stored market summary closest in time to `timestamp`
+    async fn get_market_summary_nearest(
+        &self,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<MarketSummary>>;
+
+    /// Store derived metrics for a symbol, replacing any previous value
+    async fn store_symbol_metrics(&self, metrics: &SymbolMetrics) -> Result<()>;
+
+    /// Get the cached derived metrics for a symbol
+    async fn get_symbol_metrics(&self, symbol: &str) -> Result<Option<SymbolMetrics>>;
+
+    /// Store a market-wide move event
+    async fn store_market_event(&self, event: &MarketEvent) -> Result<()>;
+
+    /// Get all recorded market events, newest first
+    async fn get_market_events(&self) -> Result<Vec<MarketEvent>>;
+
+    /// Store a completed scrape cycle
+    async fn store_scrape_cycle(&self, cycle: &ScrapeCycle) -> Result<()>;
+
+    /// Get the most recent completed scrape cycles, newest first
+    async fn get_scrape_cycles(&self, limit: usize) -> Result<Vec<ScrapeCycle>>;
+
+    /// Persist the recently requested symbols, most recent first
+    async fn store_recent_symbols(&self, symbols: &[String]) -> Result<()>;
+
+    /// Get the recently requested symbols persisted at the last shutdown
+    async fn get_recent_symbols(&self) -> Result<Vec<String>>;
+
+    /// Persist a named piece of in-memory service state, such as request counts, across restarts
+    async fn store_service_state(&self, name: &str, state: &serde_json::Value) -> Result<()>;
+
+    /// Get a named piece of service state persisted at the last shutdown
+    async fn get_service_state(&self, name: &str) -> Result<Option<serde_json::Value>>;
+
+    /// Store a bond, replacing any previous record with the same code
+    async fn store_bond(&self, bond: &Bond) -> Result<()>;
+
+    /// Get every stored bond
+    async fn get_bonds(&self) -> Result<Vec<Bond>>;
+
+    /// Store a stock split, replacing any previous split for the same symbol and ex-date
+    async fn store_split(&self, split: &StockSplit) -> Result<()>;
+
+    /// Get every stored split for a symbol, oldest ex-date first
+    async fn get_splits(&self, symbol: &str) -> Result<Vec<StockSplit>>;
+
+    /// Store a company announcement
+    async fn store_announcement(&self, announcement: &Announcement) -> Result<()>;
+
+    /// Get announcements dated from `from` to `to` inclusive, optionally for one symbol,
+    /// oldest first
+    async fn get_announcements(
+        &self,
+        symbol: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Announcement>>;
+
+    /// Store a queued webhook delivery, replacing any previous state with the same id
+    async fn store_delivery(&self, delivery: &WebhookDelivery) -> Result<()>;
+
+    /// Get every queued webhook delivery, oldest first
+    async fn get_deliveries(&self) -> Result<Vec<WebhookDelivery>>;
+
+    /// Remove a delivery from the queue
+    async fn delete_delivery(&self, id: &str) -> Result<()>;
+}
+
+/// State of the circuit breaker guarding the upstream API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests flow normally
+    Closed,
+    /// Requests are rejected with [`CircuitOpen`] without contacting the upstream
+    Open,
+    /// The cooldown has passed; the next request probes whether the upstream has recovered
+    HalfOpen,
+}
+
+impl std::fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitState::Closed => write!(f, "closed"),
+            CircuitState::Open => write!(f, "open"),
+            CircuitState::HalfOpen => write!(f, "half_open"),
+        }
+    }
+}
+
+/// Error returned without contacting the upstream while its circuit breaker is open, so callers
+/// can fall back to stored data
+#[derive(Debug)]
+pub struct CircuitOpen;
+
+impl std::fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "GSE API is unavailable; requests are paused until it recovers"
+        )
+    }
 }
 
+impl std::error::Error for CircuitOpen {}
+
 /// Repository trait for GSE API operations
 #[async_trait::async_trait]
 pub trait GseApiClient {
@@ -60,4 +222,85 @@ pub trait GseApiClient {
 
     /// Fetch detailed equity data for a specific symbol
     async fn fetch_equity_data(&self, symbol: &str) -> Result<Equity>;
+
+    /// State of the circuit breaker in front of the upstream
+    fn circuit_state(&self) -> CircuitState {
+        CircuitState::Closed
+    }
+}
+
+/// Outbound webhook delivery
+#[async_trait::async_trait]
+pub trait WebhookSender {
+    /// POST a JSON payload to the given URL
+    async fn send(&self, url: &str, payload: &serde_json::Value) -> Result<()>;
+}
+
+/// Writes a full dump of stored data as JSON lines
+#[async_trait::async_trait]
+pub trait DataExporter {
+    /// Write every stored record to `path`, one JSON object per line; returns the record count
+    async fn export_jsonl(&self, path: &std::path::Path) -> Result<usize>;
+}
+
+/// Compacts old stored data into monthly archives
+#[async_trait::async_trait]
+pub trait DataArchiver {
+    /// Move every live tick from months that ended before `cutoff` into one archive per symbol
+    /// and month, deleting the individual records
+    async fn archive_before(&self, cutoff: DateTime<Utc>) -> Result<ArchiveSummary>;
+}
+
+/// Deletes stored data that has aged out of the retention window
+#[async_trait::async_trait]
+pub trait DataPruner {
+    /// Delete every live tick and equity detail record stamped before `cutoff`, except each
+    /// symbol's most recent record of each type
+    async fn prune_older_than(&self, cutoff: DateTime<Utc>) -> Result<PruneSummary>;
+}
+
+/// Takes consistent copies of the database and restores from them
+#[async_trait::async_trait]
+pub trait DatabaseBackup {
+    /// Copy the database to a new backup while it keeps accepting writes
+    async fn create_backup(&self) -> Result<BackupInfo>;
+
+    /// Stage the named backup to replace the database the next time the service starts, or
+    /// `None` if there is no such backup. The open database can't be swapped while serving.
+    async fn schedule_restore(&self, name: &str) -> Result<Option<BackupInfo>>;
+}
+
+/// Currency that GSE prices are quoted in
+pub const PRICE_CURRENCY: &str = "GHS";
+
+/// Source of foreign exchange rates
+#[async_trait::async_trait]
+pub trait FxRateProvider {
+    /// Whether rates to and from the currency are available
+    fn supports(&self, currency: &str) -> bool;
+
+    /// Units of `to` per one unit of `from`
+    async fn rate(&self, from: &str, to: &str) -> Result<f64>;
+}
+
+/// Reports free space on the volume holding the database
+pub trait DiskSpaceProbe {
+    /// Bytes available to the service for new writes
+    fn available_bytes(&self) -> Result<u64>;
+}
+
+/// Counter of errors, labelled by `type`
+pub const ERRORS_METRIC: &str = "gse_errors_total";
+
+/// Collects operational metrics, shared by the worker, the upstream client and the API so they
+/// are all exported together
+pub trait MetricsRecorder {
+    /// Add one to a counter
+    fn increment(&self, name: &str, labels: &[(&str, &str)]);
+    /// Record a duration in seconds in a histogram
+    fn observe(&self, name: &str, labels: &[(&str, &str)], seconds: f64);
+    /// Set a gauge to its current value
+    fn set_gauge(&self, name: &str, value: f64);
+    /// Render every metric in the Prometheus text exposition format
+    fn render(&self) -> String;
 }
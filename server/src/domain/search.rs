@@ -0,0 +1,339 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Which kinds of results a search should return
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchType {
+    Symbol,
+    Sector,
+    Company,
+    #[default]
+    All,
+}
+
+impl SearchType {
+    fn includes(self, kind: SearchResultKind) -> bool {
+        match self {
+            SearchType::All => true,
+            SearchType::Symbol => kind == SearchResultKind::Symbol,
+            SearchType::Sector => kind == SearchResultKind::Sector,
+            SearchType::Company => kind == SearchResultKind::Company,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchResultKind {
+    Symbol,
+    Sector,
+    Company,
+}
+
+/// A single typed search hit; `symbols` lists every ticker the hit refers to
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    #[serde(rename = "type")]
+    pub kind: SearchResultKind,
+    pub name: String,
+    pub symbols: Vec<String>,
+    pub score: u32,
+}
+
+/// The searchable attributes of one listed symbol
+#[derive(Debug, Clone)]
+pub struct SearchEntry {
+    pub symbol: String,
+    pub company_name: Option<String>,
+    pub industry: Option<String>,
+    pub sector: Option<String>,
+}
+
+/// Symbols grouped by sector name, in sector order
+pub fn sector_index(entries: &[SearchEntry]) -> BTreeMap<String, Vec<String>> {
+    let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for entry in entries {
+        if let Some(sector) = entry.sector.as_deref().map(str::trim) {
+            if !sector.is_empty() {
+                index
+                    .entry(sector.to_string())
+                    .or_default()
+                    .push(entry.symbol.clone());
+            }
+        }
+    }
+    index
+}
+
+/// Whether `entry` is classified under `sector` and `industry` (case-insensitive, either
+/// optional), or `None` when it has no stored value for a field being filtered on
+pub fn matches_classification(
+    entry: &SearchEntry,
+    sector: Option<&str>,
+    industry: Option<&str>,
+) -> Option<bool> {
+    let field_matches = |wanted: Option<&str>, value: Option<&str>| match wanted {
+        None => Some(true),
+        Some(wanted) => value
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.eq_ignore_ascii_case(wanted.trim())),
+    };
+
+    let sector_matches = field_matches(sector, entry.sector.as_deref())?;
+    let industry_matches = field_matches(industry, entry.industry.as_deref())?;
+    Some(sector_matches && industry_matches)
+}
+
+/// Number of listed symbols in one sector
+#[derive(Debug, Clone, Serialize)]
+pub struct SectorCount {
+    pub sector: String,
+    pub count: usize,
+}
+
+/// Every sector with its symbol count, in sector order
+#[derive(Debug, Clone, Serialize)]
+pub struct SectorBreakdown {
+    pub sectors: Vec<SectorCount>,
+    /// Symbols with no stored sector, left out of `sectors`
+    pub unclassified: usize,
+}
+
+impl SectorBreakdown {
+    pub fn of(entries: &[SearchEntry]) -> Self {
+        let sectors: Vec<SectorCount> = sector_index(entries)
+            .into_iter()
+            .map(|(sector, symbols)| SectorCount {
+                sector,
+                count: symbols.len(),
+            })
+            .collect();
+        let classified: usize = sectors.iter().map(|s| s.count).sum();
+
+        Self {
+            unclassified: entries.len() - classified,
+            sectors,
+        }
+    }
+}
+
+/// Case-insensitive match score of `query` against `candidate`, or `None` when it doesn't match.
+///
+/// Exact matches rank above prefixes, then substrings, then in-order subsequences
+/// (so "mtn" still finds "MTNGH").
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+
+    if candidate == query {
+        Some(100)
+    } else if candidate.starts_with(&query) {
+        Some(80)
+    } else if candidate.contains(&query) {
+        Some(60)
+    } else {
+        let mut remaining = candidate.chars();
+        query
+            .chars()
+            .all(|c| remaining.any(|r| r == c))
+            .then_some(30)
+    }
+}
+
+/// Most results a search returns
+pub const MAX_SEARCH_RESULTS: usize = 20;
+
+/// Number of single-character insertions, deletions and substitutions turning `a` into `b`
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Typo-tolerant match score of `query` against `candidate` or any of its words, or `None` when
+/// the closest is more than a third of the query's length away (at least one edit is allowed).
+///
+/// Scores rank below every [`fuzzy_score`] match, fewer edits first.
+pub fn typo_score(query: &str, candidate: &str) -> Option<u32> {
+    let query = query.trim().to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+
+    let max_distance = (query.chars().count() / 3).max(1);
+    let distance = std::iter::once(candidate.as_str())
+        .chain(candidate.split_whitespace())
+        .map(|word| levenshtein(&query, word))
+        .min()?;
+    (distance <= max_distance).then(|| 20u32.saturating_sub(distance as u32 * 5))
+}
+
+/// Search symbols, sectors and companies, best matches first and at most
+/// `MAX_SEARCH_RESULTS` of them.
+///
+/// When nothing contains the query, falls back to matches within a few typos of it.
+pub fn search(query: &str, search_type: SearchType, entries: &[SearchEntry]) -> Vec<SearchResult> {
+    let mut results = search_with(query, search_type, entries, fuzzy_score);
+    if results.is_empty() {
+        results = search_with(query, search_type, entries, typo_score);
+    }
+    results.truncate(MAX_SEARCH_RESULTS);
+    results
+}
+
+/// Score every searchable name against `query` with `matcher`, best matches first
+fn search_with(
+    query: &str,
+    search_type: SearchType,
+    entries: &[SearchEntry],
+    matcher: fn(&str, &str) -> Option<u32>,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+
+    if search_type.includes(SearchResultKind::Symbol) {
+        for entry in entries {
+            if let Some(score) = matcher(query, &entry.symbol) {
+                results.push(SearchResult {
+                    kind: SearchResultKind::Symbol,
+                    name: entry.symbol.clone(),
+                    symbols: vec![entry.symbol.clone()],
+                    score,
+                });
+            }
+        }
+    }
+
+    if search_type.includes(SearchResultKind::Sector) {
+        for (sector, symbols) in sector_index(entries) {
+            if let Some(score) = matcher(query, &sector) {
+                results.push(SearchResult {
+                    kind: SearchResultKind::Sector,
+                    name: sector,
+                    symbols,
+                    score,
+                });
+            }
+        }
+    }
+
+    if search_type.includes(SearchResultKind::Company) {
+        for entry in entries {
+            let Some(name) = entry.company_name.as_deref() else {
+                continue;
+            };
+            // Industry matches count, but rank below a match on the name itself
+            let score = matcher(query, name).max(
+                entry
+                    .industry
+                    .as_deref()
+                    .and_then(|industry| matcher(query, industry))
+                    .map(|score| score / 2),
+            );
+            if let Some(score) = score {
+                results.push(SearchResult {
+                    kind: SearchResultKind::Company,
+                    name: name.to_string(),
+                    symbols: vec![entry.symbol.clone()],
+                    score,
+                });
+            }
+        }
+    }
+
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(symbol: &str, company: &str, sector: &str) -> SearchEntry {
+        SearchEntry {
+            symbol: symbol.to_string(),
+            company_name: Some(company.to_string()),
+            industry: None,
+            sector: Some(sector.to_string()),
+        }
+    }
+
+    fn entries() -> Vec<SearchEntry> {
+        vec![
+            entry("GCB", "GCB Bank Ltd", "Financials"),
+            entry("SCB", "Standard Chartered Bank Ghana", "Financials"),
+            entry("MTNGH", "Scancom PLC", "Telecommunications"),
+        ]
+    }
+
+    #[test]
+    fn a_sector_query_returns_the_sector_with_its_symbols() {
+        let entries = entries();
+
+        let results = search("financials", SearchType::Sector, &entries);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, SearchResultKind::Sector);
+        assert_eq!(results[0].name, "Financials");
+        assert_eq!(results[0].symbols, vec!["GCB", "SCB"]);
+    }
+
+    #[test]
+    fn a_company_name_query_returns_the_company_symbol() {
+        let entries = entries();
+
+        let results = search("scancom", SearchType::All, &entries);
+
+        let company = results
+            .iter()
+            .find(|result| result.kind == SearchResultKind::Company)
+            .expect("company result");
+        assert_eq!(company.name, "Scancom PLC");
+        assert_eq!(company.symbols, vec!["MTNGH"]);
+        assert!(results
+            .iter()
+            .all(|result| result.kind != SearchResultKind::Sector));
+    }
+
+    #[test]
+    fn a_misspelt_query_falls_back_to_typo_matches() {
+        let entries = entries();
+
+        let results = search("scamcom", SearchType::All, &entries);
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].kind, SearchResultKind::Company);
+        assert_eq!(results[0].symbols, vec!["MTNGH"]);
+        assert!(results[0].score < fuzzy_score("mtn", "MTNGH").unwrap());
+    }
+
+    #[test]
+    fn results_are_capped_and_best_first() {
+        let entries: Vec<SearchEntry> = (1..=30)
+            .map(|n| entry(&format!("BANK{:02}", n), "Bank", "Financials"))
+            .collect();
+
+        let results = search("bank", SearchType::Symbol, &entries);
+
+        assert_eq!(results.len(), MAX_SEARCH_RESULTS);
+        assert!(results
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+    }
+}
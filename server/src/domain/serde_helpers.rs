@@ -0,0 +1,79 @@
+use serde::{de, Deserialize, Deserializer};
+
+/// Upstream prices sometimes arrive as strings (`"1.23"`) instead of numbers
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(f64),
+    String(String),
+}
+
+/// Deserialize an `f64` that may be encoded as a JSON number or a numeric string
+pub fn f64_from_number_or_string<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(value) => Ok(value),
+        NumberOrString::String(value) => value
+            .trim()
+            .replace(',', "")
+            .parse::<f64>()
+            .map_err(|_| de::Error::custom(format!("invalid numeric string: {:?}", value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{Equity, EquityLive};
+    use crate::infrastructure::test_support::equity;
+    use serde_json::json;
+
+    #[test]
+    fn live_prices_may_be_strings_or_integers() {
+        let strings: EquityLive = serde_json::from_value(json!({
+            "change": "-0.05",
+            "name": "MTNGH",
+            "price": "1,234.50",
+            "volume": 100,
+        }))
+        .unwrap();
+        let integers: EquityLive = serde_json::from_value(json!({
+            "change": 0,
+            "name": "GCB",
+            "price": 5,
+            "volume": 100,
+        }))
+        .unwrap();
+
+        assert_eq!(strings.price, 1234.5);
+        assert_eq!(strings.change, -0.05);
+        assert_eq!(integers.price, 5.0);
+        assert_eq!(integers.change, 0.0);
+    }
+
+    #[test]
+    fn equity_price_may_be_a_string() {
+        let mut payload = serde_json::to_value(equity("MTNGH", 1.0)).unwrap();
+        payload["price"] = json!(" 1.23 ");
+
+        let parsed: Equity = serde_json::from_value(payload).unwrap();
+
+        assert_eq!(parsed.price, 1.23);
+    }
+
+    #[test]
+    fn a_non_numeric_price_string_is_an_error() {
+        let result = serde_json::from_value::<EquityLive>(json!({
+            "change": 0,
+            "name": "MTNGH",
+            "price": "n/a",
+            "volume": 100,
+        }));
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid numeric string"));
+    }
+}
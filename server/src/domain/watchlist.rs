@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Symbols tracked without holding them
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Watchlist {
+    pub id: String,
+    pub name: String,
+    /// Uppercase symbols in the order they were added
+    pub symbols: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Watchlist {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            name,
+            symbols: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Add a symbol unless it is already watched, returning whether it was added
+    pub fn add_symbol(&mut self, symbol: &str) -> bool {
+        if self.symbols.iter().any(|s| s == symbol) {
+            return false;
+        }
+        self.symbols.push(symbol.to_string());
+        self.updated_at = Utc::now();
+        true
+    }
+
+    /// Remove a symbol, returning whether it was watched
+    pub fn remove_symbol(&mut self, symbol: &str) -> bool {
+        let before = self.symbols.len();
+        self.symbols.retain(|s| s != symbol);
+        let removed = self.symbols.len() < before;
+        if removed {
+            self.updated_at = Utc::now();
+        }
+        removed
+    }
+}
+
+/// A symbol that can't be added to a watchlist
+#[derive(Debug)]
+pub enum WatchlistError {
+    /// No data has been stored for the symbol
+    UnknownSymbol { symbol: String },
+}
+
+impl std::fmt::Display for WatchlistError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WatchlistError::UnknownSymbol { symbol } => write!(f, "Unknown symbol: {}", symbol),
+        }
+    }
+}
+
+impl std::error::Error for WatchlistError {}
+
+#[async_trait::async_trait]
+pub trait WatchlistRepository {
+    async fn create_watchlist(&self, watchlist: &Watchlist) -> anyhow::Result<()>;
+    async fn get_watchlist(&self, id: &str) -> anyhow::Result<Option<Watchlist>>;
+    async fn get_all_watchlists(&self) -> anyhow::Result<Vec<Watchlist>>;
+    async fn update_watchlist(&self, watchlist: &Watchlist) -> anyhow::Result<()>;
+}
@@ -0,0 +1,239 @@
+use crate::domain::{BackupInfo, DatabaseBackup};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rocksdb::{checkpoint::Checkpoint, DB};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Prefix of every backup directory name
+const BACKUP_PREFIX: &str = "gse-";
+
+/// Backs the database up to RocksDB checkpoints under a directory.
+///
+/// A checkpoint is a consistent snapshot taken without pausing writes; on the same filesystem
+/// its table files are hard links, so it is cheap to take.
+pub struct RocksDbBackups {
+    db: Arc<DB>,
+    db_path: PathBuf,
+    backup_dir: PathBuf,
+}
+
+impl RocksDbBackups {
+    pub fn new(db: Arc<DB>, db_path: impl Into<PathBuf>, backup_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            db,
+            db_path: db_path.into(),
+            backup_dir: backup_dir.into(),
+        }
+    }
+
+    /// Directory of the named backup, or `None` if the name isn't one this service creates
+    fn backup_path(&self, name: &str) -> Option<PathBuf> {
+        let valid = name.starts_with(BACKUP_PREFIX)
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        valid.then(|| self.backup_dir.join(name))
+    }
+}
+
+#[async_trait::async_trait]
+impl DatabaseBackup for RocksDbBackups {
+    async fn create_backup(&self) -> Result<BackupInfo> {
+        let created_at = Utc::now();
+        let name = format!(
+            "{}{}",
+            BACKUP_PREFIX,
+            created_at.format("%Y%m%dT%H%M%S%3fZ")
+        );
+        let path = self.backup_dir.join(&name);
+        fs::create_dir_all(&self.backup_dir).context("Failed to create backup directory")?;
+
+        let db = self.db.clone();
+        let target = path.clone();
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            Checkpoint::new(&db)?
+                .create_checkpoint(&target)
+                .context("Failed to create database checkpoint")
+        })
+        .await??;
+
+        backup_info(name, &path, created_at)
+    }
+
+    async fn schedule_restore(&self, name: &str) -> Result<Option<BackupInfo>> {
+        let Some(path) = self.backup_path(name) else {
+            return Ok(None);
+        };
+        // Every RocksDB directory has a CURRENT file naming its manifest
+        if !path.join("CURRENT").is_file() {
+            return Ok(None);
+        }
+
+        let created_at = fs::metadata(&path)?
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        fs::write(
+            pending_restore_marker(&self.db_path),
+            path.to_string_lossy().as_bytes(),
+        )
+        .context("Failed to stage restore")?;
+
+        Ok(Some(backup_info(name.to_string(), &path, created_at)?))
+    }
+}
+
+/// Swap in a backup staged by `schedule_restore`, keeping the replaced database alongside it
+/// as `{db_path}.pre-restore-{timestamp}`. Returns the restored backup's path, or `None` when
+/// no restore is staged. Must run before the database is opened.
+///
+/// The backup is copied into a sibling directory first and only renamed into place once the
+/// copy is complete, so a failure part-way leaves the current database as it was. The staged
+/// restore is cleared only once it has been applied, and is retried on the next start otherwise.
+pub fn apply_pending_restore(db_path: &Path) -> Result<Option<PathBuf>> {
+    let marker = pending_restore_marker(db_path);
+    let Ok(backup) = fs::read_to_string(&marker) else {
+        return Ok(None);
+    };
+    let backup = PathBuf::from(backup.trim());
+    if !backup.join("CURRENT").is_file() {
+        anyhow::bail!(
+            "Staged backup {} is missing or incomplete; remove {} to start without restoring",
+            backup.display(),
+            marker.display()
+        );
+    }
+
+    // Copy rather than move so the backup can be restored again
+    let staging = sibling_path(db_path, ".restoring");
+    if staging.exists() {
+        fs::remove_dir_all(&staging).context("Failed to clear an earlier partial restore")?;
+    }
+    fs::create_dir_all(&staging)?;
+    for entry in fs::read_dir(&backup)? {
+        let entry = entry?;
+        fs::copy(entry.path(), staging.join(entry.file_name()))
+            .with_context(|| format!("Failed to restore {}", entry.path().display()))?;
+    }
+
+    let replaced = sibling_path(
+        db_path,
+        &format!(".pre-restore-{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+    );
+    if db_path.exists() {
+        fs::rename(db_path, &replaced).context("Failed to move the current database aside")?;
+    }
+    if let Err(e) = fs::rename(&staging, db_path) {
+        if replaced.exists() {
+            fs::rename(&replaced, db_path)
+                .context("Failed to put the current database back after a failed restore")?;
+        }
+        return Err(
+            anyhow::Error::new(e).context("Failed to move the restored database into place")
+        );
+    }
+
+    fs::remove_file(&marker).context("Failed to clear staged restore")?;
+    Ok(Some(backup))
+}
+
+/// `db_path` with `suffix` appended to its final component
+fn sibling_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn pending_restore_marker(db_path: &Path) -> PathBuf {
+    sibling_path(db_path, ".restore-pending")
+}
+
+fn backup_info(name: String, path: &Path, created_at: DateTime<Utc>) -> Result<BackupInfo> {
+    let mut size_bytes = 0;
+    for entry in fs::read_dir(path)? {
+        size_bytes += entry?.metadata()?.len();
+    }
+
+    Ok(BackupInfo {
+        name,
+        path: path.to_string_lossy().into_owned(),
+        size_bytes,
+        created_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::temp_path;
+
+    #[tokio::test]
+    async fn restores_a_backup_taken_before_the_data_was_wiped() {
+        let root = temp_path("backup");
+        let db_path = root.join("gse.db");
+        let db = Arc::new(DB::open_default(&db_path).unwrap());
+        db.put(b"stock:MTNGH:live:1", b"1.5").unwrap();
+        let backups = RocksDbBackups::new(db.clone(), &db_path, root.join("backups"));
+
+        let backup = backups.create_backup().await.unwrap();
+        db.delete(b"stock:MTNGH:live:1").unwrap();
+        db.put(b"stock:MTNGH:live:2", b"9.9").unwrap();
+        let staged = backups.schedule_restore(&backup.name).await.unwrap();
+        assert!(staged.is_some());
+        drop(backups);
+        drop(db);
+
+        let restored = apply_pending_restore(&db_path).unwrap();
+
+        assert_eq!(restored, Some(PathBuf::from(&backup.path)));
+        assert!(!pending_restore_marker(&db_path).exists());
+        let db = DB::open_default(&db_path).unwrap();
+        assert_eq!(
+            db.get(b"stock:MTNGH:live:1").unwrap(),
+            Some(b"1.5".to_vec())
+        );
+        assert_eq!(db.get(b"stock:MTNGH:live:2").unwrap(), None);
+        drop(db);
+        assert_eq!(apply_pending_restore(&db_path).unwrap(), None);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn unknown_backups_are_not_staged() {
+        let root = temp_path("backup");
+        let db_path = root.join("gse.db");
+        let db = Arc::new(DB::open_default(&db_path).unwrap());
+        let backups = RocksDbBackups::new(db, &db_path, root.join("backups"));
+
+        assert!(backups
+            .schedule_restore("gse-missing")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(backups
+            .schedule_restore("../gse.db")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(!pending_restore_marker(&db_path).exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn a_broken_staged_backup_leaves_the_database_and_marker_in_place() {
+        let root = temp_path("backup");
+        let db_path = root.join("gse.db");
+        drop(DB::open_default(&db_path).unwrap());
+        fs::write(
+            pending_restore_marker(&db_path),
+            root.join("backups/gse-gone").to_string_lossy().as_bytes(),
+        )
+        .unwrap();
+
+        assert!(apply_pending_restore(&db_path).is_err());
+
+        assert!(pending_restore_marker(&db_path).exists());
+        assert!(db_path.join("CURRENT").is_file());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::domain::{CircuitOpen, CircuitState};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Settings for cutting off an upstream that keeps failing
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failed requests that open the circuit
+    pub failure_threshold: u32,
+    /// How long an open circuit rejects requests before letting a single probe through
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    /// Set while the circuit is open or half-open
+    opened_at: Option<Instant>,
+    /// When the half-open probe request was let through. A probe that never reports back, such
+    /// as one whose caller was dropped, stops counting after another cooldown.
+    probe_started: Option<Instant>,
+}
+
+/// Circuit breaker that stops requests to a failing upstream for a cooldown, then lets one
+/// probe request decide whether to close again
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Check whether a request may be sent. Once the cooldown has passed, the first caller is
+    /// let through as the half-open probe and the rest are rejected until it completes.
+    pub fn try_acquire(&self) -> Result<(), CircuitOpen> {
+        let mut state = self.state.lock().unwrap();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+
+        let probe_pending = state
+            .probe_started
+            .is_some_and(|started| started.elapsed() < self.config.cooldown);
+        if probe_pending || opened_at.elapsed() < self.config.cooldown {
+            return Err(CircuitOpen);
+        }
+        state.probe_started = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Record a request the upstream answered, closing the circuit
+    pub fn record_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::default();
+    }
+
+    /// Record a request the upstream failed, opening the circuit after too many in a row or
+    /// when the half-open probe fails
+    pub fn record_failure(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.probe_started.take().is_some() {
+            state.opened_at = Some(Instant::now());
+            tracing::warn!("GSE API probe request failed, keeping the circuit open");
+            return;
+        }
+
+        state.consecutive_failures += 1;
+        if state.opened_at.is_none() && state.consecutive_failures >= self.config.failure_threshold
+        {
+            state.opened_at = Some(Instant::now());
+            tracing::warn!(
+                "Opening the GSE API circuit after {} consecutive failures; pausing requests for {} seconds",
+                state.consecutive_failures,
+                self.config.cooldown.as_secs()
+            );
+        }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let state = self.state.lock().unwrap();
+        match state.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at)
+                if state.probe_started.is_some() || opened_at.elapsed() >= self.config.cooldown =>
+            {
+                CircuitState::HalfOpen
+            }
+            Some(_) => CircuitState::Open,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            cooldown,
+        })
+    }
+
+    #[test]
+    fn consecutive_failures_open_the_circuit() {
+        let breaker = breaker(Duration::from_secs(60));
+
+        for _ in 0..3 {
+            breaker.try_acquire().unwrap();
+            breaker.record_failure();
+        }
+
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.try_acquire().is_err());
+    }
+
+    #[test]
+    fn a_success_resets_the_failure_count() {
+        let breaker = breaker(Duration::from_secs(60));
+
+        breaker.record_failure();
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn one_probe_is_let_through_after_the_cooldown_and_its_success_closes_the_circuit() {
+        let breaker = breaker(Duration::from_millis(20));
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+        std::thread::sleep(Duration::from_millis(30));
+
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire().is_ok());
+        assert!(breaker.try_acquire().is_err());
+        breaker.record_success();
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn a_failed_probe_reopens_the_circuit() {
+        let breaker = breaker(Duration::from_millis(20));
+        for _ in 0..3 {
+            breaker.record_failure();
+        }
+        std::thread::sleep(Duration::from_millis(30));
+        breaker.try_acquire().unwrap();
+
+        breaker.record_failure();
+
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.try_acquire().is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use anyhow::Result;
+use rocksdb::{DBRawIterator, ErrorKind, DB};
+
+/// A raw key/value pair read from RocksDB
+pub type KeyValue = (Box<[u8]>, Box<[u8]>);
+
+/// How many times a scan tries to recover from recoverable errors at the same position before
+/// giving up and yielding the error
+const MAX_RECOVERIES: usize = 3;
+
+/// Iterate over the key/value pairs stored under `prefix`.
+///
+/// The database has no prefix extractor configured, so a plain seek keeps
+/// going past the end of the prefix; stop at the first key outside it.
+/// A RocksDB iterator stops for good once it hits an error, so after a
+/// recoverable one the scan re-seeks just past the last key it returned, and
+/// if the error comes back it steps over the unreadable entry with a warning
+/// and carries on. Fatal errors, and recoverable ones the cursor can't get
+/// past, are yielded as errors rather than silently ending the scan early.
+pub fn scan_prefix<'a>(db: &'a DB, prefix: &'a str) -> impl Iterator<Item = Result<KeyValue>> + 'a {
+    scan_prefix_from(db, prefix, prefix)
+}
+
+/// Like [`scan_prefix`], but seek straight to `start` (a key under `prefix`) instead of the prefix's first key
+pub fn scan_prefix_from<'a>(
+    db: &'a DB,
+    prefix: &'a str,
+    start: &str,
+) -> impl Iterator<Item = Result<KeyValue>> + 'a {
+    PrefixScan::new(db.raw_iterator(), prefix, start, is_fatal_read_error)
+}
+
+/// Whether a RocksDB error means the rest of the scan can't be trusted
+fn is_fatal_read_error(error: &rocksdb::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::IOError | ErrorKind::ShutdownInProgress | ErrorKind::ColumnFamilyDropped
+    )
+}
+
+/// The positioning operations a prefix scan needs from a database cursor
+pub trait ScanCursor {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    fn seek(&mut self, key: &[u8]);
+    fn valid(&self) -> bool;
+    fn key(&self) -> Option<&[u8]>;
+    fn value(&self) -> Option<&[u8]>;
+    /// Step to the next entry. After an error, a cursor that knows which entry failed steps past
+    /// it; a RocksDB iterator ignores the step, so the scan ends up yielding the error.
+    fn next(&mut self);
+    /// Why the cursor stopped being valid: `Ok` at the end of the data, the error otherwise
+    fn status(&self) -> std::result::Result<(), Self::Error>;
+}
+
+impl ScanCursor for DBRawIterator<'_> {
+    type Error = rocksdb::Error;
+
+    fn seek(&mut self, key: &[u8]) {
+        DBRawIterator::seek(self, key)
+    }
+
+    fn valid(&self) -> bool {
+        DBRawIterator::valid(self)
+    }
+
+    fn key(&self) -> Option<&[u8]> {
+        DBRawIterator::key(self)
+    }
+
+    fn value(&self) -> Option<&[u8]> {
+        DBRawIterator::value(self)
+    }
+
+    fn next(&mut self) {
+        DBRawIterator::next(self)
+    }
+
+    fn status(&self) -> std::result::Result<(), rocksdb::Error> {
+        DBRawIterator::status(self)
+    }
+}
+
+/// Forward scan over the keys under a prefix that recovers from recoverable cursor errors
+pub struct PrefixScan<C, F> {
+    cursor: C,
+    prefix: Vec<u8>,
+    start: Vec<u8>,
+    is_fatal: F,
+    last_key: Option<Vec<u8>>,
+    /// Recoveries tried since the last key was returned
+    recoveries: usize,
+    done: bool,
+}
+
+impl<C, F> PrefixScan<C, F>
+where
+    C: ScanCursor,
+    F: Fn(&C::Error) -> bool,
+{
+    pub fn new(mut cursor: C, prefix: &str, start: &str, is_fatal: F) -> Self {
+        cursor.seek(start.as_bytes());
+        Self {
+            cursor,
+            prefix: prefix.as_bytes().to_vec(),
+            start: start.as_bytes().to_vec(),
+            is_fatal,
+            last_key: None,
+            recoveries: 0,
+            done: false,
+        }
+    }
+
+    /// Position the cursor on the first key after the last one returned
+    fn reseek(&mut self) {
+        match &self.last_key {
+            Some(key) => {
+                let mut after = key.clone();
+                after.push(0);
+                self.cursor.seek(&after);
+            }
+            None => self.cursor.seek(&self.start),
+        }
+    }
+}
+
+impl<C, F> Iterator for PrefixScan<C, F>
+where
+    C: ScanCursor,
+    F: Fn(&C::Error) -> bool,
+{
+    type Item = Result<KeyValue>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if self.cursor.valid() {
+                let (Some(key), Some(value)) = (self.cursor.key(), self.cursor.value()) else {
+                    break;
+                };
+                if !key.starts_with(&self.prefix) {
+                    break;
+                }
+
+                let item: KeyValue = (key.into(), value.into());
+                self.last_key = Some(key.to_vec());
+                self.recoveries = 0;
+                self.cursor.next();
+                return Some(Ok(item));
+            }
+
+            match self.cursor.status() {
+                Ok(()) => break,
+                Err(e) if !(self.is_fatal)(&e) && self.recoveries < MAX_RECOVERIES => {
+                    self.recoveries += 1;
+                    if self.recoveries == 1 {
+                        // A transient error clears on a fresh seek
+                        tracing::warn!("Re-seeking database scan after read error: {}", e);
+                        self.reseek();
+                    } else {
+                        // The entry after the last key keeps failing, so step over it
+                        tracing::warn!(
+                            "Skipping unreadable database entry after {}: {}",
+                            self.last_key
+                                .as_deref()
+                                .map(String::from_utf8_lossy)
+                                .unwrap_or_default(),
+                            e
+                        );
+                        self.cursor.next();
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(
+                        anyhow::Error::new(e).context("Error while scanning database")
+                    ));
+                }
+            }
+        }
+
+        self.done = true;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug)]
+    struct FakeError {
+        fatal: bool,
+    }
+
+    impl std::fmt::Display for FakeError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "fake read error (fatal: {})", self.fatal)
+        }
+    }
+
+    impl std::error::Error for FakeError {}
+
+    /// Cursor over sorted keys that fails when it steps onto a key listed in `failures`,
+    /// once per listed occurrence, or onto a `corrupt` key, every time. It stays failed until
+    /// the next seek or, unless `sticky` as a RocksDB iterator is, the next step.
+    struct FakeCursor {
+        entries: Vec<(Vec<u8>, Vec<u8>)>,
+        position: usize,
+        failures: Vec<(Vec<u8>, bool)>,
+        corrupt: Vec<Vec<u8>>,
+        sticky: bool,
+        error: Option<bool>,
+    }
+
+    impl FakeCursor {
+        fn new(keys: &[&str], failures: &[(&str, bool)]) -> Self {
+            let entries: BTreeMap<_, _> = keys
+                .iter()
+                .map(|key| (key.as_bytes().to_vec(), b"v".to_vec()))
+                .collect();
+            Self {
+                entries: entries.into_iter().collect(),
+                position: 0,
+                failures: failures
+                    .iter()
+                    .map(|(key, fatal)| (key.as_bytes().to_vec(), *fatal))
+                    .collect(),
+                corrupt: Vec::new(),
+                sticky: false,
+                error: None,
+            }
+        }
+
+        fn with_corrupt(mut self, keys: &[&str]) -> Self {
+            self.corrupt = keys.iter().map(|key| key.as_bytes().to_vec()).collect();
+            self
+        }
+
+        fn sticky(mut self) -> Self {
+            self.sticky = true;
+            self
+        }
+
+        fn settle(&mut self) {
+            let Some((key, _)) = self.entries.get(self.position) else {
+                return;
+            };
+            if let Some(index) = self.failures.iter().position(|(failing, _)| failing == key) {
+                self.error = Some(self.failures.remove(index).1);
+            } else if self.corrupt.contains(key) {
+                self.error = Some(false);
+            }
+        }
+    }
+
+    impl ScanCursor for FakeCursor {
+        type Error = FakeError;
+
+        fn seek(&mut self, key: &[u8]) {
+            self.error = None;
+            self.position = self
+                .entries
+                .iter()
+                .position(|(k, _)| k.as_slice() >= key)
+                .unwrap_or(self.entries.len());
+            self.settle();
+        }
+
+        fn valid(&self) -> bool {
+            self.error.is_none() && self.position < self.entries.len()
+        }
+
+        fn key(&self) -> Option<&[u8]> {
+            self.valid()
+                .then(|| self.entries[self.position].0.as_slice())
+        }
+
+        fn value(&self) -> Option<&[u8]> {
+            self.valid()
+                .then(|| self.entries[self.position].1.as_slice())
+        }
+
+        fn next(&mut self) {
+            if self.error.is_some() && self.sticky {
+                return;
+            }
+            self.error = None;
+            self.position += 1;
+            self.settle();
+        }
+
+        fn status(&self) -> std::result::Result<(), FakeError> {
+            match self.error {
+                Some(fatal) => Err(FakeError { fatal }),
+                None => Ok(()),
+            }
+        }
+    }
+
+    fn scan(cursor: FakeCursor, prefix: &str) -> Vec<std::result::Result<String, String>> {
+        PrefixScan::new(cursor, prefix, prefix, |e: &FakeError| e.fatal)
+            .map(|item| {
+                item.map(|(key, _)| String::from_utf8(key.into_vec()).unwrap())
+                    .map_err(|e| e.to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_prefix() {
+        let cursor = FakeCursor::new(&["a:1", "b:1", "b:2", "c:1"], &[]);
+
+        assert_eq!(scan(cursor, "b:"), vec![Ok("b:1".into()), Ok("b:2".into())]);
+    }
+
+    #[test]
+    fn reseeks_past_a_recoverable_error_instead_of_truncating() {
+        let cursor = FakeCursor::new(&["b:1", "b:2", "b:3"], &[("b:2", false)]);
+
+        let keys = scan(cursor, "b:");
+
+        assert_eq!(
+            keys,
+            vec![Ok("b:1".into()), Ok("b:2".into()), Ok("b:3".into())]
+        );
+    }
+
+    #[test]
+    fn steps_over_an_entry_that_fails_on_every_seek() {
+        let cursor = FakeCursor::new(&["b:1", "b:2", "b:3", "b:4"], &[]).with_corrupt(&["b:2"]);
+
+        let keys = scan(cursor, "b:");
+
+        assert_eq!(
+            keys,
+            vec![Ok("b:1".into()), Ok("b:3".into()), Ok("b:4".into())]
+        );
+    }
+
+    #[test]
+    fn yields_a_recoverable_error_the_cursor_cannot_step_past() {
+        let cursor = FakeCursor::new(&["b:1", "b:2", "b:3"], &[])
+            .with_corrupt(&["b:2"])
+            .sticky();
+
+        let keys = scan(cursor, "b:");
+
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0], Ok("b:1".into()));
+        assert!(keys[1].is_err());
+    }
+
+    #[test]
+    fn yields_fatal_errors_without_reseeking() {
+        let cursor = FakeCursor::new(&["b:1", "b:2", "b:3"], &[("b:2", true)]);
+
+        let keys = scan(cursor, "b:");
+
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0], Ok("b:1".into()));
+        assert!(keys[1].is_err());
+    }
+}
@@ -0,0 +1,20 @@
+use crate::domain::DiskSpaceProbe;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Probes free space on the filesystem containing a directory
+pub struct FsDiskSpaceProbe {
+    path: PathBuf,
+}
+
+impl FsDiskSpaceProbe {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl DiskSpaceProbe for FsDiskSpaceProbe {
+    fn available_bytes(&self) -> Result<u64> {
+        Ok(fs2::available_space(&self.path)?)
+    }
+}
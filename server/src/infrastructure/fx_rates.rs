@@ -0,0 +1,52 @@
+use crate::domain::{FxRateProvider, PRICE_CURRENCY};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// FX rates fixed at startup, quoted as units of each currency per one GHS
+pub struct StaticFxRateProvider {
+    rates: HashMap<String, f64>,
+}
+
+impl StaticFxRateProvider {
+    pub fn new(mut rates: HashMap<String, f64>) -> Self {
+        rates.insert(PRICE_CURRENCY.to_string(), 1.0);
+        Self { rates }
+    }
+
+    /// Parse a comma-separated list such as `USD=0.083,EUR=0.077`, skipping invalid entries
+    pub fn parse_rates(value: &str) -> HashMap<String, f64> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| {
+                let rate = entry
+                    .split_once('=')
+                    .and_then(|(currency, rate)| Some((currency, rate.trim().parse::<f64>().ok()?)))
+                    .filter(|(_, rate)| rate.is_finite() && *rate > 0.0);
+                if rate.is_none() {
+                    tracing::warn!("Ignoring invalid FX rate {:?}", entry);
+                }
+                rate.map(|(currency, rate)| (currency.trim().to_uppercase(), rate))
+            })
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl FxRateProvider for StaticFxRateProvider {
+    fn supports(&self, currency: &str) -> bool {
+        self.rates.contains_key(&currency.to_uppercase())
+    }
+
+    async fn rate(&self, from: &str, to: &str) -> Result<f64> {
+        let per_ghs = |currency: &str| {
+            self.rates
+                .get(&currency.to_uppercase())
+                .copied()
+                .ok_or_else(|| anyhow::anyhow!("No FX rate configured for {}", currency))
+        };
+
+        Ok(per_ghs(to)? / per_ghs(from)?)
+    }
+}
@@ -1,17 +1,67 @@
-use crate::domain::{Equity, EquityLive, EquitySummary, GseApiClient};
+use crate::domain::{
+    CircuitOpen, CircuitState, Equity, EquityLive, EquitySummary, GseApiClient, MetricsRecorder,
+    ERRORS_METRIC,
+};
+use crate::infrastructure::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use crate::infrastructure::rate_limiter::{RateLimitConfig, TokenBucket};
 use anyhow::{Context, Result};
-use reqwest::Client;
-use std::time::Duration;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
+/// Error returned when the upstream rejects a request with 429; the limiter already waits out `Retry-After`
+#[derive(Debug)]
+struct RateLimited;
+
+impl std::fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API rate limit exceeded")
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// Error returned when the upstream answers with any other unsuccessful status
+#[derive(Debug)]
+struct RequestFailed(StatusCode);
+
+impl std::fmt::Display for RequestFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API request failed with status: {}", self.0)
+    }
+}
+
+impl std::error::Error for RequestFailed {}
+
+/// Whether an error means the upstream itself is failing, as opposed to rate limiting, an
+/// unknown symbol or a response that didn't parse
+fn is_upstream_failure(e: &anyhow::Error) -> bool {
+    match e.downcast_ref::<RequestFailed>() {
+        Some(RequestFailed(status)) => status.is_server_error(),
+        None => e.downcast_ref::<reqwest::Error>().is_some(),
+    }
+}
+
+/// Public GSE API the client talks to unless configured otherwise
+pub const DEFAULT_GSE_API_BASE_URL: &str = "https://dev.kwayisi.org/apis/gse";
+
 /// GSE API client implementation
 pub struct GseApiClientImpl {
     client: Client,
     base_url: String,
+    rate_limiter: TokenBucket,
+    circuit_breaker: CircuitBreaker,
+    metrics: Arc<dyn MetricsRecorder + Send + Sync>,
 }
 
 impl GseApiClientImpl {
-    pub fn new() -> Self {
+    pub fn with_config(
+        rate_limit: RateLimitConfig,
+        circuit_breaker: CircuitBreakerConfig,
+        metrics: Arc<dyn MetricsRecorder + Send + Sync>,
+    ) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
@@ -19,18 +69,66 @@ impl GseApiClientImpl {
 
         Self {
             client,
-            base_url: "https://dev.kwayisi.org/apis/gse".to_string(),
+            base_url: DEFAULT_GSE_API_BASE_URL.to_string(),
+            rate_limiter: TokenBucket::new(rate_limit),
+            circuit_breaker: CircuitBreaker::new(circuit_breaker),
+            metrics,
         }
     }
 
+    /// Point the client at another deployment of the API, such as a staging mirror or a local
+    /// mock server
+    pub fn with_base_url(mut self, base_url: String) -> Self {
+        self.base_url = base_url.trim().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Full URL of an API path given without a leading slash
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
     /// Helper method to make HTTP requests with rate limiting
     async fn make_request<T>(&self, url: &str) -> Result<T>
     where
         T: serde::de::DeserializeOwned,
     {
-        // Rate limiting: max 60 requests per second as per API docs
-        sleep(Duration::from_millis(100)).await; // ~10 requests per second for safety
+        if let Err(e) = self.circuit_breaker.try_acquire() {
+            self.metrics
+                .increment(ERRORS_METRIC, &[("type", "upstream_circuit_open")]);
+            return Err(e.into());
+        }
+        self.rate_limiter.acquire().await;
 
+        // Time the request itself, not the wait for a rate limit token
+        let started = Instant::now();
+        let result = self.send_request(url).await;
+        self.metrics.observe(
+            "gse_upstream_request_duration_seconds",
+            &[],
+            started.elapsed().as_secs_f64(),
+        );
+        match &result {
+            Err(e) if is_upstream_failure(e) => self.circuit_breaker.record_failure(),
+            _ => self.circuit_breaker.record_success(),
+        }
+        if let Err(e) = &result {
+            let error_type = if e.downcast_ref::<RateLimited>().is_some() {
+                "upstream_rate_limited"
+            } else {
+                "upstream_request"
+            };
+            self.metrics
+                .increment(ERRORS_METRIC, &[("type", error_type)]);
+        }
+
+        result
+    }
+
+    async fn send_request<T>(&self, url: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
         let response = self
             .client
             .get(url)
@@ -38,8 +136,16 @@ impl GseApiClientImpl {
             .await
             .context("Failed to send request")?;
 
+        let headers = response.headers();
+        self.rate_limiter
+            .observe(rate_limit_remaining(headers), retry_after(headers));
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            return Err(RateLimited.into());
+        }
+
         if !response.status().is_success() {
-            anyhow::bail!("API request failed with status: {}", response.status());
+            return Err(RequestFailed(response.status()).into());
         }
 
         let data = response
@@ -62,10 +168,15 @@ impl GseApiClientImpl {
             match self.make_request(url).await {
                 Ok(data) => return Ok(data),
                 Err(e) if retries >= max_retries => return Err(e),
+                // Retrying can't help until the circuit lets requests through again
+                Err(e) if e.downcast_ref::<CircuitOpen>().is_some() => return Err(e),
                 Err(e) => {
                     tracing::warn!("Request failed (attempt {}): {}", retries + 1, e);
-                    sleep(delay).await;
-                    delay *= 2; // Exponential backoff
+                    // A 429 is paced by the limiter's Retry-After wait, so don't stack a backoff on it
+                    if e.downcast_ref::<RateLimited>().is_none() {
+                        sleep(delay).await;
+                        delay *= 2; // Exponential backoff
+                    }
                     retries += 1;
                 }
             }
@@ -73,20 +184,89 @@ impl GseApiClientImpl {
     }
 }
 
+/// Parse `X-RateLimit-Remaining`
+fn rate_limit_remaining(headers: &HeaderMap) -> Option<u32> {
+    headers
+        .get("x-ratelimit-remaining")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Parse `Retry-After`, given either as delay seconds or as an HTTP date
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
 #[async_trait::async_trait]
 impl GseApiClient for GseApiClientImpl {
     async fn fetch_all_live_data(&self) -> Result<Vec<EquityLive>> {
-        let url = format!("{}/live", self.base_url);
+        let url = self.url("live");
         self.make_request_with_retry(&url, 3).await
     }
 
     async fn fetch_all_equities(&self) -> Result<Vec<EquitySummary>> {
-        let url = format!("{}/equities", self.base_url);
+        let url = self.url("equities");
         self.make_request_with_retry(&url, 3).await
     }
 
     async fn fetch_equity_data(&self, symbol: &str) -> Result<Equity> {
-        let url = format!("{}/equities/{}", self.base_url, symbol.to_lowercase());
+        let symbol = symbol.trim().trim_matches('/').to_lowercase();
+        let url = self.url(&format!("equities/{}", symbol));
         self.make_request_with_retry(&url, 3).await
     }
+
+    fn circuit_state(&self) -> CircuitState {
+        self.circuit_breaker.state()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::PrometheusMetrics;
+
+    fn client() -> GseApiClientImpl {
+        GseApiClientImpl::with_config(
+            RateLimitConfig::default(),
+            CircuitBreakerConfig::default(),
+            Arc::new(PrometheusMetrics::new()),
+        )
+    }
+
+    #[test]
+    fn urls_join_the_base_and_path_with_a_single_slash() {
+        let client = client().with_base_url(" http://localhost:9000/gse/ ".to_string());
+
+        let live = client.url("live");
+        let equity = client.url("/equities/mtngh");
+
+        assert_eq!(live, "http://localhost:9000/gse/live");
+        assert_eq!(equity, "http://localhost:9000/gse/equities/mtngh");
+    }
+
+    #[test]
+    fn the_default_base_url_is_the_public_api() {
+        let client = client();
+
+        assert_eq!(
+            client.url("live"),
+            format!("{}/live", DEFAULT_GSE_API_BASE_URL)
+        );
+    }
 }
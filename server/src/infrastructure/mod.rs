@@ -1,7 +1,31 @@
+pub mod backup;
+pub mod circuit_breaker;
+pub mod db_scan;
+pub mod disk_space;
+pub mod fx_rates;
 pub mod gse_client;
+pub mod prometheus;
+pub mod rate_limiter;
+pub mod record_schema;
+pub mod rocksdb_alert_repository;
 pub mod rocksdb_portfolio_repository;
 pub mod rocksdb_repository;
+pub mod rocksdb_tuning;
+pub mod rocksdb_watchlist_repository;
+#[cfg(test)]
+pub mod test_support;
+pub mod webhook_client;
 
+pub use backup::*;
+pub use circuit_breaker::*;
+pub use disk_space::*;
+pub use fx_rates::*;
 pub use gse_client::*;
+pub use prometheus::*;
+pub use rate_limiter::*;
+pub use rocksdb_alert_repository::*;
 pub use rocksdb_portfolio_repository::*;
 pub use rocksdb_repository::*;
+pub use rocksdb_tuning::*;
+pub use rocksdb_watchlist_repository::*;
+pub use webhook_client::*;
@@ -0,0 +1,194 @@
+use crate::domain::MetricsRecorder;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+
+/// Histogram bucket upper bounds, in seconds
+const BUCKETS_SECONDS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    /// One count per bucket, plus a final overflow bucket for anything above the last bound
+    counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    counters: BTreeMap<String, BTreeMap<Labels, u64>>,
+    histograms: BTreeMap<String, BTreeMap<Labels, Histogram>>,
+    gauges: BTreeMap<String, f64>,
+}
+
+/// In-memory metrics registry exported in the Prometheus text format
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    registry: Mutex<Registry>,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MetricsRecorder for PrometheusMetrics {
+    fn increment(&self, name: &str, labels: &[(&str, &str)]) {
+        let mut registry = self.registry.lock().unwrap();
+        *registry
+            .counters
+            .entry(name.to_string())
+            .or_default()
+            .entry(owned_labels(labels))
+            .or_insert(0) += 1;
+    }
+
+    fn observe(&self, name: &str, labels: &[(&str, &str)], seconds: f64) {
+        let bucket = BUCKETS_SECONDS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(BUCKETS_SECONDS.len());
+
+        let mut registry = self.registry.lock().unwrap();
+        let histogram = registry
+            .histograms
+            .entry(name.to_string())
+            .or_default()
+            .entry(owned_labels(labels))
+            .or_insert_with(|| Histogram {
+                counts: vec![0; BUCKETS_SECONDS.len() + 1],
+                count: 0,
+                sum: 0.0,
+            });
+        histogram.counts[bucket] += 1;
+        histogram.count += 1;
+        histogram.sum += seconds;
+    }
+
+    fn set_gauge(&self, name: &str, value: f64) {
+        let mut registry = self.registry.lock().unwrap();
+        registry.gauges.insert(name.to_string(), value);
+    }
+
+    fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap();
+        let mut out = String::new();
+
+        for (name, series) in &registry.counters {
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            for (labels, value) in series {
+                let _ = writeln!(out, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+
+        for (name, series) in &registry.histograms {
+            let _ = writeln!(out, "# TYPE {} histogram", name);
+            for (labels, histogram) in series {
+                let mut cumulative = 0;
+                for (bound, count) in BUCKETS_SECONDS.iter().zip(&histogram.counts) {
+                    cumulative += count;
+                    let le = bound.to_string();
+                    let _ = writeln!(
+                        out,
+                        "{}_bucket{} {}",
+                        name,
+                        format_labels(labels, Some(&le)),
+                        cumulative
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some("+Inf")),
+                    histogram.count
+                );
+                let _ = writeln!(
+                    out,
+                    "{}_sum{} {}",
+                    name,
+                    format_labels(labels, None),
+                    histogram.sum
+                );
+                let _ = writeln!(
+                    out,
+                    "{}_count{} {}",
+                    name,
+                    format_labels(labels, None),
+                    histogram.count
+                );
+            }
+        }
+
+        for (name, value) in &registry.gauges {
+            let _ = writeln!(out, "# TYPE {} gauge", name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        out
+    }
+}
+
+fn owned_labels(labels: &[(&str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Render a label set as `{name="value",...}`, with an optional trailing `le` bucket label, or
+/// nothing when there are no labels
+fn format_labels(labels: &Labels, le: Option<&str>) -> String {
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .chain(le.map(|le| ("le", le)))
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+        .collect();
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_metrics_render_in_the_text_format() {
+        let metrics = PrometheusMetrics::new();
+        metrics.increment("api_requests_total", &[("route", "/api/stocks")]);
+        metrics.increment("api_requests_total", &[("route", "/api/stocks")]);
+        metrics.observe("scrape_cycle_duration_seconds", &[], 0.3);
+        metrics.observe("scrape_cycle_duration_seconds", &[], 120.0);
+        metrics.set_gauge("stored_symbols", 42.0);
+
+        let text = metrics.render();
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.contains(&"# TYPE api_requests_total counter"));
+        assert!(lines.contains(&"api_requests_total{route=\"/api/stocks\"} 2"));
+        assert!(lines.contains(&"# TYPE scrape_cycle_duration_seconds histogram"));
+        assert!(lines.contains(&"scrape_cycle_duration_seconds_bucket{le=\"0.25\"} 0"));
+        assert!(lines.contains(&"scrape_cycle_duration_seconds_bucket{le=\"0.5\"} 1"));
+        assert!(lines.contains(&"scrape_cycle_duration_seconds_bucket{le=\"60\"} 1"));
+        assert!(lines.contains(&"scrape_cycle_duration_seconds_bucket{le=\"+Inf\"} 2"));
+        assert!(lines.contains(&"scrape_cycle_duration_seconds_count 2"));
+        assert!(lines.contains(&"stored_symbols 42"));
+    }
+}
@@ -0,0 +1,171 @@
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::{sleep, Instant};
+
+/// Longest `Retry-After` honoured; longer values are treated as this long
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Settings for pacing requests to an upstream API
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Steady-state request rate used when the upstream sends no rate-limit headers
+    pub requests_per_second: f64,
+    /// Number of requests that may be made back to back before pacing kicks in
+    pub burst: u32,
+    /// When `X-RateLimit-Remaining` drops to this value or below, the rate is scaled down
+    pub low_remaining_threshold: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            // The API allows 60 requests per second; stay well under it
+            requests_per_second: 10.0,
+            burst: 1,
+            low_remaining_threshold: 10,
+        }
+    }
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+    /// Fraction of the configured rate currently in effect, lowered while the upstream quota is low
+    rate_factor: f64,
+    /// Set from `Retry-After`; no request is let through before this instant
+    blocked_until: Option<Instant>,
+}
+
+/// Token-bucket limiter whose rate adapts to the upstream's rate-limit headers
+pub struct TokenBucket {
+    config: RateLimitConfig,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    pub fn new(config: RateLimitConfig) -> Self {
+        let state = BucketState {
+            tokens: config.burst as f64,
+            last_refill: Instant::now(),
+            rate_factor: 1.0,
+            blocked_until: None,
+        };
+
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Wait until a request may be sent, then take a token for it
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let now = Instant::now();
+                self.refill(&mut state, now);
+
+                match state.blocked_until {
+                    Some(until) if until > now => until - now,
+                    _ if state.tokens >= 1.0 => {
+                        state.tokens -= 1.0;
+                        return;
+                    }
+                    _ => {
+                        Duration::from_secs_f64((1.0 - state.tokens) / self.effective_rate(&state))
+                    }
+                }
+            };
+
+            sleep(wait).await;
+        }
+    }
+
+    /// Adjust pacing from the upstream's latest rate-limit headers; `None` values mean the header was absent
+    pub fn observe(&self, remaining: Option<u32>, retry_after: Option<Duration>) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        self.refill(&mut state, now);
+
+        state.rate_factor = match remaining {
+            Some(remaining) if remaining <= self.config.low_remaining_threshold => {
+                // Spend what's left of the quota gradually rather than in a burst
+                state.tokens = state.tokens.min(0.0);
+                (remaining as f64 / (self.config.low_remaining_threshold as f64 + 1.0)).max(0.1)
+            }
+            _ => 1.0,
+        };
+
+        if let Some(retry_after) = retry_after {
+            let retry_after = retry_after.min(MAX_RETRY_AFTER);
+            state.blocked_until = Some(now.checked_add(retry_after).unwrap_or(now));
+            state.tokens = 0.0;
+        }
+    }
+
+    fn effective_rate(&self, state: &BucketState) -> f64 {
+        self.config.requests_per_second * state.rate_factor
+    }
+
+    fn refill(&self, state: &mut BucketState, now: Instant) {
+        // Time spent blocked by Retry-After does not earn tokens
+        let from = match state.blocked_until {
+            Some(until) if until > state.last_refill => until.min(now),
+            _ => state.last_refill,
+        };
+        let elapsed = now.saturating_duration_since(from).as_secs_f64();
+
+        state.tokens =
+            (state.tokens + elapsed * self.effective_rate(state)).min(self.config.burst as f64);
+        state.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket() -> TokenBucket {
+        TokenBucket::new(RateLimitConfig {
+            requests_per_second: 100.0,
+            burst: 1,
+            low_remaining_threshold: 10,
+        })
+    }
+
+    async fn time_to_acquire(bucket: &TokenBucket, requests: usize) -> Duration {
+        let start = Instant::now();
+        for _ in 0..requests {
+            bucket.acquire().await;
+        }
+        start.elapsed()
+    }
+
+    #[tokio::test]
+    async fn a_low_remaining_quota_slows_requests_down() {
+        let normal = bucket();
+        let throttled = bucket();
+        throttled.observe(Some(0), None);
+
+        let normal = time_to_acquire(&normal, 5).await;
+        let throttled = time_to_acquire(&throttled, 5).await;
+
+        assert!(
+            throttled > normal * 3,
+            "throttled {:?}, normal {:?}",
+            throttled,
+            normal
+        );
+    }
+
+    #[test]
+    fn a_huge_retry_after_is_clamped() {
+        let bucket = bucket();
+
+        bucket.observe(None, Some(Duration::MAX));
+
+        let state = bucket.state.lock().unwrap();
+        let blocked_for = state.blocked_until.unwrap() - state.last_refill;
+        assert!(blocked_for <= MAX_RETRY_AFTER);
+    }
+}
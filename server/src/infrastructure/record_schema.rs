@@ -0,0 +1,174 @@
+use crate::domain::{Equity, EquityLive};
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+/// Field carrying a stored record's schema version. Readers ignore it, so versioned and
+/// unversioned records deserialize alike.
+pub const VERSION_FIELD: &str = "_v";
+
+/// Schema version of the live and detail records written now.
+///
+/// Version 1 records predate versioning: prices may be stored as strings and fields added
+/// since (such as a live record's `source`) are missing.
+pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+
+/// Kind of per-symbol record, from the `{type}` segment of `stock:{symbol}:{type}:{timestamp}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordKind {
+    Live,
+    Detail,
+}
+
+impl RecordKind {
+    pub fn from_key_segment(segment: &str) -> Option<Self> {
+        match segment {
+            "live" => Some(Self::Live),
+            "detail" => Some(Self::Detail),
+            _ => None,
+        }
+    }
+}
+
+/// Serialize a record tagged with the current schema version
+pub fn encode<T: Serialize>(record: &T) -> Result<Vec<u8>> {
+    Ok(serde_json::to_vec(&tagged(record)?)?)
+}
+
+/// Schema version of a stored record; untagged records are version 1
+pub fn version_of(value: &Value) -> u64 {
+    value
+        .get(VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .unwrap_or(1)
+}
+
+/// Rewrite a stored record in the current shape, one version step at a time
+pub fn upgrade(kind: RecordKind, mut value: Value) -> Result<Value> {
+    for version in version_of(&value)..CURRENT_SCHEMA_VERSION {
+        value = match version {
+            1 => upgrade_v1(kind, value)?,
+            _ => anyhow::bail!("No upgrade from schema version {}", version),
+        };
+    }
+    Ok(value)
+}
+
+/// Version 1 to 2: prices stored as strings become numbers and a live record without a
+/// `source` is marked as scraped, the only source there was. The result must then decode as the
+/// current type, so a record this can't repair fails here rather than on every later read.
+fn upgrade_v1(kind: RecordKind, mut value: Value) -> Result<Value> {
+    let Value::Object(fields) = &mut value else {
+        anyhow::bail!("Record is not a JSON object");
+    };
+
+    match kind {
+        RecordKind::Live => {
+            numbers_from_strings(fields, &["price", "change", "volume"])?;
+            fields
+                .entry("source")
+                .or_insert_with(|| Value::String("scraped".to_string()));
+        }
+        RecordKind::Detail => {
+            numbers_from_strings(fields, &["price", "capital", "dps", "eps", "shares"])?;
+        }
+    }
+    fields.insert(VERSION_FIELD.to_string(), 2.into());
+
+    match kind {
+        RecordKind::Live => check_decodes::<EquityLive>(&value)?,
+        RecordKind::Detail => check_decodes::<Equity>(&value)?,
+    }
+    Ok(value)
+}
+
+/// Replace each named field holding a numeric string with the number it spells
+fn numbers_from_strings(fields: &mut Map<String, Value>, names: &[&str]) -> Result<()> {
+    for name in names {
+        let Some(Value::String(text)) = fields.get(*name) else {
+            continue;
+        };
+        let number: serde_json::Number = text
+            .trim()
+            .parse()
+            .with_context(|| format!("Field {} is not a number: {:?}", name, text))?;
+        fields.insert(name.to_string(), Value::Number(number));
+    }
+    Ok(())
+}
+
+fn check_decodes<T: DeserializeOwned>(value: &Value) -> Result<()> {
+    T::deserialize(value).context("Record doesn't match its schema")?;
+    Ok(())
+}
+
+fn tagged<T: Serialize>(record: &T) -> Result<Value> {
+    let mut value = serde_json::to_value(record)?;
+    if let Value::Object(fields) = &mut value {
+        fields.insert(VERSION_FIELD.to_string(), CURRENT_SCHEMA_VERSION.into());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::DataSource;
+    use serde_json::json;
+
+    #[test]
+    fn a_v1_live_record_gains_its_source_and_numeric_prices() {
+        let v1 = json!({ "change": "-0.05", "name": "MTNGH", "price": "1.95", "volume": 1200 });
+
+        let upgraded = upgrade(RecordKind::Live, v1).unwrap();
+
+        assert_eq!(version_of(&upgraded), CURRENT_SCHEMA_VERSION);
+        assert_eq!(upgraded["price"], json!(1.95));
+        assert_eq!(upgraded["source"], json!("scraped"));
+        let live: EquityLive = serde_json::from_value(upgraded).unwrap();
+        assert_eq!(live.change, -0.05);
+        assert_eq!(live.source, DataSource::Scraped);
+    }
+
+    #[test]
+    fn a_v1_detail_record_keeps_fields_the_current_type_does_not_know() {
+        let v1 = json!({
+            "capital": null,
+            "company": { "directors": [], "name": "MTN Ghana" },
+            "dps": "0.15",
+            "eps": null,
+            "name": "MTNGH",
+            "price": "1.95",
+            "shares": "12290474360",
+            "legacy_note": "kept"
+        });
+
+        let upgraded = upgrade(RecordKind::Detail, v1).unwrap();
+
+        assert_eq!(upgraded["legacy_note"], json!("kept"));
+        let equity: Equity = serde_json::from_value(upgraded).unwrap();
+        assert_eq!(equity.dps, Some(0.15));
+        assert_eq!(equity.shares, Some(12290474360));
+    }
+
+    #[test]
+    fn an_unrepairable_v1_record_fails_to_upgrade() {
+        let v1 = json!({ "change": 0, "name": "MTNGH", "price": "n/a", "volume": 1 });
+
+        assert!(upgrade(RecordKind::Live, v1).is_err());
+    }
+
+    #[test]
+    fn current_records_are_left_alone() {
+        let current = tagged(&EquityLive {
+            change: 0.0,
+            name: "MTNGH".to_string(),
+            price: 1.0,
+            volume: 1,
+            source: DataSource::Backfill,
+        })
+        .unwrap();
+
+        assert_eq!(upgrade(RecordKind::Live, current.clone()).unwrap(), current);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::domain::{Alert, AlertRepository};
+use crate::infrastructure::db_scan::scan_prefix;
+use anyhow::{Context, Result};
+use rocksdb::DB;
+use std::sync::Arc;
+
+pub struct RocksDbAlertRepository {
+    db: Arc<DB>,
+}
+
+impl RocksDbAlertRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+
+    fn alert_key(id: &str) -> String {
+        format!("alert:{}", id)
+    }
+}
+
+#[async_trait::async_trait]
+impl AlertRepository for RocksDbAlertRepository {
+    async fn store_alert(&self, alert: &Alert) -> Result<()> {
+        let key = Self::alert_key(&alert.id);
+        let value = serde_json::to_vec(alert)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store alert")?;
+
+        Ok(())
+    }
+
+    async fn get_alerts(&self) -> Result<Vec<Alert>> {
+        let mut alerts = Vec::new();
+
+        for item in scan_prefix(&self.db, "alert:") {
+            let (_, value) = item?;
+            if let Ok(alert) = serde_json::from_slice::<Alert>(&value) {
+                alerts.push(alert);
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    async fn delete_alert(&self, id: &str) -> Result<bool> {
+        let key = Self::alert_key(id);
+        if self.db.get(key.as_bytes())?.is_none() {
+            return Ok(false);
+        }
+
+        self.db
+            .delete(key.as_bytes())
+            .context("Failed to delete alert")?;
+        Ok(true)
+    }
+}
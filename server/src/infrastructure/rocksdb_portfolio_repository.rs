@@ -1,5 +1,7 @@
-use crate::domain::{Portfolio, PortfolioRepository};
+use crate::domain::{Portfolio, PortfolioRepository, PortfolioSnapshot, PortfolioValuePoint};
+use crate::infrastructure::db_scan::{scan_prefix, scan_prefix_from};
 use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
 use rocksdb::DB;
 use std::sync::Arc;
 
@@ -15,6 +17,18 @@ impl RocksDbPortfolioRepository {
     fn portfolio_key(id: &str) -> String {
         format!("portfolio:{}", id)
     }
+
+    fn snapshot_prefix(id: &str) -> String {
+        format!("portfolio:{}:snapshot:", id)
+    }
+
+    fn value_prefix(id: &str) -> String {
+        format!("portfolio:{}:value:", id)
+    }
+
+    fn value_key(id: &str, timestamp: &DateTime<Utc>) -> String {
+        format!("{}{}", Self::value_prefix(id), timestamp.timestamp())
+    }
 }
 
 #[async_trait::async_trait]
@@ -44,10 +58,9 @@ impl PortfolioRepository for RocksDbPortfolioRepository {
 
     async fn get_all_portfolios(&self) -> Result<Vec<Portfolio>> {
         let prefix = "portfolio:";
-        let iter = self.db.prefix_iterator(prefix);
         let mut portfolios = Vec::new();
 
-        for item in iter {
+        for item in scan_prefix(&self.db, prefix) {
             let (key, value) = item?;
             let key_str = String::from_utf8_lossy(&key);
             
@@ -57,7 +70,9 @@ impl PortfolioRepository for RocksDbPortfolioRepository {
             // Our key pattern is simple "portfolio:{uuid}", so checking if it has exactly one colon might be enough,
             // or just trying to deserialize.
             
-            if key_str.starts_with(prefix) {
+            // Snapshots and value points live under portfolio:{id}:snapshot: and portfolio:{id}:value:;
+            // only portfolio:{id} holds a portfolio
+            if key_str.starts_with(prefix) && !key_str[prefix.len()..].contains(':') {
                  match serde_json::from_slice::<Portfolio>(&value) {
                     Ok(portfolio) => portfolios.push(portfolio),
                     Err(_) => {
@@ -77,6 +92,102 @@ impl PortfolioRepository for RocksDbPortfolioRepository {
     async fn delete_portfolio(&self, id: &str) -> Result<()> {
         let key = Self::portfolio_key(id);
         self.db.delete(key.as_bytes()).context("Failed to delete portfolio")?;
+
+        for prefix in [Self::snapshot_prefix(id), Self::value_prefix(id)] {
+            for item in scan_prefix(&self.db, &prefix) {
+                let (key, _) = item?;
+                self.db
+                    .delete(&key)
+                    .context("Failed to delete portfolio history")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn store_snapshot(&self, snapshot: &PortfolioSnapshot) -> Result<()> {
+        // ISO dates sort chronologically, so key order is date order
+        let key = format!(
+            "{}{}",
+            Self::snapshot_prefix(&snapshot.portfolio_id),
+            snapshot.date.format("%Y-%m-%d")
+        );
+        let value = serde_json::to_vec(snapshot)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store portfolio snapshot")?;
+
+        Ok(())
+    }
+
+    async fn get_snapshots(&self, id: &str) -> Result<Vec<PortfolioSnapshot>> {
+        let prefix = Self::snapshot_prefix(id);
+        let mut snapshots = Vec::new();
+
+        for item in scan_prefix(&self.db, &prefix) {
+            let (_, value) = item?;
+            if let Ok(snapshot) = serde_json::from_slice::<PortfolioSnapshot>(&value) {
+                snapshots.push(snapshot);
+            }
+        }
+
+        Ok(snapshots)
+    }
+
+    async fn get_last_snapshot_date(&self, id: &str) -> Result<Option<NaiveDate>> {
+        let prefix = Self::snapshot_prefix(id);
+        let mut last = None;
+
+        // Keys are in date order, so the last one parsed is the most recent
+        for item in scan_prefix(&self.db, &prefix) {
+            let (key, _) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            if let Some(date) = key_str
+                .strip_prefix(prefix.as_str())
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+            {
+                last = Some(date);
+            }
+        }
+
+        Ok(last)
+    }
+
+    async fn store_value_point(&self, id: &str, point: &PortfolioValuePoint) -> Result<()> {
+        let key = Self::value_key(id, &point.timestamp);
+        let value = serde_json::to_vec(point)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store portfolio value point")?;
+
         Ok(())
     }
+
+    async fn get_value_points(
+        &self,
+        id: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<PortfolioValuePoint>> {
+        let prefix = Self::value_prefix(id);
+        let start = Self::value_key(id, &from);
+        let mut points = Vec::new();
+
+        for item in scan_prefix_from(&self.db, &prefix, &start) {
+            let (_, value) = item?;
+            let Ok(point) = serde_json::from_slice::<PortfolioValuePoint>(&value) else {
+                continue;
+            };
+            if point.timestamp > to {
+                break;
+            }
+            if point.timestamp >= from {
+                points.push(point);
+            }
+        }
+
+        Ok(points)
+    }
 }
@@ -1,17 +1,99 @@
-use crate::domain::{Equity, EquityLive, MarketSummary, StockRepository, TimeSeriesPoint};
+use crate::domain::{
+    Announcement, ArchiveSummary, Bond, DataArchiver, DataExporter, DataPruner, DataSource, Equity,
+    EquityLive, MarketEvent, MarketSummary, MetricsRecorder, PruneSummary, ScrapeCycle,
+    StockRepository, StockSplit, StoredRecord, SymbolMetrics, TimeSeriesPoint, WebhookDelivery,
+    ERRORS_METRIC,
+};
+use crate::infrastructure::db_scan::{scan_prefix, scan_prefix_from};
+use crate::infrastructure::record_schema::{self, RecordKind, CURRENT_SCHEMA_VERSION};
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use rocksdb::DB;
-use std::sync::Arc;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use rocksdb::{WriteBatch, DB};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// Outcome of merging case-variant symbol keys into their uppercase form
+#[derive(Debug, Default)]
+pub struct SymbolCasingMerge {
+    /// Canonical symbols that had at least one case variant
+    pub symbols: BTreeSet<String>,
+    /// Records rewritten under the canonical symbol
+    pub records_moved: usize,
+    /// Variant records dropped because the canonical symbol already had a record at that key
+    pub records_dropped: usize,
+}
+
+/// Outcome of upgrading stored records to the current schema version
+#[derive(Debug, Default)]
+pub struct SchemaMigration {
+    /// Records rewritten in the current schema
+    pub upgraded: usize,
+    /// Records already in the current schema, or a newer one
+    pub current: usize,
+    /// Records that couldn't be upgraded and were left untouched
+    pub failed: usize,
+}
+
+/// Records rewritten per write batch during a schema migration
+const MIGRATION_BATCH_SIZE: usize = 1000;
+
+/// First second timestamp with ten decimal digits, 2001-09-09T01:46:40Z
+const TEN_DIGIT_TIMESTAMPS_FROM: i64 = 1_000_000_000;
 
 /// RocksDB implementation of the StockRepository
 pub struct RocksDbStockRepository {
     db: Arc<DB>,
+    metrics: Option<Arc<dyn MetricsRecorder + Send + Sync>>,
+    compact_after_prune: bool,
+    /// Held across each read-modify-write of a timestamp pointer, so concurrent writers can't
+    /// move one back to an older record
+    pointer_lock: Mutex<()>,
 }
 
 impl RocksDbStockRepository {
     pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self {
+            db,
+            metrics: None,
+            compact_after_prune: false,
+            pointer_lock: Mutex::new(()),
+        }
+    }
+
+    /// Compact each pruned symbol's key range once retention pruning has deleted its records
+    pub fn with_compaction_after_prune(mut self, enabled: bool) -> Self {
+        self.compact_after_prune = enabled;
+        self
+    }
+
+    /// Count records skipped for failing to deserialize in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<dyn MetricsRecorder + Send + Sync>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Log and count a stored record left out of a read because it no longer deserializes.
+    /// The read carries on with the records that do.
+    fn skip_undecodable(&self, key: &str, error: &serde_json::Error) {
+        tracing::warn!("Skipping undecodable record {}: {}", key, error);
+        if let Some(metrics) = &self.metrics {
+            metrics.increment(ERRORS_METRIC, &[("type", "undecodable_record")]);
+        }
+    }
+
+    /// Timestamp of a live data key, logging and leaving out a key whose timestamp can't be
+    /// read: it has no place in any time range
+    fn live_key_timestamp(key: &str) -> Option<DateTime<Utc>> {
+        let timestamp = key
+            .rsplit(':')
+            .next()
+            .and_then(|ts| ts.parse::<i64>().ok())
+            .and_then(|ts| DateTime::from_timestamp(ts, 0));
+        if timestamp.is_none() {
+            tracing::warn!("Skipping live data with unreadable timestamp key {}", key);
+        }
+        timestamp
     }
 
     /// Generate key for live data storage
@@ -29,17 +111,626 @@ impl RocksDbStockRepository {
         format!("market:summary:{}", timestamp.timestamp())
     }
 
+    /// Generate key for market event storage
+    fn market_event_key(timestamp: &DateTime<Utc>) -> String {
+        format!("market:event:{}", timestamp.timestamp())
+    }
+
+    /// Generate key for scrape cycle storage
+    fn scrape_cycle_key(timestamp: &DateTime<Utc>) -> String {
+        format!("worker:cycle:{}", timestamp.timestamp())
+    }
+
+    /// Generate key for a queued webhook delivery
+    fn delivery_key(id: &str) -> String {
+        format!("delivery:{}", id)
+    }
+
+    /// Generate key for a symbol's monthly archive of live ticks
+    fn archive_key(symbol: &str, month: NaiveDate) -> String {
+        format!("archive:{}:{}", symbol, month.format("%Y%m"))
+    }
+
+    /// Archived live ticks of a symbol within a time range, oldest first; with a `limit`, only
+    /// the oldest `limit` of them, reading no further archive months once those are collected
+    fn get_archived_points(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: Option<usize>,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let prefix = format!("archive:{}:", symbol);
+        // Month keys sort in time order, so start at the archive holding `from`
+        let start = Self::archive_key(symbol, from.date_naive());
+        let mut data_points = Vec::new();
+
+        for item in scan_prefix_from(&self.db, &prefix, &start) {
+            let (_, value) = item?;
+            let points: Vec<TimeSeriesPoint> = match rmp_serde::from_slice(&value) {
+                Ok(points) => points,
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize archive for {}: {}", symbol, e);
+                    continue;
+                }
+            };
+            if points.first().is_some_and(|point| point.timestamp > to) {
+                break;
+            }
+            data_points.extend(
+                points
+                    .into_iter()
+                    .filter(|point| point.timestamp >= from && point.timestamp <= to),
+            );
+            // Each archive is sorted and holds an earlier month than the next
+            if let Some(limit) = limit.filter(|&limit| data_points.len() >= limit) {
+                data_points.truncate(limit);
+                break;
+            }
+        }
+
+        Ok(data_points)
+    }
+
+    /// Archive one symbol's live ticks stored before `boundary`, in a single write batch.
+    ///
+    /// The symbol's newest tick always stays live, even when it is older than the boundary, so
+    /// the latest-record reads and the symbol listing keep finding it.
+    fn archive_symbol_before(
+        &self,
+        symbol: &str,
+        boundary: DateTime<Utc>,
+        summary: &mut ArchiveSummary,
+    ) -> Result<()> {
+        let prefix = format!("stock:{}:live:", symbol);
+        let mut candidates: Vec<(Box<[u8]>, NaiveDate, TimeSeriesPoint)> = Vec::new();
+        let mut has_newer = false;
+
+        for item in scan_prefix(&self.db, &prefix) {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            let Some(dt) = key_str
+                .rsplit(':')
+                .next()
+                .and_then(|ts| ts.parse::<i64>().ok())
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            else {
+                continue;
+            };
+            if dt >= boundary {
+                has_newer = true;
+                break;
+            }
+
+            let live_data = match serde_json::from_slice::<EquityLive>(&value) {
+                Ok(live_data) => live_data,
+                Err(e) => {
+                    self.skip_undecodable(&key_str, &e);
+                    continue;
+                }
+            };
+            let Some(month) = dt.date_naive().with_day(1) else {
+                continue;
+            };
+            candidates.push((
+                key,
+                month,
+                TimeSeriesPoint {
+                    timestamp: dt,
+                    value: live_data.price,
+                    volume: Some(live_data.volume),
+                    source: live_data.source,
+                },
+            ));
+        }
+
+        if !has_newer {
+            candidates.pop();
+        }
+
+        let mut months: BTreeMap<NaiveDate, Vec<TimeSeriesPoint>> = BTreeMap::new();
+        let mut batch = WriteBatch::default();
+        let records = candidates.len();
+        for (key, month, point) in candidates {
+            months.entry(month).or_default().push(point);
+            batch.delete(&key);
+        }
+
+        if months.is_empty() {
+            return Ok(());
+        }
+
+        for (month, mut points) in months {
+            let key = Self::archive_key(symbol, month);
+            // Extend an archive written by an earlier run, e.g. after a late backfill
+            if let Some(existing) = self.db.get(key.as_bytes())? {
+                let existing: Vec<TimeSeriesPoint> =
+                    rmp_serde::from_slice(&existing).context("Failed to read existing archive")?;
+                points.extend(existing);
+            }
+            points.sort_by_key(|point| point.timestamp);
+            points.dedup_by_key(|point| point.timestamp);
+
+            batch.put(key.as_bytes(), rmp_serde::to_vec_named(&points)?);
+            summary.months += 1;
+        }
+
+        self.db
+            .write(batch)
+            .context("Failed to write monthly archives")?;
+        summary.symbols += 1;
+        summary.records_archived += records;
+        Ok(())
+    }
+
+    /// Generate key for a bond
+    fn bond_key(code: &str) -> String {
+        format!("bond:{}", code)
+    }
+
+    /// Generate key for a stock split; dates sort lexically, so a symbol's keys are in date order
+    fn split_key(symbol: &str, ex_date: NaiveDate) -> String {
+        format!("split:{}:{}", symbol, ex_date.format("%Y-%m-%d"))
+    }
+
+    /// Generate key for a company announcement; dates sort lexically, so keys are in date order
+    fn announcement_key(announcement: &Announcement) -> String {
+        format!(
+            "announcement:{}:{}:{}",
+            announcement.date.format("%Y-%m-%d"),
+            announcement.symbol,
+            announcement.id
+        )
+    }
+
+    /// Generate key for cached symbol metrics
+    fn symbol_metrics_key(symbol: &str) -> String {
+        format!("metrics:{}", symbol)
+    }
+
+    /// Key holding the recently requested symbols between restarts
+    const RECENT_SYMBOLS_KEY: &'static str = "metadata:recent_symbols";
+
+    fn service_state_key(name: &str) -> String {
+        format!("metadata:state:{}", name)
+    }
+
     /// Generate key for last update timestamp
     fn last_update_key(symbol: &str) -> String {
         format!("metadata:last_updated:{}", symbol)
     }
 
+    /// Generate key pointing at the timestamp of a symbol's newest record of a type
+    /// (`live` or `detail`), so the latest record is read without scanning its history
+    fn latest_key(record_type: &str, symbol: &str) -> String {
+        format!("latest:{}:{}", record_type, symbol)
+    }
+
+    /// Key pointing at the timestamp of the newest market summary
+    const LATEST_SUMMARY_KEY: &'static str = "latest:summary";
+
+    /// Store a record stamped `timestamp` and move the latest pointer to it, unless the pointer
+    /// already refers to a newer record
+    fn put_indexed(
+        &self,
+        key: &str,
+        timestamp: i64,
+        value: &[u8],
+        pointer_key: &str,
+    ) -> Result<()> {
+        let _pointer = self.pointer_lock.lock().unwrap();
+        let mut batch = WriteBatch::default();
+        batch.put(key.as_bytes(), value);
+        if self
+            .read_pointer(pointer_key)?
+            .is_none_or(|latest| timestamp >= latest)
+        {
+            batch.put(pointer_key.as_bytes(), timestamp.to_be_bytes());
+        }
+
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    fn read_pointer(&self, pointer_key: &str) -> Result<Option<i64>> {
+        Ok(self
+            .db
+            .get(pointer_key.as_bytes())?
+            .and_then(|value| <[u8; 8]>::try_from(value.as_slice()).ok())
+            .map(i64::from_be_bytes))
+    }
+
+    /// Newest record keyed `{prefix}{timestamp}`, with its timestamp.
+    ///
+    /// Reads the record the latest pointer refers to. Records stored before the pointers
+    /// existed, or a pointer whose record is gone, fall back to scanning the prefix, and
+    /// the pointer is rebuilt from the result.
+    fn get_latest<T: serde::de::DeserializeOwned>(
+        &self,
+        prefix: &str,
+        pointer_key: &str,
+    ) -> Result<Option<(T, i64)>> {
+        let pointer = self.read_pointer(pointer_key)?;
+        if let Some(timestamp) = pointer {
+            let key = format!("{}{}", prefix, timestamp);
+            if let Some(value) = self.db.get(key.as_bytes())? {
+                // An undecodable record is reported by the scan below, which reaches it too
+                if let Ok(record) = serde_json::from_slice(&value) {
+                    return Ok(Some((record, timestamp)));
+                }
+            }
+        }
+
+        let latest = self.scan_latest::<T>(prefix)?;
+        if let Some((_, timestamp)) = &latest {
+            // A write since the pointer was read has already moved it to a newer record
+            let _pointer = self.pointer_lock.lock().unwrap();
+            if self.read_pointer(pointer_key)? == pointer {
+                self.db
+                    .put(pointer_key.as_bytes(), timestamp.to_be_bytes())
+                    .context("Failed to rebuild latest pointer")?;
+            }
+        }
+        Ok(latest)
+    }
+
+    /// Newest record keyed `{prefix}{timestamp}` found by scanning every record under the
+    /// prefix. Records that fail to deserialize are logged and skipped.
+    fn scan_latest<T: serde::de::DeserializeOwned>(
+        &self,
+        prefix: &str,
+    ) -> Result<Option<(T, i64)>> {
+        self.scan_latest_where(prefix, |_| true)
+    }
+
+    /// Like [`Self::scan_latest`], but only considering records that `keep` accepts
+    fn scan_latest_where<T: serde::de::DeserializeOwned>(
+        &self,
+        prefix: &str,
+        keep: impl Fn(&T) -> bool,
+    ) -> Result<Option<(T, i64)>> {
+        let mut latest: Option<(T, i64)> = None;
+
+        for item in scan_prefix(&self.db, prefix) {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            // Extract timestamp from key
+            let Some(Ok(timestamp)) = key_str.rsplit(':').next().map(|s| s.parse::<i64>()) else {
+                continue;
+            };
+            if latest
+                .as_ref()
+                .is_some_and(|(_, newest)| timestamp <= *newest)
+            {
+                continue;
+            }
+            match serde_json::from_slice::<T>(&value) {
+                Ok(record) if keep(&record) => latest = Some((record, timestamp)),
+                Ok(_) => {}
+                Err(e) => self.skip_undecodable(&key_str, &e),
+            }
+        }
+
+        Ok(latest)
+    }
+
+    /// Upgrade every live and detail record stored in an older schema version to the current
+    /// one, writing in batches. Records that can't be upgraded are logged and left as they are.
+    pub fn migrate_records(&self) -> Result<SchemaMigration> {
+        let mut migration = SchemaMigration::default();
+        let mut batch = WriteBatch::default();
+
+        for item in scan_prefix(&self.db, "stock:") {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            // Parse key format: stock:{symbol}:{type}:{timestamp}
+            let Some(kind) = key_str
+                .split(':')
+                .nth(2)
+                .and_then(RecordKind::from_key_segment)
+            else {
+                continue;
+            };
+            let record: serde_json::Value = match serde_json::from_slice(&value) {
+                Ok(record) => record,
+                Err(e) => {
+                    tracing::warn!("Cannot migrate unreadable record {}: {}", key_str, e);
+                    migration.failed += 1;
+                    continue;
+                }
+            };
+            if record_schema::version_of(&record) >= CURRENT_SCHEMA_VERSION {
+                migration.current += 1;
+                continue;
+            }
+
+            match record_schema::upgrade(kind, record) {
+                Ok(upgraded) => {
+                    batch.put(&key, serde_json::to_vec(&upgraded)?);
+                    migration.upgraded += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to migrate record {}: {:#}", key_str, e);
+                    migration.failed += 1;
+                }
+            }
+
+            if batch.len() >= MIGRATION_BATCH_SIZE {
+                self.db
+                    .write(std::mem::take(&mut batch))
+                    .context("Failed to write migrated records")?;
+            }
+        }
+
+        self.db
+            .write(batch)
+            .context("Failed to write migrated records")?;
+        Ok(migration)
+    }
+
+    /// Merge records stored under non-uppercase symbols (e.g. `stock:mtn:`) into the canonical
+    /// uppercase symbol and delete the variants. This covers stored records, monthly archives,
+    /// splits and announcements; where both casings have a record at the same key the canonical
+    /// one is kept, while archives of the same month are combined. Runs as a single write batch.
+    pub fn merge_symbol_casings(&self) -> Result<SymbolCasingMerge> {
+        let mut merge = SymbolCasingMerge::default();
+        let mut batch = WriteBatch::default();
+        let mut written = HashSet::new();
+
+        for item in scan_prefix(&self.db, "stock:") {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            // Parse key format: stock:{symbol}:{type}:{timestamp}
+            let mut parts = key_str.splitn(3, ':');
+            let (Some(_), Some(symbol), Some(rest)) = (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let canonical = symbol.to_uppercase();
+            if canonical == symbol {
+                continue;
+            }
+
+            let canonical_key = format!("stock:{}:{}", canonical, rest);
+            self.queue_canonical_record(
+                &mut batch,
+                &mut written,
+                &mut merge,
+                &canonical_key,
+                &value,
+            )?;
+            batch.delete(&key);
+            merge.symbols.insert(canonical);
+        }
+
+        // Monthly archives of both casings are combined, keeping the canonical point where both
+        // have one at the same timestamp
+        let mut archives: HashMap<String, Vec<TimeSeriesPoint>> = HashMap::new();
+        for item in scan_prefix(&self.db, "archive:") {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            // Parse key format: archive:{symbol}:{month}
+            let Some((symbol, month)) = key_str
+                .strip_prefix("archive:")
+                .and_then(|rest| rest.split_once(':'))
+            else {
+                continue;
+            };
+            let canonical = symbol.to_uppercase();
+            if canonical == symbol {
+                continue;
+            }
+            let points: Vec<TimeSeriesPoint> = match rmp_serde::from_slice(&value) {
+                Ok(points) => points,
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize archive for {}: {}", symbol, e);
+                    continue;
+                }
+            };
+
+            let canonical_key = format!("archive:{}:{}", canonical, month);
+            let merged = match archives.entry(canonical_key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let existing = match self.db.get(entry.key().as_bytes())? {
+                        Some(existing) => rmp_serde::from_slice(&existing)
+                            .context("Failed to read existing archive")?,
+                        None => Vec::new(),
+                    };
+                    entry.insert(existing)
+                }
+            };
+            merged.extend(points);
+            batch.delete(&key);
+            merge.records_moved += 1;
+            merge.symbols.insert(canonical);
+        }
+        for (key, mut points) in archives {
+            points.sort_by_key(|point| point.timestamp);
+            points.dedup_by_key(|point| point.timestamp);
+            batch.put(key.as_bytes(), rmp_serde::to_vec_named(&points)?);
+        }
+
+        for item in scan_prefix(&self.db, "split:") {
+            let (key, value) = item?;
+            let mut split: StockSplit = match serde_json::from_slice(&value) {
+                Ok(split) => split,
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize split: {}", e);
+                    continue;
+                }
+            };
+            let canonical = split.symbol.to_uppercase();
+            if canonical == split.symbol {
+                continue;
+            }
+
+            split.symbol = canonical.clone();
+            let canonical_key = Self::split_key(&canonical, split.ex_date);
+            let value = serde_json::to_vec(&split)?;
+            self.queue_canonical_record(
+                &mut batch,
+                &mut written,
+                &mut merge,
+                &canonical_key,
+                &value,
+            )?;
+            batch.delete(&key);
+            merge.symbols.insert(canonical);
+        }
+
+        for item in scan_prefix(&self.db, "announcement:") {
+            let (key, value) = item?;
+            let mut announcement: Announcement = match serde_json::from_slice(&value) {
+                Ok(announcement) => announcement,
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize announcement: {}", e);
+                    continue;
+                }
+            };
+            let canonical = announcement.symbol.to_uppercase();
+            if canonical == announcement.symbol {
+                continue;
+            }
+
+            announcement.symbol = canonical.clone();
+            let canonical_key = Self::announcement_key(&announcement);
+            let value = serde_json::to_vec(&announcement)?;
+            self.queue_canonical_record(
+                &mut batch,
+                &mut written,
+                &mut merge,
+                &canonical_key,
+                &value,
+            )?;
+            batch.delete(&key);
+            merge.symbols.insert(canonical);
+        }
+
+        // Keep the most recent update time across casings
+        let mut last_updates: HashMap<String, i64> = HashMap::new();
+        for item in scan_prefix(&self.db, "metadata:last_updated:") {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            let Some(symbol) = key_str.strip_prefix("metadata:last_updated:") else {
+                continue;
+            };
+            let Ok(bytes) = <[u8; 8]>::try_from(value.as_ref()) else {
+                continue;
+            };
+
+            let timestamp = i64::from_be_bytes(bytes);
+            let latest = last_updates
+                .entry(symbol.to_uppercase())
+                .or_insert(timestamp);
+            *latest = (*latest).max(timestamp);
+            if symbol != symbol.to_uppercase() {
+                batch.delete(&key);
+            }
+        }
+        for (symbol, timestamp) in last_updates {
+            batch.put(
+                Self::last_update_key(&symbol).as_bytes(),
+                timestamp.to_be_bytes(),
+            );
+        }
+
+        // Variant pointers are orphaned and those of merged symbols may miss the moved records;
+        // drop them so the next read rebuilds them by scanning
+        for item in scan_prefix(&self.db, "latest:") {
+            let (key, _) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            let Some(symbol) = key_str.splitn(3, ':').nth(2) else {
+                continue;
+            };
+            if symbol != symbol.to_uppercase() || merge.symbols.contains(symbol) {
+                batch.delete(&key);
+            }
+        }
+
+        // Cached metrics are derived data; drop variants and let the next recompute rebuild them
+        for item in scan_prefix(&self.db, "metrics:") {
+            let (key, _) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            if let Some(symbol) = key_str.strip_prefix("metrics:") {
+                if symbol != symbol.to_uppercase() {
+                    batch.delete(&key);
+                }
+            }
+        }
+
+        self.db
+            .write(batch)
+            .context("Failed to merge symbol casings")?;
+
+        Ok(merge)
+    }
+
+    /// Queue writing a variant's record under its canonical key, or count it as dropped when
+    /// the canonical symbol already has a record there
+    fn queue_canonical_record(
+        &self,
+        batch: &mut WriteBatch,
+        written: &mut HashSet<String>,
+        merge: &mut SymbolCasingMerge,
+        canonical_key: &str,
+        value: &[u8],
+    ) -> Result<()> {
+        if written.contains(canonical_key) || self.db.get(canonical_key.as_bytes())?.is_some() {
+            merge.records_dropped += 1;
+        } else {
+            batch.put(canonical_key.as_bytes(), value);
+            written.insert(canonical_key.to_string());
+            merge.records_moved += 1;
+        }
+        Ok(())
+    }
+
+    /// Queue deletes for one symbol's records of a type stamped before `cutoff`, keeping the
+    /// newest record even when it is older than the cutoff; returns how many were queued
+    fn prune_symbol_records(
+        &self,
+        symbol: &str,
+        record_type: &str,
+        cutoff: i64,
+        batch: &mut WriteBatch,
+    ) -> Result<usize> {
+        let prefix = format!("stock:{}:{}:", symbol, record_type);
+        let mut expired = Vec::new();
+        let mut newest: Option<i64> = None;
+
+        for item in scan_prefix(&self.db, &prefix) {
+            let (key, _) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            let Some(Ok(timestamp)) = key_str.rsplit(':').next().map(|s| s.parse::<i64>()) else {
+                continue;
+            };
+
+            newest = Some(newest.map_or(timestamp, |newest| newest.max(timestamp)));
+            if timestamp < cutoff {
+                expired.push((timestamp, key));
+            }
+        }
+
+        let mut deleted = 0;
+        for (timestamp, key) in expired {
+            if Some(timestamp) != newest {
+                batch.delete(&key);
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
     /// Get all symbols from the database
     fn get_all_symbols_from_db(&self) -> Result<Vec<String>> {
         let mut symbols = std::collections::HashSet::new();
-        let iter = self.db.prefix_iterator("stock:");
 
-        for item in iter {
+        for item in scan_prefix(&self.db, "stock:") {
             let (key, _) = item?;
             let key_str = String::from_utf8_lossy(&key);
 
@@ -61,19 +752,36 @@ impl StockRepository for RocksDbStockRepository {
         data: &EquityLive,
         timestamp: DateTime<Utc>,
     ) -> Result<()> {
-        let key = Self::live_data_key(symbol, &timestamp);
-        let value = serde_json::to_vec(data)?;
+        // Skip a point identical to the latest one, as scrapes outside trading hours repeat it
+        let prefix = format!("stock:{}:live:", symbol);
+        let latest_key = Self::latest_key("live", symbol);
+        let unchanged = self
+            .get_latest::<EquityLive>(&prefix, &latest_key)?
+            .is_some_and(|(latest, latest_timestamp)| {
+                timestamp.timestamp() >= latest_timestamp && latest.is_unchanged_from(data)
+            });
 
-        self.db
-            .put(key.as_bytes(), &value)
-            .context("Failed to store live data")?;
+        if !unchanged {
+            let key = Self::live_data_key(symbol, &timestamp);
+            let value = record_schema::encode(data)?;
+
+            self.put_indexed(&key, timestamp.timestamp(), &value, &latest_key)
+                .context("Failed to store live data")?;
+        }
 
-        // Update last update timestamp
+        // Update last update timestamp, even when the point was unchanged, but never move it back
+        // for a backfilled older point
         let last_update_key = Self::last_update_key(symbol);
-        let timestamp_bytes = timestamp.timestamp().to_be_bytes().to_vec();
-        self.db
-            .put(last_update_key.as_bytes(), &timestamp_bytes)
-            .context("Failed to update last update timestamp")?;
+        let _pointer = self.pointer_lock.lock().unwrap();
+        if self
+            .read_pointer(&last_update_key)?
+            .is_none_or(|last_update| timestamp.timestamp() >= last_update)
+        {
+            let timestamp_bytes = timestamp.timestamp().to_be_bytes().to_vec();
+            self.db
+                .put(last_update_key.as_bytes(), &timestamp_bytes)
+                .context("Failed to update last update timestamp")?;
+        }
 
         Ok(())
     }
@@ -85,79 +793,116 @@ impl StockRepository for RocksDbStockRepository {
         timestamp: DateTime<Utc>,
     ) -> Result<()> {
         let key = Self::equity_data_key(symbol, &timestamp);
-        let value = serde_json::to_vec(data)?;
+        let value = record_schema::encode(data)?;
 
-        self.db
-            .put(key.as_bytes(), &value)
-            .context("Failed to store equity data")?;
+        self.put_indexed(
+            &key,
+            timestamp.timestamp(),
+            &value,
+            &Self::latest_key("detail", symbol),
+        )
+        .context("Failed to store equity data")?;
 
         Ok(())
     }
 
     async fn get_latest_live_data(&self, symbol: &str) -> Result<Option<EquityLive>> {
         let prefix = format!("stock:{}:live:", symbol);
-        let iter = self.db.prefix_iterator(&prefix);
-
-        let mut latest_timestamp = 0i64;
-        let mut latest_data = None;
-
-        for item in iter {
-            let (key, value) = item?;
-            let key_str = String::from_utf8_lossy(&key);
+        Ok(self
+            .get_latest(&prefix, &Self::latest_key("live", symbol))?
+            .map(|(data, _)| data))
+    }
 
-            // Extract timestamp from key
-            if let Some(timestamp_str) = key_str.split(':').last() {
-                if let Ok(timestamp) = timestamp_str.parse::<i64>() {
-                    if timestamp > latest_timestamp {
-                        latest_timestamp = timestamp;
-                        match serde_json::from_slice::<EquityLive>(&value) {
-                            Ok(data) => latest_data = Some(data),
-                            Err(_) => {
-                                // Silently skip incompatible data
-                            }
-                        }
-                    }
-                }
+    async fn get_latest_live_data_from(
+        &self,
+        symbol: &str,
+        source: DataSource,
+    ) -> Result<Option<EquityLive>> {
+        // The latest record is usually from the wanted source, so only scan when it isn't
+        match self.get_latest_live_data(symbol).await? {
+            Some(data) if data.source == source => Ok(Some(data)),
+            None => Ok(None),
+            Some(_) => {
+                let prefix = format!("stock:{}:live:", symbol);
+                Ok(self
+                    .scan_latest_where(&prefix, |data: &EquityLive| data.source == source)?
+                    .map(|(data, _)| data))
             }
         }
-
-        Ok(latest_data)
     }
 
     async fn get_latest_equity_data(&self, symbol: &str) -> Result<Option<Equity>> {
+        Ok(self
+            .get_latest_equity_data_with_timestamp(symbol)
+            .await?
+            .map(|(equity, _)| equity))
+    }
+
+    async fn get_latest_equity_data_with_timestamp(
+        &self,
+        symbol: &str,
+    ) -> Result<Option<(Equity, DateTime<Utc>)>> {
         let prefix = format!("stock:{}:detail:", symbol);
-        let iter = self.db.prefix_iterator(&prefix);
+        Ok(self
+            .get_latest::<Equity>(&prefix, &Self::latest_key("detail", symbol))?
+            .and_then(|(equity, timestamp)| {
+                DateTime::from_timestamp(timestamp, 0).map(|stored_at| (equity, stored_at))
+            }))
+    }
 
-        let mut latest_timestamp = 0i64;
-        let mut latest_data = None;
+    async fn get_symbol_records(&self, symbol: &str) -> Result<Vec<StoredRecord>> {
+        let prefix = format!("stock:{}:", symbol);
+        let mut records = Vec::new();
 
-        for item in iter {
+        for item in scan_prefix(&self.db, &prefix) {
             let (key, value) = item?;
             let key_str = String::from_utf8_lossy(&key);
 
-            // Extract timestamp from key
-            if let Some(timestamp_str) = key_str.split(':').last() {
-                if let Ok(timestamp) = timestamp_str.parse::<i64>() {
-                    if timestamp > latest_timestamp {
-                        latest_timestamp = timestamp;
-                        match serde_json::from_slice::<Equity>(&value) {
-                            Ok(equity) => latest_data = Some(equity),
-                            Err(_) => {
-                                // Silently skip incompatible data - equity data is optional
-                            }
-                        }
-                    }
-                }
-            }
+            // Parse key format: stock:{symbol}:{type}:{timestamp}
+            let mut parts = key_str[prefix.len()..].splitn(2, ':');
+            let record_type = parts.next().unwrap_or_default().to_string();
+            let timestamp = parts
+                .next()
+                .and_then(|ts| ts.parse::<i64>().ok())
+                .and_then(|ts| DateTime::from_timestamp(ts, 0));
+            let value = serde_json::from_slice(&value).unwrap_or_else(|_| {
+                serde_json::Value::String(String::from_utf8_lossy(&value).into_owned())
+            });
+
+            records.push(StoredRecord {
+                record_type,
+                timestamp,
+                value,
+            });
         }
 
-        Ok(latest_data)
+        Ok(records)
     }
 
     async fn get_all_symbols(&self) -> Result<Vec<String>> {
         Ok(self.get_all_symbols_from_db()?)
     }
 
+    async fn get_last_updates(&self) -> Result<BTreeMap<String, DateTime<Utc>>> {
+        let mut last_updates = BTreeMap::new();
+
+        for item in scan_prefix(&self.db, "metadata:last_updated:") {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+            let Some(symbol) = key_str.strip_prefix("metadata:last_updated:") else {
+                continue;
+            };
+            let Ok(bytes) = <[u8; 8]>::try_from(value.as_ref()) else {
+                continue;
+            };
+            if let Some(timestamp) = DateTime::from_timestamp(i64::from_be_bytes(bytes), 0) {
+                last_updates.insert(symbol.to_string(), timestamp);
+            }
+        }
+
+        Ok(last_updates)
+    }
+
     async fn get_historical_data(
         &self,
         symbol: &str,
@@ -165,28 +910,25 @@ impl StockRepository for RocksDbStockRepository {
         to: DateTime<Utc>,
     ) -> Result<Vec<TimeSeriesPoint>> {
         let prefix = format!("stock:{}:live:", symbol);
-        let iter = self.db.prefix_iterator(&prefix);
+        let mut data_points = self.get_archived_points(symbol, from, to, None)?;
 
-        let mut data_points = Vec::new();
-
-        for item in iter {
+        for item in scan_prefix(&self.db, &prefix) {
             let (key, value) = item?;
             let key_str = String::from_utf8_lossy(&key);
 
-            // Extract timestamp from key
-            if let Some(timestamp_str) = key_str.split(':').last() {
-                if let Ok(timestamp) = timestamp_str.parse::<i64>() {
-                    let dt = DateTime::from_timestamp(timestamp, 0).unwrap_or(from);
+            let Some(dt) = Self::live_key_timestamp(&key_str) else {
+                continue;
+            };
 
-                    if dt >= from && dt <= to {
-                        if let Ok(live_data) = serde_json::from_slice::<EquityLive>(&value) {
-                            data_points.push(TimeSeriesPoint {
-                                timestamp: dt,
-                                value: live_data.price,
-                                volume: Some(live_data.volume),
-                            });
-                        }
-                    }
+            if dt >= from && dt <= to {
+                match serde_json::from_slice::<EquityLive>(&value) {
+                    Ok(live_data) => data_points.push(TimeSeriesPoint {
+                        timestamp: dt,
+                        value: live_data.price,
+                        volume: Some(live_data.volume),
+                        source: live_data.source,
+                    }),
+                    Err(e) => self.skip_undecodable(&key_str, &e),
                 }
             }
         }
@@ -196,49 +938,1002 @@ impl StockRepository for RocksDbStockRepository {
         Ok(data_points)
     }
 
-    async fn store_market_summary(
+    async fn get_intraday_data(
         &self,
-        summary: &MarketSummary,
-        timestamp: DateTime<Utc>,
-    ) -> Result<()> {
-        let key = Self::market_summary_key(&timestamp);
-        let value = serde_json::to_vec(summary)?;
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        self.get_historical_data_page(symbol, from, to, usize::MAX)
+            .await
+    }
 
-        self.db
-            .put(key.as_bytes(), &value)
-            .context("Failed to store market summary")?;
+    async fn get_historical_data_page(
+        &self,
+        symbol: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let prefix = format!("stock:{}:live:", symbol);
+        // Keys hold unpadded decimal second timestamps, so key order only matches time order
+        // among timestamps with the same number of digits. Every one from September 2001 on has
+        // ten, so from then the scan can seek straight to `from`; an earlier `from` starts at
+        // the symbol's first tick.
+        let start = if from.timestamp() >= TEN_DIGIT_TIMESTAMPS_FROM {
+            Self::live_data_key(symbol, &from)
+        } else {
+            prefix.clone()
+        };
+        // Archived ticks are older than any remaining live tick, so they come first
+        let mut data_points = self.get_archived_points(symbol, from, to, Some(limit))?;
+
+        for item in scan_prefix_from(&self.db, &prefix, &start) {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            let Some(dt) = Self::live_key_timestamp(&key_str) else {
+                continue;
+            };
+            if dt > to || data_points.len() >= limit {
+                break;
+            }
+
+            match serde_json::from_slice::<EquityLive>(&value) {
+                Ok(live_data) => data_points.push(TimeSeriesPoint {
+                    timestamp: dt,
+                    value: live_data.price,
+                    volume: Some(live_data.volume),
+                    source: live_data.source,
+                }),
+                Err(e) => self.skip_undecodable(&key_str, &e),
+            }
+        }
+
+        // A late backfill into an archived month can leave live ticks older than archived ones
+        data_points.sort_by_key(|dp| dp.timestamp);
+        data_points.truncate(limit);
+        Ok(data_points)
+    }
+
+    async fn store_market_summary(
+        &self,
+        summary: &MarketSummary,
+        timestamp: DateTime<Utc>,
+    ) -> Result<()> {
+        let key = Self::market_summary_key(&timestamp);
+        let value = serde_json::to_vec(summary)?;
+
+        self.put_indexed(
+            &key,
+            timestamp.timestamp(),
+            &value,
+            Self::LATEST_SUMMARY_KEY,
+        )
+        .context("Failed to store market summary")?;
 
         Ok(())
     }
 
     async fn get_latest_market_summary(&self) -> Result<Option<MarketSummary>> {
-        let prefix = "market:summary:";
-        let iter = self.db.prefix_iterator(prefix);
+        Ok(self
+            .get_latest("market:summary:", Self::LATEST_SUMMARY_KEY)?
+            .map(|(summary, _)| summary))
+    }
 
-        let mut latest_timestamp = 0i64;
-        let mut latest_summary = None;
+    async fn get_index_history(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<TimeSeriesPoint>> {
+        let start = Self::market_summary_key(&from);
+        let mut data_points = Vec::new();
 
-        for item in iter {
+        for item in scan_prefix_from(&self.db, "market:summary:", &start) {
             let (key, value) = item?;
             let key_str = String::from_utf8_lossy(&key);
 
-            // Extract timestamp from key
-            if let Some(timestamp_str) = key_str.split(':').last() {
-                if let Ok(timestamp) = timestamp_str.parse::<i64>() {
-                    if timestamp > latest_timestamp {
-                        latest_timestamp = timestamp;
-                        match serde_json::from_slice::<MarketSummary>(&value) {
-                            Ok(summary) => latest_summary = Some(summary),
-                            Err(e) => {
-                                tracing::warn!("Failed to deserialize market summary: {}", e);
-                                // Continue to next entry
-                            }
-                        }
-                    }
+            let Some(dt) = key_str
+                .rsplit(':')
+                .next()
+                .and_then(|ts| ts.parse::<i64>().ok())
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            else {
+                continue;
+            };
+            if dt > to {
+                break;
+            }
+
+            let summary: MarketSummary = match serde_json::from_slice(&value) {
+                Ok(summary) => summary,
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize market summary: {}", e);
+                    continue;
+                }
+            };
+            // Summaries stored before the index was introduced have no level
+            if summary.index_level > 0.0 {
+                data_points.push(TimeSeriesPoint {
+                    timestamp: dt,
+                    value: summary.index_level,
+                    volume: Some(summary.total_volume),
+                    source: DataSource::Scraped,
+                });
+            }
+        }
+
+        Ok(data_points)
+    }
+
+    async fn get_market_summaries(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, MarketSummary)>> {
+        let start = Self::market_summary_key(&from);
+        let mut summaries = Vec::new();
+
+        for item in scan_prefix_from(&self.db, "market:summary:", &start) {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            let Some(dt) = key_str
+                .rsplit(':')
+                .next()
+                .and_then(|ts| ts.parse::<i64>().ok())
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+            else {
+                continue;
+            };
+            if dt > to {
+                break;
+            }
+
+            match serde_json::from_slice(&value) {
+                Ok(summary) => summaries.push((dt, summary)),
+                Err(e) => tracing::warn!("Failed to deserialize market summary: {}", e),
+            }
+        }
+
+        Ok(summaries)
+    }
+
+    async fn get_market_summary_nearest(
+        &self,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<MarketSummary>> {
+        let target = timestamp.timestamp();
+        let mut nearest: Option<(i64, Box<[u8]>)> = None;
+
+        for item in scan_prefix(&self.db, "market:summary:") {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            if let Some(Ok(ts)) = key_str.rsplit(':').next().map(|s| s.parse::<i64>()) {
+                let distance = (ts - target).abs();
+                if nearest.as_ref().is_none_or(|(best, _)| distance < *best) {
+                    nearest = Some((distance, value));
+                }
+            }
+        }
+
+        match nearest {
+            Some((_, value)) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store_symbol_metrics(&self, metrics: &SymbolMetrics) -> Result<()> {
+        let key = Self::symbol_metrics_key(&metrics.symbol);
+        let value = serde_json::to_vec(metrics)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store symbol metrics")?;
+
+        Ok(())
+    }
+
+    async fn get_symbol_metrics(&self, symbol: &str) -> Result<Option<SymbolMetrics>> {
+        let key = Self::symbol_metrics_key(symbol);
+
+        match self.db.get(key.as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store_market_event(&self, event: &MarketEvent) -> Result<()> {
+        let key = Self::market_event_key(&event.timestamp);
+        let value = serde_json::to_vec(event)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store market event")?;
+
+        Ok(())
+    }
+
+    async fn get_market_events(&self) -> Result<Vec<MarketEvent>> {
+        let mut events = Vec::new();
+
+        for item in scan_prefix(&self.db, "market:event:") {
+            let (_, value) = item?;
+            match serde_json::from_slice::<MarketEvent>(&value) {
+                Ok(event) => events.push(event),
+                Err(e) => tracing::warn!("Failed to deserialize market event: {}", e),
+            }
+        }
+
+        events.sort_by_key(|event| std::cmp::Reverse(event.timestamp));
+        Ok(events)
+    }
+
+    async fn store_scrape_cycle(&self, cycle: &ScrapeCycle) -> Result<()> {
+        let key = Self::scrape_cycle_key(&cycle.completed_at);
+        let value = serde_json::to_vec(cycle)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store scrape cycle")?;
+
+        Ok(())
+    }
+
+    async fn get_scrape_cycles(&self, limit: usize) -> Result<Vec<ScrapeCycle>> {
+        let mut cycles = Vec::new();
+
+        for item in scan_prefix(&self.db, "worker:cycle:") {
+            let (_, value) = item?;
+            match serde_json::from_slice::<ScrapeCycle>(&value) {
+                Ok(cycle) => cycles.push(cycle),
+                Err(e) => tracing::warn!("Failed to deserialize scrape cycle: {}", e),
+            }
+        }
+
+        cycles.sort_by_key(|cycle| std::cmp::Reverse(cycle.completed_at));
+        cycles.truncate(limit);
+        Ok(cycles)
+    }
+
+    async fn store_recent_symbols(&self, symbols: &[String]) -> Result<()> {
+        let value = serde_json::to_vec(symbols)?;
+
+        self.db
+            .put(Self::RECENT_SYMBOLS_KEY.as_bytes(), &value)
+            .context("Failed to store recently requested symbols")?;
+
+        Ok(())
+    }
+
+    async fn get_recent_symbols(&self) -> Result<Vec<String>> {
+        match self.db.get(Self::RECENT_SYMBOLS_KEY.as_bytes())? {
+            Some(value) => Ok(serde_json::from_slice(&value)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn store_service_state(&self, name: &str, state: &serde_json::Value) -> Result<()> {
+        let value = serde_json::to_vec(state)?;
+
+        self.db
+            .put(Self::service_state_key(name).as_bytes(), &value)
+            .with_context(|| format!("Failed to store {} state", name))?;
+
+        Ok(())
+    }
+
+    async fn get_service_state(&self, name: &str) -> Result<Option<serde_json::Value>> {
+        match self.db.get(Self::service_state_key(name).as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store_bond(&self, bond: &Bond) -> Result<()> {
+        let key = Self::bond_key(&bond.code);
+        let value = serde_json::to_vec(bond)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store bond")?;
+
+        Ok(())
+    }
+
+    async fn get_bonds(&self) -> Result<Vec<Bond>> {
+        let mut bonds = Vec::new();
+
+        for item in scan_prefix(&self.db, "bond:") {
+            let (_, value) = item?;
+            match serde_json::from_slice::<Bond>(&value) {
+                Ok(bond) => bonds.push(bond),
+                Err(e) => tracing::warn!("Failed to deserialize bond: {}", e),
+            }
+        }
+
+        Ok(bonds)
+    }
+
+    async fn store_split(&self, split: &StockSplit) -> Result<()> {
+        let key = Self::split_key(&split.symbol, split.ex_date);
+        let value = serde_json::to_vec(split)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store split")?;
+
+        Ok(())
+    }
+
+    async fn get_splits(&self, symbol: &str) -> Result<Vec<StockSplit>> {
+        let prefix = format!("split:{}:", symbol);
+        let mut splits = Vec::new();
+
+        for item in scan_prefix(&self.db, &prefix) {
+            let (_, value) = item?;
+            match serde_json::from_slice::<StockSplit>(&value) {
+                Ok(split) => splits.push(split),
+                Err(e) => tracing::warn!("Failed to deserialize split: {}", e),
+            }
+        }
+
+        Ok(splits)
+    }
+
+    async fn store_announcement(&self, announcement: &Announcement) -> Result<()> {
+        let key = Self::announcement_key(announcement);
+        let value = serde_json::to_vec(announcement)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store announcement")?;
+
+        Ok(())
+    }
+
+    async fn get_announcements(
+        &self,
+        symbol: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<Announcement>> {
+        let start = format!("announcement:{}", from.format("%Y-%m-%d"));
+        let mut announcements = Vec::new();
+
+        for item in scan_prefix_from(&self.db, "announcement:", &start) {
+            let (_, value) = item?;
+            let announcement: Announcement = match serde_json::from_slice(&value) {
+                Ok(announcement) => announcement,
+                Err(e) => {
+                    tracing::warn!("Failed to deserialize announcement: {}", e);
+                    continue;
+                }
+            };
+            if announcement.date > to {
+                break;
+            }
+            if symbol.is_none_or(|symbol| announcement.symbol == symbol) {
+                announcements.push(announcement);
+            }
+        }
+
+        Ok(announcements)
+    }
+
+    async fn store_delivery(&self, delivery: &WebhookDelivery) -> Result<()> {
+        let key = Self::delivery_key(&delivery.id);
+        let value = serde_json::to_vec(delivery)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store webhook delivery")?;
+
+        Ok(())
+    }
+
+    async fn get_deliveries(&self) -> Result<Vec<WebhookDelivery>> {
+        let mut deliveries = Vec::new();
+
+        for item in scan_prefix(&self.db, "delivery:") {
+            let (_, value) = item?;
+            match serde_json::from_slice::<WebhookDelivery>(&value) {
+                Ok(delivery) => deliveries.push(delivery),
+                Err(e) => tracing::warn!("Failed to deserialize webhook delivery: {}", e),
+            }
+        }
+
+        deliveries.sort_by_key(|delivery| delivery.created_at);
+        Ok(deliveries)
+    }
+
+    async fn delete_delivery(&self, id: &str) -> Result<()> {
+        let key = Self::delivery_key(id);
+        self.db
+            .delete(key.as_bytes())
+            .context("Failed to delete webhook delivery")?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl DataExporter for RocksDbStockRepository {
+    async fn export_jsonl(&self, path: &std::path::Path) -> Result<usize> {
+        use std::io::Write;
+
+        let file = std::fs::File::create(path).context("Failed to create export file")?;
+        let mut writer = std::io::BufWriter::new(file);
+        let mut records = 0;
+
+        // An empty prefix covers every key, including portfolios and metadata
+        for item in scan_prefix(&self.db, "") {
+            let (key, value) = item?;
+            let key = String::from_utf8_lossy(&key);
+
+            // Most values are JSON; binary ones (e.g. last-update timestamps) are written as hex
+            let line = match serde_json::from_slice::<serde_json::Value>(&value) {
+                Ok(value) => serde_json::json!({ "key": key, "value": value }),
+                Err(_) => {
+                    let hex: String = value.iter().map(|byte| format!("{:02x}", byte)).collect();
+                    serde_json::json!({ "key": key, "value_hex": hex })
                 }
+            };
+
+            serde_json::to_writer(&mut writer, &line)?;
+            writer.write_all(b"\n")?;
+            records += 1;
+        }
+
+        writer.flush().context("Failed to write export file")?;
+        Ok(records)
+    }
+}
+
+#[async_trait::async_trait]
+impl DataPruner for RocksDbStockRepository {
+    async fn prune_older_than(&self, cutoff: DateTime<Utc>) -> Result<PruneSummary> {
+        let cutoff = cutoff.timestamp();
+        let mut summary = PruneSummary::default();
+
+        for symbol in self.get_all_symbols_from_db()? {
+            let mut batch = WriteBatch::default();
+            let deleted = self.prune_symbol_records(&symbol, "live", cutoff, &mut batch)?
+                + self.prune_symbol_records(&symbol, "detail", cutoff, &mut batch)?;
+            if deleted == 0 {
+                continue;
+            }
+
+            self.db
+                .write(batch)
+                .with_context(|| format!("Failed to prune records of {}", symbol))?;
+            if self.compact_after_prune {
+                // `;` sorts right after `:`, so this range covers exactly the symbol's records
+                self.db.compact_range(
+                    Some(format!("stock:{}:", symbol)),
+                    Some(format!("stock:{};", symbol)),
+                );
             }
+            summary.symbols += 1;
+            summary.records_deleted += deleted;
         }
 
-        Ok(latest_summary)
+        Ok(summary)
+    }
+}
+
+#[async_trait::async_trait]
+impl DataArchiver for RocksDbStockRepository {
+    async fn archive_before(&self, cutoff: DateTime<Utc>) -> Result<ArchiveSummary> {
+        // Only whole months are archived, so stop at the start of the cutoff's month
+        let Some(boundary) = cutoff
+            .date_naive()
+            .with_day(1)
+            .and_then(|month| month.and_hms_opt(0, 0, 0))
+            .map(|month| month.and_utc())
+        else {
+            anyhow::bail!("Could not determine the archive boundary");
+        };
+
+        let mut summary = ArchiveSummary::default();
+        for symbol in self.get_all_symbols_from_db()? {
+            self.archive_symbol_before(&symbol, boundary, &mut summary)?;
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AnnouncementCategory;
+    use crate::infrastructure::test_support::{equity, TempDb};
+    use crate::infrastructure::PrometheusMetrics;
+    use chrono::TimeZone;
+
+    fn live(price: f64) -> EquityLive {
+        EquityLive {
+            change: 0.0,
+            name: "TEST".to_string(),
+            price,
+            volume: 100,
+            source: DataSource::Scraped,
+        }
+    }
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, 12, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn merging_symbol_casings_leaves_one_canonical_symbol() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        for (symbol, price, timestamp) in [
+            ("mtngh", 1.0, at(2024, 1, 5)),
+            ("MTNGH", 1.1, at(2024, 1, 10)),
+            ("mtngh", 1.2, at(2024, 1, 20)),
+            ("MTNGH", 1.3, at(2024, 3, 1)),
+            ("mtngh", 1.4, at(2024, 3, 10)),
+        ] {
+            repository
+                .store_live_data(symbol, &live(price), timestamp)
+                .await
+                .unwrap();
+        }
+        repository.archive_before(at(2024, 2, 15)).await.unwrap();
+        repository
+            .store_split(&StockSplit {
+                symbol: "mtngh".to_string(),
+                ex_date: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                ratio: 2.0,
+                recorded_at: at(2024, 1, 25),
+            })
+            .await
+            .unwrap();
+        repository
+            .store_announcement(&Announcement {
+                id: "agm".to_string(),
+                symbol: "mtngh".to_string(),
+                date: NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                title: "AGM".to_string(),
+                body: String::new(),
+                category: AnnouncementCategory::Agm,
+            })
+            .await
+            .unwrap();
+        // Point the canonical latest pointer at its own newest record before merging
+        repository.get_latest_live_data("MTNGH").await.unwrap();
+
+        let merge = repository.merge_symbol_casings().unwrap();
+
+        assert_eq!(merge.symbols.into_iter().collect::<Vec<_>>(), vec!["MTNGH"]);
+        assert_eq!(repository.get_all_symbols().await.unwrap(), vec!["MTNGH"]);
+        let latest = repository
+            .get_latest_live_data("MTNGH")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.price, 1.4);
+        let january = repository
+            .get_historical_data("MTNGH", at(2024, 1, 1), at(2024, 1, 31))
+            .await
+            .unwrap();
+        assert_eq!(
+            january.iter().map(|point| point.value).collect::<Vec<_>>(),
+            vec![1.0, 1.1, 1.2]
+        );
+        let splits = repository.get_splits("MTNGH").await.unwrap();
+        assert_eq!(splits.len(), 1);
+        assert_eq!(splits[0].symbol, "MTNGH");
+        assert!(repository.get_splits("mtngh").await.unwrap().is_empty());
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let announcements = repository
+            .get_announcements(Some("MTNGH"), from, to)
+            .await
+            .unwrap();
+        assert_eq!(announcements.len(), 1);
+        assert!(repository
+            .get_announcements(Some("mtngh"), from, to)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn archived_months_are_still_read_as_history() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        repository
+            .store_live_data("MTNGH", &live(1.0), at(2024, 1, 5))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &live(1.1), at(2024, 1, 20))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &live(1.2), at(2024, 3, 1))
+            .await
+            .unwrap();
+
+        let summary = repository.archive_before(at(2024, 2, 15)).await.unwrap();
+
+        assert_eq!(summary.records_archived, 2);
+        assert_eq!(summary.months, 1);
+        let january = repository
+            .get_historical_data("MTNGH", at(2024, 1, 1), at(2024, 1, 31))
+            .await
+            .unwrap();
+        let prices: Vec<f64> = january.iter().map(|point| point.value).collect();
+        assert_eq!(prices, vec![1.0, 1.1]);
+        assert_eq!(
+            repository
+                .get_latest_live_data("MTNGH")
+                .await
+                .unwrap()
+                .unwrap()
+                .price,
+            1.2
+        );
+    }
+
+    #[tokio::test]
+    async fn a_history_page_stops_reading_archives_once_it_is_full() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        for (price, day) in [
+            (1.0, at(2024, 1, 5)),
+            (1.1, at(2024, 1, 20)),
+            (1.2, at(2024, 2, 3)),
+        ] {
+            repository
+                .store_live_data("MTNGH", &live(price), day)
+                .await
+                .unwrap();
+        }
+        repository
+            .store_live_data("MTNGH", &live(1.3), at(2024, 4, 1))
+            .await
+            .unwrap();
+        repository.archive_before(at(2024, 3, 1)).await.unwrap();
+        let archived = repository
+            .get_archived_points("MTNGH", at(2024, 1, 1), at(2024, 4, 30), Some(1))
+            .unwrap();
+        let page = repository
+            .get_historical_data_page("MTNGH", at(2024, 1, 1), at(2024, 4, 30), 2)
+            .await
+            .unwrap();
+
+        let prices: Vec<f64> = archived.iter().map(|point| point.value).collect();
+        assert_eq!(prices, vec![1.0]);
+        let prices: Vec<f64> = page.iter().map(|point| point.value).collect();
+        assert_eq!(prices, vec![1.0, 1.1]);
+    }
+
+    #[tokio::test]
+    async fn archiving_keeps_the_newest_tick_of_a_symbol_gone_quiet() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        repository
+            .store_live_data("ACCESS", &live(5.0), at(2024, 1, 5))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("ACCESS", &live(5.5), at(2024, 1, 20))
+            .await
+            .unwrap();
+
+        let summary = repository.archive_before(at(2024, 6, 1)).await.unwrap();
+
+        assert_eq!(summary.records_archived, 1);
+        assert_eq!(
+            repository
+                .get_latest_live_data("ACCESS")
+                .await
+                .unwrap()
+                .unwrap()
+                .price,
+            5.5
+        );
+        assert_eq!(repository.get_all_symbols().await.unwrap(), vec!["ACCESS"]);
+        let history = repository
+            .get_historical_data("ACCESS", at(2024, 1, 1), at(2024, 1, 31))
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn pruning_deletes_only_records_before_the_cutoff_and_keeps_each_newest() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        let cutoff = at(2024, 3, 1);
+        for (price, timestamp) in [
+            (1.0, cutoff - chrono::Duration::seconds(1)),
+            (1.1, cutoff),
+            (1.2, cutoff + chrono::Duration::seconds(1)),
+        ] {
+            repository
+                .store_live_data("MTNGH", &live(price), timestamp)
+                .await
+                .unwrap();
+        }
+        // A symbol gone quiet, with only a point from before the cutoff
+        repository
+            .store_live_data("GCB", &live(4.0), at(2024, 1, 5))
+            .await
+            .unwrap();
+
+        let summary = repository.prune_older_than(cutoff).await.unwrap();
+
+        assert_eq!(summary.records_deleted, 1);
+        assert_eq!(summary.symbols, 1);
+        let mtngh = repository
+            .get_historical_data("MTNGH", at(2024, 2, 1), at(2024, 3, 31))
+            .await
+            .unwrap();
+        assert_eq!(
+            mtngh.iter().map(|point| point.value).collect::<Vec<_>>(),
+            vec![1.1, 1.2]
+        );
+        let gcb = repository
+            .get_historical_data("GCB", at(2024, 1, 1), at(2024, 1, 31))
+            .await
+            .unwrap();
+        assert_eq!(gcb.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn identical_consecutive_writes_are_stored_once() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        let first = at(2024, 3, 6);
+        let second = first + chrono::Duration::minutes(5);
+        let mut repeated = live(1.5);
+        // Float noise from the upstream's decimal strings doesn't count as a change
+        repeated.price += 1e-12;
+
+        repository
+            .store_live_data("MTNGH", &live(1.5), first)
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &repeated, second)
+            .await
+            .unwrap();
+
+        let keys = scan_prefix(&repository.db, "stock:MTNGH:live:").count();
+        assert_eq!(keys, 1);
+        let last_updates = repository.get_last_updates().await.unwrap();
+        assert_eq!(last_updates["MTNGH"], second);
+    }
+
+    #[tokio::test]
+    async fn an_older_write_arriving_late_does_not_move_the_latest_back() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        repository
+            .store_live_data("MTNGH", &live(2.0), at(2024, 3, 2))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &live(1.0), at(2024, 3, 1))
+            .await
+            .unwrap();
+
+        let latest = repository.get_latest_live_data("MTNGH").await.unwrap();
+
+        assert_eq!(latest.unwrap().price, 2.0);
+        assert_eq!(
+            repository.get_last_updates().await.unwrap().get("MTNGH"),
+            Some(&at(2024, 3, 2))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_out_of_order_writes_leave_the_newest_as_latest() {
+        let temp = TempDb::new();
+        let repository = Arc::new(RocksDbStockRepository::new(temp.db.clone()));
+        let days: Vec<u32> = (1..=28).rev().chain(1..=28).collect();
+
+        let writes: Vec<_> = days
+            .into_iter()
+            .map(|day| {
+                let repository = repository.clone();
+                tokio::spawn(async move {
+                    repository
+                        .store_equity_data("GCB", &equity("GCB", day as f64), at(2024, 2, day))
+                        .await
+                })
+            })
+            .collect();
+        for write in writes {
+            write.await.unwrap().unwrap();
+        }
+
+        let (latest, stored_at) = repository
+            .get_latest_equity_data_with_timestamp("GCB")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(latest.price, 28.0);
+        assert_eq!(stored_at, at(2024, 2, 28));
+    }
+
+    #[tokio::test]
+    async fn a_history_page_from_before_ten_digit_timestamps_still_finds_ticks() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        repository
+            .store_live_data("MTNGH", &live(1.0), at(2024, 3, 1))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &live(1.1), at(2024, 3, 2))
+            .await
+            .unwrap();
+
+        let page = repository
+            .get_historical_data_page("MTNGH", at(1990, 1, 1), at(2024, 3, 31), 10)
+            .await
+            .unwrap();
+        let from_march = repository
+            .get_historical_data_page("MTNGH", at(2024, 3, 2), at(2024, 3, 31), 10)
+            .await
+            .unwrap();
+
+        assert_eq!(page.len(), 2);
+        assert_eq!(from_march.len(), 1);
+        assert_eq!(from_march[0].value, 1.1);
+    }
+
+    #[tokio::test]
+    async fn latest_live_data_from_a_source_passes_over_newer_records_from_others() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        let synthetic = EquityLive {
+            source: DataSource::Synthetic,
+            ..live(9.9)
+        };
+        repository
+            .store_live_data("MTNGH", &live(1.5), at(2024, 3, 1))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &synthetic, at(2024, 3, 2))
+            .await
+            .unwrap();
+
+        let scraped = repository
+            .get_latest_live_data_from("MTNGH", DataSource::Scraped)
+            .await
+            .unwrap()
+            .unwrap();
+        let newest_synthetic = repository
+            .get_latest_live_data_from("MTNGH", DataSource::Synthetic)
+            .await
+            .unwrap()
+            .unwrap();
+        let backfill = repository
+            .get_latest_live_data_from("MTNGH", DataSource::Backfill)
+            .await
+            .unwrap();
+
+        assert_eq!(scraped.price, 1.5);
+        assert_eq!(newest_synthetic.price, 9.9);
+        assert!(backfill.is_none());
+    }
+
+    #[tokio::test]
+    async fn a_symbol_dump_holds_every_record_type_of_that_symbol_only() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        repository
+            .store_live_data("MTNGH", &live(1.0), at(2024, 1, 5))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGH", &live(1.1), at(2024, 1, 6))
+            .await
+            .unwrap();
+        repository
+            .store_equity_data("MTNGH", &equity("MTNGH", 1.1), at(2024, 1, 6))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("MTNGHX", &live(9.0), at(2024, 1, 6))
+            .await
+            .unwrap();
+
+        let records = repository.get_symbol_records("MTNGH").await.unwrap();
+
+        let kinds: Vec<(&str, Option<DateTime<Utc>>)> = records
+            .iter()
+            .map(|record| (record.record_type.as_str(), record.timestamp))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("detail", Some(at(2024, 1, 6))),
+                ("live", Some(at(2024, 1, 5))),
+                ("live", Some(at(2024, 1, 6))),
+            ]
+        );
+        assert_eq!(records[0].value["company"]["name"], "MTNGH Ltd");
+        assert_eq!(records[2].value["price"], 1.1);
+    }
+
+    #[tokio::test]
+    async fn history_leaves_out_records_whose_key_timestamp_is_unreadable() {
+        let temp = TempDb::new();
+        let repository = RocksDbStockRepository::new(temp.db.clone());
+        repository
+            .store_live_data("TEST", &live(1.5), at(2024, 3, 10))
+            .await
+            .unwrap();
+        let value = serde_json::to_vec(&live(99.0)).unwrap();
+        for key in [
+            "stock:TEST:live:garbage",
+            "stock:TEST:live:99999999999999999",
+        ] {
+            repository.db.put(key.as_bytes(), &value).unwrap();
+        }
+
+        let history = repository
+            .get_historical_data("TEST", at(2024, 3, 1), at(2024, 3, 31))
+            .await
+            .unwrap();
+        let page = repository
+            .get_historical_data_page("TEST", at(2024, 3, 1), at(2024, 3, 31), 10)
+            .await
+            .unwrap();
+
+        let points: Vec<(DateTime<Utc>, f64)> = history
+            .iter()
+            .map(|point| (point.timestamp, point.value))
+            .collect();
+        assert_eq!(points, [(at(2024, 3, 10), 1.5)]);
+        let page_points: Vec<(DateTime<Utc>, f64)> = page
+            .iter()
+            .map(|point| (point.timestamp, point.value))
+            .collect();
+        assert_eq!(page_points, points);
+    }
+
+    #[tokio::test]
+    async fn undecodable_latest_records_are_skipped_and_counted() {
+        let temp = TempDb::new();
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let repository = RocksDbStockRepository::new(temp.db.clone()).with_metrics(metrics.clone());
+        repository
+            .store_live_data("TEST", &live(1.5), at(2024, 3, 10))
+            .await
+            .unwrap();
+        repository
+            .store_live_data("TEST", &live(1.6), at(2024, 3, 11))
+            .await
+            .unwrap();
+        let newest = RocksDbStockRepository::live_data_key("TEST", &at(2024, 3, 11));
+        repository
+            .db
+            .put(newest.as_bytes(), br#"{"price":"not a number"}"#)
+            .unwrap();
+        repository
+            .store_equity_data("GCB", &equity("GCB", 5.0), at(2024, 3, 10))
+            .await
+            .unwrap();
+
+        let latest = repository.get_latest_live_data("TEST").await.unwrap();
+        let equity = repository.get_latest_equity_data("GCB").await.unwrap();
+
+        assert_eq!(latest.map(|data| data.price), Some(1.5));
+        assert_eq!(equity.map(|equity| equity.price), Some(5.0));
+        assert!(metrics
+            .render()
+            .lines()
+            .any(|line| line == "gse_errors_total{type=\"undecodable_record\"} 1"));
     }
 }
@@ -0,0 +1,126 @@
+use rocksdb::{BlockBasedOptions, Cache, DBCompressionType, Options};
+
+/// Storage engine settings for the market data database
+#[derive(Debug, Clone)]
+pub struct RocksDbTuning {
+    /// Bytes of uncompressed blocks kept in memory. A larger cache serves repeated history and
+    /// listing reads without touching disk, at the cost of resident memory.
+    pub block_cache_bytes: usize,
+    /// Bytes buffered in a memtable before it is flushed to a level-0 file. Larger buffers mean
+    /// fewer, bigger files and less compaction work, but more memory and a longer replay after
+    /// a crash.
+    pub write_buffer_bytes: usize,
+    /// Number of level-0 files that starts a compaction into level 1. Every level-0 file may
+    /// have to be checked on a read, so a lower trigger cuts read amplification in exchange for
+    /// compacting more often.
+    pub level0_compaction_trigger: i32,
+    /// Compression of table files. Applies to files written from now on; existing files keep
+    /// theirs until compacted.
+    pub compression: DBCompressionType,
+    /// Compact the key ranges of pruned symbols after retention pruning, so the space held by
+    /// deleted records is reclaimed and reads stop skipping over their tombstones straight away
+    /// rather than whenever background compaction reaches them
+    pub compact_after_prune: bool,
+}
+
+impl Default for RocksDbTuning {
+    fn default() -> Self {
+        Self {
+            block_cache_bytes: 64 * 1024 * 1024,
+            write_buffer_bytes: 32 * 1024 * 1024,
+            level0_compaction_trigger: 4,
+            compression: DBCompressionType::Lz4,
+            compact_after_prune: false,
+        }
+    }
+}
+
+impl RocksDbTuning {
+    /// Options for opening the database with these settings, creating it if missing
+    pub fn options(&self) -> Options {
+        let mut table = BlockBasedOptions::default();
+        table.set_block_cache(&Cache::new_lru_cache(self.block_cache_bytes));
+
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.set_compression_type(self.compression);
+        options.set_write_buffer_size(self.write_buffer_bytes);
+        options.set_level_zero_file_num_compaction_trigger(self.level0_compaction_trigger);
+        options.set_block_based_table_factory(&table);
+        options
+    }
+}
+
+/// Compression type by name: `none`, `snappy`, `lz4`, `lz4hc`, `zlib`, `bz2` or `zstd`
+pub fn parse_compression(name: &str) -> Option<DBCompressionType> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "none" => Some(DBCompressionType::None),
+        "snappy" => Some(DBCompressionType::Snappy),
+        "lz4" => Some(DBCompressionType::Lz4),
+        "lz4hc" => Some(DBCompressionType::Lz4hc),
+        "zlib" => Some(DBCompressionType::Zlib),
+        "bz2" => Some(DBCompressionType::Bz2),
+        "zstd" => Some(DBCompressionType::Zstd),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DataPruner, DataSource, EquityLive, StockRepository};
+    use crate::infrastructure::test_support::TempDb;
+    use crate::infrastructure::RocksDbStockRepository;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn compression_names_parse_case_insensitively() {
+        assert!(matches!(
+            parse_compression(" ZSTD "),
+            Some(DBCompressionType::Zstd)
+        ));
+        assert!(matches!(
+            parse_compression("none"),
+            Some(DBCompressionType::None)
+        ));
+        assert!(parse_compression("gzip").is_none());
+    }
+
+    #[tokio::test]
+    async fn a_tuned_database_compacted_after_pruning_keeps_the_newer_records() {
+        let tuning = RocksDbTuning {
+            block_cache_bytes: 1024 * 1024,
+            write_buffer_bytes: 1024 * 1024,
+            level0_compaction_trigger: 2,
+            compression: DBCompressionType::Zstd,
+            compact_after_prune: true,
+        };
+        let temp = TempDb::with_options(&tuning.options());
+        let repository = RocksDbStockRepository::new(temp.db.clone())
+            .with_compaction_after_prune(tuning.compact_after_prune);
+        let at = |day| Utc.with_ymd_and_hms(2024, 3, day, 12, 0, 0).unwrap();
+        for (price, day) in [(1.0, 1), (1.1, 10)] {
+            let data = EquityLive {
+                change: 0.0,
+                name: "MTNGH".to_string(),
+                price,
+                volume: 100,
+                source: DataSource::Scraped,
+            };
+            repository
+                .store_live_data("MTNGH", &data, at(day))
+                .await
+                .unwrap();
+        }
+
+        let summary = repository.prune_older_than(at(5)).await.unwrap();
+
+        assert_eq!(summary.records_deleted, 1);
+        let history = repository
+            .get_historical_data("MTNGH", at(1), at(31))
+            .await
+            .unwrap();
+        let prices: Vec<f64> = history.iter().map(|point| point.value).collect();
+        assert_eq!(prices, [1.1]);
+    }
+}
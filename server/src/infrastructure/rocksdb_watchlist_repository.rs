@@ -0,0 +1,65 @@
+use crate::domain::{Watchlist, WatchlistRepository};
+use crate::infrastructure::db_scan::scan_prefix;
+use anyhow::{Context, Result};
+use rocksdb::DB;
+use std::sync::Arc;
+
+pub struct RocksDbWatchlistRepository {
+    db: Arc<DB>,
+}
+
+impl RocksDbWatchlistRepository {
+    pub fn new(db: Arc<DB>) -> Self {
+        Self { db }
+    }
+
+    fn watchlist_key(id: &str) -> String {
+        format!("watchlist:{}", id)
+    }
+}
+
+#[async_trait::async_trait]
+impl WatchlistRepository for RocksDbWatchlistRepository {
+    async fn create_watchlist(&self, watchlist: &Watchlist) -> Result<()> {
+        let key = Self::watchlist_key(&watchlist.id);
+        let value = serde_json::to_vec(watchlist)?;
+
+        self.db
+            .put(key.as_bytes(), &value)
+            .context("Failed to store watchlist")?;
+
+        Ok(())
+    }
+
+    async fn get_watchlist(&self, id: &str) -> Result<Option<Watchlist>> {
+        let key = Self::watchlist_key(id);
+
+        match self.db.get(key.as_bytes())? {
+            Some(value) => Ok(Some(serde_json::from_slice(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_all_watchlists(&self) -> Result<Vec<Watchlist>> {
+        let prefix = "watchlist:";
+        let mut watchlists = Vec::new();
+
+        for item in scan_prefix(&self.db, prefix) {
+            let (key, value) = item?;
+            let key_str = String::from_utf8_lossy(&key);
+
+            // Only watchlist:{id} holds a watchlist, leaving room for sub-keys like portfolios have
+            if !key_str[prefix.len()..].contains(':') {
+                if let Ok(watchlist) = serde_json::from_slice::<Watchlist>(&value) {
+                    watchlists.push(watchlist);
+                }
+            }
+        }
+
+        Ok(watchlists)
+    }
+
+    async fn update_watchlist(&self, watchlist: &Watchlist) -> Result<()> {
+        self.create_watchlist(watchlist).await
+    }
+}
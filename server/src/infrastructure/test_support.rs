@@ -0,0 +1,244 @@
+use crate::domain::{CircuitOpen, Company, Equity, EquityLive, EquitySummary, GseApiClient};
+use anyhow::Result;
+use rocksdb::{Options, DB};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// A database in a fresh temporary directory, deleted when dropped
+pub struct TempDb {
+    pub db: Arc<DB>,
+    path: PathBuf,
+}
+
+impl TempDb {
+    pub fn new() -> Self {
+        let path = temp_path("db");
+        let db = Arc::new(DB::open_default(&path).expect("failed to open temporary database"));
+        Self { db, path }
+    }
+
+    pub fn with_options(options: &Options) -> Self {
+        let path = temp_path("db");
+        let db = Arc::new(DB::open(options, &path).expect("failed to open temporary database"));
+        Self { db, path }
+    }
+}
+
+impl Drop for TempDb {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A path under the system temp directory that no other test uses
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gse-test-{}-{}", name, uuid::Uuid::new_v4()))
+}
+
+/// Upstream stand-in serving canned data and counting the requests made to it
+#[derive(Default)]
+pub struct MockGseApiClient {
+    pub live: Mutex<Vec<EquityLive>>,
+    pub failing: AtomicBool,
+    /// Reject requests as an open circuit breaker would
+    pub circuit_open: AtomicBool,
+    pub live_calls: AtomicUsize,
+    pub equity_calls: AtomicUsize,
+}
+
+impl MockGseApiClient {
+    pub fn with_live(live: Vec<EquityLive>) -> Self {
+        Self {
+            live: Mutex::new(live),
+            ..Self::default()
+        }
+    }
+
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+
+    pub fn set_circuit_open(&self, open: bool) {
+        self.circuit_open.store(open, Ordering::SeqCst);
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.circuit_open.load(Ordering::SeqCst) {
+            return Err(CircuitOpen.into());
+        }
+        if self.failing.load(Ordering::SeqCst) {
+            anyhow::bail!("upstream unavailable");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl GseApiClient for MockGseApiClient {
+    async fn fetch_all_live_data(&self) -> Result<Vec<EquityLive>> {
+        self.live_calls.fetch_add(1, Ordering::SeqCst);
+        self.check()?;
+        Ok(self.live.lock().unwrap().clone())
+    }
+
+    async fn fetch_all_equities(&self) -> Result<Vec<EquitySummary>> {
+        self.check()?;
+        Ok(self
+            .live
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|live| EquitySummary {
+                name: live.name.clone(),
+                price: live.price,
+            })
+            .collect())
+    }
+
+    async fn fetch_equity_data(&self, symbol: &str) -> Result<Equity> {
+        self.equity_calls.fetch_add(1, Ordering::SeqCst);
+        self.check()?;
+        let price = self
+            .live
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|live| live.name == symbol)
+            .map_or(1.0, |live| live.price);
+        Ok(equity(symbol, price))
+    }
+}
+
+/// Live data for `symbol` at `price`, with the given change and a volume of 1000
+pub fn live(symbol: &str, price: f64, change: f64) -> EquityLive {
+    EquityLive {
+        change,
+        name: symbol.to_string(),
+        price,
+        volume: 1000,
+        source: Default::default(),
+    }
+}
+
+/// Equity details for `symbol` at `price`, with no per-share figures
+pub fn equity(symbol: &str, price: f64) -> Equity {
+    Equity {
+        capital: None,
+        company: Company {
+            address: None,
+            directors: Vec::new(),
+            email: None,
+            facsimile: None,
+            industry: None,
+            name: format!("{} Ltd", symbol),
+            sector: None,
+            telephone: None,
+            website: None,
+        },
+        dps: None,
+        eps: None,
+        name: symbol.to_string(),
+        price,
+        shares: None,
+    }
+}
+
+/// Local HTTP server standing in for a webhook receiver: it answers every request with
+/// `200 OK` and hands the JSON body of each to the test
+pub struct MockWebhookServer {
+    pub url: String,
+    payloads: mpsc::UnboundedReceiver<serde_json::Value>,
+}
+
+impl MockWebhookServer {
+    pub async fn start() -> Self {
+        Self::answering(
+            "HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n".to_string(),
+        )
+        .await
+    }
+
+    /// A server answering every request with a redirect to `location`
+    pub async fn redirecting_to(location: &str) -> Self {
+        Self::answering(format!(
+            "HTTP/1.1 302 Found\r\nlocation: {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            location
+        ))
+        .await
+    }
+
+    async fn answering(response: String) -> Self {
+        let response = Arc::new(response);
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind mock webhook server");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, payloads) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let sender = sender.clone();
+                let response = response.clone();
+                tokio::spawn(async move {
+                    let Some(body) = read_request_body(&mut stream).await else {
+                        return;
+                    };
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = sender.send(serde_json::from_slice(&body).unwrap_or_default());
+                });
+            }
+        });
+
+        Self { url, payloads }
+    }
+
+    /// The next payload POSTed to the server, waiting up to five seconds for it
+    pub async fn next_payload(&mut self) -> serde_json::Value {
+        tokio::time::timeout(Duration::from_secs(5), self.payloads.recv())
+            .await
+            .expect("no webhook was delivered")
+            .expect("mock webhook server stopped")
+    }
+
+    /// Whether no payload has been POSTed to the server so far
+    pub fn received_nothing(&mut self) -> bool {
+        self.payloads.try_recv().is_err()
+    }
+}
+
+/// Read one HTTP request from the stream and return its body
+async fn read_request_body(stream: &mut TcpStream) -> Option<Vec<u8>> {
+    let mut request = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let mut body_range = None;
+
+    loop {
+        if let Some((start, length)) = body_range {
+            if request.len() >= start + length {
+                return Some(request[start..start + length].to_vec());
+            }
+        }
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        request.extend_from_slice(&chunk[..read]);
+
+        if body_range.is_none() {
+            if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                let headers = String::from_utf8_lossy(&request[..end]).to_ascii_lowercase();
+                let length = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .and_then(|value| value.trim().parse().ok())
+                    .unwrap_or(0);
+                body_range = Some((end + 4, length));
+            }
+        }
+    }
+}
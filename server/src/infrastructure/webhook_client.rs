@@ -0,0 +1,264 @@
+use crate::domain::{RejectedWebhookUrl, WebhookSender, WebhookTargetPolicy};
+use anyhow::{Context, Result};
+use reqwest::{redirect, Client, Url};
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+use tokio::time::sleep;
+
+/// HTTP client for delivering webhook payloads
+pub struct WebhookClientImpl {
+    max_retries: u32,
+    allow_private_targets: bool,
+}
+
+impl WebhookClientImpl {
+    /// A client that only delivers to publicly routable addresses
+    pub fn new() -> Self {
+        Self {
+            max_retries: 3,
+            allow_private_targets: false,
+        }
+    }
+
+    /// Also deliver to loopback, private and link-local addresses, for webhooks an operator
+    /// runs inside the deployment's own network
+    pub fn allowing_private_targets(mut self) -> Self {
+        self.allow_private_targets = true;
+        self
+    }
+
+    /// POST the payload to the addresses the host resolves to right now, which must pass the
+    /// address check. The connection is pinned to those addresses, so a DNS answer that changes
+    /// after the check can't redirect it, and redirects are not followed.
+    async fn post(&self, url: &str, payload: &serde_json::Value) -> Result<()> {
+        let (host, addresses) = resolve(url).await?;
+        if !self.allow_private_targets {
+            reject_non_public(&host, &addresses)?;
+        }
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .redirect(redirect::Policy::none())
+            .resolve_to_addrs(&host, &addresses)
+            .build()
+            .context("Failed to create HTTP client")?;
+        let response = client
+            .post(url)
+            .json(payload)
+            .send()
+            .await
+            .context("Failed to send webhook")?;
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .unwrap_or("an unknown location");
+            return Err(rejection(format!("redirects to {}", location)));
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Webhook delivery failed with status: {}", response.status());
+        }
+
+        Ok(())
+    }
+}
+
+fn rejection(reason: String) -> anyhow::Error {
+    anyhow::Error::new(RejectedWebhookUrl { reason })
+}
+
+/// Resolve an http(s) URL's host to the socket addresses it currently points at
+async fn resolve(url: &str) -> Result<(String, Vec<SocketAddr>)> {
+    let url = Url::parse(url).map_err(|e| rejection(format!("not a valid URL ({})", e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(rejection("must be an http or https URL".to_string()));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| rejection("has no host".to_string()))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    // IPv6 literals keep their brackets in the URL but can't be resolved with them
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| rejection(format!("host {} could not be resolved ({})", host, e)))?
+        .collect();
+    if addresses.is_empty() {
+        return Err(rejection(format!("host {} could not be resolved", host)));
+    }
+    Ok((host.to_string(), addresses))
+}
+
+fn reject_non_public(host: &str, addresses: &[SocketAddr]) -> Result<()> {
+    match addresses
+        .iter()
+        .find(|address| !is_public_address(address.ip()))
+    {
+        Some(address) => Err(rejection(format!(
+            "host {} resolves to the non-public address {}",
+            host,
+            address.ip()
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[async_trait::async_trait]
+impl WebhookSender for WebhookClientImpl {
+    /// Deliver a payload, retrying with exponential backoff
+    async fn send(&self, url: &str, payload: &serde_json::Value) -> Result<()> {
+        let mut retries = 0;
+        let mut delay = Duration::from_secs(1);
+
+        loop {
+            match self.post(url, payload).await {
+                Ok(()) => return Ok(()),
+                // A disallowed target stays disallowed, so there is nothing to retry
+                Err(e) if e.downcast_ref::<RejectedWebhookUrl>().is_some() => return Err(e),
+                Err(e) if retries >= self.max_retries => return Err(e),
+                Err(e) => {
+                    tracing::warn!("Webhook delivery failed (attempt {}): {}", retries + 1, e);
+                    sleep(delay).await;
+                    delay *= 2; // Exponential backoff
+                    retries += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Allows webhook URLs only when every address their host resolves to is publicly routable,
+/// keeping loopback, link-local (including cloud metadata endpoints), private and unspecified
+/// addresses out of reach
+pub struct PublicWebhookTargets;
+
+#[async_trait::async_trait]
+impl WebhookTargetPolicy for PublicWebhookTargets {
+    async fn check(&self, url: &str) -> Result<()> {
+        let (host, addresses) = resolve(url).await?;
+        reject_non_public(&host, &addresses)
+    }
+}
+
+/// Whether an address is reachable on the public internet rather than only from inside the
+/// deployment's own host or network
+pub fn is_public_address(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            let shared = a == 100 && (64..128).contains(&b); // Carrier-grade NAT, 100.64.0.0/10
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_multicast()
+                || shared
+                || a == 0)
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            let unique_local = first & 0xfe00 == 0xfc00; // fc00::/7
+            let link_local = first & 0xffc0 == 0xfe80; // fe80::/10
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || unique_local
+                || link_local)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::MockWebhookServer;
+
+    async fn rejection(url: &str) -> String {
+        let error = PublicWebhookTargets.check(url).await.unwrap_err();
+        error
+            .downcast_ref::<RejectedWebhookUrl>()
+            .expect("rejection should be a RejectedWebhookUrl")
+            .reason
+            .clone()
+    }
+
+    #[tokio::test]
+    async fn internal_hosts_are_rejected() {
+        for url in [
+            "http://169.254.169.254/latest/meta-data/",
+            "http://localhost:8080/hook",
+            "http://127.0.0.1/hook",
+            "http://10.0.0.5/hook",
+            "http://192.168.1.1/hook",
+            "http://172.16.0.1/hook",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[fd00::1]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+        ] {
+            assert!(
+                rejection(url).await.contains("non-public address"),
+                "{} should be rejected",
+                url
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn non_http_urls_are_rejected() {
+        assert_eq!(
+            rejection("file:///etc/passwd").await,
+            "must be an http or https URL"
+        );
+        assert!(rejection("not a url").await.starts_with("not a valid URL"));
+    }
+
+    #[tokio::test]
+    async fn public_addresses_are_allowed() {
+        PublicWebhookTargets
+            .check("https://93.184.216.34/hook")
+            .await
+            .unwrap();
+        PublicWebhookTargets
+            .check("http://[2606:4700::1111]:8443/hook")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_redirect_to_an_internal_address_is_not_followed() {
+        let mut internal = MockWebhookServer::start().await;
+        // Stands in for a public host; private targets are allowed only so it can run locally
+        let redirecting = MockWebhookServer::redirecting_to(&internal.url).await;
+        let client = WebhookClientImpl::new().allowing_private_targets();
+
+        let error = client
+            .send(&redirecting.url, &serde_json::json!({ "symbol": "MTNGH" }))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("redirects to"));
+        assert!(internal.received_nothing());
+    }
+
+    #[tokio::test]
+    async fn deliveries_to_internal_addresses_are_rejected_at_send_time() {
+        let mut internal = MockWebhookServer::start().await;
+        let url = internal.url.replace("127.0.0.1", "localhost");
+
+        let error = WebhookClientImpl::new()
+            .send(&url, &serde_json::json!({ "symbol": "MTNGH" }))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("non-public address"));
+        assert!(internal.received_nothing());
+    }
+}
@@ -1,8 +1,27 @@
-use crate::application::worker::{DataScrapingWorker, WorkerConfig};
-use crate::application::{FetchStockDataUseCase, GetStockDataUseCase};
-use crate::infrastructure::{GseApiClientImpl, RocksDbStockRepository};
-use crate::presentation::create_router;
-use anyhow::Result;
+use crate::application::worker::{DataScrapingWorker, WorkerConfig, WorkerDependencies};
+use crate::application::{
+    ArchiveConfig, ArchiveScheduler, DeliveryConfig, DeliveryQueue, ExportConfig, ExportScheduler,
+    FetchConfig, FetchStockDataUseCase, GetStockDataUseCase, PriceFilter, QueryConfig,
+    RecentlyRequested, ResponseCache, WorkerStatus,
+};
+use crate::domain::StockRepository;
+use crate::infrastructure::{
+    CircuitBreakerConfig, GseApiClientImpl, RateLimitConfig, RocksDbBackups,
+    RocksDbStockRepository, RocksDbTuning, WebhookClientImpl,
+};
+use crate::presentation::auth::AdminApiKey;
+use crate::presentation::client_rate_limit::{
+    limit_client_rate, ClientRateLimitConfig, ClientRateLimiter,
+};
+use crate::presentation::latency::LatencyHistogram;
+use crate::presentation::request_id::{propagate_request_id, REQUEST_ID_HEADER};
+use crate::presentation::runtime_config::{
+    ClientSettings, FeatureFlags, QuerySettings, RetentionSettings, RuntimeConfig, WorkerSettings,
+};
+use crate::presentation::{create_router, RouterServices};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::signal;
 use tower_http::{
@@ -16,63 +35,416 @@ mod domain;
 mod infrastructure;
 mod presentation;
 
+const DB_PATH: &str = "./data/gse.db";
+
+#[derive(Parser)]
+#[command(name = "gse-backend", about = "GSE market data service")]
+struct Cli {
+    /// Upgrade stored records to the current schema version before running the command
+    #[arg(long, global = true)]
+    migrate: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the API server and scraping worker (the default)
+    Serve,
+    /// Import a symbol's historical points from a `timestamp,price,volume` CSV file
+    Backfill {
+        #[arg(long)]
+        symbol: String,
+        #[arg(long)]
+        file: PathBuf,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     // Initialize logging
     tracing_subscriber::fmt().with_max_level(Level::INFO).init();
 
     info!("Starting GSE Backend Service");
 
+    // Metrics registry shared by the repository, the upstream client, the worker and the API
+    let metrics: Arc<dyn crate::domain::MetricsRecorder + Send + Sync> =
+        Arc::new(crate::infrastructure::PrometheusMetrics::new());
+
+    // Apply a restore staged through the admin API before the database is opened
+    if let Some(backup) = crate::infrastructure::apply_pending_restore(Path::new(DB_PATH))? {
+        info!("Restored database from backup {}", backup.display());
+    }
+
     // Initialize database
-    let db = Arc::new(rocksdb::DB::open_default("./data/gse.db")?);
-    let repository = Arc::new(RocksDbStockRepository::new(db.clone()));
+    let default_tuning = RocksDbTuning::default();
+    let tuning = RocksDbTuning {
+        block_cache_bytes: std::env::var("ROCKSDB_BLOCK_CACHE_MB")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .map(|mb| mb * 1024 * 1024)
+            .unwrap_or(default_tuning.block_cache_bytes),
+        write_buffer_bytes: std::env::var("ROCKSDB_WRITE_BUFFER_MB")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|mb| *mb > 0)
+            .map(|mb| mb * 1024 * 1024)
+            .unwrap_or(default_tuning.write_buffer_bytes),
+        level0_compaction_trigger: std::env::var("ROCKSDB_L0_COMPACTION_TRIGGER")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|trigger: &i32| *trigger > 0)
+            .unwrap_or(default_tuning.level0_compaction_trigger),
+        compression: std::env::var("ROCKSDB_COMPRESSION")
+            .ok()
+            .and_then(|s| crate::infrastructure::parse_compression(&s))
+            .unwrap_or(default_tuning.compression),
+        compact_after_prune: std::env::var("ROCKSDB_COMPACT_AFTER_PRUNE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default_tuning.compact_after_prune),
+    };
+    let db = Arc::new(rocksdb::DB::open(&tuning.options(), DB_PATH)?);
+    let repository = Arc::new(
+        RocksDbStockRepository::new(db.clone())
+            .with_metrics(metrics.clone())
+            .with_compaction_after_prune(tuning.compact_after_prune),
+    );
     info!("Database initialized");
 
+    if cli.migrate {
+        let migration = repository.migrate_records()?;
+        info!(
+            "Migrated stored records to schema version {}: {} upgraded, {} already current, {} failed",
+            crate::infrastructure::record_schema::CURRENT_SCHEMA_VERSION,
+            migration.upgraded,
+            migration.current,
+            migration.failed
+        );
+    }
+
+    if let Some(Command::Backfill { symbol, file }) = cli.command {
+        let reader = std::fs::File::open(&file)
+            .with_context(|| format!("Failed to open {}", file.display()))?;
+        let summary =
+            crate::application::backfill::import_history_csv(repository.as_ref(), &symbol, reader)
+                .await?;
+        info!(
+            "Backfilled {} points for {} ({} rows skipped)",
+            summary.imported,
+            symbol.to_uppercase(),
+            summary.skipped
+        );
+        return Ok(());
+    }
+
+    // Responses served by the API, invalidated whenever the data behind them changes
+    let response_cache = Arc::new(ResponseCache::new());
+
+    // One-time cleanup of histories stored under inconsistent symbol casing
+    let merge_symbol_casings = std::env::var("MERGE_SYMBOL_CASINGS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(false);
+    if merge_symbol_casings {
+        let merge = repository.merge_symbol_casings()?;
+        if merge.symbols.is_empty() {
+            info!("No case-variant symbol keys found");
+        } else {
+            response_cache.invalidate(chrono::Utc::now());
+            info!(
+                "Merged case-variant keys for {} symbols ({:?}): {} records moved, {} duplicates dropped",
+                merge.symbols.len(),
+                merge.symbols,
+                merge.records_moved,
+                merge.records_dropped
+            );
+        }
+    }
+
     // Initialize API client
-    let api_client = Arc::new(GseApiClientImpl::new());
-    info!("GSE API client initialized");
+    let default_rate_limit = RateLimitConfig::default();
+    let rate_limit = RateLimitConfig {
+        requests_per_second: std::env::var("GSE_REQUESTS_PER_SECOND")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|rate: &f64| *rate > 0.0)
+            .unwrap_or(default_rate_limit.requests_per_second),
+        burst: std::env::var("GSE_REQUEST_BURST")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|burst: &u32| *burst > 0)
+            .unwrap_or(default_rate_limit.burst),
+        low_remaining_threshold: std::env::var("GSE_LOW_REMAINING_THRESHOLD")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default_rate_limit.low_remaining_threshold),
+    };
+    let default_circuit_breaker = CircuitBreakerConfig::default();
+    let circuit_breaker = CircuitBreakerConfig {
+        failure_threshold: std::env::var("GSE_CIRCUIT_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|threshold: &u32| *threshold > 0)
+            .unwrap_or(default_circuit_breaker.failure_threshold),
+        cooldown: std::env::var("GSE_CIRCUIT_COOLDOWN")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(default_circuit_breaker.cooldown),
+    };
+    let api_base_url = std::env::var("GSE_API_BASE_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| crate::infrastructure::DEFAULT_GSE_API_BASE_URL.to_string());
+    let api_client = Arc::new(
+        GseApiClientImpl::with_config(rate_limit.clone(), circuit_breaker.clone(), metrics.clone())
+            .with_base_url(api_base_url.clone()),
+    );
+    info!("GSE API client initialized for {}", api_base_url);
+
+    // Trading calendar shared by the worker and the calendar endpoint
+    let pause_windows = std::env::var("MARKET_PAUSE_WINDOWS")
+        .ok()
+        .map(|s| crate::domain::PauseWindow::parse_list(&s))
+        .unwrap_or_default();
+    let market_open_hour = std::env::var("MARKET_OPEN_HOUR")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(crate::domain::DEFAULT_OPEN_HOUR);
+    let market_close_hour = std::env::var("MARKET_CLOSE_HOUR")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(crate::domain::DEFAULT_CLOSE_HOUR);
+    let (market_open_hour, market_close_hour) =
+        if market_open_hour < market_close_hour && market_close_hour < 24 {
+            (market_open_hour, market_close_hour)
+        } else {
+            tracing::warn!(
+                "Ignoring invalid trading hours {}-{}",
+                market_open_hour,
+                market_close_hour
+            );
+            (
+                crate::domain::DEFAULT_OPEN_HOUR,
+                crate::domain::DEFAULT_CLOSE_HOUR,
+            )
+        };
+    // Holidays listed inline and in a JSON file of `YYYY-MM-DD` dates are combined; with neither
+    // configured, the built-in GSE holidays apply
+    let mut holidays = std::env::var("MARKET_HOLIDAYS")
+        .ok()
+        .map(|s| crate::domain::MarketCalendar::parse_holidays(&s));
+    if let Ok(path) = std::env::var("MARKET_HOLIDAYS_FILE") {
+        let from_file = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| {
+                serde_json::from_str::<Vec<chrono::NaiveDate>>(&json).map_err(anyhow::Error::from)
+            });
+        match from_file {
+            Ok(dates) => holidays.get_or_insert_with(Vec::new).extend(dates),
+            Err(e) => tracing::warn!("Failed to load market holidays from {}: {}", path, e),
+        }
+    }
+    let holidays = holidays.unwrap_or_else(crate::domain::MarketCalendar::default_holidays);
 
     // Initialize use cases
-    let fetch_use_case = Arc::new(FetchStockDataUseCase::new(
+    let price_filter = PriceFilter {
+        min_valid_price: std::env::var("MIN_VALID_PRICE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0.0),
+        mode: std::env::var("INVALID_PRICE_MODE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default(),
+        max_change_percent: std::env::var("MAX_CHANGE_PERCENT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(1000.0),
+    };
+    let fetch_config = FetchConfig {
+        market_move_alert_percent: std::env::var("MARKET_MOVE_ALERT_PERCENT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5.0),
+        price_filter,
+        manual_refresh_wait: std::env::var("MANUAL_REFRESH_WAIT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0),
+        summary_fresh_within: std::env::var("SUMMARY_FRESH_WITHIN")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(chrono::Duration::seconds)
+            .unwrap_or_else(|| FetchConfig::default().summary_fresh_within),
+    };
+    let fetch_use_case = Arc::new(FetchStockDataUseCase::with_config(
         api_client.clone(),
         repository.clone(),
+        response_cache.clone(),
+        fetch_config.clone(),
     ));
-    let get_use_case = Arc::new(GetStockDataUseCase::new(
+    let recently_requested_capacity = std::env::var("RECENTLY_REQUESTED_CAPACITY")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(20);
+    let recently_requested = Arc::new(RecentlyRequested::new(recently_requested_capacity));
+    let query_config = QueryConfig {
+        price_filter,
+        market_timezone: std::env::var("MARKET_UTC_OFFSET")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| QueryConfig::default().market_timezone),
+        equity_cache_ttl: std::env::var("EQUITY_CACHE_TTL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .map(chrono::Duration::seconds)
+            .unwrap_or_else(|| QueryConfig::default().equity_cache_ttl),
+        max_batch_symbols: std::env::var("MAX_BATCH_SYMBOLS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_else(|| QueryConfig::default().max_batch_symbols),
+        market_calendar: crate::domain::MarketCalendar::new(
+            pause_windows.clone(),
+            holidays.clone(),
+        )
+        .with_trading_hours(market_open_hour, market_close_hour),
+        freshness_sla: crate::domain::FreshnessSla {
+            default: std::env::var("FRESHNESS_SLA")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|secs: &i64| *secs > 0)
+                .map(chrono::Duration::seconds)
+                .unwrap_or_else(|| crate::domain::FreshnessSla::default().default),
+            overrides: std::env::var("FRESHNESS_SLA_OVERRIDES")
+                .ok()
+                .map(|s| crate::domain::FreshnessSla::parse_overrides(&s))
+                .unwrap_or_default(),
+        },
+        ladder: crate::domain::analytics::ladder::LadderConfig {
+            spread_percent: std::env::var("LADDER_SPREAD_PERCENT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|percent: &f64| *percent >= 0.0)
+                .unwrap_or_else(|| QueryConfig::default().ladder.spread_percent),
+            levels: std::env::var("LADDER_LEVELS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|levels: &usize| *levels > 0)
+                .unwrap_or_else(|| QueryConfig::default().ladder.levels),
+        },
+    };
+    let get_use_case = Arc::new(GetStockDataUseCase::with_config(
         repository.clone(),
         api_client.clone(),
+        recently_requested.clone(),
+        response_cache.clone(),
+        query_config.clone(),
     ));
 
     // Initialize portfolio components
     let portfolio_repository = Arc::new(crate::infrastructure::RocksDbPortfolioRepository::new(db.clone()));
-    let portfolio_use_case = Arc::new(crate::application::PortfolioUseCase::new(portfolio_repository));
+    let fx_rates = Arc::new(crate::infrastructure::StaticFxRateProvider::new(
+        std::env::var("FX_RATES")
+            .ok()
+            .map(|s| crate::infrastructure::StaticFxRateProvider::parse_rates(&s))
+            .unwrap_or_default(),
+    ));
+    let portfolio_use_case = Arc::new(crate::application::PortfolioUseCase::new(
+        portfolio_repository,
+        repository.clone(),
+        fx_rates,
+        crate::domain::MarketCalendar::new(pause_windows.clone(), holidays.clone())
+            .with_trading_hours(market_open_hour, market_close_hour),
+    ));
+
+    let watchlist_use_case = Arc::new(crate::application::WatchlistUseCase::new(
+        Arc::new(crate::infrastructure::RocksDbWatchlistRepository::new(
+            db.clone(),
+        )),
+        repository.clone(),
+    ));
+
+    // Decide on bootstrapping before the worker writes its first records
+    let worker_status = Arc::new(WorkerStatus::new());
+    let bootstrap_equities = std::env::var("BOOTSTRAP_EQUITIES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(false);
+    if bootstrap_equities && fetch_use_case.claim_bootstrap(&worker_status).await? {
+        info!("Empty database detected, bootstrapping equity data in the background");
+        tokio::spawn({
+            let fetch_use_case = fetch_use_case.clone();
+            let worker_status = worker_status.clone();
+            async move {
+                match fetch_use_case.bootstrap_equities(&worker_status).await {
+                    Ok(()) => info!("Equity bootstrap completed"),
+                    Err(e) => tracing::error!("Equity bootstrap failed: {}", e),
+                }
+            }
+        });
+    }
 
     // Start background worker
-    let worker_config = WorkerConfig {
-        scrape_interval: std::env::var("SCRAPE_INTERVAL")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(3600), // Default 1 hour
-        max_retries: std::env::var("MAX_RETRIES")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(3),
-        retry_delay: std::env::var("RETRY_DELAY")
+    let worker_config =
+        worker_config_from_env(pause_windows, market_open_hour, market_close_hour, holidays);
+
+    let delivery_config = DeliveryConfig {
+        max_attempts: std::env::var("WEBHOOK_MAX_ATTEMPTS")
             .ok()
             .and_then(|s| s.parse().ok())
+            .filter(|attempts: &u32| *attempts > 0)
             .unwrap_or(5),
-        fetch_equity_data: std::env::var("FETCH_EQUITY_DATA")
+        retry_backoff: std::env::var("WEBHOOK_RETRY_BACKOFF")
             .ok()
             .and_then(|s| s.parse().ok())
-            .unwrap_or(true),
-        generate_market_summary: std::env::var("GENERATE_MARKET_SUMMARY")
+            .unwrap_or(60),
+    };
+    // Webhooks only reach public addresses unless the operator runs receivers internally
+    let mut webhook_client = WebhookClientImpl::new();
+    if std::env::var("WEBHOOK_ALLOW_PRIVATE_TARGETS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(false)
+    {
+        webhook_client = webhook_client.allowing_private_targets();
+    }
+    let delivery_queue = Arc::new(DeliveryQueue::new(
+        Arc::new(webhook_client),
+        repository.clone(),
+        delivery_config.clone(),
+    ));
+
+    let alert_use_case = Arc::new(crate::application::AlertUseCase::new(
+        Arc::new(crate::infrastructure::RocksDbAlertRepository::new(
+            db.clone(),
+        )),
+        repository.clone(),
+        delivery_queue.clone(),
+        Arc::new(crate::infrastructure::PublicWebhookTargets),
+        std::env::var("ALERT_COOLDOWN")
             .ok()
             .and_then(|s| s.parse().ok())
-            .unwrap_or(true),
-    };
+            .map(chrono::Duration::seconds)
+            .unwrap_or_else(|| chrono::Duration::hours(1)),
+    ));
 
     let worker = Arc::new(DataScrapingWorker::new(
         fetch_use_case.clone(),
         worker_config.clone(),
+        WorkerDependencies {
+            recently_requested: recently_requested.clone(),
+            deliveries: delivery_queue,
+            portfolio_use_case: portfolio_use_case.clone(),
+            alert_use_case: alert_use_case.clone(),
+            disk_probe: Arc::new(crate::infrastructure::FsDiskSpaceProbe::new(DB_PATH)),
+            pruner: repository.clone(),
+            status: worker_status.clone(),
+            metrics: metrics.clone(),
+        },
     ));
 
     // Start worker in background
@@ -85,31 +457,188 @@ async fn main() -> Result<()> {
 
     info!("Background worker started with config: {:?}", worker_config);
 
-    // Generate initial market summary if none exists
-    tokio::spawn({
+    // Generate initial market summary if none exists. An unreachable upstream puts the service
+    // in degraded mode, serving stored data as best-effort, unless that is disabled.
+    let allow_degraded_start = std::env::var("ALLOW_DEGRADED_START")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(true);
+    let initial_fetch = {
         let fetch_use_case = fetch_use_case.clone();
         async move {
             info!("Ensuring initial data availability...");
+            let _scrape = fetch_use_case.lock_scrapes().await;
             if let Err(e) = fetch_use_case.fetch_and_store_all_live_data().await {
                 tracing::error!("Initial data fetch failed: {}", e);
+                return Err(e);
+            }
+            if let Err(e) = fetch_use_case.generate_and_store_market_summary().await {
+                tracing::error!("Initial market summary generation failed: {}", e);
             } else {
-                if let Err(e) = fetch_use_case.generate_and_store_market_summary().await {
-                    tracing::error!("Initial market summary generation failed: {}", e);
-                } else {
-                    info!("Initial data and summary ready");
-                }
+                info!("Initial data and summary ready");
             }
+            Ok(())
         }
+    };
+    if allow_degraded_start {
+        tokio::spawn({
+            let fetch_use_case = fetch_use_case.clone();
+            async move {
+                if initial_fetch.await.is_err() {
+                    tracing::warn!(
+                        "GSE API unreachable at boot, serving stored data in degraded mode"
+                    );
+                    fetch_use_case.mark_degraded();
+                }
+            }
+        });
+    } else if let Err(e) = initial_fetch.await {
+        anyhow::bail!(
+            "GSE API unreachable at boot and ALLOW_DEGRADED_START is disabled: {}",
+            e
+        );
+    }
+
+    // Start periodic exports if an interval is configured
+    let export_config = std::env::var("EXPORT_INTERVAL")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|interval: &u64| *interval > 0)
+        .map(|export_interval| {
+            let default_export_config = ExportConfig::default();
+            ExportConfig {
+                interval: export_interval,
+                directory: std::env::var("EXPORT_DIR")
+                    .map(std::path::PathBuf::from)
+                    .unwrap_or(default_export_config.directory),
+                keep: std::env::var("EXPORT_KEEP")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(default_export_config.keep),
+            }
+        });
+    if let Some(export_config) = export_config.clone() {
+        let export_scheduler = ExportScheduler::new(repository.clone(), export_config);
+        tokio::spawn(async move {
+            if let Err(e) = export_scheduler.start().await {
+                tracing::error!("Export scheduler failed: {}", e);
+            }
+        });
+    }
+
+    // Start periodic compaction of old ticks if an age threshold is configured
+    let archive_config = std::env::var("ARCHIVE_AFTER_DAYS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|days: &i64| *days > 0)
+        .map(|older_than_days| ArchiveConfig {
+            interval: std::env::var("ARCHIVE_INTERVAL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|interval: &u64| *interval > 0)
+                .unwrap_or(ArchiveConfig::default().interval),
+            older_than_days,
+        });
+    if let Some(archive_config) = archive_config.clone() {
+        let archive_scheduler = ArchiveScheduler::new(repository.clone(), archive_config);
+        tokio::spawn(async move {
+            if let Err(e) = archive_scheduler.start().await {
+                tracing::error!("Archive scheduler failed: {}", e);
+            }
+        });
+    }
+
+    let latency_histogram = Arc::new(LatencyHistogram::new(
+        std::env::var("LATENCY_BUCKETS_MS")
+            .ok()
+            .map(|s| LatencyHistogram::parse_buckets(&s))
+            .unwrap_or_default(),
+    ));
+    restore_state(&repository, &recently_requested, &latency_histogram).await;
+
+    let admin_api_key = AdminApiKey::new(std::env::var("ADMIN_API_KEY").ok());
+    if !admin_api_key.is_configured() {
+        tracing::warn!("ADMIN_API_KEY is not set; admin endpoints will reject every request");
+    }
+
+    // Per-client limit on API requests; a rate of 0 disables it
+    let client_rate_limit = {
+        let defaults = ClientRateLimitConfig::default();
+        let config = ClientRateLimitConfig {
+            requests_per_minute: std::env::var("CLIENT_RATE_LIMIT_PER_MINUTE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.requests_per_minute),
+            burst: std::env::var("CLIENT_RATE_LIMIT_BURST")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.burst),
+            trust_forwarded_for: std::env::var("TRUST_FORWARDED_FOR")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.trust_forwarded_for),
+        };
+        (config.requests_per_minute > 0).then_some(config)
+    };
+
+    // Database backups, written as checkpoints under BACKUP_DIR
+    let backup_dir = std::env::var("BACKUP_DIR").unwrap_or_else(|_| "./data/backups".to_string());
+    let backups = Arc::new(RocksDbBackups::new(db.clone(), DB_PATH, backup_dir));
+
+    // Effective configuration, reported by the admin config endpoint
+    let runtime_config = Arc::new(RuntimeConfig {
+        worker: WorkerSettings::from(&worker_config),
+        client: ClientSettings::new(&api_base_url, &rate_limit, &circuit_breaker),
+        query: QuerySettings::new(&query_config, &fetch_config),
+        retention: RetentionSettings::new(
+            &delivery_config,
+            export_config.as_ref(),
+            archive_config.as_ref(),
+            recently_requested_capacity,
+        ),
+        features: FeatureFlags {
+            fetch_equity_data: worker_config.fetch_equity_data,
+            generate_market_summary: worker_config.generate_market_summary,
+            refresh_requested_symbols: worker_config.refresh_requested_symbols,
+            bootstrap_equities,
+            merge_symbol_casings,
+            allow_degraded_start,
+            exports_enabled: export_config.is_some(),
+            admin_auth: admin_api_key.is_configured(),
+        },
+        client_rate_limit: client_rate_limit.clone(),
     });
 
     // Create and start web server
-    let app = create_router(get_use_case, fetch_use_case, portfolio_use_case)
+    let mut app = create_router(RouterServices {
+        get_use_case,
+        fetch_use_case,
+        portfolio_use_case,
+        watchlist_use_case,
+        alert_use_case,
+        worker_status,
+        latency_histogram: latency_histogram.clone(),
+        metrics,
+        runtime_config,
+        backups,
+        admin_api_key,
+    });
+    if let Some(config) = client_rate_limit {
+        app = app.layer(axum::middleware::from_fn_with_state(
+            Arc::new(ClientRateLimiter::new(config)),
+            limit_client_rate,
+        ));
+    }
+    // The request id layer wraps tracing so the trace layer's logs carry the id too
+    let app = app
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(propagate_request_id))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
                 .allow_methods(Any)
-                .allow_headers(Any),
+                .allow_headers(Any)
+                .expose_headers([REQUEST_ID_HEADER.clone()]),
         );
 
     let port = std::env::var("PORT")
@@ -121,14 +650,149 @@ async fn main() -> Result<()> {
     info!("Server listening on port {}", port);
 
     // Start server
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // Connection info gives the rate limiter each client's address
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
+
+    flush_state(&repository, &recently_requested, &latency_histogram, &db).await;
 
     info!("Server shutdown complete");
     Ok(())
 }
 
+const REQUEST_COUNTS_STATE: &str = "request_counts";
+const LATENCY_STATE: &str = "latency";
+
+/// Worker settings from the environment, trading on the given calendar
+fn worker_config_from_env(
+    pause_windows: Vec<crate::domain::PauseWindow>,
+    market_open_hour: u32,
+    market_close_hour: u32,
+    holidays: Vec<chrono::NaiveDate>,
+) -> WorkerConfig {
+    WorkerConfig {
+        scrape_interval: std::env::var("SCRAPE_INTERVAL")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(3600), // Default 1 hour
+        max_retries: std::env::var("MAX_RETRIES")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(3),
+        retry_delay: std::env::var("RETRY_DELAY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(5),
+        fetch_equity_data: std::env::var("FETCH_EQUITY_DATA")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true),
+        generate_market_summary: std::env::var("GENERATE_MARKET_SUMMARY")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true),
+        refresh_requested_symbols: std::env::var("REFRESH_REQUESTED_SYMBOLS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true),
+        pause_windows,
+        market_open_hour,
+        market_close_hour,
+        holidays,
+        scrape_webhook_url: std::env::var("SCRAPE_WEBHOOK_URL")
+            .ok()
+            .filter(|s| !s.is_empty()),
+        min_free_disk_bytes: std::env::var("MIN_FREE_DISK_MB")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(|mb| mb * 1024 * 1024)
+            .unwrap_or(0),
+        retention_days: std::env::var("RETENTION_DAYS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|days: &i64| *days > 0),
+    }
+}
+
+/// Reload the in-memory state persisted by `flush_state` at the last shutdown
+async fn restore_state(
+    repository: &RocksDbStockRepository,
+    recently_requested: &RecentlyRequested,
+    latency_histogram: &LatencyHistogram,
+) {
+    match repository.get_recent_symbols().await {
+        Ok(symbols) => recently_requested.restore(symbols),
+        Err(e) => tracing::warn!("Failed to restore recently requested symbols: {}", e),
+    }
+
+    match repository.get_service_state(REQUEST_COUNTS_STATE).await {
+        Ok(Some(state)) => match serde_json::from_value(state) {
+            Ok(counts) => recently_requested.restore_counts(counts),
+            Err(e) => tracing::warn!("Ignoring unreadable request counts: {}", e),
+        },
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to restore request counts: {}", e),
+    }
+
+    match repository.get_service_state(LATENCY_STATE).await {
+        Ok(Some(state)) => match serde_json::from_value(state) {
+            Ok(state) => {
+                if !latency_histogram.restore(state) {
+                    info!("Latency buckets changed since the last run; starting a fresh histogram");
+                }
+            }
+            Err(e) => tracing::warn!("Ignoring unreadable latency histogram: {}", e),
+        },
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to restore latency histogram: {}", e),
+    }
+}
+
+/// Persist in-memory state so it survives the restart, then flush RocksDB's memtables to disk.
+/// Cached symbol metrics are written through to the database and only need the flush.
+async fn flush_state(
+    repository: &RocksDbStockRepository,
+    recently_requested: &RecentlyRequested,
+    latency_histogram: &LatencyHistogram,
+    db: &rocksdb::DB,
+) {
+    if let Err(e) = repository
+        .store_recent_symbols(&recently_requested.symbols())
+        .await
+    {
+        tracing::error!("Failed to persist recently requested symbols: {}", e);
+    }
+
+    let states = [
+        (
+            REQUEST_COUNTS_STATE,
+            serde_json::to_value(recently_requested.counts()),
+        ),
+        (
+            LATENCY_STATE,
+            serde_json::to_value(latency_histogram.export()),
+        ),
+    ];
+    for (name, state) in states {
+        let stored = match state {
+            Ok(state) => repository.store_service_state(name, &state).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = stored {
+            tracing::error!("Failed to persist {} state: {}", name, e);
+        }
+    }
+
+    match db.flush() {
+        Ok(()) => info!("Flushed in-memory state to the database"),
+        Err(e) => tracing::error!("Failed to flush database: {}", e),
+    }
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
@@ -154,3 +818,117 @@ async fn shutdown_signal() {
 
     info!("Shutdown signal received");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::SymbolMetrics;
+    use crate::infrastructure::test_support::temp_path;
+    use chrono::Utc;
+    use std::time::Duration;
+
+    fn open(path: &Path) -> (Arc<rocksdb::DB>, RocksDbStockRepository) {
+        let db = Arc::new(rocksdb::DB::open_default(path).unwrap());
+        let repository = RocksDbStockRepository::new(db.clone());
+        (db, repository)
+    }
+
+    #[tokio::test]
+    async fn flushed_state_is_restored_after_reopening_the_database() {
+        let path = temp_path("restart");
+        {
+            let (db, repository) = open(&path);
+            let recently_requested = RecentlyRequested::new(10);
+            recently_requested.touch("GCB");
+            recently_requested.touch("MTNGH");
+            recently_requested.touch("MTNGH");
+            let latency_histogram = LatencyHistogram::default();
+            latency_histogram.record("GET /api/stocks", Duration::from_millis(7));
+            latency_histogram.record("GET /api/stocks", Duration::from_millis(70));
+            repository
+                .store_symbol_metrics(&SymbolMetrics {
+                    symbol: "MTNGH".to_string(),
+                    high_52w: Some(2.5),
+                    low_52w: Some(1.5),
+                    average_daily_volume: Some(1000.0),
+                    market_cap: None,
+                    computed_at: Utc::now(),
+                })
+                .await
+                .unwrap();
+
+            flush_state(&repository, &recently_requested, &latency_histogram, &db).await;
+        }
+
+        let (db, repository) = open(&path);
+        let recently_requested = RecentlyRequested::new(10);
+        let latency_histogram = LatencyHistogram::default();
+        restore_state(&repository, &recently_requested, &latency_histogram).await;
+
+        assert_eq!(recently_requested.symbols(), vec!["MTNGH", "GCB"]);
+        let popular = recently_requested.popular(10);
+        assert_eq!(popular[0].symbol, "MTNGH");
+        assert_eq!(popular[0].requests, 2);
+        assert_eq!(popular[1].requests, 1);
+        let latency = latency_histogram.snapshot();
+        assert_eq!(latency[0].endpoint, "GET /api/stocks");
+        assert_eq!(latency[0].count, 2);
+        assert_eq!(latency[0].p50_ms, Some(10.0));
+        let metrics = repository
+            .get_symbol_metrics("MTNGH")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(metrics.high_52w, Some(2.5));
+
+        drop(repository);
+        drop(db);
+        let _ = std::fs::remove_dir_all(&path);
+    }
+
+    #[tokio::test]
+    async fn latency_saved_under_other_buckets_is_not_restored() {
+        let saved = LatencyHistogram::new(vec![10.0, 100.0]);
+        saved.record("GET /api/stocks", Duration::from_millis(7));
+
+        let histogram = LatencyHistogram::default();
+
+        assert!(!histogram.restore(saved.export()));
+        assert!(histogram.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn the_config_endpoint_reports_the_configured_scrape_interval() {
+        let worker_config = WorkerConfig {
+            scrape_interval: 120,
+            ..WorkerConfig::default()
+        };
+        let runtime_config = Arc::new(RuntimeConfig {
+            worker: WorkerSettings::from(&worker_config),
+            client: ClientSettings::new(
+                "http://localhost",
+                &RateLimitConfig::default(),
+                &CircuitBreakerConfig::default(),
+            ),
+            query: QuerySettings::new(&QueryConfig::default(), &FetchConfig::default()),
+            retention: RetentionSettings::new(&DeliveryConfig::default(), None, None, 10),
+            features: FeatureFlags {
+                fetch_equity_data: worker_config.fetch_equity_data,
+                generate_market_summary: worker_config.generate_market_summary,
+                refresh_requested_symbols: worker_config.refresh_requested_symbols,
+                bootstrap_equities: false,
+                merge_symbol_casings: false,
+                allow_degraded_start: false,
+                exports_enabled: false,
+                admin_auth: true,
+            },
+            client_rate_limit: None,
+        });
+
+        let response = crate::presentation::handlers::get_runtime_config(runtime_config).await;
+
+        let body = serde_json::to_value(&response.0).unwrap();
+        assert_eq!(body["data"]["worker"]["scrape_interval"], 120);
+        assert_eq!(body["data"]["worker"]["max_retries"], 3);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::application::AlertUseCase;
+use crate::domain::{AlertCondition, RejectedWebhookUrl};
+use crate::presentation::handlers::ApiError;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateAlertRequest {
+    symbol: String,
+    /// e.g. `{"type": "below", "price": 2.5}` or `{"type": "percent_move", "percent": 5}`
+    condition: AlertCondition,
+    /// Receives a JSON payload each time the alert fires
+    webhook_url: String,
+}
+
+pub fn alert_routes(use_case: Arc<AlertUseCase>) -> Router {
+    Router::new()
+        .route("/", post(create_alert).get(get_alerts))
+        .route("/:id", delete(delete_alert))
+        .with_state(use_case)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/alerts",
+    tag = "alerts",
+    security(("admin_key" = [])),
+    request_body = CreateAlertRequest,
+    responses(
+        (status = 201, description = "Created alert", body = Alert),
+        (status = 400, description = "Threshold not positive, or webhook URL not http(s) or not publicly routable"),
+    )
+)]
+async fn create_alert(
+    State(use_case): State<Arc<AlertUseCase>>,
+    Json(payload): Json<CreateAlertRequest>,
+) -> Result<Response, ApiError> {
+    let threshold = match payload.condition {
+        AlertCondition::Above { price } | AlertCondition::Below { price } => price,
+        AlertCondition::PercentMove { percent } => percent,
+    };
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(ApiError::bad_request(
+            "Alert threshold must be a positive number",
+        ));
+    }
+
+    match use_case
+        .create_alert(&payload.symbol, payload.condition, payload.webhook_url)
+        .await
+    {
+        Ok(alert) => Ok((StatusCode::CREATED, Json(alert)).into_response()),
+        Err(e) if e.downcast_ref::<RejectedWebhookUrl>().is_some() => {
+            Err(ApiError::bad_request(e.to_string()))
+        }
+        Err(e) => Err(ApiError::storage("Failed to create alert", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/alerts",
+    tag = "alerts",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Every alert", body = [Alert]),
+    )
+)]
+async fn get_alerts(State(use_case): State<Arc<AlertUseCase>>) -> Result<Response, ApiError> {
+    match use_case.get_alerts().await {
+        Ok(alerts) => Ok(Json(alerts).into_response()),
+        Err(e) => Err(ApiError::storage("Failed to get alerts", e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/alerts/{id}",
+    tag = "alerts",
+    security(("admin_key" = [])),
+    params(("id" = String, Path, description = "Alert id")),
+    responses(
+        (status = 204, description = "Alert deleted"),
+        (status = 404, description = "Alert not found"),
+    )
+)]
+async fn delete_alert(
+    State(use_case): State<Arc<AlertUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.delete_alert(&id).await {
+        Ok(true) => Ok(StatusCode::NO_CONTENT.into_response()),
+        Ok(false) => Err(ApiError::not_found("Alert not found")),
+        Err(e) => Err(ApiError::storage("Failed to delete alert", e)),
+    }
+}
@@ -0,0 +1,126 @@
+use crate::presentation::handlers::ApiError;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+
+/// Key admin requests must present as a bearer token. With no key configured every admin
+/// request is refused.
+#[derive(Clone)]
+pub struct AdminApiKey(Option<Arc<str>>);
+
+impl AdminApiKey {
+    pub fn new(key: Option<String>) -> Self {
+        Self(key.filter(|key| !key.is_empty()).map(Arc::from))
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Compare in constant time so response timing doesn't reveal how much of a guess matched
+    fn matches(&self, token: &str) -> bool {
+        self.0
+            .as_deref()
+            .is_some_and(|key| bool::from(key.as_bytes().ct_eq(token.as_bytes())))
+    }
+}
+
+/// Middleware rejecting requests without a bearer token (`401`) or with the wrong one (`403`)
+pub async fn require_admin_key(
+    State(key): State<AdminApiKey>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    match token {
+        None => {
+            let mut response =
+                ApiError::new(StatusCode::UNAUTHORIZED, "Missing bearer token").into_response();
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            response
+        }
+        Some(token) if !key.matches(token) => {
+            ApiError::new(StatusCode::FORBIDDEN, "Invalid API key").into_response()
+        }
+        Some(_) => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::Service;
+
+    fn admin_router(key: Option<&str>) -> Router {
+        Router::new()
+            .route("/admin/refresh", post(|| async { "refreshed" }))
+            .route_layer(middleware::from_fn_with_state(
+                AdminApiKey::new(key.map(String::from)),
+                require_admin_key,
+            ))
+    }
+
+    async fn call(mut router: Router, authorization: Option<&str>) -> Response {
+        let mut request = Request::post("/admin/refresh");
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        router
+            .call(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn error_message(response: Response) -> String {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        json["error"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn admin_route_without_a_token_is_unauthorized() {
+        let response = call(admin_router(Some("secret")), None).await;
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        assert_eq!(error_message(response).await, "Missing bearer token");
+    }
+
+    #[tokio::test]
+    async fn admin_route_with_the_wrong_token_is_forbidden() {
+        let response = call(admin_router(Some("secret")), Some("Bearer guess")).await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(error_message(response).await, "Invalid API key");
+    }
+
+    #[tokio::test]
+    async fn admin_route_with_the_key_is_let_through() {
+        let response = call(admin_router(Some("secret")), Some("Bearer secret")).await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn admin_route_is_refused_when_no_key_is_configured() {
+        let response = call(admin_router(None), Some("Bearer ")).await;
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::presentation::handlers::ApiError;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Independently locked bucket maps, so concurrent clients rarely contend for the same lock
+const SHARDS: usize = 16;
+
+/// Buckets a shard holds before idle ones are dropped
+const MAX_BUCKETS_PER_SHARD: usize = 4096;
+
+/// Settings of the per-client limit on API requests
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientRateLimitConfig {
+    /// Steady-state requests each client may make per minute
+    pub requests_per_minute: u32,
+    /// Requests a client may make back to back before being limited
+    pub burst: u32,
+    /// Identify clients by the last `X-Forwarded-For` address, for deployments behind a proxy.
+    /// The proxy appends the address it saw, so earlier entries are whatever the client sent.
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for ClientRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 120,
+            burst: 30,
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+struct ClientBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token buckets keyed by client IP, spread over shards by a hash of the address
+pub struct ClientRateLimiter {
+    config: ClientRateLimitConfig,
+    shards: Vec<Mutex<HashMap<IpAddr, ClientBucket>>>,
+}
+
+impl ClientRateLimiter {
+    pub fn new(config: ClientRateLimitConfig) -> Self {
+        Self {
+            config,
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+        }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        self.config.requests_per_minute as f64 / 60.0
+    }
+
+    /// Take a token for a request from `client`, or return how long until one is available
+    pub fn check(&self, client: IpAddr) -> Result<(), Duration> {
+        let mut hasher = DefaultHasher::new();
+        client.hash(&mut hasher);
+        let shard = &self.shards[hasher.finish() as usize % self.shards.len()];
+
+        let mut buckets = shard.lock().unwrap();
+        let now = Instant::now();
+        let rate = self.tokens_per_second();
+        let burst = self.config.burst.max(1) as f64;
+
+        if buckets.len() >= MAX_BUCKETS_PER_SHARD && !buckets.contains_key(&client) {
+            // A bucket refilled to the burst behaves exactly like a new one, so it can go
+            buckets.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.last_refill).as_secs_f64() * rate < burst
+            });
+            // Every client is still limited, so make room by dropping the longest idle one
+            if buckets.len() >= MAX_BUCKETS_PER_SHARD {
+                let oldest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.last_refill)
+                    .map(|(ip, _)| *ip);
+                if let Some(oldest) = oldest {
+                    buckets.remove(&oldest);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(client).or_insert(ClientBucket {
+            tokens: burst,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    /// The address a request is counted against, if one can be determined
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        if self.config.trust_forwarded_for {
+            let forwarded = request
+                .headers()
+                .get("x-forwarded-for")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.rsplit(',').next())
+                .and_then(|ip| ip.trim().parse().ok());
+            if forwarded.is_some() {
+                return forwarded;
+            }
+        }
+
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    }
+}
+
+/// Whether `path` is `/health` or one of the routes under it, but not e.g. `/healthcare`
+fn is_health_check(path: &str) -> bool {
+    path.strip_prefix("/health")
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Middleware answering `429 Too Many Requests` with `Retry-After` once a client has spent its
+/// tokens. Health checks are never limited.
+pub async fn limit_client_rate(
+    State(limiter): State<Arc<ClientRateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_health_check(request.uri().path()) {
+        return next.run(request).await;
+    }
+    let Some(client) = limiter.client_ip(&request) else {
+        return next.run(request).await;
+    };
+
+    match limiter.check(client) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let mut response = ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests, slow down",
+            )
+            .into_response();
+            // Whole seconds, rounded up so a client honouring it isn't refused again
+            let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(burst: u32) -> ClientRateLimiter {
+        ClientRateLimiter::new(ClientRateLimitConfig {
+            requests_per_minute: 1,
+            burst,
+            trust_forwarded_for: false,
+        })
+    }
+
+    fn ip(n: u32) -> IpAddr {
+        IpAddr::from(n.to_be_bytes())
+    }
+
+    #[test]
+    fn only_health_and_its_subroutes_skip_the_limit() {
+        assert!(is_health_check("/health"));
+        assert!(is_health_check("/health/"));
+        assert!(is_health_check("/health/ready"));
+        assert!(!is_health_check("/healthcare"));
+        assert!(!is_health_check("/api/health"));
+    }
+
+    #[test]
+    fn a_client_is_limited_after_its_burst() {
+        let limiter = limiter(2);
+
+        assert!(limiter.check(ip(1)).is_ok());
+        assert!(limiter.check(ip(1)).is_ok());
+        assert!(limiter.check(ip(1)).is_err());
+        assert!(limiter.check(ip(2)).is_ok());
+    }
+
+    #[test]
+    fn a_full_shard_of_limited_clients_evicts_the_longest_idle_one() {
+        let limiter = limiter(1);
+        let shard = &limiter.shards[0];
+        let start = Instant::now();
+        {
+            let mut buckets = shard.lock().unwrap();
+            for n in 0..MAX_BUCKETS_PER_SHARD as u32 {
+                buckets.insert(
+                    ip(n),
+                    ClientBucket {
+                        tokens: 0.0,
+                        last_refill: start + Duration::from_millis(n as u64),
+                    },
+                );
+            }
+        }
+        // Find a new client that hashes to the same shard
+        let newcomer = (MAX_BUCKETS_PER_SHARD as u32..)
+            .map(ip)
+            .find(|client| {
+                let mut hasher = DefaultHasher::new();
+                client.hash(&mut hasher);
+                hasher.finish() as usize % SHARDS == 0
+            })
+            .unwrap();
+
+        assert!(limiter.check(newcomer).is_ok());
+
+        let buckets = shard.lock().unwrap();
+        assert_eq!(buckets.len(), MAX_BUCKETS_PER_SHARD);
+        assert!(!buckets.contains_key(&ip(0)));
+        assert!(buckets.contains_key(&ip(1)));
+        assert!(buckets.contains_key(&newcomer));
+    }
+
+    #[test]
+    fn a_spoofed_leading_forwarded_for_address_counts_against_the_proxied_client() {
+        let limiter = ClientRateLimiter::new(ClientRateLimitConfig {
+            requests_per_minute: 1,
+            burst: 1,
+            trust_forwarded_for: true,
+        });
+        let request = |forwarded_for: &str| {
+            Request::builder()
+                .header("x-forwarded-for", forwarded_for)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let first = limiter.client_ip(&request("6.6.6.6, 203.0.113.7"));
+        let second = limiter.client_ip(&request("7.7.7.7,203.0.113.7"));
+
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        assert_eq!((first, second), (Some(client), Some(client)));
+        assert!(limiter.check(first.unwrap()).is_ok());
+        assert!(limiter.check(second.unwrap()).is_err());
+    }
+}
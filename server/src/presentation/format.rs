@@ -0,0 +1,125 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+/// Content type used for MessagePack-encoded responses
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Wire format selected for a response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    MessagePack,
+}
+
+impl ResponseFormat {
+    /// Pick MessagePack when the client explicitly accepts it, JSON otherwise
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let accepts_msgpack = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .map(|accept| {
+                accept.split(',').any(|part| {
+                    let mime = part.split(';').next().unwrap_or("").trim();
+                    mime == MSGPACK_CONTENT_TYPE || mime == "application/x-msgpack"
+                })
+            })
+            .unwrap_or(false);
+
+        if accepts_msgpack {
+            Self::MessagePack
+        } else {
+            Self::Json
+        }
+    }
+}
+
+/// Response body serialized in the format negotiated with the client
+pub struct Negotiated<T> {
+    pub format: ResponseFormat,
+    pub body: T,
+}
+
+impl<T> Negotiated<T> {
+    pub fn new(format: ResponseFormat, body: T) -> Self {
+        Self { format, body }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        match self.format {
+            ResponseFormat::Json => Json(self.body).into_response(),
+            ResponseFormat::MessagePack => match rmp_serde::to_vec_named(&self.body) {
+                Ok(bytes) => (
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(MSGPACK_CONTENT_TYPE),
+                    )],
+                    bytes,
+                )
+                    .into_response(),
+                Err(e) => {
+                    tracing::error!("Failed to encode MessagePack response: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::EquityLive;
+    use crate::infrastructure::test_support::live;
+    use crate::presentation::handlers::ApiResponse;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Envelope {
+        success: bool,
+        data: Option<EquityLive>,
+        error: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn msgpack_bodies_decode_back_to_the_envelope() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT,
+            HeaderValue::from_static("application/json;q=0.5, application/msgpack"),
+        );
+        let format = ResponseFormat::from_headers(&headers);
+
+        let response =
+            Negotiated::new(format, ApiResponse::success(live("MTNGH", 1.5, 0.1))).into_response();
+
+        assert_eq!(format, ResponseFormat::MessagePack);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            MSGPACK_CONTENT_TYPE
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let envelope: Envelope = rmp_serde::from_slice(&body).unwrap();
+        assert!(envelope.success);
+        assert!(envelope.error.is_none());
+        let data = envelope.data.unwrap();
+        assert_eq!(data.name, "MTNGH");
+        assert_eq!(data.price, 1.5);
+    }
+
+    #[test]
+    fn json_is_served_unless_msgpack_is_accepted() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Json);
+
+        headers.insert(header::ACCEPT, HeaderValue::from_static("application/json"));
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Json);
+    }
+}
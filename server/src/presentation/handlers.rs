@@ -1,28 +1,418 @@
+use crate::application::CachedResponse;
 use crate::application::FetchStockDataUseCase;
 use crate::application::GetStockDataUseCase;
+use crate::application::WorkerStatus;
+use crate::domain::analytics::candles::CandleInterval;
+use crate::domain::analytics::history::HistoryStat;
+use crate::domain::analytics::indicators::Indicator;
+use crate::domain::{
+    Announcement, AnnouncementCategory, BackupInfo, CalendarDay, CircuitState, DataSource,
+    DatabaseBackup, Equity, EquityLive, LiveUpdate, MarketSummary, MetricsRecorder, SearchType,
+    StockSplit,
+};
+use crate::presentation::format::{Negotiated, ResponseFormat};
+use crate::presentation::latency::{EndpointLatency, LatencyHistogram};
+use crate::presentation::runtime_config::RuntimeConfig;
+use crate::presentation::summary_card::summary_card;
 use axum::{
-    extract::{Path, Query},
-    http::StatusCode,
-    response::Json,
+    body::{Body, Bytes},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query,
+    },
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio_stream::{
+    wrappers::{BroadcastStream, ReceiverStream},
+    StreamExt,
+};
+use tracing::Instrument;
+use utoipa::{IntoParams, ToSchema};
 
 /// Query parameters for historical data requests
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct HistoricalDataQuery {
     pub from: Option<String>,
     pub to: Option<String>,
+    pub source: Option<DataSource>,
+    /// Benchmark symbol to compare against; switches the response to a rebased comparison
+    pub vs: Option<String>,
+    /// Set to `both` to return raw and split-adjusted daily closes side by side
+    pub adjusted: Option<AdjustedMode>,
+    /// Comma-separated figures such as `sma50,sma200,high52w,low52w`, returned in an `analytics`
+    /// section next to the points
+    pub indicators: Option<String>,
+}
+
+/// Split adjustment requested from the history endpoint
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AdjustedMode {
+    Both,
+}
+
+/// Query parameters for candle requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CandleQuery {
+    /// RFC 3339, defaults to 30 days ago
+    pub from: Option<String>,
+    /// RFC 3339, defaults to now
+    pub to: Option<String>,
+    /// `1h`, `1d` or `1w`, defaults to `1d`
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub interval: CandleInterval,
+}
+
+/// Query parameters for market snapshot comparison requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SnapshotDiffQuery {
+    pub from: String,
+    pub to: String,
+}
+
+/// Query parameters for relative strength requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RelativeStrengthQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// Query parameters for market summary timeline requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SummaryTimelineQuery {
+    /// RFC 3339, defaults to 30 days before `to`
+    pub from: Option<String>,
+    /// RFC 3339, defaults to now
+    pub to: Option<String>,
+    /// Step between points such as `30m`, `6h`, `1d` or `1w`, defaults to `1d`
+    pub step: Option<String>,
+}
+
+const MAX_TIMELINE_POINTS: i64 = 1000;
+
+/// Parse a step such as `30m`, `6h`, `1d` or `1w`
+fn parse_step(value: &str) -> Option<chrono::Duration> {
+    let value = value.trim();
+    let unit = value.chars().last()?;
+    let count: i64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    if count <= 0 {
+        return None;
+    }
+
+    match unit.to_ascii_lowercase() {
+        'm' => Some(chrono::Duration::minutes(count)),
+        'h' => Some(chrono::Duration::hours(count)),
+        'd' => Some(chrono::Duration::days(count)),
+        'w' => Some(chrono::Duration::weeks(count)),
+        _ => None,
+    }
+}
+
+/// Query parameters for market summary card requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SummaryCardQuery {
+    /// Gainers and losers shown on the card, defaults to 3, at most 5
+    pub movers: Option<usize>,
+}
+
+const DEFAULT_CARD_MOVERS: usize = 3;
+/// Summaries keep five gainers and five losers
+const MAX_CARD_MOVERS: usize = 5;
+
+/// Query parameters for volume alert requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VolumeAlertQuery {
+    /// Multiple of the average daily volume that triggers an alert, defaults to 3
+    pub threshold: Option<f64>,
+}
+
+const DEFAULT_VOLUME_ALERT_THRESHOLD: f64 = 3.0;
+
+/// Request body for correlation matrix requests
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CorrelationRequest {
+    pub symbols: Vec<String>,
+    /// RFC 3339, defaults to 90 days before `to`
+    pub from: Option<String>,
+    /// RFC 3339, defaults to now
+    pub to: Option<String>,
+}
+
+/// Most symbols a single correlation matrix may include, below the configured batch limit since
+/// the matrix grows with the square of the symbol count
+const MAX_CORRELATION_SYMBOLS: usize = 20;
+
+/// Query parameters for multi-symbol requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SymbolsQuery {
+    /// Comma-separated symbols
+    pub symbols: String,
+}
+
+/// Query parameters for trading calendar requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CalendarQuery {
+    /// `YYYY-MM-DD`, defaults to today
+    pub from: Option<String>,
+    /// `YYYY-MM-DD`, defaults to 30 days after `from`
+    pub to: Option<String>,
+}
+
+/// Query parameters for scrape history requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScrapeHistoryQuery {
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for popular symbol requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PopularSymbolsQuery {
+    /// Defaults to 10, at most 100
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for volatility cone requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct VolatilityConeQuery {
+    /// Comma-separated rolling window lengths in trading days
+    pub windows: Option<String>,
+}
+
+/// Query parameters for technical indicator requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IndicatorQuery {
+    /// Lookback period in trading days; ignored by MACD
+    pub period: Option<usize>,
+}
+
+/// Query parameters for technical indicator requests naming the indicator as a parameter
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct IndicatorSeriesQuery {
+    /// `sma`, `ema`, `rsi`, `macd` or `bollinger`
+    #[serde(rename = "type")]
+    #[param(value_type = String)]
+    pub indicator: Indicator,
+    /// Lookback period in trading days; ignored by MACD
+    pub period: Option<usize>,
+}
+
+/// Query parameters for announcement requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AnnouncementQuery {
+    /// `YYYY-MM-DD`, defaults to 90 days before `to`
+    pub from: Option<String>,
+    /// `YYYY-MM-DD`, defaults to today
+    pub to: Option<String>,
+}
+
+/// Request body for recording a company announcement
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordAnnouncementRequest {
+    pub symbol: String,
+    /// `YYYY-MM-DD`
+    pub date: NaiveDate,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[schema(value_type = String)]
+    pub category: AnnouncementCategory,
+}
+
+/// Query parameters for turnover requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TurnoverQuery {
+    /// Window in calendar days, defaults to 30
+    pub days: Option<i64>,
+}
+
+/// Query parameters for market summary requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MarketSummaryQuery {
+    pub source: Option<DataSource>,
+    /// Comma-separated parts to return (`gainers`, `losers`, `totals`, `prices`, `sectors`);
+    /// defaults to all
+    pub include: Option<String>,
+}
+
+/// Summary fields returned for each `include` part; `last_updated` is always returned
+const MARKET_SUMMARY_PARTS: &[(&str, &[&str])] = &[
+    ("gainers", &["top_gainers"]),
+    ("losers", &["top_losers"]),
+    (
+        "totals",
+        &[
+            "total_market_cap",
+            "total_volume",
+            "total_stocks",
+            "index_level",
+        ],
+    ),
+    ("prices", &["prices"]),
+    ("sectors", &["sectors"]),
+];
+
+/// Request body for recording a bond
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordBondRequest {
+    pub code: String,
+    pub issuer: String,
+    /// `YYYY-MM-DD`
+    pub maturity: Option<NaiveDate>,
+    /// Annual yield in percent
+    pub yield_percent: f64,
+}
+
+/// Request body for recording a stock split
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordSplitRequest {
+    pub symbol: String,
+    /// `YYYY-MM-DD`, first trading day on the post-split basis
+    pub ex_date: NaiveDate,
+    /// Shares held after the split for each share held before
+    pub ratio: f64,
+}
+
+/// Request body for restoring a database backup
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RestoreBackupRequest {
+    /// Backup name as returned by `POST /api/admin/backup`
+    pub name: String,
+}
+
+/// Query parameters for listing stocks
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StockListQuery {
+    pub source: Option<DataSource>,
+    /// 1-based page number, defaults to 1
+    pub page: Option<usize>,
+    /// Defaults to 50, at most 500
+    pub page_size: Option<usize>,
+    #[serde(default)]
+    pub sort_by: StockSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Only stocks whose stored company sector matches, case-insensitively
+    pub sector: Option<String>,
+    /// Only stocks whose stored company industry matches, case-insensitively
+    pub industry: Option<String>,
+}
+
+/// Field the stock list is sorted by
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StockSortField {
+    Price,
+    Change,
+    Volume,
+    #[default]
+    Name,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+const DEFAULT_STOCK_PAGE_SIZE: usize = 50;
+const MAX_STOCK_PAGE_SIZE: usize = 500;
+
+/// Message a live stream client sends to choose the symbols it receives
+#[derive(Debug, Deserialize)]
+pub struct LiveSubscription {
+    /// Symbols to receive; empty for every symbol
+    #[serde(default)]
+    pub symbols: Vec<String>,
+}
+
+/// Query parameters for search requests
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    pub q: String,
+    /// `all`, `symbol`, `sector` or `company`, defaults to `all`
+    #[serde(default, rename = "type")]
+    #[param(value_type = Option<String>)]
+    pub search_type: SearchType,
 }
 
 /// API response wrapper
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    ApiResponseValue = ApiResponse<serde_json::Value>,
+    ApiResponseStatus = ApiResponse<HashMap<String, String>>,
+    ApiResponseEquityLiveList = ApiResponse<Vec<EquityLive>>,
+    ApiResponseEquity = ApiResponse<Equity>,
+    ApiResponseMarketSummary = ApiResponse<MarketSummary>,
+    ApiResponseReadiness = ApiResponse<Readiness>,
+)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Number of matching items across all pages, for paginated listings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    /// Items left out of a filtered listing because the data filtered on isn't stored for them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
+}
+
+/// Where the data in a stock response comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DataOrigin {
+    /// Kept current by scrapes of the upstream API
+    Live,
+    /// Served from storage while the upstream has not been reached, so best-effort
+    Degraded,
+}
+
+impl DataOrigin {
+    pub fn of(use_case: &FetchStockDataUseCase) -> Self {
+        if use_case.degraded_since().is_some() {
+            Self::Degraded
+        } else {
+            Self::Live
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ResponseMeta {
+    pub source: DataOrigin,
 }
 
 impl<T> ApiResponse<T> {
@@ -31,100 +421,583 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            total: None,
+            skipped: None,
+            meta: None,
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(message.into()),
+            total: None,
+            skipped: None,
+            meta: None,
+        }
+    }
+
+    pub fn with_total(mut self, total: usize) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    pub fn with_skipped(mut self, skipped: usize) -> Self {
+        self.skipped = Some(skipped);
+        self
+    }
+
+    pub fn with_source(mut self, source: DataOrigin) -> Self {
+        self.meta = Some(ResponseMeta { source });
+        self
+    }
+}
+
+/// Error response: a status with an `ApiResponse` error body saying what went wrong, so
+/// clients can tell a missing resource from bad input or a server failure
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+
+    /// A storage or upstream failure; the cause is logged by the handler, not returned
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    /// Log a failed repository call and report it as `internal` with `context` as the message
+    pub fn storage(context: &str, error: anyhow::Error) -> Self {
+        tracing::error!("{}: {}", context, error);
+        Self::internal(context)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(ApiResponse::<()>::error(self.message))).into_response()
+    }
+}
+
+/// Parse a comma-separated `symbols` parameter into unique uppercase symbols, rejecting lists
+/// longer than `max` before anything is read from storage
+pub fn parse_symbol_list(raw: &str, max: usize) -> Result<Vec<String>, ApiError> {
+    let mut symbols: Vec<String> = Vec::new();
+    for symbol in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let symbol = symbol.to_uppercase();
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
         }
+        if symbols.len() > max {
+            return Err(ApiError::bad_request(format!(
+                "Too many symbols: at most {} may be requested at once",
+                max
+            )));
+        }
+    }
+
+    if symbols.is_empty() {
+        return Err(ApiError::bad_request("At least one symbol is required"));
     }
+
+    Ok(symbols)
 }
 
-/// Handler for getting all stocks
+/// Handler for getting all stocks, one sorted page at a time
+#[utoipa::path(
+    get,
+    path = "/api/stocks",
+    tag = "stocks",
+    params(StockListQuery),
+    responses(
+        (
+            status = 200,
+            description = "One page of stocks with their latest live data",
+            body = ApiResponseEquityLiveList
+        ),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 400, description = "Invalid page or page size", body = ApiResponseValue),
+    )
+)]
 pub async fn get_all_stocks(
+    Query(params): Query<StockListQuery>,
+    headers: HeaderMap,
     use_case: Arc<GetStockDataUseCase>,
-) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, StatusCode> {
-    match use_case.get_all_latest_live_data().await {
-        Ok(data) => {
+    fetch_use_case: Arc<FetchStockDataUseCase>,
+) -> Result<Response, ApiError> {
+    let format = ResponseFormat::from_headers(&headers);
+
+    let page = params.page.unwrap_or(1);
+    let page_size = params.page_size.unwrap_or(DEFAULT_STOCK_PAGE_SIZE);
+    if page == 0 || page_size == 0 || page_size > MAX_STOCK_PAGE_SIZE {
+        return Err(ApiError::bad_request(format!(
+            "page must be at least 1 and page_size between 1 and {}",
+            MAX_STOCK_PAGE_SIZE
+        )));
+    }
+
+    // The origin is part of the key so leaving degraded mode changes the ETag
+    let origin = DataOrigin::of(&fetch_use_case);
+    let key = format!(
+        "/api/stocks?source={:?}&page={}&page_size={}&sort_by={:?}&order={:?}&sector={:?}&industry={:?}&origin={:?}",
+        params.source,
+        page,
+        page_size,
+        params.sort_by,
+        params.order,
+        params.sector,
+        params.industry,
+        origin
+    );
+    let use_case = use_case.as_ref();
+    let cached = use_case
+        .cached_response(&key, || async move {
+            let filtered = params.sector.is_some() || params.industry.is_some();
+            let (mut data, skipped) = if filtered {
+                use_case
+                    .get_live_data_by_classification(
+                        params.source,
+                        params.sector.as_deref(),
+                        params.industry.as_deref(),
+                    )
+                    .await
+            } else {
+                use_case
+                    .get_all_latest_live_data(params.source)
+                    .await
+                    .map(|data| (data, 0))
+            }
+            .map_err(|e| {
+                tracing::error!("Failed to get all stocks: {}", e);
+                ApiError::internal("Failed to get stocks")
+            })?;
+            data.sort_by(|a, b| {
+                let ordering = match params.sort_by {
+                    StockSortField::Price => a.price.total_cmp(&b.price),
+                    StockSortField::Change => a.change.total_cmp(&b.change),
+                    StockSortField::Volume => a.volume.cmp(&b.volume),
+                    StockSortField::Name => a.name.cmp(&b.name),
+                };
+                // Ties keep a stable order by name whichever way the list is sorted
+                let ordering = match params.order {
+                    SortOrder::Asc => ordering,
+                    SortOrder::Desc => ordering.reverse(),
+                };
+                ordering.then_with(|| a.name.cmp(&b.name))
+            });
+
+            let total = data.len();
             let stocks: Vec<serde_json::Value> = data
+                .into_iter()
+                .skip((page - 1).saturating_mul(page_size))
+                .take(page_size)
+                .map(|stock| serde_json::to_value(stock).unwrap())
+                .collect();
+            let mut response = ApiResponse::success(stocks)
+                .with_total(total)
+                .with_source(origin);
+            if filtered {
+                response = response.with_skipped(skipped);
+            }
+            Ok::<_, ApiError>(serde_json::to_value(response).unwrap())
+        })
+        .await?;
+
+    Ok(conditional_response(&headers, format, &cached))
+}
+
+/// Handler for listing sectors with the number of stocks in each
+#[utoipa::path(
+    get,
+    path = "/api/sectors",
+    tag = "stocks",
+    responses(
+        (
+            status = 200,
+            description = "Every sector with its stock count, and how many stocks have no sector",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_sectors(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    match use_case.get_sectors().await {
+        Ok(sectors) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(sectors).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to get sectors: {}", e);
+            Err(ApiError::internal("Failed to get sectors"))
+        }
+    }
+}
+
+/// Handler for getting the latest data of several stocks at once
+#[utoipa::path(
+    get,
+    path = "/api/stocks/batch",
+    tag = "stocks",
+    params(SymbolsQuery),
+    responses(
+        (
+            status = 200,
+            description = "Latest live data of each known symbol",
+            body = ApiResponseEquityLiveList
+        ),
+        (status = 400, description = "No symbols or too many", body = ApiResponseValue),
+    )
+)]
+pub async fn get_stocks_batch(
+    Query(params): Query<SymbolsQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    let symbols = parse_symbol_list(&params.symbols, use_case.max_batch_symbols())?;
+
+    match use_case.get_batch_latest_live_data(&symbols).await {
+        Ok(stocks) => {
+            let json_stocks: Vec<serde_json::Value> = stocks
                 .into_iter()
                 .map(|stock| serde_json::to_value(stock).unwrap())
                 .collect();
-            Ok(Json(ApiResponse::success(stocks)))
+            Ok(Json(ApiResponse::success(json_stocks)))
         }
         Err(e) => {
-            tracing::error!("Failed to get all stocks: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!("Failed to get batch stock data: {}", e);
+            Err(ApiError::internal("Failed to get stock data"))
         }
     }
 }
 
 /// Handler for getting a specific stock by symbol
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}",
+    tag = "stocks",
+    params(("symbol" = String, Path, description = "Stock symbol, case-insensitive")),
+    responses(
+        (
+            status = 200,
+            description = "Equity details with `metrics` and `live_data` added",
+            body = ApiResponseEquity
+        ),
+        (status = 304, description = "Unchanged since the `If-None-Match` ETag"),
+        (status = 404, description = "Unknown symbol"),
+    )
+)]
 pub async fn get_stock_by_symbol(
     Path(symbol): Path<String>,
+    headers: HeaderMap,
     use_case: Arc<GetStockDataUseCase>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
+    fetch_use_case: Arc<FetchStockDataUseCase>,
+) -> Result<Response, ApiError> {
+    let format = ResponseFormat::from_headers(&headers);
     let symbol_upper = symbol.to_uppercase();
     tracing::info!(
         "Request for stock symbol: {} (normalized: {})",
         symbol,
         symbol_upper
     );
-    // First check database
-    match use_case.get_symbol_data(&symbol_upper).await {
-        Ok(Some((equity, live_data))) => {
-            let mut response = serde_json::to_value(equity).unwrap();
-
-            if let Some(live) = live_data {
-                response["live_data"] = serde_json::to_value(live).unwrap();
-            }
-
-            Ok(Json(ApiResponse::success(response)))
-        }
-        Ok(None) => {
-            // If no equity data in DB, fetch from API on-demand
-            match use_case.fetch_fresh_equity_data(&symbol_upper).await {
-                Ok(equity) => {
-                    let live_data = use_case
-                        .get_latest_live_data(&symbol_upper)
-                        .await
-                        .ok()
-                        .flatten();
+    let origin = DataOrigin::of(&fetch_use_case);
+    let key = format!("/api/stocks/{}?origin={:?}", symbol_upper, origin);
+    let use_case = use_case.as_ref();
+    let cached = use_case
+        .cached_response(&key, || async move {
+            // First check database
+            match use_case.get_symbol_data(&symbol_upper).await {
+                Ok(Some((equity, live_data))) => {
+                    let metrics = equity.valuation_metrics();
                     let mut response = serde_json::to_value(equity).unwrap();
+                    response["metrics"] = serde_json::to_value(metrics).unwrap();
 
                     if let Some(live) = live_data {
                         response["live_data"] = serde_json::to_value(live).unwrap();
                     }
 
-                    Ok(Json(ApiResponse::success(response)))
+                    let response = ApiResponse::success(response).with_source(origin);
+                    Ok(serde_json::to_value(response).unwrap())
                 }
-                Err(_) => {
-                    // If API fetch fails, return just live data
-                    match use_case.get_latest_live_data(&symbol_upper).await {
-                        Ok(Some(live_data)) => {
-                            let response = serde_json::json!({
-                                "name": symbol_upper,
-                                "price": live_data.price,
-                                "live_data": live_data
-                            });
-                            Ok(Json(ApiResponse::success(response)))
+                Ok(None) => {
+                    // If no equity data in DB, fetch from API on-demand
+                    match use_case.fetch_fresh_equity_data(&symbol_upper).await {
+                        Ok(equity) => {
+                            let live_data = use_case
+                                .get_latest_live_data(&symbol_upper)
+                                .await
+                                .unwrap_or_else(|e| {
+                                    tracing::error!(
+                                        "Failed to get live data for {}: {}",
+                                        symbol_upper,
+                                        e
+                                    );
+                                    None
+                                });
+                            let metrics = equity.valuation_metrics();
+                            let mut response = serde_json::to_value(equity).unwrap();
+                            response["metrics"] = serde_json::to_value(metrics).unwrap();
+
+                            if let Some(live) = live_data {
+                                response["live_data"] = serde_json::to_value(live).unwrap();
+                            }
+
+                            let response = ApiResponse::success(response).with_source(origin);
+                            Ok(serde_json::to_value(response).unwrap())
                         }
-                        _ => {
-                            tracing::warn!("Stock not found: {}", symbol_upper);
-                            Err(StatusCode::NOT_FOUND)
+                        Err(e) => {
+                            tracing::warn!(
+                                "Failed to fetch equity data for {}, falling back to live data: {}",
+                                symbol_upper,
+                                e
+                            );
+                            // If API fetch fails, return just live data
+                            match use_case.get_latest_live_data(&symbol_upper).await {
+                                Ok(Some(live_data)) => {
+                                    let response = serde_json::json!({
+                                        "name": symbol_upper,
+                                        "price": live_data.price,
+                                        "live_data": live_data
+                                    });
+                                    let response =
+                                        ApiResponse::success(response).with_source(origin);
+                                    Ok(serde_json::to_value(response).unwrap())
+                                }
+                                Ok(None) => {
+                                    tracing::warn!("Stock not found: {}", symbol_upper);
+                                    Err(ApiError::not_found(format!(
+                                        "Unknown symbol: {}",
+                                        symbol_upper
+                                    )))
+                                }
+                                Err(e) => {
+                                    tracing::error!(
+                                        "Failed to get live data for {}: {}",
+                                        symbol_upper,
+                                        e
+                                    );
+                                    Err(ApiError::internal(format!(
+                                        "Failed to read stored data for {}",
+                                        symbol_upper
+                                    )))
+                                }
+                            }
                         }
                     }
                 }
+                Err(e) => {
+                    tracing::error!("Failed to get stock {}: {}", symbol, e);
+                    Err(ApiError::internal(format!(
+                        "Failed to read stored data for {}",
+                        symbol_upper
+                    )))
+                }
+            }
+        })
+        .await?;
+
+    Ok(conditional_response(&headers, format, &cached))
+}
+
+/// `304 Not Modified` if the request's `If-None-Match` names the cached response, the cached
+/// body in the negotiated format otherwise
+fn conditional_response(
+    headers: &HeaderMap,
+    format: ResponseFormat,
+    cached: &CachedResponse,
+) -> Response {
+    let not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|if_none_match| cached.matches(if_none_match));
+    let mut response = if not_modified {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        Negotiated::new(format, &cached.body).into_response()
+    };
+
+    if let Ok(etag) = HeaderValue::from_str(&cached.etag) {
+        response.headers_mut().insert(header::ETAG, etag);
+    }
+    // JSON and MessagePack bodies share an ETag, so caches must key on the format too
+    response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
+/// Handler upgrading to a WebSocket that pushes each batch of stored live data
+#[utoipa::path(
+    get,
+    path = "/api/ws/live",
+    tag = "stocks",
+    responses(
+        (
+            status = 101,
+            description = "Live data batches; send `{\"symbols\": [...]}` to filter them"
+        ),
+    )
+)]
+pub async fn live_updates_socket(
+    ws: WebSocketUpgrade,
+    use_case: Arc<FetchStockDataUseCase>,
+) -> Response {
+    let updates = use_case.subscribe_live_updates();
+    ws.on_upgrade(move |socket| stream_live_updates(socket, updates))
+}
+
+/// Seconds between keep-alive comments on the market summary stream
+const SUMMARY_STREAM_KEEP_ALIVE_SECS: u64 = 15;
+
+/// Handler streaming each newly generated market summary as a server-sent event
+#[utoipa::path(
+    get,
+    path = "/api/market/summary/stream",
+    tag = "market",
+    responses(
+        (
+            status = 200,
+            description = "Server-sent `summary` events, one per generated summary",
+            content_type = "text/event-stream",
+            body = MarketSummary
+        ),
+    )
+)]
+pub async fn stream_market_summary(use_case: Arc<FetchStockDataUseCase>) -> Response {
+    let events = BroadcastStream::new(use_case.subscribe_summary_updates()).filter_map(|update| {
+        // A client that can't keep up skips the summaries it missed
+        let summary = update.ok()?;
+        let event = Event::default()
+            .event("summary")
+            .id(summary.last_updated.to_rfc3339())
+            .json_data(summary.as_ref());
+        match event {
+            Ok(event) => Some(Ok::<_, std::convert::Infallible>(event)),
+            Err(e) => {
+                tracing::error!("Failed to serialize market summary event: {}", e);
+                None
             }
         }
-        Err(e) => {
-            tracing::error!("Failed to get stock {}: {}", symbol, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+    });
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(std::time::Duration::from_secs(
+            SUMMARY_STREAM_KEEP_ALIVE_SECS,
+        )))
+        .into_response()
+}
+
+/// Forward live updates to one client until either side closes. A client that can't keep up
+/// misses the oldest updates instead of holding back the fetch.
+async fn stream_live_updates(
+    mut socket: WebSocket,
+    mut updates: broadcast::Receiver<Arc<LiveUpdate>>,
+) {
+    // Every symbol until the client subscribes to a subset
+    let mut symbols: HashSet<String> = HashSet::new();
+
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(update) => {
+                    let data: Vec<&EquityLive> = update
+                        .data
+                        .iter()
+                        .filter(|data| {
+                            symbols.is_empty() || symbols.contains(&data.name.to_uppercase())
+                        })
+                        .collect();
+                    if data.is_empty() {
+                        continue;
+                    }
+
+                    let message = serde_json::json!({
+                        "timestamp": update.timestamp,
+                        "data": data,
+                    });
+                    if socket.send(Message::Text(message.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Live stream client lagged, skipped {} updates", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<LiveSubscription>(&text) {
+                        Ok(subscription) => {
+                            symbols = subscription
+                                .symbols
+                                .iter()
+                                .map(|symbol| symbol.trim().to_uppercase())
+                                .filter(|symbol| !symbol.is_empty())
+                                .collect();
+                        }
+                        Err(e) => {
+                            let error =
+                                ApiResponse::<()>::error(format!("Invalid subscription: {}", e));
+                            let text = serde_json::to_string(&error).unwrap();
+                            if socket.send(Message::Text(text)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum; other frames carry nothing to act on
+                Some(Ok(_)) => {}
+            },
         }
     }
 }
 
 /// Handler for getting historical data for a stock
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/history",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        HistoricalDataQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Stored points, or the comparison, adjusted series or analytics requested",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Unknown indicator"),
+    )
+)]
 pub async fn get_stock_history(
     Path(symbol): Path<String>,
     Query(params): Query<HistoricalDataQuery>,
+    headers: HeaderMap,
     use_case: Arc<GetStockDataUseCase>,
-) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, StatusCode> {
+) -> Result<Negotiated<ApiResponse<serde_json::Value>>, ApiError> {
+    let format = ResponseFormat::from_headers(&headers);
+
     // Parse date parameters
     let from = params
         .from
@@ -138,80 +1011,744 @@ pub async fn get_stock_history(
         .map(|dt| dt.with_timezone(&Utc))
         .unwrap_or_else(Utc::now);
 
-    match use_case.get_historical_data(&symbol, from, to).await {
+    if let Some(benchmark) = params.vs {
+        let (symbol_upper, benchmark_upper) = (symbol.to_uppercase(), benchmark.to_uppercase());
+        return match use_case
+            .get_benchmark_comparison(&symbol_upper, &benchmark_upper, from, to, params.source)
+            .await
+        {
+            Ok(points) => {
+                let response = serde_json::json!({
+                    "symbol": symbol_upper,
+                    "benchmark": benchmark_upper,
+                    "points": points,
+                });
+                Ok(Negotiated::new(format, ApiResponse::success(response)))
+            }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to compare {} against {}: {}",
+                    symbol_upper,
+                    benchmark_upper,
+                    e
+                );
+                Err(ApiError::internal(format!(
+                    "Failed to compare {} against {}",
+                    symbol_upper, benchmark_upper
+                )))
+            }
+        };
+    }
+
+    if let Some(AdjustedMode::Both) = params.adjusted {
+        let symbol_upper = symbol.to_uppercase();
+        return match use_case
+            .get_adjusted_history(&symbol_upper, from, to, params.source)
+            .await
+        {
+            Ok(history) => Ok(Negotiated::new(
+                format,
+                ApiResponse::success(serde_json::to_value(history).unwrap()),
+            )),
+            Err(e) => {
+                tracing::error!("Failed to get adjusted history for {}: {}", symbol_upper, e);
+                Err(ApiError::internal(format!(
+                    "Failed to get adjusted history for {}",
+                    symbol_upper
+                )))
+            }
+        };
+    }
+
+    let stats = match &params.indicators {
+        Some(indicators) => Some(
+            indicators
+                .split(',')
+                .filter(|s| !s.trim().is_empty())
+                .map(str::parse::<HistoryStat>)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| ApiError::bad_request("Unknown indicator"))?,
+        ),
+        None => None,
+    };
+
+    match use_case
+        .get_historical_data(&symbol, from, to, params.source)
+        .await
+    {
         Ok(data) => {
             let history: Vec<serde_json::Value> = data
                 .into_iter()
                 .map(|point| serde_json::to_value(point).unwrap())
                 .collect();
-            Ok(Json(ApiResponse::success(history)))
+            let Some(stats) = stats else {
+                return Ok(Negotiated::new(
+                    format,
+                    ApiResponse::success(serde_json::Value::Array(history)),
+                ));
+            };
+
+            let analytics = use_case
+                .get_history_stats(&symbol, &stats, to)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to compute history analytics for {}: {}", symbol, e);
+                    ApiError::internal(format!(
+                        "Failed to compute history analytics for {}",
+                        symbol
+                    ))
+                })?;
+            let response = serde_json::json!({
+                "points": history,
+                "analytics": analytics,
+            });
+            Ok(Negotiated::new(format, ApiResponse::success(response)))
         }
         Err(e) => {
             tracing::error!("Failed to get historical data for {}: {}", symbol, e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(ApiError::internal(format!(
+                "Failed to get historical data for {}",
+                symbol
+            )))
         }
     }
 }
 
-/// Handler for getting market summary
-pub async fn get_market_summary(
+/// Handler for OHLC candles of a stock's stored points
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/candles",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        CandleQuery,
+    ),
+    responses(
+        (status = 200, description = "OHLC candles per interval", body = ApiResponseValue),
+    )
+)]
+pub async fn get_candles(
+    Path(symbol): Path<String>,
+    Query(params): Query<CandleQuery>,
+    headers: HeaderMap,
     use_case: Arc<GetStockDataUseCase>,
-) -> Result<Json<ApiResponse<serde_json::Value>>, StatusCode> {
-    match use_case.get_latest_market_summary().await {
-        Ok(Some(summary)) => {
-            let response = serde_json::to_value(summary).unwrap();
-            Ok(Json(ApiResponse::success(response)))
-        }
-        Ok(None) => {
-            tracing::warn!("No market summary available");
-            Err(StatusCode::NOT_FOUND)
+) -> Result<Negotiated<ApiResponse<serde_json::Value>>, ApiError> {
+    let format = ResponseFormat::from_headers(&headers);
+    let symbol_upper = symbol.to_uppercase();
+    let from = params
+        .from
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc::now() - chrono::Duration::days(30));
+    let to = params
+        .to
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+
+    match use_case
+        .get_candles(&symbol_upper, from, to, params.interval)
+        .await
+    {
+        Ok(candles) => {
+            let response = serde_json::json!({
+                "symbol": symbol_upper,
+                "interval": params.interval,
+                "candles": candles,
+            });
+            Ok(Negotiated::new(format, ApiResponse::success(response)))
         }
         Err(e) => {
-            tracing::error!("Failed to get market summary: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            tracing::error!("Failed to get candles for {}: {}", symbol_upper, e);
+            Err(ApiError::internal(format!(
+                "Failed to get candles for {}",
+                symbol_upper
+            )))
         }
     }
 }
 
-/// Handler for manual data refresh trigger
-pub async fn trigger_data_refresh(
-    use_case: Arc<FetchStockDataUseCase>,
-) -> Result<Json<ApiResponse<HashMap<String, String>>>, StatusCode> {
-    // Run the scraping in a background task
-    let use_case_clone = use_case.clone();
-    tokio::spawn(async move {
-        if let Err(e) = use_case_clone.fetch_and_store_all_live_data().await {
-            tracing::error!("Background data refresh failed: {}", e);
-        } else {
-            tracing::info!("Background data refresh completed successfully");
+/// Handler for the synthetic bid/ask ladder around a stock's last price
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/ladder",
+    tag = "stocks",
+    params(("symbol" = String, Path, description = "Stock symbol, case-insensitive")),
+    responses(
+        (status = 200, description = "Synthetic bid/ask ladder", body = ApiResponseValue),
+        (status = 404, description = "No live data for the symbol"),
+    )
+)]
+pub async fn get_price_ladder(
+    Path(symbol): Path<String>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+
+    match use_case.get_price_ladder(&symbol_upper).await {
+        Ok(Some(ladder)) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(ladder).unwrap(),
+        ))),
+        Ok(None) => Err(ApiError::not_found(format!(
+            "No live data for {}",
+            symbol_upper
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to build price ladder for {}: {}", symbol_upper, e);
+            Err(ApiError::internal(format!(
+                "Failed to build price ladder for {}",
+                symbol_upper
+            )))
         }
-    });
+    }
+}
 
-    let mut response = HashMap::new();
-    response.insert("message".to_string(), "Data refresh triggered".to_string());
-    response.insert("status".to_string(), "started".to_string());
+/// Handler for computing a stock's share turnover ratio
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/turnover",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        TurnoverQuery,
+    ),
+    responses(
+        (status = 200, description = "Share turnover ratio", body = ApiResponseValue),
+        (status = 400, description = "Window outside 1 to 366 days"),
+    )
+)]
+pub async fn get_turnover(
+    Path(symbol): Path<String>,
+    Query(params): Query<TurnoverQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+    let days = params.days.unwrap_or(30);
+    if !(1..=366).contains(&days) {
+        return Err(ApiError::bad_request("days must be between 1 and 366"));
+    }
 
-    Ok(Json(ApiResponse::success(response)))
+    match use_case.get_turnover(&symbol_upper, days).await {
+        Ok(turnover) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(turnover).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to compute turnover for {}: {}", symbol_upper, e);
+            Err(ApiError::internal(format!(
+                "Failed to compute turnover for {}",
+                symbol_upper
+            )))
+        }
+    }
 }
 
-/// Handler for fetching all equity data (use sparingly due to rate limits)
-pub async fn trigger_equity_refresh(
-    use_case: Arc<FetchStockDataUseCase>,
-) -> Result<Json<ApiResponse<HashMap<String, String>>>, StatusCode> {
-    // Run in background with rate limiting
-    let use_case_clone = use_case.clone();
-    tokio::spawn(async move {
-        tracing::info!("Starting equity data refresh...");
-        if let Err(e) = use_case_clone.fetch_and_store_all_equity_data().await {
-            tracing::error!("Equity data refresh failed: {}", e);
-        } else {
-            tracing::info!("Equity data refresh completed successfully");
-            // Regenerate market summary with new data
-            if let Err(e) = use_case_clone.generate_and_store_market_summary().await {
-                tracing::error!("Market summary generation failed: {}", e);
-            }
-        }
-    });
+/// Handler for computing a stock's historical volatility cone
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/volatility-cone",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        VolatilityConeQuery,
+    ),
+    responses(
+        (status = 200, description = "Volatility percentiles per window", body = ApiResponseValue),
+        (status = 400, description = "Invalid windows"),
+    )
+)]
+pub async fn get_volatility_cone(
+    Path(symbol): Path<String>,
+    Query(params): Query<VolatilityConeQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+    let windows: Vec<usize> = match params.windows {
+        Some(windows) => windows
+            .split(',')
+            .map(|w| w.trim().parse::<usize>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| ApiError::bad_request("windows must be comma-separated day counts"))?,
+        None => vec![10, 20, 30, 60],
+    };
+    if windows.is_empty() || windows.iter().any(|w| !(2..=252).contains(w)) {
+        return Err(ApiError::bad_request(
+            "windows must each be between 2 and 252 days",
+        ));
+    }
+
+    match use_case.get_volatility_cone(&symbol_upper, &windows).await {
+        Ok(cone) => {
+            let omitted: Vec<usize> = windows
+                .iter()
+                .copied()
+                .filter(|w| !cone.iter().any(|c| c.window_days == *w))
+                .collect();
+            let response = serde_json::json!({
+                "symbol": symbol_upper,
+                "windows": cone,
+                "omitted_windows": omitted,
+            });
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to compute volatility cone for {}: {}",
+                symbol_upper,
+                e
+            );
+            Err(ApiError::internal(format!(
+                "Failed to compute volatility cone for {}",
+                symbol_upper
+            )))
+        }
+    }
+}
+
+/// Reject a series shorter than an endpoint needs with a uniform client error
+pub fn require_min_points<T>(series: &[T], n: usize) -> Result<(), ApiError> {
+    if series.len() < n {
+        return Err(ApiError::bad_request(format!(
+            "need at least {} points, have {}",
+            n,
+            series.len()
+        )));
+    }
+    Ok(())
+}
+
+/// The requested indicator period, or the indicator's default, rejecting periods outside 1 to 252
+fn indicator_period(indicator: Indicator, period: Option<usize>) -> Result<usize, ApiError> {
+    let period = period.unwrap_or(indicator.default_period());
+    if !(1..=252).contains(&period) {
+        return Err(ApiError::bad_request("period must be between 1 and 252"));
+    }
+    Ok(period)
+}
+
+async fn get_indicator_closes(
+    use_case: &GetStockDataUseCase,
+    symbol: &str,
+) -> Result<Vec<(NaiveDate, f64)>, ApiError> {
+    use_case.get_daily_closes(symbol).await.map_err(|e| {
+        tracing::error!("Failed to get daily closes for {}: {}", symbol, e);
+        ApiError::internal("Failed to get stock history")
+    })
+}
+
+/// Handler for computing a technical indicator over a stock's daily closes
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/indicators/{indicator}",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        ("indicator" = String, Path, description = "`sma`, `ema`, `rsi`, `macd` or `bollinger`"),
+        IndicatorQuery,
+    ),
+    responses(
+        (status = 200, description = "Indicator series over daily closes", body = ApiResponseValue),
+        (
+            status = 400,
+            description = "Invalid period or too short a history",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_indicator(
+    Path((symbol, indicator)): Path<(String, Indicator)>,
+    Query(params): Query<IndicatorQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+    let period = indicator_period(indicator, params.period)?;
+
+    let closes = get_indicator_closes(&use_case, &symbol_upper).await?;
+    require_min_points(&closes, indicator.min_points(period))?;
+
+    let response = serde_json::json!({
+        "symbol": symbol_upper,
+        "period": period,
+        "points": indicator.compute(&closes, period),
+    });
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Handler for computing a technical indicator named by the `type` parameter. Unlike
+/// `get_indicator`, too short a history yields an empty series with a note rather than an error.
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/indicators",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        IndicatorSeriesQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Indicator series, empty with a `note` when the history is too short",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid period", body = ApiResponseValue),
+    )
+)]
+pub async fn get_indicator_series(
+    Path(symbol): Path<String>,
+    Query(params): Query<IndicatorSeriesQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+    let indicator = params.indicator;
+    let period = indicator_period(indicator, params.period)?;
+
+    let closes = get_indicator_closes(&use_case, &symbol_upper).await?;
+    let min_points = indicator.min_points(period);
+    let mut response = serde_json::json!({
+        "symbol": symbol_upper,
+        "type": indicator,
+        "period": period,
+        "points": [],
+    });
+    if closes.len() < min_points {
+        response["note"] = serde_json::json!(format!(
+            "need at least {} daily closes, have {}",
+            min_points,
+            closes.len()
+        ));
+    } else {
+        response["points"] = serde_json::to_value(indicator.compute(&closes, period)).unwrap();
+    }
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Handler for getting market summary
+#[utoipa::path(
+    get,
+    path = "/api/market/summary",
+    tag = "market",
+    params(MarketSummaryQuery),
+    responses(
+        (
+            status = 200,
+            description = "Latest market summary, limited to the included parts",
+            body = ApiResponseMarketSummary
+        ),
+        (status = 400, description = "Unknown include part"),
+        (status = 404, description = "No summary generated yet"),
+    )
+)]
+pub async fn get_market_summary(
+    Query(params): Query<MarketSummaryQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    // Resolve the requested parts to the summary fields they keep
+    let fields: Option<Vec<&str>> = match &params.include {
+        Some(include) => {
+            let mut fields = vec!["last_updated"];
+            for part in include.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+                let (_, part_fields) = MARKET_SUMMARY_PARTS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(part))
+                    .ok_or_else(|| {
+                        ApiError::bad_request(format!("Unknown include part: {}", part))
+                    })?;
+                fields.extend_from_slice(part_fields);
+            }
+            Some(fields)
+        }
+        None => None,
+    };
+
+    // A source filter can't be answered from the stored summary, so compute it on the fly
+    let summary = match params.source {
+        Some(source) => use_case
+            .get_market_summary_for_source(source)
+            .await
+            .map(Some),
+        None => use_case.get_latest_market_summary().await,
+    };
+
+    match summary {
+        Ok(Some(summary)) => {
+            let mut response = serde_json::to_value(summary).unwrap();
+            if let (Some(fields), Some(object)) = (fields, response.as_object_mut()) {
+                object.retain(|key, _| fields.contains(&key.as_str()));
+            }
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Ok(None) => {
+            tracing::warn!("No market summary available");
+            Err(ApiError::not_found("No market summary generated yet"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get market summary: {}", e);
+            Err(ApiError::internal("Failed to get market summary"))
+        }
+    }
+}
+
+/// Handler for the latest market summary as preformatted strings for share cards. The numeric
+/// summary stays available unchanged from `/api/market/summary`.
+#[utoipa::path(
+    get,
+    path = "/api/market/summary/card",
+    tag = "market",
+    params(SummaryCardQuery),
+    responses(
+        (
+            status = 200,
+            description = "Latest market summary as preformatted strings",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Too many movers"),
+        (status = 404, description = "No summary generated yet"),
+    )
+)]
+pub async fn get_market_summary_card(
+    Query(params): Query<SummaryCardQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let movers = params.movers.unwrap_or(DEFAULT_CARD_MOVERS);
+    if movers > MAX_CARD_MOVERS {
+        return Err(ApiError::bad_request(format!(
+            "movers must be at most {}",
+            MAX_CARD_MOVERS
+        )));
+    }
+
+    match use_case.get_latest_market_summary().await {
+        Ok(Some(summary)) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(summary_card(&summary, movers)).unwrap(),
+        ))),
+        Ok(None) => {
+            tracing::warn!("No market summary available");
+            Err(ApiError::not_found("No market summary generated yet"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get market summary: {}", e);
+            Err(ApiError::internal("Failed to get market summary"))
+        }
+    }
+}
+
+/// Handler for replaying stored market summaries at a fixed step, for animating the market's
+/// evolution
+#[utoipa::path(
+    get,
+    path = "/api/market/summary/timeline",
+    tag = "market",
+    params(SummaryTimelineQuery),
+    responses(
+        (
+            status = 200,
+            description = "Stored summaries sampled at a fixed step",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid range or step"),
+    )
+)]
+pub async fn get_market_summary_timeline(
+    Query(params): Query<SummaryTimelineQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let parse = |value: Option<String>| {
+        value
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| ApiError::bad_request(format!("Invalid date: {}", s)))
+            })
+            .transpose()
+    };
+    let to = parse(params.to)?.unwrap_or_else(Utc::now);
+    let from = parse(params.from)?.unwrap_or_else(|| to - chrono::Duration::days(30)); // Default to 30 days before `to`
+    let step = match params.step.as_deref() {
+        Some(step) => parse_step(step)
+            .ok_or_else(|| ApiError::bad_request(format!("Invalid step: {}", step)))?,
+        None => chrono::Duration::days(1),
+    };
+    if from > to {
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+    if (to - from).num_seconds() / step.num_seconds() >= MAX_TIMELINE_POINTS {
+        return Err(ApiError::bad_request(format!(
+            "At most {} points may be requested; widen the step or narrow the range",
+            MAX_TIMELINE_POINTS
+        )));
+    }
+
+    match use_case.get_summary_timeline(from, to, step).await {
+        Ok(points) => {
+            let response = serde_json::json!({
+                "from": from,
+                "to": to,
+                "step_seconds": step.num_seconds(),
+                "points": points,
+            });
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to build market summary timeline: {}", e);
+            Err(ApiError::internal(
+                "Failed to build market summary timeline",
+            ))
+        }
+    }
+}
+
+/// Handler for comparing the market summaries nearest two timestamps
+#[utoipa::path(
+    get,
+    path = "/api/market/snapshot-diff",
+    tag = "market",
+    params(SnapshotDiffQuery),
+    responses(
+        (
+            status = 200,
+            description = "Changes between the summaries nearest both timestamps",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid timestamps"),
+        (status = 404, description = "No summaries stored"),
+    )
+)]
+pub async fn get_snapshot_diff(
+    Query(params): Query<SnapshotDiffQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let parse = |s: &str| {
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|_| ApiError::bad_request(format!("Invalid date: {}", s)))
+    };
+    let from = parse(&params.from)?;
+    let to = parse(&params.to)?;
+
+    match use_case.get_snapshot_diff(from, to).await {
+        Ok(Some(diff)) => {
+            let response = serde_json::to_value(diff).unwrap();
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Ok(None) => {
+            tracing::warn!("No market summaries available for snapshot diff");
+            Err(ApiError::not_found("No market summaries stored"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to compute snapshot diff: {}", e);
+            Err(ApiError::internal("Failed to compute snapshot diff"))
+        }
+    }
+}
+
+/// Handler for getting recorded market-wide move events
+#[utoipa::path(
+    get,
+    path = "/api/market/events",
+    tag = "market",
+    responses(
+        (status = 200, description = "Recorded market-wide moves", body = ApiResponseValue),
+    )
+)]
+pub async fn get_market_events(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    match use_case.get_market_events().await {
+        Ok(events) => {
+            let events: Vec<serde_json::Value> = events
+                .into_iter()
+                .map(|event| serde_json::to_value(event).unwrap())
+                .collect();
+            Ok(Json(ApiResponse::success(events)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get market events: {}", e);
+            Err(ApiError::internal("Failed to get market events"))
+        }
+    }
+}
+
+/// Error returned when a manual refresh is triggered while another scrape is running
+fn scrape_in_progress() -> ApiError {
+    ApiError::conflict("Another scrape is in progress, try again later")
+}
+
+/// Handler for manual data refresh trigger
+#[utoipa::path(
+    post,
+    path = "/api/admin/refresh",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Live data refresh started", body = ApiResponseStatus),
+        (status = 409, description = "Another scrape is running", body = ApiResponseValue),
+    )
+)]
+pub async fn trigger_data_refresh(
+    use_case: Arc<FetchStockDataUseCase>,
+) -> Result<Json<ApiResponse<HashMap<String, String>>>, ApiError> {
+    let scrape = use_case
+        .try_lock_scrapes()
+        .await
+        .ok_or_else(scrape_in_progress)?;
+
+    // Run the scraping in a background task
+    let use_case_clone = use_case.clone();
+    tokio::spawn(
+        async move {
+            let _scrape = scrape;
+            if let Err(e) = use_case_clone.fetch_and_store_all_live_data().await {
+                tracing::error!("Background data refresh failed: {}", e);
+            } else {
+                tracing::info!("Background data refresh completed successfully");
+            }
+        }
+        .in_current_span(),
+    );
+
+    let mut response = HashMap::new();
+    response.insert("message".to_string(), "Data refresh triggered".to_string());
+    response.insert("status".to_string(), "started".to_string());
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Handler for fetching all equity data (use sparingly due to rate limits)
+#[utoipa::path(
+    post,
+    path = "/api/admin/refresh-equity",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Equity data refresh started", body = ApiResponseStatus),
+        (status = 409, description = "Another scrape is running", body = ApiResponseValue),
+    )
+)]
+pub async fn trigger_equity_refresh(
+    use_case: Arc<FetchStockDataUseCase>,
+) -> Result<Json<ApiResponse<HashMap<String, String>>>, ApiError> {
+    let scrape = use_case
+        .try_lock_scrapes()
+        .await
+        .ok_or_else(scrape_in_progress)?;
+
+    // Run in background with rate limiting
+    let use_case_clone = use_case.clone();
+    tokio::spawn(
+        async move {
+            let _scrape = scrape;
+            tracing::info!("Starting equity data refresh...");
+            if let Err(e) = use_case_clone.fetch_and_store_all_equity_data().await {
+                tracing::error!("Equity data refresh failed: {}", e);
+            } else {
+                tracing::info!("Equity data refresh completed successfully");
+                // Regenerate market summary with new data
+                if let Err(e) = use_case_clone.generate_and_store_market_summary().await {
+                    tracing::error!("Market summary generation failed: {}", e);
+                }
+            }
+        }
+        .in_current_span(),
+    );
 
     let mut response = HashMap::new();
     response.insert(
@@ -223,11 +1760,1694 @@ pub async fn trigger_equity_refresh(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// Handler for streaming historical data for a stock as JSON lines
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/history/stream",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        HistoricalDataQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "One stored point per line",
+            content_type = "application/x-ndjson",
+            body = String
+        ),
+    )
+)]
+pub async fn stream_stock_history(
+    Path(symbol): Path<String>,
+    Query(params): Query<HistoricalDataQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Response {
+    let from = params
+        .from
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc::now() - chrono::Duration::days(30)); // Default to 30 days ago
+
+    let to = params
+        .to
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+
+    let symbol_upper = symbol.to_uppercase();
+    let points = use_case.stream_historical_data(symbol_upper.clone(), from, to, params.source);
+    let lines = ReceiverStream::new(points).map(move |point| match point {
+        Ok(point) => {
+            let mut line = serde_json::to_vec(&point).map_err(std::io::Error::other)?;
+            line.push(b'\n');
+            Ok(Bytes::from(line))
+        }
+        Err(e) => {
+            // Headers are already sent, so the error can only end the stream
+            tracing::error!(
+                "Failed to stream historical data for {}: {}",
+                symbol_upper,
+                e
+            );
+            Err(std::io::Error::other(e.to_string()))
+        }
+    });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
+
+/// One row of the CSV history export
+#[derive(Serialize)]
+struct HistoryCsvRow {
+    timestamp: DateTime<Utc>,
+    price: f64,
+    volume: Option<i64>,
+}
+
+/// Handler for downloading historical data for a stock as CSV, streamed row by row
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/history.csv",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        HistoricalDataQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Stored points as `timestamp,price,volume` rows",
+            content_type = "text/csv",
+            body = String
+        ),
+    )
+)]
+pub async fn stream_stock_history_csv(
+    Path(symbol): Path<String>,
+    Query(params): Query<HistoricalDataQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Response {
+    let from = params
+        .from
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc::now() - chrono::Duration::days(30)); // Default to 30 days ago
+
+    let to = params
+        .to
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(Utc::now);
+
+    let symbol_upper = symbol.to_uppercase();
+    let filename = format!(
+        "{}_{}_{}.csv",
+        symbol_upper,
+        from.format("%Y%m%d"),
+        to.format("%Y%m%d")
+    );
+    let points = use_case.stream_historical_data(symbol_upper.clone(), from, to, params.source);
+    let rows = ReceiverStream::new(points).map(move |point| match point {
+        Ok(point) => {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(Vec::new());
+            writer
+                .serialize(HistoryCsvRow {
+                    timestamp: point.timestamp,
+                    price: point.value,
+                    volume: point.volume,
+                })
+                .map_err(std::io::Error::other)?;
+            let row = writer.into_inner().map_err(std::io::Error::other)?;
+            Ok(Bytes::from(row))
+        }
+        Err(e) => {
+            // Headers are already sent, so the error can only end the stream
+            tracing::error!("Failed to stream CSV history for {}: {}", symbol_upper, e);
+            Err(std::io::Error::other(e.to_string()))
+        }
+    });
+    let header_row = tokio_stream::once(Ok(Bytes::from_static(b"timestamp,price,volume\n")));
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(header_row.chain(rows)),
+    )
+        .into_response()
+}
+
+/// Handler for getting today's intraday tick series for a stock
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/intraday",
+    tag = "stocks",
+    params(("symbol" = String, Path, description = "Stock symbol, case-insensitive")),
+    responses(
+        (status = 200, description = "Today's intraday ticks", body = ApiResponseValue),
+    )
+)]
+pub async fn get_stock_intraday(
+    Path(symbol): Path<String>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+
+    match use_case.get_intraday_data(&symbol_upper).await {
+        Ok(points) => {
+            let response = serde_json::json!({
+                "symbol": symbol_upper,
+                "points": points,
+            });
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get intraday data for {}: {}", symbol_upper, e);
+            Err(ApiError::internal(format!(
+                "Failed to get intraday data for {}",
+                symbol_upper
+            )))
+        }
+    }
+}
+
+/// Handler for the latest market summary broken down by sector
+#[utoipa::path(
+    get,
+    path = "/api/market/sectors",
+    tag = "market",
+    responses(
+        (
+            status = 200,
+            description = "Volume, average change, market cap and top mover of each sector",
+            body = ApiResponseValue
+        ),
+        (status = 404, description = "No summary generated yet"),
+    )
+)]
+pub async fn get_market_sectors(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    match use_case.get_sector_summaries().await {
+        Ok(Some(sectors)) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(sectors).unwrap(),
+        ))),
+        Ok(None) => {
+            tracing::warn!("No market summary available");
+            Err(ApiError::not_found("No market summary generated yet"))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get sector summaries: {}", e);
+            Err(ApiError::internal("Failed to get sector summaries"))
+        }
+    }
+}
+
+/// Handler for market breadth indicators
+#[utoipa::path(
+    get,
+    path = "/api/market/breadth",
+    tag = "market",
+    responses(
+        (
+            status = 200,
+            description = "Advancers, decliners and related breadth figures",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_market_breadth(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    match use_case.get_market_breadth().await {
+        Ok(breadth) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(breadth).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to compute market breadth: {}", e);
+            Err(ApiError::internal("Failed to compute market breadth"))
+        }
+    }
+}
+
+/// Handler for stocks trading at a multiple of their average daily volume
+#[utoipa::path(
+    get,
+    path = "/api/market/volume-alerts",
+    tag = "market",
+    params(VolumeAlertQuery),
+    responses(
+        (
+            status = 200,
+            description = "Stocks trading at a multiple of their average volume",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Threshold not positive"),
+    )
+)]
+pub async fn get_volume_alerts(
+    Query(params): Query<VolumeAlertQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let threshold = params.threshold.unwrap_or(DEFAULT_VOLUME_ALERT_THRESHOLD);
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(ApiError::bad_request("threshold must be positive"));
+    }
+
+    match use_case.get_volume_alerts(threshold).await {
+        Ok(alerts) => {
+            let response = serde_json::json!({
+                "threshold": threshold,
+                "alerts": alerts,
+            });
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to compute volume alerts: {}", e);
+            Err(ApiError::internal("Failed to compute volume alerts"))
+        }
+    }
+}
+
+/// Handler for ranking all stocks by total return over a window
+#[utoipa::path(
+    get,
+    path = "/api/market/relative-strength",
+    tag = "analysis",
+    params(RelativeStrengthQuery),
+    responses(
+        (
+            status = 200,
+            description = "Stocks ranked by total return over the window",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid range"),
+    )
+)]
+pub async fn get_relative_strength(
+    Query(params): Query<RelativeStrengthQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let parse = |value: Option<String>| {
+        value
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| ApiError::bad_request(format!("Invalid date: {}", s)))
+            })
+            .transpose()
+    };
+    let to = parse(params.to)?.unwrap_or_else(Utc::now);
+    let from = parse(params.from)?.unwrap_or_else(|| to - chrono::Duration::days(30)); // Default to 30 days before `to`
+    if from >= to {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    match use_case.get_relative_strength(from, to).await {
+        Ok((leaderboard, excluded)) => {
+            let response = serde_json::json!({
+                "from": from,
+                "to": to,
+                "leaderboard": leaderboard,
+                "excluded_count": excluded,
+            });
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to compute relative strength: {}", e);
+            Err(ApiError::internal("Failed to compute relative strength"))
+        }
+    }
+}
+
+/// Handler for the pairwise correlation matrix of several symbols' daily returns
+#[utoipa::path(
+    post,
+    path = "/api/analysis/correlation",
+    tag = "analysis",
+    request_body = CorrelationRequest,
+    responses(
+        (
+            status = 200,
+            description = "Pairwise correlation of daily returns",
+            body = ApiResponseValue
+        ),
+        (
+            status = 400,
+            description = "Too few or too many symbols, or an invalid range",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_correlation_matrix(
+    use_case: Arc<GetStockDataUseCase>,
+    Json(payload): Json<CorrelationRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let max_symbols = MAX_CORRELATION_SYMBOLS.min(use_case.max_batch_symbols());
+    let symbols = parse_symbol_list(&payload.symbols.join(","), max_symbols)?;
+    if symbols.len() < 2 {
+        return Err(ApiError::bad_request("At least two symbols are required"));
+    }
+
+    let parse = |value: Option<String>| {
+        value
+            .map(|s| {
+                DateTime::parse_from_rfc3339(&s)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .map_err(|_| ApiError::bad_request(format!("Invalid date: {}", s)))
+            })
+            .transpose()
+    };
+    let to = parse(payload.to)?.unwrap_or_else(Utc::now);
+    let from = parse(payload.from)?.unwrap_or_else(|| to - chrono::Duration::days(90));
+    if from >= to {
+        return Err(ApiError::bad_request("from must be before to"));
+    }
+
+    match use_case.get_correlation_matrix(&symbols, from, to).await {
+        Ok(matrix) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(matrix).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to compute correlation matrix: {}", e);
+            Err(ApiError::internal("Failed to compute correlation matrix"))
+        }
+    }
+}
+
+/// Longest range the trading calendar endpoint will enumerate
+const MAX_CALENDAR_DAYS: i64 = 366 * 5;
+
+/// Handler for listing trading and non-trading days
+#[utoipa::path(
+    get,
+    path = "/api/market/calendar",
+    tag = "market",
+    params(CalendarQuery),
+    responses(
+        (
+            status = 200,
+            description = "Trading and non-trading days in the range",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid range"),
+    )
+)]
+pub async fn get_trading_calendar(
+    Query(params): Query<CalendarQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<CalendarDay>>>, ApiError> {
+    let parse = |value: Option<String>| {
+        value
+            .map(|s| {
+                NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                    .map_err(|_| ApiError::bad_request(format!("Invalid date: {}", s)))
+            })
+            .transpose()
+    };
+    let from = parse(params.from)?.unwrap_or_else(|| Utc::now().date_naive());
+    let to = parse(params.to)?.unwrap_or(from + chrono::Duration::days(30));
+    if to < from {
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+    if (to - from).num_days() > MAX_CALENDAR_DAYS {
+        return Err(ApiError::bad_request(format!(
+            "The range may span at most {} days",
+            MAX_CALENDAR_DAYS
+        )));
+    }
+
+    Ok(Json(ApiResponse::success(
+        use_case.get_trading_calendar(from, to),
+    )))
+}
+
+/// Parse an announcement date range, defaulting to the 90 days up to today
+fn announcement_range(params: AnnouncementQuery) -> Result<(NaiveDate, NaiveDate), ApiError> {
+    let parse = |value: Option<String>| {
+        value
+            .map(|s| {
+                NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                    .map_err(|_| ApiError::bad_request(format!("Invalid date: {}", s)))
+            })
+            .transpose()
+    };
+    let to = parse(params.to)?.unwrap_or_else(|| Utc::now().date_naive());
+    let from = parse(params.from)?.unwrap_or(to - chrono::Duration::days(90));
+    if to < from {
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+    Ok((from, to))
+}
+
+/// Handler for listing a stock's announcements
+#[utoipa::path(
+    get,
+    path = "/api/stocks/{symbol}/announcements",
+    tag = "stocks",
+    params(
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+        AnnouncementQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "The stock's announcements in the range",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid range"),
+    )
+)]
+pub async fn get_stock_announcements(
+    Path(symbol): Path<String>,
+    Query(params): Query<AnnouncementQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<Announcement>>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+    let (from, to) = announcement_range(params)?;
+
+    match use_case
+        .get_announcements(Some(&symbol_upper), from, to)
+        .await
+    {
+        Ok(announcements) => Ok(Json(ApiResponse::success(announcements))),
+        Err(e) => {
+            tracing::error!("Failed to get announcements for {}: {}", symbol_upper, e);
+            Err(ApiError::internal(format!(
+                "Failed to get announcements for {}",
+                symbol_upper
+            )))
+        }
+    }
+}
+
+/// Handler for listing announcements across the market
+#[utoipa::path(
+    get,
+    path = "/api/market/announcements",
+    tag = "market",
+    params(AnnouncementQuery),
+    responses(
+        (
+            status = 200,
+            description = "Announcements across the market in the range",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Invalid range"),
+    )
+)]
+pub async fn get_market_announcements(
+    Query(params): Query<AnnouncementQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<Announcement>>>, ApiError> {
+    let (from, to) = announcement_range(params)?;
+
+    match use_case.get_announcements(None, from, to).await {
+        Ok(announcements) => Ok(Json(ApiResponse::success(announcements))),
+        Err(e) => {
+            tracing::error!("Failed to get announcements: {}", e);
+            Err(ApiError::internal("Failed to get announcements"))
+        }
+    }
+}
+
+/// Handler for recording a company announcement
+#[utoipa::path(
+    post,
+    path = "/api/admin/announcements",
+    tag = "admin",
+    security(("admin_key" = [])),
+    request_body = RecordAnnouncementRequest,
+    responses(
+        (status = 200, description = "Recorded announcement", body = ApiResponseValue),
+        (status = 400, description = "Missing symbol or title"),
+    )
+)]
+pub async fn record_announcement(
+    use_case: Arc<FetchStockDataUseCase>,
+    Json(payload): Json<RecordAnnouncementRequest>,
+) -> Result<Json<ApiResponse<Announcement>>, ApiError> {
+    if payload.symbol.trim().is_empty() || payload.title.trim().is_empty() {
+        return Err(ApiError::bad_request("symbol and title are required"));
+    }
+
+    match use_case
+        .record_announcement(
+            payload.symbol.trim(),
+            payload.date,
+            payload.title,
+            payload.body,
+            payload.category,
+        )
+        .await
+    {
+        Ok(announcement) => Ok(Json(ApiResponse::success(announcement))),
+        Err(e) => {
+            tracing::error!("Failed to record announcement: {}", e);
+            Err(ApiError::internal("Failed to record announcement"))
+        }
+    }
+}
+
+/// Handler for the bond yield curve
+#[utoipa::path(
+    get,
+    path = "/api/bonds/yield-curve",
+    tag = "market",
+    responses(
+        (status = 200, description = "Bond yields by maturity", body = ApiResponseValue),
+    )
+)]
+pub async fn get_yield_curve(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    match use_case.get_yield_curve().await {
+        Ok(curve) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(curve).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to compute yield curve: {}", e);
+            Err(ApiError::internal("Failed to compute yield curve"))
+        }
+    }
+}
+
+/// Handler for recording a bond's yield
+#[utoipa::path(
+    post,
+    path = "/api/admin/bonds",
+    tag = "admin",
+    security(("admin_key" = [])),
+    request_body = RecordBondRequest,
+    responses(
+        (status = 200, description = "Recorded bond", body = ApiResponseValue),
+        (status = 400, description = "Missing code or invalid yield"),
+    )
+)]
+pub async fn record_bond(
+    use_case: Arc<FetchStockDataUseCase>,
+    Json(payload): Json<RecordBondRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    if payload.code.trim().is_empty() {
+        return Err(ApiError::bad_request("code is required"));
+    }
+    if !payload.yield_percent.is_finite() {
+        return Err(ApiError::bad_request("yield_percent must be a number"));
+    }
+
+    match use_case
+        .record_bond(
+            payload.code.trim(),
+            payload.issuer,
+            payload.maturity,
+            payload.yield_percent,
+        )
+        .await
+    {
+        Ok(bond) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(bond).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to record bond: {}", e);
+            Err(ApiError::internal("Failed to record bond"))
+        }
+    }
+}
+
+/// Handler for recording a stock split
+#[utoipa::path(
+    post,
+    path = "/api/admin/splits",
+    tag = "admin",
+    security(("admin_key" = [])),
+    request_body = RecordSplitRequest,
+    responses(
+        (status = 200, description = "Recorded split", body = ApiResponseValue),
+        (status = 400, description = "Missing symbol or invalid ratio"),
+    )
+)]
+pub async fn record_split(
+    use_case: Arc<FetchStockDataUseCase>,
+    Json(payload): Json<RecordSplitRequest>,
+) -> Result<Json<ApiResponse<StockSplit>>, ApiError> {
+    if payload.symbol.trim().is_empty() {
+        return Err(ApiError::bad_request("symbol is required"));
+    }
+    if !payload.ratio.is_finite() || payload.ratio <= 0.0 {
+        return Err(ApiError::bad_request("ratio must be positive"));
+    }
+
+    match use_case
+        .record_split(payload.symbol.trim(), payload.ex_date, payload.ratio)
+        .await
+    {
+        Ok(split) => Ok(Json(ApiResponse::success(split))),
+        Err(e) => {
+            tracing::error!("Failed to record split: {}", e);
+            Err(ApiError::internal("Failed to record split"))
+        }
+    }
+}
+
+/// Handler for backing the database up to a checkpoint, safe while the worker is writing
+#[utoipa::path(
+    post,
+    path = "/api/admin/backup",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Backup name, path and size", body = ApiResponseValue),
+    )
+)]
+pub async fn create_backup(
+    backups: Arc<dyn DatabaseBackup + Send + Sync>,
+) -> Result<Json<ApiResponse<BackupInfo>>, ApiError> {
+    match backups.create_backup().await {
+        Ok(backup) => {
+            tracing::info!(
+                "Created database backup {} ({} bytes)",
+                backup.path,
+                backup.size_bytes
+            );
+            Ok(Json(ApiResponse::success(backup)))
+        }
+        Err(e) => Err(ApiError::storage("Failed to create database backup", e)),
+    }
+}
+
+/// Handler for restoring a backup. The open database can't be swapped out, so the restore is
+/// staged and applied when the service next starts.
+#[utoipa::path(
+    post,
+    path = "/api/admin/restore",
+    tag = "admin",
+    security(("admin_key" = [])),
+    request_body = RestoreBackupRequest,
+    responses(
+        (status = 202, description = "Restore staged for the next restart", body = ApiResponseValue),
+        (status = 404, description = "No such backup"),
+    )
+)]
+pub async fn restore_backup(
+    backups: Arc<dyn DatabaseBackup + Send + Sync>,
+    Json(payload): Json<RestoreBackupRequest>,
+) -> Result<(StatusCode, Json<ApiResponse<BackupInfo>>), ApiError> {
+    match backups.schedule_restore(payload.name.trim()).await {
+        Ok(Some(backup)) => {
+            tracing::warn!(
+                "Staged restore of {}; it applies on the next restart",
+                backup.path
+            );
+            Ok((StatusCode::ACCEPTED, Json(ApiResponse::success(backup))))
+        }
+        Ok(None) => Err(ApiError::not_found(format!(
+            "No backup named {}",
+            payload.name.trim()
+        ))),
+        Err(e) => Err(ApiError::storage("Failed to stage database restore", e)),
+    }
+}
+
+/// Handler for searching symbols, sectors and companies
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    tag = "stocks",
+    params(SearchQuery),
+    responses(
+        (
+            status = 200,
+            description = "Matching symbols, sectors or companies",
+            body = ApiResponseValue
+        ),
+        (status = 400, description = "Empty query"),
+    )
+)]
+pub async fn search(
+    Query(params): Query<SearchQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    if params.q.trim().is_empty() {
+        return Err(ApiError::bad_request("q must not be empty"));
+    }
+
+    match use_case.search(&params.q, params.search_type).await {
+        Ok(results) => {
+            let json_results: Vec<serde_json::Value> = results
+                .into_iter()
+                .map(|result| serde_json::to_value(result).unwrap())
+                .collect();
+            Ok(Json(ApiResponse::success(json_results)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to search for {:?}: {}", params.q, e);
+            Err(ApiError::internal("Failed to search"))
+        }
+    }
+}
+
+/// Handler for recomputing derived per-symbol metrics in bulk
+#[utoipa::path(
+    post,
+    path = "/api/admin/recompute-metrics",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Recompute started", body = ApiResponseStatus),
+        (status = 409, description = "A recompute is already running"),
+    )
+)]
+pub async fn trigger_metrics_recompute(
+    use_case: Arc<FetchStockDataUseCase>,
+    status: Arc<WorkerStatus>,
+) -> Result<Json<ApiResponse<HashMap<String, String>>>, ApiError> {
+    // Only one recompute at a time; progress is reported through the worker status
+    if !status.try_start_job(|s| &mut s.recompute_metrics) {
+        return Err(ApiError::conflict("A metrics recompute is already running"));
+    }
+
+    tokio::spawn(
+        async move {
+            if let Err(e) = use_case.recompute_metrics(&status).await {
+                tracing::error!("Metrics recompute failed: {}", e);
+            }
+        }
+        .in_current_span(),
+    );
+
+    let mut response = HashMap::new();
+    response.insert(
+        "message".to_string(),
+        "Metrics recompute triggered (progress at /api/admin/worker-status)".to_string(),
+    );
+    response.insert("status".to_string(), "started".to_string());
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Handler for listing the most recent completed scrape cycles
+#[utoipa::path(
+    get,
+    path = "/api/admin/scrape-history",
+    tag = "admin",
+    security(("admin_key" = [])),
+    params(ScrapeHistoryQuery),
+    responses(
+        (
+            status = 200,
+            description = "Most recent completed scrape cycles",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_scrape_history(
+    Query(params): Query<ScrapeHistoryQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    let limit = params.limit.unwrap_or(20).clamp(1, 1000);
+
+    match use_case.get_scrape_history(limit).await {
+        Ok(cycles) => {
+            let cycles: Vec<serde_json::Value> = cycles
+                .into_iter()
+                .map(|cycle| serde_json::to_value(cycle).unwrap())
+                .collect();
+            Ok(Json(ApiResponse::success(cycles)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get scrape history: {}", e);
+            Err(ApiError::internal("Failed to get scrape history"))
+        }
+    }
+}
+
+/// Handler for dumping every stored record of a single symbol
+#[utoipa::path(
+    get,
+    path = "/api/admin/stocks/{symbol}/dump",
+    tag = "admin",
+    security(("admin_key" = [])),
+    params(("symbol" = String, Path, description = "Stock symbol, case-insensitive")),
+    responses(
+        (
+            status = 200,
+            description = "Every stored record of the symbol, grouped by type",
+            body = ApiResponseValue
+        ),
+        (status = 404, description = "No stored records"),
+    )
+)]
+pub async fn dump_stock_records(
+    Path(symbol): Path<String>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let symbol_upper = symbol.to_uppercase();
+
+    match use_case.get_symbol_records(&symbol_upper).await {
+        Ok(records) if records.is_empty() => {
+            tracing::warn!("No stored records for symbol: {}", symbol_upper);
+            Err(ApiError::not_found(format!(
+                "No stored records for {}",
+                symbol_upper
+            )))
+        }
+        Ok(records) => {
+            let mut grouped: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
+            let count = records.len();
+            for record in records {
+                grouped
+                    .entry(record.record_type.clone())
+                    .or_default()
+                    .push(serde_json::to_value(record).unwrap());
+            }
+
+            let response = serde_json::json!({
+                "symbol": symbol_upper,
+                "count": count,
+                "records": grouped,
+            });
+            Ok(Json(ApiResponse::success(response)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to dump records for {}: {}", symbol_upper, e);
+            Err(ApiError::internal(format!(
+                "Failed to dump records for {}",
+                symbol_upper
+            )))
+        }
+    }
+}
+
+/// Handler for listing symbols that stopped updating within their freshness SLA
+#[utoipa::path(
+    get,
+    path = "/api/admin/stale-symbols",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Symbols past their freshness SLA", body = ApiResponseValue),
+    )
+)]
+pub async fn get_stale_symbols(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    match use_case.get_stale_symbols().await {
+        Ok(stale) => Ok(Json(ApiResponse::success(
+            serde_json::to_value(stale).unwrap(),
+        ))),
+        Err(e) => {
+            tracing::error!("Failed to compute stale symbols: {}", e);
+            Err(ApiError::internal("Failed to compute stale symbols"))
+        }
+    }
+}
+
+/// Handler for listing the symbols most often requested on demand
+#[utoipa::path(
+    get,
+    path = "/api/admin/popular-symbols",
+    tag = "admin",
+    security(("admin_key" = [])),
+    params(PopularSymbolsQuery),
+    responses(
+        (status = 200, description = "Most requested symbols", body = ApiResponseValue),
+        (status = 400, description = "Limit outside 1 to 100"),
+    )
+)]
+pub async fn get_popular_symbols(
+    Query(params): Query<PopularSymbolsQuery>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
+    let limit = params.limit.unwrap_or(10);
+    if !(1..=100).contains(&limit) {
+        return Err(ApiError::bad_request("limit must be between 1 and 100"));
+    }
+
+    Ok(Json(ApiResponse::success(
+        serde_json::to_value(use_case.get_popular_symbols(limit)).unwrap(),
+    )))
+}
+
+/// Handler for listing queued and failed webhook deliveries
+#[utoipa::path(
+    get,
+    path = "/api/admin/deliveries",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (
+            status = 200,
+            description = "Queued and failed webhook deliveries",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_deliveries(
+    use_case: Arc<GetStockDataUseCase>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, ApiError> {
+    match use_case.get_deliveries().await {
+        Ok(deliveries) => {
+            let json_deliveries: Vec<serde_json::Value> = deliveries
+                .into_iter()
+                .map(|delivery| serde_json::to_value(delivery).unwrap())
+                .collect();
+            Ok(Json(ApiResponse::success(json_deliveries)))
+        }
+        Err(e) => {
+            tracing::error!("Failed to get webhook deliveries: {}", e);
+            Err(ApiError::internal("Failed to get webhook deliveries"))
+        }
+    }
+}
+
+/// Handler for reporting the background worker's status
+#[utoipa::path(
+    get,
+    path = "/api/admin/worker-status",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Background worker status", body = ApiResponseValue),
+    )
+)]
+pub async fn get_worker_status(status: Arc<WorkerStatus>) -> Json<ApiResponse<serde_json::Value>> {
+    let response = serde_json::to_value(status.snapshot()).unwrap();
+    Json(ApiResponse::success(response))
+}
+
+/// Handler for reporting the configuration the service loaded, with secrets redacted
+#[utoipa::path(
+    get,
+    path = "/api/admin/config",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (
+            status = 200,
+            description = "Loaded configuration with secrets redacted",
+            body = ApiResponseValue
+        ),
+    )
+)]
+pub async fn get_runtime_config(config: Arc<RuntimeConfig>) -> Json<ApiResponse<RuntimeConfig>> {
+    Json(ApiResponse::success(config.as_ref().clone()))
+}
+
+/// Handler exporting operational metrics in the Prometheus text format
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses(
+        (
+            status = 200,
+            description = "Prometheus metrics",
+            content_type = "text/plain",
+            body = String
+        ),
+    )
+)]
+pub async fn get_metrics(
+    metrics: Arc<dyn MetricsRecorder + Send + Sync>,
+    use_case: Arc<GetStockDataUseCase>,
+) -> Response {
+    match use_case.count_symbols().await {
+        Ok(count) => metrics.set_gauge("gse_stored_symbols", count as f64),
+        Err(e) => tracing::error!("Failed to count stored symbols for metrics: {}", e),
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.render(),
+    )
+        .into_response()
+}
+
+/// Handler for reporting per-endpoint request latency
+#[utoipa::path(
+    get,
+    path = "/api/admin/latency",
+    tag = "admin",
+    security(("admin_key" = [])),
+    responses(
+        (status = 200, description = "Request latency per endpoint", body = ApiResponseValue),
+    )
+)]
+pub async fn get_latency_stats(
+    histogram: Arc<LatencyHistogram>,
+) -> Json<ApiResponse<Vec<EndpointLatency>>> {
+    Json(ApiResponse::success(histogram.snapshot()))
+}
+
 /// Handler for health check
-pub async fn health_check() -> Json<ApiResponse<HashMap<String, String>>> {
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (
+            status = 200,
+            description = "Service and upstream circuit status",
+            body = ApiResponseStatus
+        ),
+    )
+)]
+pub async fn health_check(
+    use_case: Arc<FetchStockDataUseCase>,
+) -> Json<ApiResponse<HashMap<String, String>>> {
+    let circuit = use_case.upstream_circuit_state();
+    // The API still serves stored data while the upstream is cut off
+    let status = if circuit == CircuitState::Closed {
+        "healthy"
+    } else {
+        "degraded"
+    };
+
     let mut response = HashMap::new();
-    response.insert("status".to_string(), "healthy".to_string());
+    response.insert("status".to_string(), status.to_string());
+    response.insert("upstream_circuit".to_string(), circuit.to_string());
     response.insert("timestamp".to_string(), Utc::now().to_rfc3339());
 
     Json(ApiResponse::success(response))
 }
+
+/// Readiness of the service to serve traffic
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub source: DataOrigin,
+    /// When the service entered degraded mode, while it is in it
+    pub degraded_since: Option<DateTime<Utc>>,
+}
+
+/// Handler for readiness checks. A degraded service is still ready, since it keeps serving
+/// stored data, but clients are told that data is best-effort.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    responses(
+        (
+            status = 200,
+            description = "Readiness and whether data is live or degraded",
+            body = ApiResponseReadiness
+        ),
+    )
+)]
+pub async fn readiness_check(use_case: Arc<FetchStockDataUseCase>) -> Json<ApiResponse<Readiness>> {
+    let degraded_since = use_case.degraded_since();
+    Json(ApiResponse::success(Readiness {
+        ready: true,
+        source: if degraded_since.is_some() {
+            DataOrigin::Degraded
+        } else {
+            DataOrigin::Live
+        },
+        degraded_since,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{FetchConfig, QueryConfig, RecentlyRequested, ResponseCache};
+    use crate::domain::StockRepository;
+    use crate::infrastructure::test_support::{live, MockGseApiClient, TempDb};
+    use crate::infrastructure::RocksDbStockRepository;
+    use chrono::TimeZone;
+
+    /// Use cases over a fresh database, sharing its repository and response cache
+    struct Fixture {
+        _temp: TempDb,
+        repository: Arc<RocksDbStockRepository>,
+        api: Arc<MockGseApiClient>,
+        get_use_case: Arc<GetStockDataUseCase>,
+        fetch_use_case: Arc<FetchStockDataUseCase>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self::with_config(QueryConfig::default())
+        }
+
+        fn with_config(config: QueryConfig) -> Self {
+            let temp = TempDb::new();
+            let repository = Arc::new(RocksDbStockRepository::new(temp.db.clone()));
+            let api = Arc::new(MockGseApiClient::default());
+            let cache = Arc::new(ResponseCache::new());
+            Self {
+                get_use_case: Arc::new(GetStockDataUseCase::with_config(
+                    repository.clone(),
+                    api.clone(),
+                    Arc::new(RecentlyRequested::new(10)),
+                    cache.clone(),
+                    config,
+                )),
+                fetch_use_case: Arc::new(FetchStockDataUseCase::with_config(
+                    api.clone(),
+                    repository.clone(),
+                    cache,
+                    FetchConfig::default(),
+                )),
+                repository,
+                api,
+                _temp: temp,
+            }
+        }
+
+        async fn store_live(&self, symbol: &str, price: f64, change: f64, volume: i64) {
+            let mut data = live(symbol, price, change);
+            data.volume = volume;
+            self.repository
+                .store_live_data(symbol, &data, Utc::now())
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn body_json(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn stock_query(
+        page: Option<usize>,
+        page_size: Option<usize>,
+        sort_by: StockSortField,
+        order: SortOrder,
+    ) -> StockListQuery {
+        StockListQuery {
+            source: None,
+            page,
+            page_size,
+            sort_by,
+            order,
+            sector: None,
+            industry: None,
+        }
+    }
+
+    async fn list_stocks(fixture: &Fixture, query: StockListQuery) -> Result<Response, ApiError> {
+        get_all_stocks(
+            Query(query),
+            HeaderMap::new(),
+            fixture.get_use_case.clone(),
+            fixture.fetch_use_case.clone(),
+        )
+        .await
+    }
+
+    fn names(body: &serde_json::Value) -> Vec<&str> {
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|stock| stock["name"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn stock_list_defaults_to_the_first_fifty_by_name() {
+        let fixture = Fixture::new();
+        for i in (0..60).rev() {
+            fixture
+                .store_live(&format!("S{:02}", i), 1.0, 0.0, 100)
+                .await;
+        }
+
+        let response = list_stocks(
+            &fixture,
+            stock_query(None, None, StockSortField::default(), SortOrder::default()),
+        )
+        .await
+        .unwrap();
+
+        let body = body_json(response).await;
+        assert_eq!(body["total"], 60);
+        let names = names(&body);
+        assert_eq!(names.len(), 50);
+        assert_eq!(names[0], "S00");
+        assert_eq!(names[49], "S49");
+    }
+
+    #[tokio::test]
+    async fn stock_list_sorts_by_each_field_both_ways() {
+        let fixture = Fixture::new();
+        fixture.store_live("ACCESS", 5.0, -0.2, 300).await;
+        fixture.store_live("CAL", 0.5, 0.1, 100).await;
+        fixture.store_live("GCB", 6.0, 0.0, 200).await;
+
+        let cases = [
+            (StockSortField::Price, ["CAL", "ACCESS", "GCB"]),
+            (StockSortField::Change, ["ACCESS", "GCB", "CAL"]),
+            (StockSortField::Volume, ["CAL", "GCB", "ACCESS"]),
+            (StockSortField::Name, ["ACCESS", "CAL", "GCB"]),
+        ];
+        for (sort_by, ascending) in cases {
+            let asc = list_stocks(&fixture, stock_query(None, None, sort_by, SortOrder::Asc))
+                .await
+                .unwrap();
+            let desc = list_stocks(&fixture, stock_query(None, None, sort_by, SortOrder::Desc))
+                .await
+                .unwrap();
+
+            let mut descending = ascending;
+            descending.reverse();
+            assert_eq!(names(&body_json(asc).await), ascending, "{:?} asc", sort_by);
+            assert_eq!(
+                names(&body_json(desc).await),
+                descending,
+                "{:?} desc",
+                sort_by
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn stock_list_pages_report_the_full_total() {
+        let fixture = Fixture::new();
+        for symbol in ["ACCESS", "CAL", "GCB", "MTNGH", "SCB"] {
+            fixture.store_live(symbol, 1.0, 0.0, 100).await;
+        }
+
+        let response = list_stocks(
+            &fixture,
+            stock_query(Some(2), Some(2), StockSortField::Name, SortOrder::Asc),
+        )
+        .await
+        .unwrap();
+
+        let body = body_json(response).await;
+        assert_eq!(body["total"], 5);
+        assert_eq!(names(&body), ["GCB", "MTNGH"]);
+    }
+
+    #[tokio::test]
+    async fn stock_list_rejects_a_page_size_over_the_maximum() {
+        let fixture = Fixture::new();
+
+        let error = list_stocks(
+            &fixture,
+            stock_query(None, Some(501), StockSortField::Name, SortOrder::Asc),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error.message,
+            "page must be at least 1 and page_size between 1 and 500"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_page_past_the_end_of_usize_is_empty_rather_than_wrapping() {
+        let fixture = Fixture::new();
+        fixture.store_live("MTNGH", 1.0, 0.0, 100).await;
+
+        let response = list_stocks(
+            &fixture,
+            stock_query(
+                Some(usize::MAX),
+                Some(500),
+                StockSortField::Name,
+                SortOrder::Asc,
+            ),
+        )
+        .await
+        .unwrap();
+
+        let body = body_json(response).await;
+        assert_eq!(body["total"], 1);
+        assert!(names(&body).is_empty());
+    }
+
+    fn symbols(count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("SYM{}", index)).collect()
+    }
+
+    #[tokio::test]
+    async fn a_batch_of_101_symbols_is_rejected_with_the_limit() {
+        let fixture = Fixture::new();
+        let query = SymbolsQuery {
+            symbols: symbols(101).join(","),
+        };
+
+        let error = get_stocks_batch(Query(query), fixture.get_use_case.clone())
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error.message,
+            "Too many symbols: at most 100 may be requested at once"
+        );
+    }
+
+    #[tokio::test]
+    async fn correlation_symbols_are_capped_by_the_lower_of_both_limits() {
+        let default_limits = Fixture::new();
+        let small_batches = Fixture::with_config(QueryConfig {
+            max_batch_symbols: 5,
+            ..QueryConfig::default()
+        });
+        let request = |count| CorrelationRequest {
+            symbols: symbols(count),
+            from: None,
+            to: None,
+        };
+
+        let over_matrix_limit =
+            get_correlation_matrix(default_limits.get_use_case.clone(), Json(request(21)))
+                .await
+                .unwrap_err();
+        let over_batch_limit =
+            get_correlation_matrix(small_batches.get_use_case.clone(), Json(request(6)))
+                .await
+                .unwrap_err();
+
+        assert_eq!(over_matrix_limit.status, StatusCode::BAD_REQUEST);
+        assert!(over_matrix_limit.message.contains("at most 20"));
+        assert_eq!(over_batch_limit.status, StatusCode::BAD_REQUEST);
+        assert!(over_batch_limit.message.contains("at most 5"));
+    }
+
+    #[tokio::test]
+    async fn streamed_history_emits_one_line_per_point_in_time_order() {
+        let fixture = Fixture::new();
+        let now = Utc::now();
+        // More points than one page of the stream's reads
+        for minutes_ago in (1..=520).rev() {
+            let price = 1.0 + minutes_ago as f64 / 1000.0;
+            fixture
+                .repository
+                .store_live_data(
+                    "MTNGH",
+                    &live("MTNGH", price, 0.0),
+                    now - chrono::Duration::minutes(minutes_ago),
+                )
+                .await
+                .unwrap();
+        }
+        let query = HistoricalDataQuery {
+            from: None,
+            to: None,
+            source: None,
+            vs: None,
+            adjusted: None,
+            indicators: None,
+        };
+
+        let response = stream_stock_history(
+            Path("mtngh".to_string()),
+            Query(query),
+            fixture.get_use_case.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/x-ndjson"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let timestamps: Vec<DateTime<Utc>> = body
+            .split(|byte| *byte == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let point: serde_json::Value = serde_json::from_slice(line).unwrap();
+                point["timestamp"].as_str().unwrap().parse().unwrap()
+            })
+            .collect();
+        assert_eq!(timestamps.len(), 520);
+        assert!(timestamps.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn every_indicator_rejects_a_history_below_its_minimum() {
+        let fixture = Fixture::new();
+        let indicators = [
+            Indicator::Sma,
+            Indicator::Ema,
+            Indicator::Rsi,
+            Indicator::Macd,
+            Indicator::Bollinger,
+        ];
+        let store_closes = |symbol: String, days: i64| {
+            let repository = fixture.repository.clone();
+            async move {
+                for days_ago in (1..=days).rev() {
+                    let price = 1.0 + (days_ago % 4) as f64 / 10.0;
+                    repository
+                        .store_live_data(
+                            &symbol,
+                            &live(&symbol, price, 0.0),
+                            Utc::now() - chrono::Duration::days(days_ago),
+                        )
+                        .await
+                        .unwrap();
+                }
+            }
+        };
+        let indicator_of = |symbol: &str, indicator: Indicator| {
+            get_indicator(
+                Path((symbol.to_string(), indicator)),
+                Query(IndicatorQuery { period: None }),
+                fixture.get_use_case.clone(),
+            )
+        };
+
+        for indicator in indicators {
+            let symbol = format!("{:?}", indicator).to_uppercase();
+            let min_points = indicator.min_points(indicator.default_period());
+            store_closes(symbol.clone(), min_points as i64 - 1).await;
+
+            let error = indicator_of(&symbol, indicator).await.unwrap_err();
+
+            assert_eq!(error.status, StatusCode::BAD_REQUEST, "{:?}", indicator);
+            assert_eq!(
+                error.message,
+                format!(
+                    "need at least {} points, have {}",
+                    min_points,
+                    min_points - 1
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn a_summary_limited_to_totals_omits_gainers_and_losers() {
+        let fixture = Fixture::new();
+        fixture.store_live("MTNGH", 1.5, 0.1, 1000).await;
+        fixture.store_live("GCB", 4.0, -0.2, 500).await;
+        fixture
+            .fetch_use_case
+            .generate_and_store_market_summary()
+            .await
+            .unwrap();
+        let query = |include: Option<&str>| MarketSummaryQuery {
+            source: None,
+            include: include.map(str::to_string),
+        };
+
+        let totals = get_market_summary(Query(query(Some("totals"))), fixture.get_use_case.clone())
+            .await
+            .unwrap();
+        let everything = get_market_summary(Query(query(None)), fixture.get_use_case.clone())
+            .await
+            .unwrap();
+
+        let keys = |response: &Json<ApiResponse<serde_json::Value>>| -> Vec<String> {
+            let mut keys: Vec<String> = response
+                .0
+                .data
+                .as_ref()
+                .unwrap()
+                .as_object()
+                .unwrap()
+                .keys()
+                .cloned()
+                .collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(
+            keys(&totals),
+            [
+                "index_level",
+                "last_updated",
+                "total_market_cap",
+                "total_stocks",
+                "total_volume"
+            ]
+        );
+        assert_eq!(totals.0.data.as_ref().unwrap()["total_volume"], 1500);
+        assert!(keys(&everything).contains(&"top_gainers".to_string()));
+        assert!(keys(&everything).contains(&"top_losers".to_string()));
+    }
+
+    #[tokio::test]
+    async fn manual_refreshes_are_rejected_while_a_scrape_holds_the_lock() {
+        let fixture = Fixture::new();
+        let scrape = fixture.fetch_use_case.lock_scrapes().await;
+
+        let live = trigger_data_refresh(fixture.fetch_use_case.clone()).await;
+        let equity = trigger_equity_refresh(fixture.fetch_use_case.clone()).await;
+        drop(scrape);
+        let after = trigger_data_refresh(fixture.fetch_use_case.clone()).await;
+
+        for error in [live.unwrap_err(), equity.unwrap_err()] {
+            assert_eq!(error.status, StatusCode::CONFLICT);
+            assert_eq!(
+                error.message,
+                "Another scrape is in progress, try again later"
+            );
+        }
+        assert!(after.is_ok());
+    }
+
+    #[tokio::test]
+    async fn each_generated_summary_is_streamed_as_an_event_with_its_timestamp() {
+        let fixture = Fixture::new();
+        fixture.store_live("MTNGH", 1.5, 0.1, 1000).await;
+        let response = stream_market_summary(fixture.fetch_use_case.clone()).await;
+
+        fixture
+            .fetch_use_case
+            .generate_and_store_market_summary()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let mut body = response.into_body().into_data_stream();
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let event = String::from_utf8(event.to_vec()).unwrap();
+        let summary = fixture
+            .repository
+            .get_latest_market_summary()
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(event.starts_with("event: summary\n"), "{}", event);
+        assert!(event.contains(&format!("id: {}\n", summary.last_updated.to_rfc3339())));
+        let data = event
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let streamed: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            streamed["last_updated"],
+            serde_json::to_value(summary.last_updated).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn history_csv_has_a_header_and_one_row_per_point() {
+        let fixture = Fixture::new();
+        let at = |hour: u32| Utc.with_ymd_and_hms(2024, 3, 7, hour, 0, 0).unwrap();
+        for (hour, price) in [(10, 1.5), (11, 1.6)] {
+            fixture
+                .repository
+                .store_live_data("MTNGH", &live("MTNGH", price, 0.0), at(hour))
+                .await
+                .unwrap();
+        }
+        let query = HistoricalDataQuery {
+            from: Some("2024-03-07T00:00:00Z".to_string()),
+            to: Some("2024-03-08T00:00:00Z".to_string()),
+            source: None,
+            vs: None,
+            adjusted: None,
+            indicators: None,
+        };
+
+        let response = stream_stock_history_csv(
+            Path("mtngh".to_string()),
+            Query(query),
+            fixture.get_use_case.clone(),
+        )
+        .await;
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=utf-8"
+        );
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"MTNGH_20240307_20240308.csv\""
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(
+            body.lines().collect::<Vec<_>>(),
+            [
+                "timestamp,price,volume",
+                "2024-03-07T10:00:00Z,1.5,1000",
+                "2024-03-07T11:00:00Z,1.6,1000",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn errors_carry_a_json_body_with_a_distinct_message() {
+        let fixture = Fixture::new();
+        fixture.api.set_failing(true);
+
+        let unknown = get_stock_by_symbol(
+            Path("nope".to_string()),
+            HeaderMap::new(),
+            fixture.get_use_case.clone(),
+            fixture.fetch_use_case.clone(),
+        )
+        .await
+        .unwrap_err()
+        .into_response();
+        let bad_input = get_turnover(
+            Path("MTNGH".to_string()),
+            Query(TurnoverQuery { days: Some(0) }),
+            fixture.get_use_case.clone(),
+        )
+        .await
+        .unwrap_err()
+        .into_response();
+
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            body_json(unknown).await,
+            serde_json::json!({
+                "success": false,
+                "data": null,
+                "error": "Unknown symbol: NOPE",
+            })
+        );
+        assert_eq!(bad_input.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body_json(bad_input).await["error"],
+            "days must be between 1 and 366"
+        );
+    }
+}
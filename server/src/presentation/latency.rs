@@ -0,0 +1,241 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default histogram bucket upper bounds, in milliseconds
+pub const DEFAULT_LATENCY_BUCKETS_MS: &[f64] = &[
+    5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EndpointHistogram {
+    /// One count per bucket, plus a final overflow bucket for anything above the last bound
+    counts: Vec<u64>,
+    count: u64,
+    total_ms: f64,
+}
+
+/// Latency summary for one endpoint; percentiles are estimated as the upper bound of their bucket
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointLatency {
+    pub endpoint: String,
+    pub count: u64,
+    pub mean_ms: f64,
+    /// `None` when the percentile falls in the overflow bucket above the largest bound
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    /// Cumulative count of requests at or below each bucket bound
+    pub buckets: BTreeMap<String, u64>,
+}
+
+/// Saved histogram contents, restored only into a histogram with the same bucket bounds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyState {
+    bounds_ms: Vec<f64>,
+    endpoints: BTreeMap<String, EndpointHistogram>,
+}
+
+/// In-memory per-endpoint request latency histogram
+pub struct LatencyHistogram {
+    bounds_ms: Vec<f64>,
+    endpoints: Mutex<BTreeMap<String, EndpointHistogram>>,
+}
+
+impl LatencyHistogram {
+    /// Create a histogram with the given bucket upper bounds in milliseconds, falling back to
+    /// the defaults when none are valid
+    pub fn new(mut bounds_ms: Vec<f64>) -> Self {
+        bounds_ms.retain(|bound| bound.is_finite() && *bound > 0.0);
+        bounds_ms.sort_by(|a, b| a.total_cmp(b));
+        bounds_ms.dedup();
+        if bounds_ms.is_empty() {
+            bounds_ms = DEFAULT_LATENCY_BUCKETS_MS.to_vec();
+        }
+
+        Self {
+            bounds_ms,
+            endpoints: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Parse comma-separated bucket bounds such as `10,50,100`, skipping invalid entries
+    pub fn parse_buckets(value: &str) -> Vec<f64> {
+        value
+            .split(',')
+            .filter_map(|bound| bound.trim().parse().ok())
+            .collect()
+    }
+
+    pub fn record(&self, endpoint: &str, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let bucket = self
+            .bounds_ms
+            .iter()
+            .position(|bound| latency_ms <= *bound)
+            .unwrap_or(self.bounds_ms.len());
+
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let histogram =
+            endpoints
+                .entry(endpoint.to_string())
+                .or_insert_with(|| EndpointHistogram {
+                    counts: vec![0; self.bounds_ms.len() + 1],
+                    count: 0,
+                    total_ms: 0.0,
+                });
+        histogram.counts[bucket] += 1;
+        histogram.count += 1;
+        histogram.total_ms += latency_ms;
+    }
+
+    pub fn snapshot(&self) -> Vec<EndpointLatency> {
+        let endpoints = self.endpoints.lock().unwrap();
+
+        endpoints
+            .iter()
+            .map(|(endpoint, histogram)| {
+                let mut cumulative = 0;
+                let buckets = self
+                    .bounds_ms
+                    .iter()
+                    .zip(&histogram.counts)
+                    .map(|(bound, count)| {
+                        cumulative += count;
+                        (format!("le_{}", bound), cumulative)
+                    })
+                    .collect();
+
+                EndpointLatency {
+                    endpoint: endpoint.clone(),
+                    count: histogram.count,
+                    mean_ms: histogram.total_ms / histogram.count as f64,
+                    p50_ms: self.percentile(histogram, 0.50),
+                    p90_ms: self.percentile(histogram, 0.90),
+                    p99_ms: self.percentile(histogram, 0.99),
+                    buckets,
+                }
+            })
+            .collect()
+    }
+
+    /// Copy out the recorded latencies so they can be persisted
+    pub fn export(&self) -> LatencyState {
+        LatencyState {
+            bounds_ms: self.bounds_ms.clone(),
+            endpoints: self.endpoints.lock().unwrap().clone(),
+        }
+    }
+
+    /// Reload latencies saved from `export`, returning false when the bucket bounds have changed
+    /// since, as the saved counts no longer line up with the buckets
+    pub fn restore(&self, state: LatencyState) -> bool {
+        if state.bounds_ms != self.bounds_ms {
+            return false;
+        }
+
+        let mut endpoints = self.endpoints.lock().unwrap();
+        for (endpoint, saved) in state.endpoints {
+            match endpoints.get_mut(&endpoint) {
+                Some(histogram) => {
+                    for (count, saved) in histogram.counts.iter_mut().zip(&saved.counts) {
+                        *count += saved;
+                    }
+                    histogram.count += saved.count;
+                    histogram.total_ms += saved.total_ms;
+                }
+                None => {
+                    endpoints.insert(endpoint, saved);
+                }
+            }
+        }
+        true
+    }
+
+    fn percentile(&self, histogram: &EndpointHistogram, quantile: f64) -> Option<f64> {
+        let rank = (quantile * histogram.count as f64).ceil().max(1.0) as u64;
+        let mut cumulative = 0;
+
+        for (index, count) in histogram.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= rank {
+                return self.bounds_ms.get(index).copied();
+            }
+        }
+
+        None
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_BUCKETS_MS.to_vec())
+    }
+}
+
+/// Middleware recording each request's latency under its method and route pattern
+pub async fn record_latency(
+    State(histogram): State<Arc<LatencyHistogram>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Group by route pattern (e.g. `/api/stocks/:symbol`) rather than the concrete path
+    let endpoint = match request.extensions().get::<MatchedPath>() {
+        Some(path) => format!("{} {}", request.method(), path.as_str()),
+        None => format!("{} {}", request.method(), request.uri().path()),
+    };
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    histogram.record(&endpoint, started.elapsed());
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::Service;
+
+    #[tokio::test]
+    async fn every_request_is_counted_under_its_route() {
+        let histogram = Arc::new(LatencyHistogram::default());
+        let mut router = Router::new()
+            .route("/api/stocks/:symbol", get(|| async { "ok" }))
+            .route("/api/market", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(
+                histogram.clone(),
+                record_latency,
+            ));
+
+        for path in [
+            "/api/stocks/MTNGH",
+            "/api/stocks/GCB",
+            "/api/stocks/SCB",
+            "/api/market",
+        ] {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            router.call(request).await.unwrap();
+        }
+
+        let snapshot = histogram.snapshot();
+        let counts: Vec<(&str, u64)> = snapshot
+            .iter()
+            .map(|latency| (latency.endpoint.as_str(), latency.count))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![("GET /api/market", 1), ("GET /api/stocks/:symbol", 3)]
+        );
+        let stocks = &snapshot[1];
+        assert_eq!(stocks.buckets.values().last(), Some(&3));
+        assert!(stocks.p99_ms.is_some());
+    }
+}
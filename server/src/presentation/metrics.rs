@@ -0,0 +1,37 @@
+use crate::domain::{MetricsRecorder, ERRORS_METRIC};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+/// Middleware counting each request under its method, route pattern and response status
+pub async fn record_request_metrics(
+    State(metrics): State<Arc<dyn MetricsRecorder + Send + Sync>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    // Label by route pattern (e.g. `/api/stocks/:symbol`) so each symbol isn't its own series
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_string(),
+        None => request.uri().path().to_string(),
+    };
+
+    let response = next.run(request).await;
+    let status = response.status();
+    metrics.increment(
+        "gse_http_requests_total",
+        &[
+            ("method", method.as_str()),
+            ("route", route.as_str()),
+            ("status", status.as_str()),
+        ],
+    );
+    if status.is_server_error() {
+        metrics.increment(ERRORS_METRIC, &[("type", "http_server_error")]);
+    }
+
+    response
+}
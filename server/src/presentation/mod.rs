@@ -1,5 +1,17 @@
+pub mod alert_routes;
+pub mod auth;
+pub mod client_rate_limit;
+pub mod format;
 pub mod handlers;
+pub mod latency;
+pub mod metrics;
+pub mod openapi;
+pub mod pagination;
 pub mod portfolio_routes;
+pub mod request_id;
 pub mod routes;
+pub mod runtime_config;
+pub mod summary_card;
+pub mod watchlist_routes;
 
 pub use routes::*;
@@ -0,0 +1,219 @@
+use crate::presentation::{alert_routes, handlers, portfolio_routes, watchlist_routes};
+use axum::Router;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+/// OpenAPI description of every route, assembled from the handlers' annotations
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Ghana Stock Exchange API",
+        description = "Live and historical GSE market data, analytics, portfolios and alerts"
+    ),
+    paths(
+        handlers::health_check,
+        handlers::readiness_check,
+        handlers::get_metrics,
+        handlers::get_all_stocks,
+        handlers::get_sectors,
+        handlers::get_stocks_batch,
+        handlers::get_stock_by_symbol,
+        handlers::get_stock_history,
+        handlers::stream_stock_history_csv,
+        handlers::live_updates_socket,
+        handlers::stream_stock_history,
+        handlers::get_stock_intraday,
+        handlers::get_stock_announcements,
+        handlers::get_candles,
+        handlers::get_indicator_series,
+        handlers::get_indicator,
+        handlers::get_price_ladder,
+        handlers::get_turnover,
+        handlers::get_volatility_cone,
+        handlers::search,
+        handlers::stream_market_summary,
+        handlers::get_market_summary_timeline,
+        handlers::get_market_summary_card,
+        handlers::get_market_summary,
+        handlers::get_market_events,
+        handlers::get_volume_alerts,
+        handlers::get_market_sectors,
+        handlers::get_market_breadth,
+        handlers::get_yield_curve,
+        handlers::get_market_announcements,
+        handlers::get_trading_calendar,
+        handlers::get_relative_strength,
+        handlers::get_correlation_matrix,
+        handlers::get_snapshot_diff,
+        handlers::trigger_data_refresh,
+        handlers::trigger_equity_refresh,
+        handlers::trigger_metrics_recompute,
+        handlers::record_bond,
+        handlers::record_split,
+        handlers::create_backup,
+        handlers::restore_backup,
+        handlers::record_announcement,
+        handlers::get_scrape_history,
+        handlers::dump_stock_records,
+        handlers::get_stale_symbols,
+        handlers::get_popular_symbols,
+        handlers::get_deliveries,
+        handlers::get_latency_stats,
+        handlers::get_worker_status,
+        handlers::get_runtime_config,
+        portfolio_routes::create_portfolio,
+        portfolio_routes::get_all_portfolios,
+        portfolio_routes::get_portfolio,
+        portfolio_routes::delete_portfolio,
+        portfolio_routes::add_transaction,
+        portfolio_routes::list_transactions,
+        portfolio_routes::update_transaction,
+        portfolio_routes::delete_transaction,
+        portfolio_routes::get_cost_summary,
+        portfolio_routes::get_realized_gains,
+        portfolio_routes::get_valuation,
+        portfolio_routes::get_as_of,
+        portfolio_routes::get_risk,
+        portfolio_routes::get_yield_on_cost,
+        portfolio_routes::record_dividend,
+        portfolio_routes::get_dividends,
+        portfolio_routes::get_value_history,
+        portfolio_routes::get_valuation_history,
+        watchlist_routes::create_watchlist,
+        watchlist_routes::get_all_watchlists,
+        watchlist_routes::get_watchlist,
+        watchlist_routes::add_symbol,
+        watchlist_routes::remove_symbol,
+        alert_routes::create_alert,
+        alert_routes::get_alerts,
+        alert_routes::delete_alert,
+    ),
+    components(schemas(
+        handlers::ApiResponseValue,
+        handlers::ApiResponseStatus,
+        handlers::ApiResponseEquityLiveList,
+        handlers::ApiResponseEquity,
+        handlers::ApiResponseMarketSummary,
+        handlers::ApiResponseReadiness,
+        handlers::ResponseMeta,
+        handlers::DataOrigin,
+        handlers::Readiness,
+        handlers::AdjustedMode,
+        handlers::StockSortField,
+        handlers::SortOrder,
+        handlers::CorrelationRequest,
+        handlers::RecordAnnouncementRequest,
+        handlers::RecordBondRequest,
+        handlers::RecordSplitRequest,
+        handlers::RestoreBackupRequest,
+        portfolio_routes::CreatePortfolioRequest,
+        portfolio_routes::AddTransactionRequest,
+        portfolio_routes::RecordDividendRequest,
+        watchlist_routes::CreateWatchlistRequest,
+        watchlist_routes::AddSymbolRequest,
+        alert_routes::CreateAlertRequest,
+        crate::domain::DataSource,
+        crate::domain::EquityLive,
+        crate::domain::Equity,
+        crate::domain::Company,
+        crate::domain::Director,
+        crate::domain::MarketSummary,
+        crate::domain::analytics::sectors::SectorSummary,
+        crate::domain::analytics::sectors::SectorMover,
+        crate::domain::Portfolio,
+        crate::domain::PortfolioItem,
+        crate::domain::PurchaseLot,
+        crate::domain::Transaction,
+        crate::domain::TransactionType,
+        crate::domain::CostBasisMethod,
+        crate::domain::PortfolioValuePoint,
+        crate::domain::DividendRecord,
+        crate::domain::Watchlist,
+        crate::application::WatchlistView,
+        crate::application::WatchedStock,
+        crate::domain::Alert,
+        crate::domain::AlertCondition,
+    )),
+    tags(
+        (name = "health", description = "Liveness, readiness and metrics"),
+        (name = "stocks", description = "Per-stock live data, history and indicators"),
+        (name = "market", description = "Market-wide summaries and events"),
+        (name = "analysis", description = "Cross-stock analytics"),
+        (name = "admin", description = "Operational endpoints"),
+        (name = "portfolios", description = "Portfolios and their transactions"),
+        (name = "watchlists", description = "Watched symbols with live prices"),
+        (name = "alerts", description = "Price alerts delivered to webhooks"),
+    ),
+    modifiers(&AdminKeyScheme)
+)]
+pub struct ApiDoc;
+
+/// Declares the bearer token admin endpoints require
+struct AdminKeyScheme;
+
+impl Modify for AdminKeyScheme {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "admin_key",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
+/// Serve the OpenAPI document at `/api-docs/openapi.json` and Swagger UI at `/docs`
+pub fn docs_router() -> Router {
+    SwaggerUi::new("/docs")
+        .url("/api-docs/openapi.json", ApiDoc::openapi())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every `$ref` in a JSON document
+    fn refs(value: &serde_json::Value, found: &mut Vec<String>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, value) in map {
+                    match value {
+                        serde_json::Value::String(target) if key == "$ref" => {
+                            found.push(target.clone())
+                        }
+                        _ => refs(value, found),
+                    }
+                }
+            }
+            serde_json::Value::Array(values) => values.iter().for_each(|v| refs(v, found)),
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn the_document_describes_the_routes_and_resolves_every_schema() {
+        let doc = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let paths = doc["paths"].as_object().unwrap();
+        for path in [
+            "/api/stocks/{symbol}",
+            "/api/market/summary",
+            "/api/portfolios",
+        ] {
+            assert!(paths.contains_key(path), "missing {}", path);
+        }
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        assert_eq!(
+            schemas["ApiResponseEquity"]["properties"]["data"]["allOf"][0]["$ref"],
+            "#/components/schemas/Equity"
+        );
+        let mut found = Vec::new();
+        refs(&doc, &mut found);
+        for target in found {
+            let name = target.trim_start_matches("#/components/schemas/");
+            assert!(schemas.contains_key(name), "unresolved {}", target);
+        }
+    }
+}
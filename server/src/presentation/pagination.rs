@@ -0,0 +1,86 @@
+use axum::http::Uri;
+use serde::Serialize;
+
+/// Navigation links for a paginated listing; `next`/`prev` are omitted at the boundaries
+#[derive(Debug, Clone, Serialize)]
+pub struct PaginationLinks {
+    #[serde(rename = "self")]
+    pub self_link: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+    pub first: String,
+    pub last: String,
+}
+
+/// A page of results with its navigation links alongside the page's own fields
+#[derive(Debug, Serialize)]
+pub struct WithLinks<T> {
+    #[serde(flatten)]
+    pub data: T,
+    pub links: PaginationLinks,
+}
+
+impl PaginationLinks {
+    /// Build links from the request URI, keeping every query parameter except `page`.
+    /// `uri` must be the original request URI so nested routers get the full path.
+    pub fn from_uri(uri: &Uri, page: usize, total_pages: usize) -> Self {
+        let last_page = total_pages.max(1);
+        let link = |page: usize| page_link(uri, page);
+
+        Self {
+            self_link: link(page),
+            next: (page < last_page).then(|| link(page + 1)),
+            prev: (page > 1).then(|| link((page - 1).min(last_page))),
+            first: link(1),
+            last: link(last_page),
+        }
+    }
+}
+
+fn page_link(uri: &Uri, page: usize) -> String {
+    // Other parameters are copied as sent, so their encoding is preserved
+    let mut params: Vec<String> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .filter(|param| param.split('=').next() != Some("page"))
+        .map(str::to_string)
+        .collect();
+    params.push(format!("page={}", page));
+
+    format!("{}?{}", uri.path(), params.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_first_page_links_to_the_next_but_not_the_previous() {
+        let uri: Uri = "/api/stocks?sort=price&page=1&limit=20".parse().unwrap();
+
+        let links = PaginationLinks::from_uri(&uri, 1, 3);
+
+        assert_eq!(links.self_link, "/api/stocks?sort=price&limit=20&page=1");
+        assert_eq!(
+            links.next.as_deref(),
+            Some("/api/stocks?sort=price&limit=20&page=2")
+        );
+        assert_eq!(links.prev, None);
+        assert_eq!(links.last, "/api/stocks?sort=price&limit=20&page=3");
+    }
+
+    #[test]
+    fn the_last_page_links_to_the_previous_but_not_the_next() {
+        let uri: Uri = "/api/stocks?page=3".parse().unwrap();
+
+        let links = PaginationLinks::from_uri(&uri, 3, 3);
+
+        assert_eq!(links.next, None);
+        assert_eq!(links.prev.as_deref(), Some("/api/stocks?page=2"));
+        assert_eq!(links.first, "/api/stocks?page=1");
+    }
+}
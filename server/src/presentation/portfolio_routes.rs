@@ -1,78 +1,232 @@
-use crate::application::PortfolioUseCase;
-use crate::domain::{Transaction, TransactionType};
+use crate::application::{
+    NewDividend, PortfolioUseCase, PriceSource, TransactionEdit, TransactionQuery,
+};
+use crate::domain::{CostBasisMethod, Transaction, TransactionError, TransactionType};
+use crate::presentation::handlers::ApiError;
+use crate::presentation::pagination::{PaginationLinks, WithLinks};
 use axum::{
-    extract::{Path, State},
+    extract::{OriginalUri, Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
-    routing::{get, post},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
     Json, Router,
 };
 use serde::Deserialize;
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreatePortfolioRequest {
     name: String,
+    /// Currency to value the portfolio in; defaults to GHS
+    #[serde(default)]
+    base_currency: Option<String>,
+    /// How sells are matched against buys for realized gains; defaults to average cost
+    #[serde(default)]
+    cost_basis_method: CostBasisMethod,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AddTransactionRequest {
     symbol: String,
     transaction_type: TransactionType,
     quantity: i64,
     price_per_share: f64,
-    /// Optional ISO8601 date for "when I bought" (e.g. backdating). If omitted, uses now.
+    #[serde(default)]
+    fee: f64,
+    /// Optional ISO8601 date for "when I bought" (e.g. backdating). If omitted, uses now; an
+    /// unreadable date is rejected.
     #[serde(default)]
     pub timestamp: Option<String>,
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct RecordDividendRequest {
+    symbol: String,
+    /// Dividend per share in GHS; defaults to the symbol's latest stored dividend per share
+    #[serde(default)]
+    amount_per_share: Option<f64>,
+    /// `YYYY-MM-DD`; shares held at the end of the day before are paid
+    ex_date: chrono::NaiveDate,
+    /// `YYYY-MM-DD`, on or after the ex-date
+    pay_date: chrono::NaiveDate,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListTransactionsQuery {
+    from: Option<String>,
+    to: Option<String>,
+    symbol: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ValuationQuery {
+    /// `live` or `close`; defaults to live prices during trading hours and closing prices otherwise
+    #[param(value_type = Option<String>)]
+    price_source: Option<PriceSource>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AsOfQuery {
+    /// `YYYY-MM-DD`, no later than today
+    date: chrono::NaiveDate,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RiskQuery {
+    /// Lookback window in calendar days
+    days: Option<i64>,
+    /// Value-at-risk confidence level, e.g. 0.95
+    confidence: Option<f64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ValueHistoryQuery {
+    /// RFC 3339 start of the range; defaults to 30 days before `to`
+    from: Option<String>,
+    /// RFC 3339 end of the range; defaults to now
+    to: Option<String>,
+}
+
+const DEFAULT_VALUE_HISTORY_DAYS: i64 = 30;
+
+const DEFAULT_RISK_DAYS: i64 = 90;
+const DEFAULT_RISK_CONFIDENCE: f64 = 0.95;
+
+const DEFAULT_TRANSACTION_PAGE_SIZE: usize = 50;
+const MAX_TRANSACTION_PAGE_SIZE: usize = 500;
+
 pub fn portfolio_routes(use_case: Arc<PortfolioUseCase>) -> Router {
     Router::new()
         .route("/", post(create_portfolio).get(get_all_portfolios))
         .route("/:id", get(get_portfolio).delete(delete_portfolio))
-        .route("/:id/transactions", post(add_transaction))
+        .route(
+            "/:id/transactions",
+            post(add_transaction).get(list_transactions),
+        )
+        .route(
+            "/:id/transactions/:txid",
+            put(update_transaction).delete(delete_transaction),
+        )
+        .route("/:id/dividends", post(record_dividend).get(get_dividends))
+        .route("/:id/cost-summary", get(get_cost_summary))
+        .route("/:id/realized-gains", get(get_realized_gains))
+        .route("/:id/valuation", get(get_valuation))
+        .route("/:id/as-of", get(get_as_of))
+        .route("/:id/risk", get(get_risk))
+        .route("/:id/yield-on-cost", get(get_yield_on_cost))
+        .route("/:id/history", get(get_value_history))
+        .route("/:id/snapshots", get(get_valuation_history))
         .with_state(use_case)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/portfolios",
+    tag = "portfolios",
+    request_body = CreatePortfolioRequest,
+    responses(
+        (status = 201, description = "Created portfolio", body = Portfolio),
+        (status = 400, description = "Unsupported base currency"),
+    )
+)]
 async fn create_portfolio(
     State(use_case): State<Arc<PortfolioUseCase>>,
     Json(payload): Json<CreatePortfolioRequest>,
-) -> impl IntoResponse {
-    match use_case.create_portfolio(payload.name).await {
-        Ok(portfolio) => (StatusCode::CREATED, Json(portfolio)).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+) -> Result<Response, ApiError> {
+    if let Some(currency) = &payload.base_currency {
+        if !use_case.supports_currency(currency) {
+            return Err(ApiError::bad_request(format!(
+                "Unsupported base currency: {}",
+                currency
+            )));
+        }
+    }
+
+    match use_case
+        .create_portfolio(
+            payload.name,
+            payload.base_currency,
+            payload.cost_basis_method,
+        )
+        .await
+    {
+        Ok(portfolio) => Ok((StatusCode::CREATED, Json(portfolio)).into_response()),
+        Err(e) => Err(ApiError::storage("Failed to create portfolio", e)),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/portfolios",
+    tag = "portfolios",
+    responses(
+        (status = 200, description = "Every portfolio", body = [Portfolio]),
+    )
+)]
 async fn get_all_portfolios(
     State(use_case): State<Arc<PortfolioUseCase>>,
-) -> impl IntoResponse {
+) -> Result<Response, ApiError> {
     match use_case.get_all_portfolios().await {
-        Ok(portfolios) => Json(portfolios).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Ok(portfolios) => Ok(Json(portfolios).into_response()),
+        Err(e) => Err(ApiError::storage("Failed to list portfolios", e)),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (
+            status = 200,
+            description = "The portfolio with its holdings and transactions",
+            body = Portfolio
+        ),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
 async fn get_portfolio(
     State(use_case): State<Arc<PortfolioUseCase>>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<Response, ApiError> {
     match use_case.get_portfolio(&id).await {
-        Ok(Some(portfolio)) => Json(portfolio).into_response(),
-        Ok(None) => (StatusCode::NOT_FOUND, "Portfolio not found").into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Ok(Some(portfolio)) => Ok(Json(portfolio).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get portfolio", e)),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/portfolios/{id}/transactions",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    request_body = AddTransactionRequest,
+    responses(
+        (
+            status = 200,
+            description = "Portfolio with the transaction applied",
+            body = Portfolio
+        ),
+        (status = 400, description = "Invalid transaction or timestamp, or one the holdings can't absorb"),
+    )
+)]
 async fn add_transaction(
     State(use_case): State<Arc<PortfolioUseCase>>,
     Path(id): Path<String>,
     Json(payload): Json<AddTransactionRequest>,
-) -> impl IntoResponse {
-    let timestamp = payload
-        .timestamp
-        .and_then(|s| chrono::DateTime::parse_from_rfc3339(&s).ok())
-        .map(|dt| dt.with_timezone(&chrono::Utc))
+) -> Result<Response, ApiError> {
+    let timestamp = parse_optional_date(payload.timestamp)
+        .map_err(ApiError::bad_request)?
         .unwrap_or_else(chrono::Utc::now);
 
     let transaction = Transaction {
@@ -81,21 +235,533 @@ async fn add_transaction(
         transaction_type: payload.transaction_type,
         quantity: payload.quantity,
         price_per_share: payload.price_per_share,
+        fee: payload.fee,
         timestamp,
     };
 
     match use_case.add_transaction(&id, transaction).await {
-        Ok(portfolio) => Json(portfolio).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Ok(portfolio) => Ok(Json(portfolio).into_response()),
+        Err(e) => Err(transaction_error(e)),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/portfolios/{id}/transactions/{txid}",
+    tag = "portfolios",
+    params(
+        ("id" = String, Path, description = "Portfolio id"),
+        ("txid" = String, Path, description = "Transaction id"),
+    ),
+    request_body = AddTransactionRequest,
+    responses(
+        (
+            status = 200,
+            description = "Portfolio with holdings rebuilt from the edited log",
+            body = Portfolio
+        ),
+        (status = 400, description = "Invalid timestamp, or an edit that leaves the log inconsistent"),
+        (status = 404, description = "Portfolio or transaction not found"),
+    )
+)]
+async fn update_transaction(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path((id, txid)): Path<(String, String)>,
+    Json(payload): Json<AddTransactionRequest>,
+) -> Result<Response, ApiError> {
+    let timestamp = parse_optional_date(payload.timestamp).map_err(ApiError::bad_request)?;
+    let edit = TransactionEdit {
+        symbol: payload.symbol,
+        transaction_type: payload.transaction_type,
+        quantity: payload.quantity,
+        price_per_share: payload.price_per_share,
+        fee: payload.fee,
+        timestamp,
+    };
+
+    match use_case.update_transaction(&id, &txid, edit).await {
+        Ok(portfolio) => Ok(Json(portfolio).into_response()),
+        Err(e) => Err(transaction_error(e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/portfolios/{id}/transactions/{txid}",
+    tag = "portfolios",
+    params(
+        ("id" = String, Path, description = "Portfolio id"),
+        ("txid" = String, Path, description = "Transaction id"),
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Portfolio with holdings rebuilt without the transaction",
+            body = Portfolio
+        ),
+        (status = 400, description = "Removal leaves the log inconsistent"),
+        (status = 404, description = "Portfolio or transaction not found"),
+    )
+)]
+async fn delete_transaction(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path((id, txid)): Path<(String, String)>,
+) -> Result<Response, ApiError> {
+    match use_case.delete_transaction(&id, &txid).await {
+        Ok(portfolio) => Ok(Json(portfolio).into_response()),
+        Err(e) => Err(transaction_error(e)),
+    }
+}
+
+/// Unknown transactions are not found and transactions the holdings can't absorb are bad requests
+fn transaction_error(e: anyhow::Error) -> ApiError {
+    match e.downcast_ref::<TransactionError>() {
+        Some(TransactionError::UnknownTransaction { .. }) => ApiError::not_found(e.to_string()),
+        Some(_) => ApiError::bad_request(e.to_string()),
+        None => ApiError::storage("Failed to update portfolio", e),
     }
 }
 
+/// Parse an optional RFC 3339 parameter, reporting the offending value on failure
+fn parse_optional_date(
+    value: Option<String>,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    value
+        .map(|s| {
+            chrono::DateTime::parse_from_rfc3339(&s)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+                .map_err(|_| format!("Invalid date: {}", s))
+        })
+        .transpose()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/portfolios/{id}/dividends",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    request_body = RecordDividendRequest,
+    responses(
+        (
+            status = 200,
+            description = "Portfolio with the dividend recorded",
+            body = Portfolio
+        ),
+        (status = 400, description = "Invalid amount or dates, or no stored amount to default to"),
+    )
+)]
+async fn record_dividend(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+    Json(payload): Json<RecordDividendRequest>,
+) -> Result<Response, ApiError> {
+    if payload.amount_per_share.is_some_and(|amount| amount <= 0.0) {
+        return Err(ApiError::bad_request("amount_per_share must be positive"));
+    }
+    if payload.pay_date < payload.ex_date {
+        return Err(ApiError::bad_request("pay_date must not be before ex_date"));
+    }
+
+    let dividend = NewDividend {
+        symbol: payload.symbol,
+        amount_per_share: payload.amount_per_share,
+        ex_date: payload.ex_date,
+        pay_date: payload.pay_date,
+    };
+
+    match use_case.record_dividend(&id, dividend).await {
+        Ok(portfolio) => Ok(Json(portfolio).into_response()),
+        Err(e) => Err(transaction_error(e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/dividends",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (status = 200, description = "Income from each recorded dividend", body = Object),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_dividends(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.get_dividends(&id).await {
+        Ok(Some(dividends)) => Ok(Json(dividends).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get dividends", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/transactions",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id"), ListTransactionsQuery),
+    responses(
+        (
+            status = 200,
+            description = "One page of transactions with pagination links",
+            body = Object
+        ),
+        (status = 400, description = "Invalid dates or page"),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn list_transactions(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    Query(params): Query<ListTransactionsQuery>,
+) -> Result<Response, ApiError> {
+    let (from, to) = match (
+        parse_optional_date(params.from),
+        parse_optional_date(params.to),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return Err(ApiError::bad_request(e)),
+    };
+
+    let page = params.page.unwrap_or(1);
+    let page_size = params.page_size.unwrap_or(DEFAULT_TRANSACTION_PAGE_SIZE);
+    if page == 0 || page_size == 0 || page_size > MAX_TRANSACTION_PAGE_SIZE {
+        return Err(ApiError::bad_request(format!(
+            "page must be at least 1 and page_size between 1 and {}",
+            MAX_TRANSACTION_PAGE_SIZE
+        )));
+    }
+
+    let query = TransactionQuery {
+        from,
+        to,
+        symbol: params.symbol,
+        page,
+        page_size,
+    };
+
+    match use_case.list_transactions(&id, &query).await {
+        Ok(Some(page)) => {
+            let links = PaginationLinks::from_uri(&uri, page.page, page.total_pages);
+            Ok(Json(WithLinks { data: page, links }).into_response())
+        }
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to list transactions", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/cost-summary",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (status = 200, description = "Money invested, fees paid and proceeds", body = Object),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_cost_summary(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.get_portfolio(&id).await {
+        Ok(Some(portfolio)) => Ok(Json(portfolio.cost_summary()).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get cost summary", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/realized-gains",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (status = 200, description = "Gain or loss of each sale", body = Object),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_realized_gains(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.get_portfolio(&id).await {
+        Ok(Some(portfolio)) => Ok(Json(portfolio.realized_sales()).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get realized gains", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/valuation",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id"), ValuationQuery),
+    responses(
+        (status = 200, description = "Holdings valued in the base currency", body = Object),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_valuation(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+    Query(params): Query<ValuationQuery>,
+) -> Result<Response, ApiError> {
+    match use_case.get_valuation(&id, params.price_source).await {
+        Ok(Some(valuation)) => Ok(Json(valuation).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get valuation", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/as-of",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id"), AsOfQuery),
+    responses(
+        (
+            status = 200,
+            description = "Holdings as they stood at the end of the date",
+            body = Object
+        ),
+        (status = 400, description = "Date in the future"),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_as_of(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+    Query(params): Query<AsOfQuery>,
+) -> Result<Response, ApiError> {
+    if params.date > chrono::Utc::now().date_naive() {
+        return Err(ApiError::bad_request("date must not be in the future"));
+    }
+
+    match use_case.get_as_of(&id, params.date).await {
+        Ok(Some(portfolio)) => Ok(Json(portfolio).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage(
+            "Failed to get holdings as of the date",
+            e,
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/risk",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id"), RiskQuery),
+    responses(
+        (status = 200, description = "Volatility and value at risk", body = Object),
+        (status = 400, description = "Invalid window or confidence"),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_risk(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+    Query(params): Query<RiskQuery>,
+) -> Result<Response, ApiError> {
+    let days = params.days.unwrap_or(DEFAULT_RISK_DAYS);
+    let confidence = params.confidence.unwrap_or(DEFAULT_RISK_CONFIDENCE);
+    if days < 2 || !(confidence > 0.0 && confidence < 1.0) {
+        return Err(ApiError::bad_request(
+            "days must be at least 2 and confidence between 0 and 1",
+        ));
+    }
+
+    match use_case.get_risk(&id, days, confidence).await {
+        Ok(Some(risk)) => Ok(Json(risk).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get risk", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/yield-on-cost",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (status = 200, description = "Dividend yield on each holding's cost", body = Object),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_yield_on_cost(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.get_yield_on_cost(&id).await {
+        Ok(Some(yield_on_cost)) => Ok(Json(yield_on_cost).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get yield on cost", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/history",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id"), ValueHistoryQuery),
+    responses(
+        (
+            status = 200,
+            description = "Value points recorded within the range, oldest first",
+            body = [PortfolioValuePoint]
+        ),
+        (status = 400, description = "Invalid or inverted range"),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_value_history(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+    Query(params): Query<ValueHistoryQuery>,
+) -> Result<Response, ApiError> {
+    let (from, to) = match (
+        parse_optional_date(params.from),
+        parse_optional_date(params.to),
+    ) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return Err(ApiError::bad_request(e)),
+    };
+    let to = to.unwrap_or_else(chrono::Utc::now);
+    let from = from.unwrap_or(to - chrono::Duration::days(DEFAULT_VALUE_HISTORY_DAYS));
+    if from > to {
+        return Err(ApiError::bad_request("from must not be after to"));
+    }
+
+    match use_case.get_value_history(&id, from, to).await {
+        Ok(Some(points)) => Ok(Json(points).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get value history", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolios/{id}/snapshots",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (status = 200, description = "Stored daily valuations", body = Object),
+        (status = 404, description = "Portfolio not found"),
+    )
+)]
+async fn get_valuation_history(
+    State(use_case): State<Arc<PortfolioUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.get_valuation_history(&id).await {
+        Ok(Some(history)) => Ok(Json(history).into_response()),
+        Ok(None) => Err(ApiError::not_found("Portfolio not found")),
+        Err(e) => Err(ApiError::storage("Failed to get valuation history", e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/portfolios/{id}",
+    tag = "portfolios",
+    params(("id" = String, Path, description = "Portfolio id")),
+    responses(
+        (status = 204, description = "Portfolio deleted"),
+    )
+)]
 async fn delete_portfolio(
     State(use_case): State<Arc<PortfolioUseCase>>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
+) -> Result<Response, ApiError> {
     match use_case.delete_portfolio(&id).await {
-        Ok(_) => StatusCode::NO_CONTENT.into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        Ok(_) => Ok(StatusCode::NO_CONTENT.into_response()),
+        Err(e) => Err(ApiError::storage("Failed to delete portfolio", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MarketCalendar;
+    use crate::infrastructure::test_support::TempDb;
+    use crate::infrastructure::{
+        RocksDbPortfolioRepository, RocksDbStockRepository, StaticFxRateProvider,
+    };
+
+    fn use_case(temp: &TempDb) -> Arc<PortfolioUseCase> {
+        Arc::new(PortfolioUseCase::new(
+            Arc::new(RocksDbPortfolioRepository::new(temp.db.clone())),
+            Arc::new(RocksDbStockRepository::new(temp.db.clone())),
+            Arc::new(StaticFxRateProvider::new(Default::default())),
+            MarketCalendar::default(),
+        ))
+    }
+
+    fn buy(timestamp: Option<&str>) -> Json<AddTransactionRequest> {
+        Json(AddTransactionRequest {
+            symbol: "MTNGH".to_string(),
+            transaction_type: TransactionType::Buy,
+            quantity: 100,
+            price_per_share: 1.5,
+            fee: 0.0,
+            timestamp: timestamp.map(str::to_string),
+        })
+    }
+
+    #[tokio::test]
+    async fn an_unreadable_transaction_timestamp_is_a_bad_request() {
+        let temp = TempDb::new();
+        let use_case = use_case(&temp);
+        let portfolio = use_case
+            .create_portfolio("Main".to_string(), None, CostBasisMethod::default())
+            .await
+            .unwrap();
+        let portfolio = use_case
+            .add_transaction(
+                &portfolio.id,
+                Transaction {
+                    id: "tx-1".to_string(),
+                    symbol: "MTNGH".to_string(),
+                    transaction_type: TransactionType::Buy,
+                    quantity: 100,
+                    price_per_share: 1.5,
+                    fee: 0.0,
+                    timestamp: chrono::Utc::now(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let added = add_transaction(
+            State(use_case.clone()),
+            Path(portfolio.id.clone()),
+            buy(Some("last tuesday")),
+        )
+        .await
+        .unwrap_err();
+        let updated = update_transaction(
+            State(use_case.clone()),
+            Path((portfolio.id.clone(), "tx-1".to_string())),
+            buy(Some("2024-13-01")),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(added.status, StatusCode::BAD_REQUEST);
+        assert_eq!(added.message, "Invalid date: last tuesday");
+        assert_eq!(updated.status, StatusCode::BAD_REQUEST);
+        let stored = use_case
+            .get_portfolio(&portfolio.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.transactions.len(), 1);
+        assert_eq!(
+            stored.transactions[0].timestamp,
+            portfolio.transactions[0].timestamp
+        );
     }
 }
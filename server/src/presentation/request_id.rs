@@ -0,0 +1,89 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+/// Header carrying the id that correlates a request's logs
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request id that is propagated rather than replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Middleware tagging each request with an id: the client's `X-Request-Id` when it sends a
+/// usable one, a fresh UUID otherwise. Everything logged while the request is handled carries
+/// the id through a `request` span, and the response echoes it back.
+pub async fn propagate_request_id(request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_usable(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = next.run(request).instrument(span).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER.clone(), value);
+    }
+    response
+}
+
+/// Client ids are echoed into logs and headers, so only short printable ones are kept
+fn is_usable(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::Service;
+
+    async fn echoed_id(sent: Option<&str>) -> String {
+        let mut app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn(propagate_request_id));
+        let mut request = Request::builder().uri("/");
+        if let Some(id) = sent {
+            request = request.header(&REQUEST_ID_HEADER, id);
+        }
+
+        // A router is always ready, so it can be called without polling first
+        let response = app
+            .call(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        response.headers()[&REQUEST_ID_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn a_client_id_is_echoed_and_a_missing_or_unusable_one_replaced() {
+        let propagated = echoed_id(Some("checkout-42")).await;
+        let generated = echoed_id(None).await;
+        let replaced = echoed_id(Some("has spaces")).await;
+
+        assert_eq!(propagated, "checkout-42");
+        assert!(uuid::Uuid::parse_str(&generated).is_ok());
+        assert!(uuid::Uuid::parse_str(&replaced).is_ok());
+    }
+}
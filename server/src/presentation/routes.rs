@@ -1,64 +1,443 @@
+use crate::domain::{DatabaseBackup, MetricsRecorder};
+use crate::presentation::auth::{require_admin_key, AdminApiKey};
 use crate::presentation::handlers::*;
+use crate::presentation::latency::{record_latency, LatencyHistogram};
+use crate::presentation::metrics::record_request_metrics;
+use crate::presentation::runtime_config::RuntimeConfig;
 use axum::{
+    middleware,
     routing::{get, post},
     Router,
 };
 use std::sync::Arc;
 
+/// Everything the API routes are served from
+pub struct RouterServices {
+    pub get_use_case: Arc<crate::application::GetStockDataUseCase>,
+    pub fetch_use_case: Arc<crate::application::FetchStockDataUseCase>,
+    pub portfolio_use_case: Arc<crate::application::PortfolioUseCase>,
+    pub watchlist_use_case: Arc<crate::application::WatchlistUseCase>,
+    pub alert_use_case: Arc<crate::application::AlertUseCase>,
+    pub worker_status: Arc<crate::application::WorkerStatus>,
+    pub latency_histogram: Arc<LatencyHistogram>,
+    pub metrics: Arc<dyn MetricsRecorder + Send + Sync>,
+    pub runtime_config: Arc<RuntimeConfig>,
+    pub backups: Arc<dyn DatabaseBackup + Send + Sync>,
+    pub admin_api_key: AdminApiKey,
+}
+
 /// Create the main API router
-pub fn create_router(
-    get_use_case: Arc<crate::application::GetStockDataUseCase>,
-    fetch_use_case: Arc<crate::application::FetchStockDataUseCase>,
-    portfolio_use_case: Arc<crate::application::PortfolioUseCase>,
-) -> Router {
+pub fn create_router(services: RouterServices) -> Router {
+    let RouterServices {
+        get_use_case,
+        fetch_use_case,
+        portfolio_use_case,
+        watchlist_use_case,
+        alert_use_case,
+        worker_status,
+        latency_histogram,
+        metrics,
+        runtime_config,
+        backups,
+        admin_api_key,
+    } = services;
+    let admin_routes = Router::new()
+        .route(
+            "/api/admin/refresh",
+            post({
+                let fetch_use_case = fetch_use_case.clone();
+                move || trigger_data_refresh(fetch_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/refresh-equity",
+            post({
+                let fetch_use_case = fetch_use_case.clone();
+                move || trigger_equity_refresh(fetch_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/recompute-metrics",
+            post({
+                let fetch_use_case = fetch_use_case.clone();
+                let worker_status = worker_status.clone();
+                move || trigger_metrics_recompute(fetch_use_case, worker_status)
+            }),
+        )
+        .route(
+            "/api/admin/bonds",
+            post({
+                let fetch_use_case = fetch_use_case.clone();
+                move |body| record_bond(fetch_use_case, body)
+            }),
+        )
+        .route(
+            "/api/admin/splits",
+            post({
+                let fetch_use_case = fetch_use_case.clone();
+                move |body| record_split(fetch_use_case, body)
+            }),
+        )
+        .route(
+            "/api/admin/announcements",
+            post({
+                let fetch_use_case = fetch_use_case.clone();
+                move |body| record_announcement(fetch_use_case, body)
+            }),
+        )
+        .route(
+            "/api/admin/backup",
+            post({
+                let backups = backups.clone();
+                move || create_backup(backups)
+            }),
+        )
+        .route(
+            "/api/admin/restore",
+            post(move |body| restore_backup(backups, body)),
+        )
+        .route(
+            "/api/admin/scrape-history",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_scrape_history(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/stocks/:symbol/dump",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path| dump_stock_records(path, get_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/stale-symbols",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_stale_symbols(get_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/popular-symbols",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_popular_symbols(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/deliveries",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_deliveries(get_use_case)
+            }),
+        )
+        .route(
+            "/api/admin/latency",
+            get({
+                let latency_histogram = latency_histogram.clone();
+                move || get_latency_stats(latency_histogram)
+            }),
+        )
+        .route(
+            "/api/admin/worker-status",
+            get({
+                let worker_status = worker_status.clone();
+                move || get_worker_status(worker_status)
+            }),
+        )
+        .route(
+            "/api/admin/config",
+            get(move || get_runtime_config(runtime_config)),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            admin_api_key.clone(),
+            require_admin_key,
+        ));
+
     Router::new()
         // Health check
-        .route("/health", get(health_check))
+        .route(
+            "/health",
+            get({
+                let fetch_use_case = fetch_use_case.clone();
+                move || health_check(fetch_use_case)
+            }),
+        )
+        .route(
+            "/health/ready",
+            get({
+                let fetch_use_case = fetch_use_case.clone();
+                move || readiness_check(fetch_use_case)
+            }),
+        )
+        // Prometheus scrape endpoint
+        .route(
+            "/metrics",
+            get({
+                let get_use_case = get_use_case.clone();
+                let metrics = metrics.clone();
+                move || get_metrics(metrics, get_use_case)
+            }),
+        )
         // Stock endpoints
         .route(
             "/api/stocks",
             get({
                 let get_use_case = get_use_case.clone();
-                move || get_all_stocks(get_use_case)
+                let fetch_use_case = fetch_use_case.clone();
+                move |query, headers| get_all_stocks(query, headers, get_use_case, fetch_use_case)
+            }),
+        )
+        .route(
+            "/api/sectors",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_sectors(get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/batch",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_stocks_batch(query, get_use_case)
             }),
         )
         .route(
             "/api/stocks/:symbol",
             get({
                 let get_use_case = get_use_case.clone();
-                move |path| get_stock_by_symbol(path, get_use_case)
+                let fetch_use_case = fetch_use_case.clone();
+                move |path, headers| {
+                    get_stock_by_symbol(path, headers, get_use_case, fetch_use_case)
+                }
             }),
         )
         .route(
             "/api/stocks/:symbol/history",
             get({
                 let get_use_case = get_use_case.clone();
-                move |path, query| get_stock_history(path, query, get_use_case)
+                move |path, query, headers| get_stock_history(path, query, headers, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/history.csv",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| stream_stock_history_csv(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/ws/live",
+            get({
+                let fetch_use_case = fetch_use_case.clone();
+                move |ws| live_updates_socket(ws, fetch_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/history/stream",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| stream_stock_history(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/intraday",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path| get_stock_intraday(path, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/announcements",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| get_stock_announcements(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/candles",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query, headers| get_candles(path, query, headers, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/indicators",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| get_indicator_series(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/indicators/:indicator",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| get_indicator(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/ladder",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path| get_price_ladder(path, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/turnover",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| get_turnover(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/:symbol/volatility-cone",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |path, query| get_volatility_cone(path, query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/stocks/search",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| search(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/search",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| search(query, get_use_case)
             }),
         )
         // Market endpoints
+        .route(
+            "/api/market/summary/stream",
+            get({
+                let fetch_use_case = fetch_use_case.clone();
+                move || stream_market_summary(fetch_use_case)
+            }),
+        )
+        .route(
+            "/api/market/summary/timeline",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_market_summary_timeline(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/market/summary/card",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_market_summary_card(query, get_use_case)
+            }),
+        )
         .route(
             "/api/market/summary",
             get({
                 let get_use_case = get_use_case.clone();
-                move || get_market_summary(get_use_case)
+                move |query| get_market_summary(query, get_use_case)
             }),
         )
-        // Admin endpoints
         .route(
-            "/api/admin/refresh",
-            post({
-                let fetch_use_case = fetch_use_case.clone();
-                move || trigger_data_refresh(fetch_use_case)
+            "/api/market/events",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_market_events(get_use_case)
             }),
         )
         .route(
-            "/api/admin/refresh-equity",
+            "/api/market/volume-alerts",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_volume_alerts(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/market/sectors",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_market_sectors(get_use_case)
+            }),
+        )
+        .route(
+            "/api/market/breadth",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_market_breadth(get_use_case)
+            }),
+        )
+        .route(
+            "/api/bonds/yield-curve",
+            get({
+                let get_use_case = get_use_case.clone();
+                move || get_yield_curve(get_use_case)
+            }),
+        )
+        .route(
+            "/api/market/announcements",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_market_announcements(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/market/calendar",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_trading_calendar(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/market/relative-strength",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_relative_strength(query, get_use_case)
+            }),
+        )
+        .route(
+            "/api/analysis/correlation",
             post({
-                let fetch_use_case = fetch_use_case.clone();
-                move || trigger_equity_refresh(fetch_use_case)
+                let get_use_case = get_use_case.clone();
+                move |body| get_correlation_matrix(get_use_case, body)
+            }),
+        )
+        .route(
+            "/api/market/snapshot-diff",
+            get({
+                let get_use_case = get_use_case.clone();
+                move |query| get_snapshot_diff(query, get_use_case)
             }),
         )
+        // Admin endpoints, behind the API key
+        .merge(admin_routes)
         // Portfolio endpoints
-        .nest("/api/portfolios", crate::presentation::portfolio_routes::portfolio_routes(portfolio_use_case))
+        .nest(
+            "/api/portfolios",
+            crate::presentation::portfolio_routes::portfolio_routes(portfolio_use_case),
+        )
+        // Watchlist endpoints
+        .nest(
+            "/api/watchlists",
+            crate::presentation::watchlist_routes::watchlist_routes(watchlist_use_case),
+        )
+        // Price alert endpoints, behind the API key since alerts make the service call out to
+        // caller-chosen URLs
+        .nest(
+            "/api/alerts",
+            crate::presentation::alert_routes::alert_routes(alert_use_case).route_layer(
+                middleware::from_fn_with_state(admin_api_key, require_admin_key),
+            ),
+        )
+        // OpenAPI document and Swagger UI
+        .merge(crate::presentation::openapi::docs_router())
+        // Record latency for every matched route, including the nested ones above
+        .route_layer(middleware::from_fn_with_state(
+            latency_histogram,
+            record_latency,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            metrics,
+            record_request_metrics,
+        ))
 }
@@ -0,0 +1,191 @@
+use crate::application::worker::WorkerConfig;
+use crate::application::{
+    ArchiveConfig, DeliveryConfig, ExportConfig, FetchConfig, InvalidPriceMode, QueryConfig,
+};
+use crate::infrastructure::{CircuitBreakerConfig, RateLimitConfig};
+use crate::presentation::client_rate_limit::ClientRateLimitConfig;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Placeholder reported instead of configured secrets
+const REDACTED: &str = "[redacted]";
+
+/// The configuration the running service actually loaded, after env parsing and defaults
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeConfig {
+    pub worker: WorkerSettings,
+    pub client: ClientSettings,
+    pub query: QuerySettings,
+    pub retention: RetentionSettings,
+    pub features: FeatureFlags,
+    /// `None` when per-client rate limiting is disabled
+    pub client_rate_limit: Option<ClientRateLimitConfig>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerSettings {
+    pub scrape_interval: u64,
+    pub max_retries: u32,
+    pub retry_delay: u64,
+    /// Pause windows formatted as `HH:MM-HH:MM`
+    pub pause_windows: Vec<String>,
+    /// Trading hours in Accra time, formatted as `HH:00-HH:00`
+    pub trading_hours: String,
+    pub holidays: Vec<chrono::NaiveDate>,
+    /// Webhook URLs can embed tokens, so only whether one is set is reported
+    pub scrape_webhook_url: Option<&'static str>,
+    pub min_free_disk_bytes: u64,
+    pub retention_days: Option<i64>,
+}
+
+impl From<&WorkerConfig> for WorkerSettings {
+    fn from(config: &WorkerConfig) -> Self {
+        Self {
+            scrape_interval: config.scrape_interval,
+            max_retries: config.max_retries,
+            retry_delay: config.retry_delay,
+            pause_windows: config
+                .pause_windows
+                .iter()
+                .map(|w| format!("{}-{}", w.start.format("%H:%M"), w.end.format("%H:%M")))
+                .collect(),
+            trading_hours: format!(
+                "{:02}:00-{:02}:00",
+                config.market_open_hour, config.market_close_hour
+            ),
+            holidays: config.holidays.clone(),
+            scrape_webhook_url: config.scrape_webhook_url.as_ref().map(|_| REDACTED),
+            min_free_disk_bytes: config.min_free_disk_bytes,
+            retention_days: config.retention_days,
+        }
+    }
+}
+
+/// Settings of the upstream GSE API client
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientSettings {
+    pub base_url: String,
+    pub requests_per_second: f64,
+    pub burst: u32,
+    pub low_remaining_threshold: u32,
+    pub circuit_failure_threshold: u32,
+    /// In seconds
+    pub circuit_cooldown: u64,
+}
+
+impl ClientSettings {
+    pub fn new(
+        base_url: &str,
+        rate_limit: &RateLimitConfig,
+        circuit_breaker: &CircuitBreakerConfig,
+    ) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            requests_per_second: rate_limit.requests_per_second,
+            burst: rate_limit.burst,
+            low_remaining_threshold: rate_limit.low_remaining_threshold,
+            circuit_failure_threshold: circuit_breaker.failure_threshold,
+            circuit_cooldown: circuit_breaker.cooldown.as_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuerySettings {
+    pub min_valid_price: f64,
+    pub invalid_price_mode: &'static str,
+    pub max_change_percent: f64,
+    pub market_move_alert_percent: f64,
+    /// In seconds
+    pub manual_refresh_wait: u64,
+    /// In seconds
+    pub summary_fresh_within: i64,
+    /// Offset from UTC, e.g. `+00:00`
+    pub market_utc_offset: String,
+    /// In seconds
+    pub equity_cache_ttl: i64,
+    pub max_batch_symbols: usize,
+    /// Per-symbol freshness SLAs (in seconds), with `default` applying to all other symbols
+    pub freshness_sla: BTreeMap<String, i64>,
+    pub ladder_spread_percent: f64,
+    pub ladder_levels: usize,
+}
+
+impl QuerySettings {
+    pub fn new(query: &QueryConfig, fetch: &FetchConfig) -> Self {
+        Self {
+            min_valid_price: query.price_filter.min_valid_price,
+            invalid_price_mode: match query.price_filter.mode {
+                InvalidPriceMode::SkipStore => "skip_store",
+                InvalidPriceMode::FilterOnRead => "filter_on_read",
+            },
+            max_change_percent: query.price_filter.max_change_percent,
+            market_move_alert_percent: fetch.market_move_alert_percent,
+            manual_refresh_wait: fetch.manual_refresh_wait,
+            summary_fresh_within: fetch.summary_fresh_within.num_seconds(),
+            market_utc_offset: query.market_timezone.to_string(),
+            equity_cache_ttl: query.equity_cache_ttl.num_seconds(),
+            max_batch_symbols: query.max_batch_symbols,
+            freshness_sla: query
+                .freshness_sla
+                .overrides
+                .iter()
+                .map(|(symbol, sla)| (symbol.clone(), sla.num_seconds()))
+                .chain([(
+                    "default".to_string(),
+                    query.freshness_sla.default.num_seconds(),
+                )])
+                .collect(),
+            ladder_spread_percent: query.ladder.spread_percent,
+            ladder_levels: query.ladder.levels,
+        }
+    }
+}
+
+/// How long queued and exported data is kept around
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionSettings {
+    pub webhook_max_attempts: u32,
+    /// Initial backoff between webhook retries (in seconds)
+    pub webhook_retry_backoff: i64,
+    /// Unset when periodic exports are disabled
+    pub export_interval: Option<u64>,
+    pub export_dir: Option<String>,
+    pub export_keep: Option<usize>,
+    /// Unset when archiving is disabled
+    pub archive_after_days: Option<i64>,
+    pub recently_requested_capacity: usize,
+}
+
+impl RetentionSettings {
+    pub fn new(
+        delivery: &DeliveryConfig,
+        export: Option<&ExportConfig>,
+        archive: Option<&ArchiveConfig>,
+        recently_requested_capacity: usize,
+    ) -> Self {
+        Self {
+            webhook_max_attempts: delivery.max_attempts,
+            webhook_retry_backoff: delivery.retry_backoff,
+            export_interval: export.map(|e| e.interval),
+            export_dir: export.map(|e| e.directory.display().to_string()),
+            export_keep: export.map(|e| e.keep),
+            archive_after_days: archive.map(|a| a.older_than_days),
+            recently_requested_capacity,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureFlags {
+    pub fetch_equity_data: bool,
+    pub generate_market_summary: bool,
+    pub refresh_requested_symbols: bool,
+    pub bootstrap_equities: bool,
+    pub merge_symbol_casings: bool,
+    /// Keep serving stored data when the upstream is unreachable at boot, rather than exiting
+    pub allow_degraded_start: bool,
+    pub exports_enabled: bool,
+    /// Whether `ADMIN_API_KEY` is set; admin endpoints refuse every request without it
+    pub admin_auth: bool,
+}
@@ -0,0 +1,149 @@
+use crate::domain::{EquityLive, MarketSummary, PRICE_CURRENCY};
+use serde::Serialize;
+
+/// Market summary flattened into preformatted strings, ready to draw onto a share card
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryCard {
+    pub title: String,
+    /// e.g. `16 Oct 2026, 14:30 GMT`
+    pub as_of: String,
+    pub index_level: String,
+    pub market_cap: String,
+    pub volume: String,
+    pub stocks: String,
+    /// Set when the summary was generated during a partial upstream outage, e.g. `85% of data`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_coverage: Option<String>,
+    pub top_gainers: Vec<CardMover>,
+    pub top_losers: Vec<CardMover>,
+}
+
+/// One gainer or loser on a share card
+#[derive(Debug, Clone, Serialize)]
+pub struct CardMover {
+    pub symbol: String,
+    pub price: String,
+    /// Percent change with a direction arrow, e.g. `▲ +5.26%`
+    pub change: String,
+    /// `up`, `down` or `flat`, for picking colours
+    pub direction: &'static str,
+}
+
+/// Render a market summary as a share card showing up to `movers` gainers and losers
+pub fn summary_card(summary: &MarketSummary, movers: usize) -> SummaryCard {
+    let to_movers = |stocks: &[EquityLive]| stocks.iter().take(movers).map(card_mover).collect();
+
+    SummaryCard {
+        title: "GSE Market Summary".to_string(),
+        as_of: summary
+            .last_updated
+            .format("%-d %b %Y, %H:%M GMT")
+            .to_string(),
+        index_level: group_thousands(summary.index_level, 2),
+        market_cap: format!(
+            "{} {}",
+            PRICE_CURRENCY,
+            format_compact(summary.total_market_cap)
+        ),
+        volume: format_compact(summary.total_volume as f64),
+        stocks: match summary.total_stocks {
+            1 => "1 stock".to_string(),
+            count => format!("{} stocks", count),
+        },
+        data_coverage: summary
+            .data_completeness
+            .filter(|completeness| *completeness < 1.0)
+            .map(|completeness| format!("{:.0}% of data", completeness * 100.0)),
+        top_gainers: to_movers(&summary.top_gainers),
+        top_losers: to_movers(&summary.top_losers),
+    }
+}
+
+fn card_mover(stock: &EquityLive) -> CardMover {
+    let (change, direction) = match stock.change_percent() {
+        Some(percent) if percent > 0.0 => (format!("▲ +{:.2}%", percent), "up"),
+        Some(percent) if percent < 0.0 => (format!("▼ {:.2}%", percent), "down"),
+        Some(_) => ("► 0.00%".to_string(), "flat"),
+        None => ("–".to_string(), "flat"),
+    };
+
+    CardMover {
+        symbol: stock.name.to_uppercase(),
+        price: format!("{} {:.2}", PRICE_CURRENCY, stock.price),
+        change,
+        direction,
+    }
+}
+
+/// Abbreviate large amounts, e.g. `1.25B`, `380.40M`, `12.5K`
+fn format_compact(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude >= 1e9 {
+        format!("{:.2}B", value / 1e9)
+    } else if magnitude >= 1e6 {
+        format!("{:.2}M", value / 1e6)
+    } else if magnitude >= 1e3 {
+        format!("{:.1}K", value / 1e3)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+/// Format with `decimals` places and commas between thousands, e.g. `12,345.67`
+fn group_thousands(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if !fraction.is_empty() {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    if value < 0.0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::test_support::live;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn a_gainer_is_rendered_with_an_up_arrow_and_a_formatted_percent() {
+        let summary = MarketSummary {
+            total_market_cap: 1_250_000_000.0,
+            total_volume: 12_500,
+            total_stocks: 2,
+            top_gainers: vec![live("mtngh", 2.1, 0.1)],
+            top_losers: vec![live("GCB", 4.5, -0.5)],
+            index_level: 12345.678,
+            prices: Default::default(),
+            data_completeness: Some(0.85),
+            sectors: Vec::new(),
+            last_updated: Utc.with_ymd_and_hms(2024, 3, 6, 14, 30, 0).unwrap(),
+        };
+
+        let card = summary_card(&summary, 5);
+
+        let gainer = &card.top_gainers[0];
+        assert_eq!(gainer.symbol, "MTNGH");
+        assert_eq!(gainer.change, "▲ +5.00%");
+        assert_eq!(gainer.direction, "up");
+        assert_eq!(card.top_losers[0].change, "▼ -10.00%");
+        assert_eq!(card.index_level, "12,345.68");
+        assert_eq!(card.market_cap, format!("{} 1.25B", PRICE_CURRENCY));
+        assert_eq!(card.as_of, "6 Mar 2024, 14:30 GMT");
+        assert_eq!(card.data_coverage.as_deref(), Some("85% of data"));
+    }
+}
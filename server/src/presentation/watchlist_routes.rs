@@ -0,0 +1,144 @@
+use crate::application::WatchlistUseCase;
+use crate::domain::WatchlistError;
+use crate::presentation::handlers::ApiError;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateWatchlistRequest {
+    name: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AddSymbolRequest {
+    symbol: String,
+}
+
+pub fn watchlist_routes(use_case: Arc<WatchlistUseCase>) -> Router {
+    Router::new()
+        .route("/", post(create_watchlist).get(get_all_watchlists))
+        .route("/:id", get(get_watchlist))
+        .route("/:id/symbols", post(add_symbol))
+        .route("/:id/symbols/:symbol", delete(remove_symbol))
+        .with_state(use_case)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/watchlists",
+    tag = "watchlists",
+    request_body = CreateWatchlistRequest,
+    responses(
+        (status = 201, description = "Created watchlist", body = Watchlist),
+    )
+)]
+async fn create_watchlist(
+    State(use_case): State<Arc<WatchlistUseCase>>,
+    Json(payload): Json<CreateWatchlistRequest>,
+) -> Result<Response, ApiError> {
+    match use_case.create_watchlist(payload.name).await {
+        Ok(watchlist) => Ok((StatusCode::CREATED, Json(watchlist)).into_response()),
+        Err(e) => Err(ApiError::storage("Failed to create watchlist", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/watchlists",
+    tag = "watchlists",
+    responses(
+        (status = 200, description = "Every watchlist", body = [Watchlist]),
+    )
+)]
+async fn get_all_watchlists(
+    State(use_case): State<Arc<WatchlistUseCase>>,
+) -> Result<Response, ApiError> {
+    match use_case.get_all_watchlists().await {
+        Ok(watchlists) => Ok(Json(watchlists).into_response()),
+        Err(e) => Err(ApiError::storage("Failed to list watchlists", e)),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/watchlists/{id}",
+    tag = "watchlists",
+    params(("id" = String, Path, description = "Watchlist id")),
+    responses(
+        (
+            status = 200,
+            description = "The watchlist with the latest live data of each symbol",
+            body = WatchlistView
+        ),
+        (status = 404, description = "Watchlist not found"),
+    )
+)]
+async fn get_watchlist(
+    State(use_case): State<Arc<WatchlistUseCase>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    match use_case.get_watchlist(&id).await {
+        Ok(Some(watchlist)) => Ok(Json(watchlist).into_response()),
+        Ok(None) => Err(ApiError::not_found("Watchlist not found")),
+        Err(e) => Err(ApiError::storage("Failed to get watchlist", e)),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/watchlists/{id}/symbols",
+    tag = "watchlists",
+    params(("id" = String, Path, description = "Watchlist id")),
+    request_body = AddSymbolRequest,
+    responses(
+        (status = 200, description = "Updated watchlist", body = Watchlist),
+        (status = 400, description = "Unknown symbol"),
+        (status = 404, description = "Watchlist not found"),
+    )
+)]
+async fn add_symbol(
+    State(use_case): State<Arc<WatchlistUseCase>>,
+    Path(id): Path<String>,
+    Json(payload): Json<AddSymbolRequest>,
+) -> Result<Response, ApiError> {
+    match use_case.add_symbol(&id, &payload.symbol).await {
+        Ok(Some(watchlist)) => Ok(Json(watchlist).into_response()),
+        Ok(None) => Err(ApiError::not_found("Watchlist not found")),
+        Err(e) if e.downcast_ref::<WatchlistError>().is_some() => {
+            Err(ApiError::bad_request(e.to_string()))
+        }
+        Err(e) => Err(ApiError::storage("Failed to add symbol", e)),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/watchlists/{id}/symbols/{symbol}",
+    tag = "watchlists",
+    params(
+        ("id" = String, Path, description = "Watchlist id"),
+        ("symbol" = String, Path, description = "Stock symbol, case-insensitive"),
+    ),
+    responses(
+        (status = 200, description = "Updated watchlist", body = Watchlist),
+        (status = 404, description = "Watchlist not found"),
+    )
+)]
+async fn remove_symbol(
+    State(use_case): State<Arc<WatchlistUseCase>>,
+    Path((id, symbol)): Path<(String, String)>,
+) -> Result<Response, ApiError> {
+    match use_case.remove_symbol(&id, &symbol).await {
+        Ok(Some(watchlist)) => Ok(Json(watchlist).into_response()),
+        Ok(None) => Err(ApiError::not_found("Watchlist not found")),
+        Err(e) => Err(ApiError::storage("Failed to remove symbol", e)),
+    }
+}